@@ -0,0 +1,764 @@
+use crate::api::polyline;
+use crate::api::types::{Activity, ActivityZone, BestEffort, StreamSet, ZoneBucket};
+use crate::config::Units;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Race distances predicted on the dashboard, in meters
+pub const RACES: [(&str, f64); 4] = [
+    ("5k", 5000.0),
+    ("10k", 10000.0),
+    ("Half", 21097.5),
+    ("Marathon", 42195.0),
+];
+// Riegel's fatigue exponent
+const RIEGEL_EXPONENT: f64 = 1.06;
+// Shorter efforts are mostly speed and overestimate endurance
+const MIN_EFFORT_METERS: f64 = 1600.0;
+
+/// Current running fitness estimated from one recent best effort.
+#[derive(Debug, Clone)]
+pub struct RaceEstimate<'a> {
+    /// The effort with the highest VDOT, which the predictions start from
+    pub effort: &'a BestEffort,
+    /// Jack Daniels' VDOT, a stand-in for VO2max
+    pub vdot: f64,
+    /// Predicted finish times in seconds, in the order of `RACES`
+    pub predictions: Vec<(&'static str, f64)>,
+}
+
+/// Daniels and Gilbert's VDOT for running `meters` in `seconds`.
+pub fn vdot(meters: f64, seconds: f64) -> Option<f64> {
+    if meters <= 0.0 || seconds <= 0.0 {
+        return None;
+    }
+    let minutes = seconds / 60.0;
+    let velocity = meters / minutes;
+    let oxygen_cost = -4.60 + 0.182258 * velocity + 0.000104 * velocity * velocity;
+    let sustainable =
+        0.8 + 0.1894393 * (-0.012778 * minutes).exp() + 0.2989558 * (-0.1932605 * minutes).exp();
+    Some(oxygen_cost / sustainable)
+}
+
+/// Riegel's prediction for `target` meters from `seconds` over `meters`.
+pub fn riegel(meters: f64, seconds: f64, target: f64) -> f64 {
+    seconds * (target / meters).powf(RIEGEL_EXPONENT)
+}
+
+/// Estimates race times from the best of the efforts started at or after
+/// `since`. None if there's no effort of at least a mile in that window.
+pub fn estimate_races<'a>(
+    efforts: impl IntoIterator<Item = &'a BestEffort>,
+    since: DateTime<Utc>,
+) -> Option<RaceEstimate<'a>> {
+    let (effort, vdot) = efforts
+        .into_iter()
+        .filter(|e| e.start_date >= since && e.distance >= MIN_EFFORT_METERS)
+        .filter_map(|e| Some((e, vdot(e.distance, e.elapsed_time as f64)?)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let predictions = RACES
+        .iter()
+        .map(|&(name, meters)| {
+            (
+                name,
+                riegel(effort.distance, effort.elapsed_time as f64, meters),
+            )
+        })
+        .collect();
+    Some(RaceEstimate {
+        effort,
+        vdot,
+        predictions,
+    })
+}
+
+/// What a personal record measures.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordMetric {
+    Distance,
+    Elevation,
+    /// Average speed of activities at least this many meters long
+    AverageSpeed {
+        min_distance: f64,
+    },
+}
+
+/// A record kept per sport, independent of Strava's achievements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordKind {
+    pub name: &'static str,
+    /// Matched against `sport_type`, so "Run" covers trail and virtual runs
+    pub sport: &'static str,
+    pub metric: RecordMetric,
+}
+
+const fn record(name: &'static str, sport: &'static str, metric: RecordMetric) -> RecordKind {
+    RecordKind {
+        name,
+        sport,
+        metric,
+    }
+}
+
+#[rustfmt::skip]
+pub const RECORDS: [RecordKind; 10] = [
+    record("Longest run", "Run", RecordMetric::Distance),
+    record("Biggest climb (run)", "Run", RecordMetric::Elevation),
+    record("Fastest run over 5k", "Run", RecordMetric::AverageSpeed { min_distance: 5000.0 }),
+    record("Fastest run over 10k", "Run", RecordMetric::AverageSpeed { min_distance: 10000.0 }),
+    record("Fastest run over 21k", "Run", RecordMetric::AverageSpeed { min_distance: 21097.5 }),
+    record("Longest ride", "Ride", RecordMetric::Distance),
+    record("Biggest climb (ride)", "Ride", RecordMetric::Elevation),
+    record("Fastest ride over 20k", "Ride", RecordMetric::AverageSpeed { min_distance: 20000.0 }),
+    record("Fastest ride over 50k", "Ride", RecordMetric::AverageSpeed { min_distance: 50000.0 }),
+    record("Fastest ride over 100k", "Ride", RecordMetric::AverageSpeed { min_distance: 100000.0 }),
+];
+
+impl RecordKind {
+    fn value(&self, activity: &Activity) -> Option<f64> {
+        if !activity.sport_type.contains(self.sport) {
+            return None;
+        }
+        let value = match self.metric {
+            RecordMetric::Distance => activity.distance,
+            RecordMetric::Elevation => activity.total_elevation_gain,
+            RecordMetric::AverageSpeed { min_distance } if activity.distance >= min_distance => {
+                activity.average_speed?
+            }
+            RecordMetric::AverageSpeed { .. } => return None,
+        };
+        (value > 0.0).then_some(value)
+    }
+}
+
+/// Current holder of each record in `activities`. On a tie the earlier
+/// activity keeps the record, since it was set first.
+pub fn personal_records<'a>(
+    activities: impl IntoIterator<Item = &'a Activity>,
+) -> Vec<(&'static RecordKind, &'a Activity)> {
+    let activities: Vec<&Activity> = activities.into_iter().collect();
+    RECORDS
+        .iter()
+        .filter_map(|kind| {
+            let mut best: Option<(&Activity, f64)> = None;
+            for &activity in &activities {
+                let Some(value) = kind.value(activity) else {
+                    continue;
+                };
+                let beats = match best {
+                    None => true,
+                    Some((holder, record)) => {
+                        value > record
+                            || (value == record && activity.start_date < holder.start_date)
+                    }
+                };
+                if beats {
+                    best = Some((activity, value));
+                }
+            }
+            best.map(|(activity, _)| (kind, activity))
+        })
+        .collect()
+}
+
+// Routes match when their distances differ by at most this fraction...
+const ROUTE_DISTANCE_TOLERANCE: f64 = 0.05;
+// ...and every sample point lies within this many meters of the other's
+const ROUTE_POINT_TOLERANCE_METERS: f64 = 250.0;
+// Start, quarter, half, three quarters and end of the track
+const ROUTE_SAMPLES: usize = 5;
+const EARTH_RADIUS_METERS: f64 = 6_371_000.0;
+
+struct RouteShape<'a> {
+    activity: &'a Activity,
+    samples: [(f64, f64); ROUTE_SAMPLES],
+}
+
+impl<'a> RouteShape<'a> {
+    fn new(activity: &'a Activity) -> Option<Self> {
+        let encoded = activity.map.as_ref()?.summary_polyline.as_deref()?;
+        let points = polyline::decode(encoded);
+        if points.len() < 2 || activity.distance <= 0.0 {
+            return None;
+        }
+
+        // Summary polylines are simplified, so samples are taken by distance
+        // along the track rather than by point index
+        let mut along = vec![0.0];
+        for pair in points.windows(2) {
+            along.push(along[along.len() - 1] + haversine(pair[0], pair[1]));
+        }
+        let total = along[along.len() - 1];
+        let mut samples = [points[0]; ROUTE_SAMPLES];
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let target = total * i as f64 / (ROUTE_SAMPLES - 1) as f64;
+            let index = along.partition_point(|&d| d < target).min(points.len() - 1);
+            *sample = points[index];
+        }
+        Some(Self { activity, samples })
+    }
+
+    fn matches(&self, other: &RouteShape) -> bool {
+        let (a, b) = (self.activity, other.activity);
+        a.sport_type == b.sport_type
+            && (a.distance - b.distance).abs()
+                <= ROUTE_DISTANCE_TOLERANCE * a.distance.max(b.distance)
+            && self
+                .samples
+                .iter()
+                .zip(&other.samples)
+                .all(|(&p, &q)| haversine(p, q) <= ROUTE_POINT_TOLERANCE_METERS)
+    }
+}
+
+fn haversine((lat1, lng1): (f64, f64), (lat2, lng2): (f64, f64)) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let dlat = lat2 - lat1;
+    let dlng = (lng2 - lng1).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_METERS * h.sqrt().asin()
+}
+
+/// Groups activities that follow roughly the same route: same sport, similar
+/// distance and the same start, end and points in between. Only routes done
+/// at least twice are returned, the most repeated first, each oldest first.
+/// Activities without a map are skipped.
+pub fn repeated_routes<'a>(
+    activities: impl IntoIterator<Item = &'a Activity>,
+) -> Vec<Vec<&'a Activity>> {
+    let mut shapes: Vec<RouteShape> = activities.into_iter().filter_map(RouteShape::new).collect();
+    shapes.sort_by_key(|s| s.activity.start_date);
+
+    // Each route is represented by its first activity
+    let mut routes: Vec<(RouteShape, Vec<&Activity>)> = Vec::new();
+    for shape in shapes {
+        match routes.iter_mut().find(|(first, _)| first.matches(&shape)) {
+            Some((_, members)) => members.push(shape.activity),
+            None => {
+                let first = shape.activity;
+                routes.push((shape, vec![first]));
+            }
+        }
+    }
+
+    let mut routes: Vec<Vec<&Activity>> = routes
+        .into_iter()
+        .map(|(_, members)| members)
+        .filter(|members| members.len() > 1)
+        .collect();
+    routes.sort_by_key(|members| std::cmp::Reverse(members.len()));
+    routes
+}
+
+/// Totals of one sport type across the cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SportTotals {
+    pub sport: String,
+    pub count: u32,
+    /// Meters
+    pub distance: f64,
+    /// Seconds
+    pub moving_time: u64,
+    /// Meters
+    pub elevation: f64,
+    /// Distance of the longest activity, in meters
+    pub longest: f64,
+    pub last: DateTime<Utc>,
+}
+
+impl SportTotals {
+    /// Meters per second over the whole moving time, so long activities
+    /// weigh more than short ones. None for sports without distance.
+    pub fn average_speed(&self) -> Option<f64> {
+        (self.distance > 0.0 && self.moving_time > 0)
+            .then(|| self.distance / self.moving_time as f64)
+    }
+}
+
+/// Every sport type in `activities` with its totals, the one with the most
+/// moving time first.
+pub fn sport_breakdown<'a>(activities: impl IntoIterator<Item = &'a Activity>) -> Vec<SportTotals> {
+    let mut sports: Vec<SportTotals> = Vec::new();
+    for activity in activities {
+        let index = match sports.iter().position(|s| s.sport == activity.sport_type) {
+            Some(index) => index,
+            None => {
+                sports.push(SportTotals {
+                    sport: activity.sport_type.clone(),
+                    count: 0,
+                    distance: 0.0,
+                    moving_time: 0,
+                    elevation: 0.0,
+                    longest: 0.0,
+                    last: activity.start_date,
+                });
+                sports.len() - 1
+            }
+        };
+        let totals = &mut sports[index];
+        totals.count += 1;
+        totals.distance += activity.distance;
+        totals.moving_time += activity.moving_time as u64;
+        totals.elevation += activity.total_elevation_gain;
+        totals.longest = totals.longest.max(activity.distance);
+        totals.last = totals.last.max(activity.start_date);
+    }
+    sports.sort_by(|a, b| {
+        b.moving_time
+            .cmp(&a.moving_time)
+            .then_with(|| a.sport.cmp(&b.sport))
+    });
+    sports
+}
+
+// Shorter activities, like a gym session with a few meters logged, say
+// nothing about the terrain
+const MIN_CLIMB_DENSITY_DISTANCE: f64 = 1000.0;
+
+/// Meters climbed per meter covered, None below a kilometer.
+pub fn climb_density(elevation_gain: f64, distance: f64) -> Option<f64> {
+    (distance >= MIN_CLIMB_DENSITY_DISTANCE).then(|| elevation_gain.max(0.0) / distance)
+}
+
+/// Elevation gain and distance added up over some activities.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ClimbTotals {
+    /// Meters
+    pub elevation: f64,
+    /// Meters
+    pub distance: f64,
+    pub count: u32,
+}
+
+impl ClimbTotals {
+    pub fn density(&self) -> Option<f64> {
+        climb_density(self.elevation, self.distance)
+    }
+}
+
+pub fn climb_totals<'a>(activities: impl IntoIterator<Item = &'a Activity>) -> ClimbTotals {
+    activities
+        .into_iter()
+        .fold(ClimbTotals::default(), |totals, activity| ClimbTotals {
+            elevation: totals.elevation + activity.total_elevation_gain,
+            distance: totals.distance + activity.distance,
+            count: totals.count + 1,
+        })
+}
+
+/// What the RelPerf column shows, chosen with `efficiency_metric`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EfficiencyMetric {
+    /// The original RelPerf: moving time per beat of average heart rate
+    #[default]
+    TimePerBeat,
+    PaceHr,
+    EfficiencyFactor,
+    AerobicDecoupling,
+}
+
+impl EfficiencyMetric {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EfficiencyMetric::TimePerBeat => "Time per beat",
+            EfficiencyMetric::PaceHr => "Pace ÷ HR",
+            EfficiencyMetric::EfficiencyFactor => "Efficiency factor (EF)",
+            EfficiencyMetric::AerobicDecoupling => "Aerobic decoupling (Pa:HR)",
+        }
+    }
+
+    /// How the value is calculated and read, for the help popup.
+    pub fn formula(&self) -> &'static str {
+        match self {
+            EfficiencyMetric::TimePerBeat => {
+                "(distance ÷ average speed) ÷ average HR, i.e. moving seconds per bpm. \
+                 Grows with duration, so only compare similar workouts."
+            }
+            EfficiencyMetric::PaceHr => {
+                "Pace in seconds per km or mi ÷ average HR. Lower is better: \
+                 the same pace at a lower heart rate."
+            }
+            EfficiencyMetric::EfficiencyFactor => {
+                "Normalized power ÷ average HR with a power meter, otherwise \
+                 speed in m/min ÷ average HR. Higher is better."
+            }
+            EfficiencyMetric::AerobicDecoupling => {
+                "Drop of speed ÷ HR from the first to the second half of the \
+                 moving time, in %. Below 5% means well coupled. Needs the \
+                 streams, so it shows once the activity has been opened."
+            }
+        }
+    }
+
+    /// None when the activity lacks the data the metric needs.
+    pub fn value(
+        &self,
+        activity: &Activity,
+        streams: Option<&StreamSet>,
+        units: Units,
+    ) -> Option<f64> {
+        let heartrate = activity.average_heartrate.filter(|&hr| hr > 0.0);
+        let speed = activity.average_speed.filter(|&s| s > 0.0);
+        match self {
+            EfficiencyMetric::TimePerBeat => Some(activity.distance / speed? / heartrate?),
+            EfficiencyMetric::PaceHr => Some(1.0 / units.distance(speed?) / heartrate?),
+            EfficiencyMetric::EfficiencyFactor => {
+                let heartrate = heartrate?;
+                match activity.weighted_average_watts.or(activity.average_watts) {
+                    Some(watts) if watts > 0.0 => Some(watts / heartrate),
+                    _ => Some(speed? * 60.0 / heartrate),
+                }
+            }
+            EfficiencyMetric::AerobicDecoupling => aerobic_decoupling(streams?),
+        }
+    }
+
+    /// Digits after the decimal point that keep differences visible
+    pub fn precision(&self) -> usize {
+        match self {
+            EfficiencyMetric::TimePerBeat => 0,
+            EfficiencyMetric::AerobicDecoupling => 1,
+            _ => 2,
+        }
+    }
+}
+
+// Speeds below this count as standing still
+const MOVING_SPEED: f64 = 0.5;
+// Coefficient of variation of speed up to which an effort counts as steady
+const STEADY_VARIATION: f64 = 0.2;
+
+/// Whether the moving speed stayed even enough for decoupling to mean
+/// anything; intervals and stop-and-go rides drift for other reasons.
+pub fn is_steady(streams: &StreamSet) -> bool {
+    let Some(speed) = &streams.velocity_smooth else {
+        return false;
+    };
+    let moving: Vec<f64> = speed
+        .data
+        .iter()
+        .copied()
+        .filter(|&s| s >= MOVING_SPEED)
+        .collect();
+    if moving.len() < 4 {
+        return false;
+    }
+    let mean = moving.iter().sum::<f64>() / moving.len() as f64;
+    let variance = moving.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / moving.len() as f64;
+    variance.sqrt() / mean <= STEADY_VARIATION
+}
+
+/// Rough reading of a decoupling percentage.
+pub fn decoupling_label(percent: f64) -> &'static str {
+    if percent < 5.0 {
+        "well coupled, good aerobic base"
+    } else if percent < 10.0 {
+        "some drift"
+    } else {
+        "heavy drift, beyond aerobic endurance"
+    }
+}
+
+/// How much speed per heartbeat drops from the first to the second half of
+/// the moving time, in percent. Negative when the second half was better.
+pub fn aerobic_decoupling(streams: &StreamSet) -> Option<f64> {
+    let time = &streams.time.as_ref()?.data;
+    let speed = &streams.velocity_smooth.as_ref()?.data;
+    let heartrate = &streams.heartrate.as_ref()?.data;
+    let len = time.len().min(speed.len()).min(heartrate.len());
+    if len < 4 {
+        return None;
+    }
+    let midpoint = time[0] + (time[len - 1] - time[0]) / 2;
+    let split = time[..len].partition_point(|&t| t < midpoint);
+
+    // Mean speed over mean heart rate of the samples with a heart rate
+    let ratio = |range: std::ops::Range<usize>| {
+        let (speed_sum, heartrate_sum) = range
+            .filter(|&i| heartrate[i] > 0.0)
+            .fold((0.0, 0.0), |(s, h), i| (s + speed[i], h + heartrate[i]));
+        (heartrate_sum > 0.0).then(|| speed_sum / heartrate_sum)
+    };
+    let first = ratio(0..split)?;
+    let second = ratio(split..len)?;
+    (first > 0.0).then(|| (first - second) / first * 100.0)
+}
+
+/// Lower bounds of the five heart rate zones as fractions of the maximum
+pub const HEART_RATE_ZONES: [f64; 5] = [0.0, 0.6, 0.7, 0.8, 0.9];
+// Longer gaps between two samples are pauses, not time spent in a zone
+const MAX_SAMPLE_GAP_SECONDS: u32 = 60;
+
+/// Time in each of the `HEART_RATE_ZONES` of `max_heartrate`, bucketed from
+/// the heart rate stream like Strava's zones endpoint does it.
+pub fn heart_rate_zones(streams: &StreamSet, max_heartrate: f64) -> Option<ActivityZone> {
+    let time = &streams.time.as_ref()?.data;
+    let heartrate = &streams.heartrate.as_ref()?.data;
+    let len = time.len().min(heartrate.len());
+    if len < 2 || max_heartrate <= 0.0 {
+        return None;
+    }
+
+    let bounds: Vec<f64> = HEART_RATE_ZONES
+        .iter()
+        .map(|share| (share * max_heartrate).round())
+        .collect();
+    let mut times = vec![0.0; bounds.len()];
+    for i in 1..len {
+        let gap = time[i].saturating_sub(time[i - 1]);
+        if heartrate[i] <= 0.0 || gap > MAX_SAMPLE_GAP_SECONDS {
+            continue;
+        }
+        let zone = bounds.iter().rposition(|&low| heartrate[i] >= low)?;
+        times[zone] += f64::from(gap);
+    }
+    if times.iter().all(|&t| t == 0.0) {
+        return None;
+    }
+
+    let distribution_buckets = bounds
+        .iter()
+        .zip(times)
+        .enumerate()
+        .map(|(i, (&min, time))| ZoneBucket {
+            min,
+            max: bounds.get(i + 1).map_or(-1.0, |&high| high),
+            time,
+        })
+        .collect();
+    Some(ActivityZone {
+        zone_type: "heartrate".to_string(),
+        score: None,
+        sensor_based: true,
+        custom_zones: false,
+        distribution_buckets,
+    })
+}
+
+// Fewer activities don't pin down both speed and climbing rate
+const MIN_ESTIMATE_ACTIVITIES: usize = 3;
+// Climbing rates outside this range (meters per hour) mean the fit found
+// noise rather than hills, so the plain average speed is used instead
+const CLIMB_RATE_RANGE: std::ops::RangeInclusive<f64> = 100.0..=3000.0;
+
+/// How long a route should take at the athlete's recent form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteEstimate {
+    /// Seconds
+    pub moving_time: f64,
+    /// Meters per second on the flat, or the average speed without a climb rate
+    pub flat_speed: f64,
+    /// Meters climbed per hour on top of the flat speed
+    pub climb_rate: Option<f64>,
+    /// How many activities the estimate comes from
+    pub activities: usize,
+}
+
+/// Estimates the moving time for `distance` meters with `elevation_gain`
+/// meters of climbing from activities of `sport` (e.g. `Run` also takes
+/// trail and virtual runs) started at or after `since`. Moving time is
+/// fitted as distance / flat speed + climbing / climb rate by least squares;
+/// when that doesn't give plausible rates, the average speed alone is used.
+pub fn estimate_route_time<'a>(
+    activities: impl IntoIterator<Item = &'a Activity>,
+    sport: &str,
+    since: DateTime<Utc>,
+    distance: f64,
+    elevation_gain: f64,
+) -> Option<RouteEstimate> {
+    let samples: Vec<(f64, f64, f64)> = activities
+        .into_iter()
+        .filter(|a| a.sport_type.contains(sport) && a.start_date >= since)
+        .filter(|a| a.distance > 0.0 && a.moving_time > 0)
+        .map(|a| {
+            (
+                a.distance,
+                a.total_elevation_gain.max(0.0),
+                a.moving_time as f64,
+            )
+        })
+        .collect();
+    if samples.len() < MIN_ESTIMATE_ACTIVITIES {
+        return None;
+    }
+
+    // Seconds per meter of distance and per meter climbed
+    let (mut dd, mut dg, mut gg, mut dt, mut gt) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for &(d, g, t) in &samples {
+        dd += d * d;
+        dg += d * g;
+        gg += g * g;
+        dt += d * t;
+        gt += g * t;
+    }
+    let det = dd * gg - dg * dg;
+    let fit = (det.abs() > f64::EPSILON * dd * gg)
+        .then(|| ((dt * gg - gt * dg) / det, (gt * dd - dt * dg) / det))
+        .filter(|&(per_meter, per_climb)| {
+            per_meter > 0.0 && per_climb > 0.0 && CLIMB_RATE_RANGE.contains(&(3600.0 / per_climb))
+        });
+
+    let (per_meter, per_climb) = fit.unwrap_or_else(|| {
+        let (meters, seconds) = samples
+            .iter()
+            .fold((0.0, 0.0), |(m, s), &(d, _, t)| (m + d, s + t));
+        (seconds / meters, 0.0)
+    });
+    Some(RouteEstimate {
+        moving_time: per_meter * distance + per_climb * elevation_gain,
+        flat_speed: 1.0 / per_meter,
+        climb_rate: fit.map(|_| 3600.0 / per_climb),
+        activities: samples.len(),
+    })
+}
+
+/// Strava's current sport types, as in `sport_type`.
+#[rustfmt::skip]
+pub const SPORT_TYPES: [&str; 50] = [
+    "AlpineSki", "BackcountrySki", "Badminton", "Canoeing", "Crossfit", "EBikeRide",
+    "Elliptical", "EMountainBikeRide", "Golf", "GravelRide", "Handcycle",
+    "HighIntensityIntervalTraining", "Hike", "IceSkate", "InlineSkate", "Kayaking", "Kitesurf",
+    "MountainBikeRide", "NordicSki", "Pickleball", "Pilates", "Racquetball", "Ride",
+    "RockClimbing", "RollerSki", "Rowing", "Run", "Sail", "Skateboard", "Snowboard", "Snowshoe",
+    "Soccer", "Squash", "StairStepper", "StandUpPaddling", "Surfing", "Swim", "TableTennis",
+    "Tennis", "TrailRun", "Velomobile", "VirtualRide", "VirtualRow", "VirtualRun", "Walk",
+    "WeightTraining", "Wheelchair", "Windsurf", "Workout", "Yoga",
+];
+
+/// Related sport types that share a color and are treated alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SportGroup {
+    Run,
+    VirtualRun,
+    Ride,
+    VirtualRide,
+    EBike,
+    /// Hiking and walking
+    Foot,
+    /// Swimming, paddling, rowing and sailing
+    Water,
+    Winter,
+    Other,
+}
+
+impl SportGroup {
+    pub fn of(sport_type: &str) -> Self {
+        match sport_type {
+            "Run" | "TrailRun" => SportGroup::Run,
+            "VirtualRun" => SportGroup::VirtualRun,
+            "Ride" | "MountainBikeRide" | "GravelRide" | "Velomobile" | "Handcycle" => {
+                SportGroup::Ride
+            }
+            "VirtualRide" => SportGroup::VirtualRide,
+            "EBikeRide" | "EMountainBikeRide" => SportGroup::EBike,
+            "Hike" | "Walk" => SportGroup::Foot,
+            "Swim" | "Canoeing" | "Kayaking" | "Kitesurf" | "Rowing" | "VirtualRow" | "Sail"
+            | "StandUpPaddling" | "Surfing" | "Windsurf" => SportGroup::Water,
+            "AlpineSki" | "BackcountrySki" | "NordicSki" | "RollerSki" | "Snowboard"
+            | "Snowshoe" | "IceSkate" => SportGroup::Winter,
+            _ => SportGroup::Other,
+        }
+    }
+}
+
+/// Sports where pace per distance reads better than speed, and where the
+/// grade adjusted pace applies.
+pub fn is_on_foot(sport_type: &str) -> bool {
+    matches!(
+        SportGroup::of(sport_type),
+        SportGroup::Run | SportGroup::VirtualRun | SportGroup::Foot
+    )
+}
+
+/// Virtual rides and runs, and anything else recorded on a trainer or
+/// treadmill.
+pub fn is_indoor(activity: &Activity) -> bool {
+    activity.trainer == Some(true) || activity.sport_type.starts_with("Virtual")
+}
+
+// Grade is measured over at least this distance, so altitude noise between
+// two samples doesn't read as a wall
+const GRADE_WINDOW_METERS: f64 = 20.0;
+// Beyond this the energy cost model has no data
+const MAX_GRADE: f64 = 0.45;
+
+/// Energy cost of running at a grade relative to the flat, after Minetti et
+/// al. (2002). Gentle descents are cheaper, steep ones cost again.
+pub fn grade_cost(grade: f64) -> f64 {
+    let i = grade.clamp(-MAX_GRADE, MAX_GRADE);
+    let cost =
+        155.4 * i.powi(5) - 30.4 * i.powi(4) - 43.3 * i.powi(3) + 46.3 * i.powi(2) + 19.5 * i + 3.6;
+    cost / 3.6
+}
+
+/// Flat-equivalent speed in m/s over the part of the activity whose
+/// distance falls in `range`, from the time, distance and altitude streams.
+/// Stopped time doesn't count when the speed stream is there.
+pub fn grade_adjusted_speed(streams: &StreamSet, range: std::ops::Range<f64>) -> Option<f64> {
+    let time = &streams.time.as_ref()?.data;
+    let distance = &streams.distance.as_ref()?.data;
+    let altitude = &streams.altitude.as_ref()?.data;
+    let speed = streams.velocity_smooth.as_ref().map(|s| &s.data);
+    let len = time.len().min(distance.len()).min(altitude.len());
+    let moving = |i: usize| {
+        speed
+            .and_then(|s| s.get(i))
+            .is_none_or(|&v| v >= MOVING_SPEED)
+    };
+
+    let (mut adjusted, mut seconds) = (0.0, 0.0);
+    let mut start = 0;
+    while start + 1 < len {
+        let mut end = start + 1;
+        while end + 1 < len && distance[end] - distance[start] < GRADE_WINDOW_METERS {
+            end += 1;
+        }
+        let covered = distance[end] - distance[start];
+        let midpoint = (distance[start] + distance[end]) / 2.0;
+        if covered > 0.0 && range.contains(&midpoint) {
+            let elapsed: u32 = (start..end)
+                .filter(|&i| moving(i + 1))
+                .map(|i| time[i + 1].saturating_sub(time[i]))
+                .sum();
+            let grade = (altitude[end] - altitude[start]) / covered;
+            adjusted += covered * grade_cost(grade);
+            seconds += elapsed as f64;
+        }
+        start = end;
+    }
+    (seconds > 0.0).then(|| adjusted / seconds)
+}
+
+/// (current, longest) runs of consecutive active days. A streak still counts
+/// as current if today has no activity yet but yesterday did.
+pub fn streaks(days: &BTreeSet<NaiveDate>, today: NaiveDate) -> (u32, u32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in days {
+        run = match previous {
+            Some(p) if p.succ_opt() == Some(day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let mut current = 0;
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today.pred_opt().unwrap_or(today)
+    };
+    while days.contains(&day) {
+        current += 1;
+        match day.pred_opt() {
+            Some(d) => day = d,
+            None => break,
+        }
+    }
+    (current, longest)
+}
@@ -1,184 +1,754 @@
-use crate::api::types::{Activity, Athlete, AthleteStats, DetailedActivity, TokenResponse};
+use crate::api::de;
+use crate::api::oauth::TOKEN_URL;
+use crate::api::types::{
+    Activity, ActivityZone, Athlete, AthleteStats, ClubActivity, Comment, DateRange,
+    DetailedActivity, DetailedAthlete, DetailedSegment, FeedActivity, Gear, Page, Photo, Route,
+    StreamKey, StreamSet, SummaryAthlete, SummaryClub, SummarySegment, TokenResponse,
+    UpdatableActivity, Upload, UploadDataType,
+};
+use crate::api::StravaApi;
+use crate::config::{Config, RetryConfig};
+use crate::error::{self, Error, RateLimit};
 use anyhow::{anyhow, Result};
+use chrono::Utc;
 use directories::ProjectDirs;
-use parking_lot::Mutex;
-use reqwest::blocking::Client;
+use rand::Rng;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+// Refresh a little before Strava's expiry so in-flight requests don't race it
+const TOKEN_EXPIRY_MARGIN_SECS: i64 = 60;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// Strava usually processes an upload within a few seconds
+const UPLOAD_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const UPLOAD_POLL_ATTEMPTS: u32 = 60;
+// Large enough for the detail view, small enough to download quickly
+const PHOTO_SIZE: &str = "600";
+
+pub const API_URL: &str = "https://www.strava.com/api/v3";
+
+// OAuth scopes, named in errors when Strava refuses a request without saying why
+const SCOPE_READ: &str = "read";
+const SCOPE_ACTIVITY_READ: &str = "activity:read_all";
+const SCOPE_ACTIVITY_WRITE: &str = "activity:write";
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
-struct Config {
-    client_id: String,
-    client_secret: String,
-    refresh_token: String,
+struct CachedToken {
+    access_token: String,
+    expires_at: i64,
 }
 
-pub struct StravaClient {
-    client: Client,
-    config: Config,
-    access_token: Mutex<Option<String>>,
-    config_path: PathBuf,
+impl CachedToken {
+    fn is_valid(&self) -> bool {
+        self.expires_at - TOKEN_EXPIRY_MARGIN_SECS > chrono::Utc::now().timestamp()
+    }
+}
+
+/// Response bodies by URL with the ETag Strava sent for them, persisted as
+/// `http_cache.json`. Requests for a cached URL send `If-None-Match`, and a
+/// `304 Not Modified` answer is served from here.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EtagCache {
+    entries: HashMap<String, CachedBody>,
 }
 
-impl Clone for StravaClient {
-    fn clone(&self) -> Self {
-        Self {
-            client: Client::new(),
-            config: self.config.clone(),
-            access_token: Mutex::new(None),
-            config_path: self.config_path.clone(),
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedBody {
+    etag: String,
+    body: String,
+}
+
+impl EtagCache {
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
         }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn etag(&self, url: &str) -> Option<&str> {
+        self.entries.get(url).map(|entry| entry.etag.as_str())
     }
+
+    pub fn body(&self, url: &str) -> Option<&str> {
+        self.entries.get(url).map(|entry| entry.body.as_str())
+    }
+
+    pub fn insert(&mut self, url: &str, etag: String, body: String) {
+        self.entries
+            .insert(url.to_string(), CachedBody { etag, body });
+    }
+}
+
+#[derive(Debug)]
+struct TokenState {
+    refresh_token: String,
+    cached: Option<CachedToken>,
+}
+
+/// Async Strava API client. Clones share the connection pool and the cached
+/// access token, so a client can be handed to background tasks.
+#[derive(Clone)]
+pub struct StravaClient {
+    client: Client,
+    client_id: String,
+    client_secret: Option<String>,
+    token: Arc<Mutex<TokenState>>,
+    config_path: PathBuf,
+    token_path: PathBuf,
+    retry: RetryConfig,
+    lenient: bool,
+    etags: Arc<Mutex<EtagCache>>,
+    etag_path: PathBuf,
+    api_url: String,
+    token_url: String,
+    rate_limit: Arc<std::sync::Mutex<Option<RateLimit>>>,
 }
 
 impl StravaClient {
     pub fn new() -> Result<Self> {
-        let config_path = Self::get_config_path()?;
+        let config_path = Config::path()?;
 
         if !config_path.exists() {
             return Err(anyhow!("No config file found"));
         }
 
-        let config_content = fs::read_to_string(&config_path)?;
-        let config: Config = toml::from_str(&config_content)
-            .map_err(|e| anyhow!("Failed to parse config: {}", e))?;
-
-        Ok(Self {
-            client: Client::new(),
-            config,
-            access_token: Mutex::new(None),
-            config_path,
-        })
+        Self::from_config(&Config::load_at(&config_path)?, config_path)
     }
 
     pub fn from_credentials(
         client_id: String,
-        client_secret: String,
+        client_secret: Option<String>,
         refresh_token: String,
     ) -> Result<Self> {
-        let config_path = Self::get_config_path()?;
+        let config_path = Config::path()?;
 
-        let config = Config {
-            client_id: client_id.clone(),
-            client_secret: client_secret.clone(),
-            refresh_token: refresh_token.clone(),
-        };
+        let mut config = Config::load_at(&config_path)?;
+        config.client_id = Some(client_id);
+        config.client_secret = client_secret;
+        config.refresh_token = Some(refresh_token);
+        config.save_at(&config_path)?;
 
-        let config_content = toml::to_string(&config)?;
-        if let Some(parent) = config_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        fs::write(&config_path, config_content)?;
+        Self::from_config(&config, config_path)
+    }
+
+    pub fn from_config(config: &Config, config_path: PathBuf) -> Result<Self> {
+        Self::from_config_in(config, config_path, &Self::get_data_dir()?)
+    }
+
+    /// Like `from_config`, but keeps the access token and HTTP cache in
+    /// `data_dir` instead of the user's data directory.
+    pub fn from_config_in(config: &Config, config_path: PathBuf, data_dir: &Path) -> Result<Self> {
+        let client_id = config
+            .client_id
+            .clone()
+            .ok_or_else(|| anyhow!("Config is missing client_id"))?;
+        let refresh_token = config
+            .refresh_token
+            .clone()
+            .ok_or_else(|| anyhow!("Config is missing refresh_token"))?;
+        let token_path = data_dir.join("token.toml");
+        let etag_path = data_dir.join("http_cache.json");
+
+        // A missing or unreadable token file just means we refresh on first use
+        let cached = fs::read_to_string(&token_path)
+            .ok()
+            .and_then(|content| toml::from_str::<CachedToken>(&content).ok());
 
         Ok(Self {
-            client: Client::new(),
-            config,
-            access_token: Mutex::new(None),
+            client: Client::builder().timeout(REQUEST_TIMEOUT).build()?,
+            client_id,
+            client_secret: config.client_secret.clone(),
+            token: Arc::new(Mutex::new(TokenState {
+                refresh_token,
+                cached,
+            })),
             config_path,
+            token_path,
+            retry: config.retry.clone(),
+            lenient: !config.strict_parsing,
+            // Like the token, a missing or broken cache only costs a full response
+            etags: Arc::new(Mutex::new(EtagCache::load_from(&etag_path))),
+            etag_path,
+            api_url: config
+                .api_url
+                .as_deref()
+                .unwrap_or(API_URL)
+                .trim_end_matches('/')
+                .to_string(),
+            token_url: config
+                .token_url
+                .clone()
+                .unwrap_or_else(|| TOKEN_URL.to_string()),
+            rate_limit: Arc::default(),
         })
     }
 
-    fn get_config_path() -> Result<PathBuf> {
+    /// Sends API requests to `api_url` (e.g. a proxy or a mock server)
+    /// instead of `https://www.strava.com/api/v3`.
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_token_url(mut self, token_url: impl Into<String>) -> Self {
+        self.token_url = token_url.into();
+        self
+    }
+
+    fn get_data_dir() -> Result<PathBuf> {
         let proj_dirs = ProjectDirs::from("com", "strava-tui", "strava-tui")
-            .ok_or_else(|| anyhow!("Could not determine config directory"))?;
-        Ok(proj_dirs.config_dir().join("config.toml"))
+            .ok_or_else(|| anyhow!("Could not determine data directory"))?;
+        Ok(proj_dirs.data_dir().to_path_buf())
     }
 
-    fn get_access_token(&self) -> Result<String> {
-        let mut token_guard = self.access_token.lock();
+    async fn get_access_token(&self) -> error::Result<String> {
+        let mut state = self.token.lock().await;
 
-        if let Some(ref token) = *token_guard {
-            return Ok(token.clone());
+        if let Some(token) = state.cached.as_ref().filter(|t| t.is_valid()) {
+            return Ok(token.access_token.clone());
+        }
+
+        let mut params = vec![
+            ("client_id", self.client_id.as_str()),
+            ("refresh_token", state.refresh_token.as_str()),
+            ("grant_type", "refresh_token"),
+        ];
+        if let Some(secret) = self.client_secret.as_deref() {
+            params.push(("client_secret", secret));
         }
 
         let response = self
-            .client
-            .post("https://www.strava.com/oauth/token")
-            .form(&[
-                ("client_id", &self.config.client_id),
-                ("client_secret", &self.config.client_secret),
-                ("refresh_token", &self.config.refresh_token),
-                ("grant_type", &"refresh_token".to_string()),
-            ])
-            .send()?
-            .json::<TokenResponse>()?;
+            .send_with_retry(|| self.client.post(&self.token_url).form(&params))
+            .await?;
+        // A revoked refresh token comes back as 400 or 401
+        let response = match response.status() {
+            StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED => {
+                return Err(Error::Auth(response.text().await.unwrap_or_default()));
+            }
+            _ => Self::check(response, SCOPE_READ).await?,
+        }
+        .json::<TokenResponse>()
+        .await?;
+
+        // Strava may rotate the refresh token; the old one stops working
+        if response.refresh_token != state.refresh_token {
+            state.refresh_token = response.refresh_token.clone();
+            self.save_refresh_token(&response.refresh_token)?;
+        }
+
+        let token = CachedToken {
+            access_token: response.access_token.clone(),
+            expires_at: response.expires_at,
+        };
+        self.save_cached_token(&token)?;
+        state.cached = Some(token);
 
-        *token_guard = Some(response.access_token.clone());
         Ok(response.access_token)
     }
 
-    pub fn get_athlete(&self) -> Result<Athlete> {
-        let token = self.get_access_token()?;
-        let response = self
-            .client
-            .get("https://www.strava.com/api/v3/athlete")
-            .header("Authorization", format!("Bearer {}", token))
-            .send()?
-            .json::<Athlete>()?;
-        Ok(response)
+    async fn invalidate_access_token(&self) {
+        self.token.lock().await.cached = None;
+    }
+
+    fn save_cached_token(&self, token: &CachedToken) -> Result<()> {
+        if let Some(parent) = self.token_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.token_path, toml::to_string(token)?)?;
+        Ok(())
+    }
+
+    // Re-reads the file so settings edited while the app runs aren't overwritten
+    fn save_refresh_token(&self, refresh_token: &str) -> Result<()> {
+        let mut config = Config::load_at(&self.config_path)?;
+        config.refresh_token = Some(refresh_token.to_string());
+        config.save_at(&self.config_path)
     }
 
-    pub fn get_athlete_stats(&self, athlete_id: u64) -> Result<AthleteStats> {
-        let token = self.get_access_token()?;
+    // Retries rate limits and failed connections with jittered backoff. Timeouts
+    // and server errors are retried only for GET: a POST, PUT or DELETE may
+    // have gone through, and sending it again could upload or edit twice.
+    async fn send_with_retry<F>(&self, build: F) -> error::Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            let (client, request) = build().build_split();
+            let request = request?;
+            let idempotent = request.method() == Method::GET;
+            let result = client.execute(request).await;
+            let retryable = match &result {
+                Ok(response) => {
+                    let status = response.status();
+                    status == StatusCode::TOO_MANY_REQUESTS
+                        || (idempotent && status.is_server_error())
+                }
+                Err(e) => e.is_connect() || (idempotent && e.is_timeout()),
+            };
+            if !retryable || attempt >= self.retry.max_retries {
+                return Ok(result?);
+            }
+
+            let delay = self.retry.delay(attempt);
+            let jittered = rand::thread_rng().gen_range(delay / 2..=delay);
+            tracing::warn!(attempt, ?jittered, "transient API failure, retrying");
+            tokio::time::sleep(jittered).await;
+            attempt += 1;
+        }
+    }
+
+    // Sends an authorized request, retrying once with a fresh token on 401
+    async fn send_authorized<F>(&self, build: F) -> error::Result<Response>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        let token = self.get_access_token().await?;
         let response = self
-            .client
-            .get(format!(
-                "https://www.strava.com/api/v3/athletes/{}/stats",
-                athlete_id
-            ))
-            .header("Authorization", format!("Bearer {}", token))
-            .send()?
-            .json::<AthleteStats>()?;
+            .send_with_retry(|| build(&self.client).bearer_auth(&token))
+            .await?;
+
+        if response.status() != StatusCode::UNAUTHORIZED {
+            self.track_rate_limit(&response);
+            return Ok(response);
+        }
+
+        self.invalidate_access_token().await;
+        let token = self.get_access_token().await?;
+        let response = self
+            .send_with_retry(|| build(&self.client).bearer_auth(&token))
+            .await?;
+        self.track_rate_limit(&response);
         Ok(response)
     }
 
-    pub fn get_activities(&self, page: u32, per_page: u32) -> Result<Vec<Activity>> {
-        let token = self.get_access_token()?;
+    fn track_rate_limit(&self, response: &Response) {
+        if let Some(limit) = RateLimit::from_headers(response.headers()) {
+            if let Ok(mut current) = self.rate_limit.lock() {
+                *current = Some(limit);
+            }
+        }
+    }
+
+    /// What the last API response said about the remaining rate limit.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit.lock().ok().and_then(|limit| *limit)
+    }
+
+    // Passes successful responses through and classifies the rest; `scope`
+    // is what the request needs
+    async fn check(response: Response, scope: &str) -> error::Result<Response> {
+        let status = response.status();
+        if status.is_success() || status == StatusCode::NOT_MODIFIED {
+            return Ok(response);
+        }
+        let headers = response.headers().clone();
+        let body = response.text().await.unwrap_or_default();
+        Err(Error::from_response(
+            status,
+            &headers,
+            &body,
+            scope,
+            Utc::now(),
+        ))
+    }
+
+    // GET for rarely changing resources, revalidated with the ETag of the
+    // last response instead of downloading the same body again
+    async fn get_conditional<T: DeserializeOwned>(&self, url: &str) -> error::Result<T> {
+        let etag = self.etags.lock().await.etag(url).map(str::to_string);
         let response = self
-            .client
-            .get("https://www.strava.com/api/v3/athlete/activities")
-            .header("Authorization", format!("Bearer {}", token))
-            .query(&[
-                ("page", page.to_string()),
-                ("per_page", per_page.to_string()),
-            ])
-            .send()?;
+            .send_authorized(|c| match &etag {
+                Some(etag) => c.get(url).header(IF_NONE_MATCH, etag),
+                None => c.get(url),
+            })
+            .await?;
+        let response = Self::check(response, SCOPE_READ).await?;
 
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(body) = self.etags.lock().await.body(url) {
+                return Ok(serde_json::from_str(body)?);
+            }
+        }
         let status = response.status();
-        let text = response.text()?;
-
-        if !status.is_success() {
-            if text.contains("activity:read_permission") || text.contains("missing") {
-                return Err(anyhow!(
-                    "API returned {}. This usually means your token lacks activity read permissions.\n\
-                    \n\
-                    To fix:\n\
-                    1. Go to https://www.strava.com/playground\n\
-                    2. Click Authorize and ensure you check 'activity:read' or 'activity:read_all' scope\n\
-                    3. Get a new refresh token and update your config",
-                    status
-                ));
+        let new_etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await?;
+        let value = serde_json::from_str(&body)?;
+
+        if let Some(new_etag) = new_etag.filter(|_| status == StatusCode::OK) {
+            let mut etags = self.etags.lock().await;
+            etags.insert(url, new_etag, body);
+            // Failing to persist only means a full response next session
+            let _ = etags.save_to(&self.etag_path);
+        }
+        Ok(value)
+    }
+
+    pub async fn get_athlete(&self) -> error::Result<Athlete> {
+        self.get_conditional(&format!("{}/athlete", self.api_url))
+            .await
+    }
+
+    /// The same endpoint as `get_athlete`, read with every profile field
+    pub async fn get_detailed_athlete(&self) -> error::Result<DetailedAthlete> {
+        self.get_conditional(&format!("{}/athlete", self.api_url))
+            .await
+    }
+
+    pub async fn get_athlete_stats(&self, athlete_id: u64) -> error::Result<AthleteStats> {
+        self.get_conditional(&format!("{}/athletes/{}/stats", self.api_url, athlete_id))
+            .await
+    }
+
+    pub async fn get_activities(
+        &self,
+        page: u32,
+        per_page: u32,
+        range: DateRange,
+    ) -> error::Result<Page<Activity>> {
+        let mut params = vec![
+            ("page", page.to_string()),
+            ("per_page", per_page.to_string()),
+        ];
+        if let Some(after) = range.after {
+            params.push(("after", after.to_string()));
+        }
+        if let Some(before) = range.before {
+            params.push(("before", before.to_string()));
+        }
+
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!("{}/athlete/activities", self.api_url))
+                    .query(&params)
+            })
+            .await?;
+        let text = Self::check(response, SCOPE_ACTIVITY_READ)
+            .await?
+            .text()
+            .await?;
+
+        Ok(de::page(&text, self.lenient)?)
+    }
+
+    pub async fn get_activity(&self, activity_id: u64) -> error::Result<DetailedActivity> {
+        let response = self
+            .send_authorized(|c| c.get(format!("{}/activities/{}", self.api_url, activity_id)))
+            .await?;
+        Ok(Self::check(response, SCOPE_ACTIVITY_READ)
+            .await?
+            .json::<DetailedActivity>()
+            .await?)
+    }
+
+    pub async fn update_activity(
+        &self,
+        activity_id: u64,
+        update: &UpdatableActivity,
+    ) -> error::Result<DetailedActivity> {
+        let response = self
+            .send_authorized(|c| {
+                c.put(format!("{}/activities/{}", self.api_url, activity_id))
+                    .json(update)
+            })
+            .await?;
+
+        let response = Self::check(response, SCOPE_ACTIVITY_WRITE).await?;
+
+        Ok(response.json::<DetailedActivity>().await?)
+    }
+
+    /// Uploads a FIT, TCX or GPX file and waits until Strava has turned it
+    /// into an activity.
+    pub async fn upload_activity(
+        &self,
+        path: &Path,
+        data_type: UploadDataType,
+        name: Option<&str>,
+    ) -> error::Result<Upload> {
+        let mut upload = self.create_upload(path, data_type, name).await?;
+        for _ in 0..UPLOAD_POLL_ATTEMPTS {
+            if let Some(error) = upload.error {
+                return Err(anyhow!("Upload {} failed: {}", upload.id, error).into());
+            }
+            if upload.activity_id.is_some() {
+                return Ok(upload);
             }
-            return Err(anyhow!("API error {}: {}", status, text));
+            tokio::time::sleep(UPLOAD_POLL_INTERVAL).await;
+            upload = self.get_upload(upload.id).await?;
         }
+        Err(anyhow!(
+            "Upload {} is still being processed: {}",
+            upload.id,
+            upload.status
+        )
+        .into())
+    }
+
+    /// Sends a FIT, TCX or GPX file without waiting for Strava to process
+    /// it; `get_upload` tells when the activity is ready.
+    pub async fn create_upload(
+        &self,
+        path: &Path,
+        data_type: UploadDataType,
+        name: Option<&str>,
+    ) -> error::Result<Upload> {
+        let bytes = fs::read(path)?;
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("activity")
+            .to_string();
+
+        let response = self
+            .send_authorized(|c| {
+                let mut form = Form::new().text("data_type", data_type.as_str()).part(
+                    "file",
+                    Part::bytes(bytes.clone()).file_name(file_name.clone()),
+                );
+                if let Some(name) = name {
+                    form = form.text("name", name.to_string());
+                }
+                c.post(format!("{}/uploads", self.api_url)).multipart(form)
+            })
+            .await?;
 
-        let activities: Vec<Activity> = serde_json::from_str(&text)?;
-        Ok(activities)
+        let response = Self::check(response, SCOPE_ACTIVITY_WRITE).await?;
+        Ok(response.json::<Upload>().await?)
     }
 
-    pub fn get_activity(&self, activity_id: u64) -> Result<DetailedActivity> {
-        let token = self.get_access_token()?;
+    pub async fn get_upload(&self, upload_id: u64) -> error::Result<Upload> {
         let response = self
-            .client
-            .get(format!(
-                "https://www.strava.com/api/v3/activities/{}",
-                activity_id
-            ))
-            .header("Authorization", format!("Bearer {}", token))
-            .send()?
-            .json::<DetailedActivity>()?;
-        Ok(response)
+            .send_authorized(|c| c.get(format!("{}/uploads/{}", self.api_url, upload_id)))
+            .await?;
+        Ok(Self::check(response, SCOPE_ACTIVITY_WRITE)
+            .await?
+            .json::<Upload>()
+            .await?)
+    }
+
+    pub async fn delete_activity(&self, activity_id: u64) -> error::Result<()> {
+        let response = self
+            .send_authorized(|c| c.delete(format!("{}/activities/{}", self.api_url, activity_id)))
+            .await?;
+
+        Self::check(response, SCOPE_ACTIVITY_WRITE).await?;
+        Ok(())
+    }
+
+    pub async fn get_gear(&self, gear_id: &str) -> error::Result<Gear> {
+        self.get_conditional(&format!("{}/gear/{}", self.api_url, gear_id))
+            .await
+    }
+
+    pub async fn get_clubs(&self) -> error::Result<Vec<SummaryClub>> {
+        self.get_conditional(&format!("{}/athlete/clubs?per_page=100", self.api_url))
+            .await
+    }
+
+    pub async fn get_club_activities(
+        &self,
+        club_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> error::Result<Page<ClubActivity>> {
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!("{}/clubs/{}/activities", self.api_url, club_id))
+                    .query(&[("page", page), ("per_page", per_page)])
+            })
+            .await?;
+        let text = Self::check(response, SCOPE_READ).await?.text().await?;
+        Ok(de::page::<ClubActivity>(&text, self.lenient)?)
+    }
+
+    // Strava only serves this to some apps; others get a 404
+    pub async fn get_following_activities(
+        &self,
+        page: u32,
+        per_page: u32,
+    ) -> error::Result<Page<FeedActivity>> {
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!("{}/activities/following", self.api_url))
+                    .query(&[("page", page), ("per_page", per_page)])
+            })
+            .await?;
+        let text = Self::check(response, SCOPE_READ).await?.text().await?;
+        Ok(de::page::<FeedActivity>(&text, self.lenient)?)
+    }
+
+    pub async fn get_routes(
+        &self,
+        athlete_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> error::Result<Vec<Route>> {
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!("{}/athletes/{}/routes", self.api_url, athlete_id))
+                    .query(&[("page", page), ("per_page", per_page)])
+            })
+            .await?;
+        let text = Self::check(response, SCOPE_READ).await?.text().await?;
+        Ok(de::list::<Route>(&text, self.lenient)?)
+    }
+
+    /// The route as a GPX track, ready to copy onto a watch or bike computer
+    pub async fn export_route_gpx(&self, route_id: u64) -> error::Result<Vec<u8>> {
+        let response = self
+            .send_authorized(|c| c.get(format!("{}/routes/{}/export_gpx", self.api_url, route_id)))
+            .await?;
+        let response = Self::check(response, SCOPE_READ).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    // Distance, altitude and latlng, as far as the route has them
+    pub async fn get_route_streams(&self, route_id: u64) -> error::Result<StreamSet> {
+        let response = self
+            .send_authorized(|c| c.get(format!("{}/routes/{}/streams", self.api_url, route_id)))
+            .await?;
+        let text = Self::check(response, SCOPE_READ).await?.text().await?;
+        Ok(de::keyed_streams(&text)?)
+    }
+
+    pub async fn get_starred_segments(
+        &self,
+        page: u32,
+        per_page: u32,
+    ) -> error::Result<Vec<SummarySegment>> {
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!("{}/segments/starred", self.api_url))
+                    .query(&[("page", page), ("per_page", per_page)])
+            })
+            .await?;
+        let text = Self::check(response, SCOPE_READ).await?.text().await?;
+        Ok(de::list::<SummarySegment>(&text, self.lenient)?)
+    }
+
+    pub async fn get_segment(&self, segment_id: u64) -> error::Result<DetailedSegment> {
+        self.get_conditional(&format!("{}/segments/{}", self.api_url, segment_id))
+            .await
+    }
+
+    pub async fn get_activity_streams(
+        &self,
+        activity_id: u64,
+        keys: &[StreamKey],
+    ) -> error::Result<StreamSet> {
+        let keys = keys
+            .iter()
+            .map(|k| k.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!(
+                    "{}/activities/{}/streams",
+                    self.api_url, activity_id
+                ))
+                .query(&[("keys", keys.as_str()), ("key_by_type", "true")])
+            })
+            .await?;
+        Ok(Self::check(response, SCOPE_ACTIVITY_READ)
+            .await?
+            .json::<StreamSet>()
+            .await?)
+    }
+
+    pub async fn get_activity_kudoers(
+        &self,
+        activity_id: u64,
+    ) -> error::Result<Vec<SummaryAthlete>> {
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!("{}/activities/{}/kudos", self.api_url, activity_id))
+                    .query(&[("per_page", "200")])
+            })
+            .await?;
+        let text = Self::check(response, SCOPE_ACTIVITY_READ)
+            .await?
+            .text()
+            .await?;
+        Ok(de::list::<SummaryAthlete>(&text, self.lenient)?)
+    }
+
+    pub async fn get_activity_comments(&self, activity_id: u64) -> error::Result<Vec<Comment>> {
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!(
+                    "{}/activities/{}/comments",
+                    self.api_url, activity_id
+                ))
+                .query(&[("per_page", "200")])
+            })
+            .await?;
+        let text = Self::check(response, SCOPE_ACTIVITY_READ)
+            .await?
+            .text()
+            .await?;
+        Ok(de::list::<Comment>(&text, self.lenient)?)
+    }
+
+    pub async fn get_activity_photos(&self, activity_id: u64) -> error::Result<Vec<Photo>> {
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!(
+                    "{}/activities/{}/photos",
+                    self.api_url, activity_id
+                ))
+                .query(&[("size", PHOTO_SIZE), ("photo_sources", "true")])
+            })
+            .await?;
+        let text = Self::check(response, SCOPE_ACTIVITY_READ)
+            .await?
+            .text()
+            .await?;
+        Ok(de::list::<Photo>(&text, self.lenient)?)
+    }
+
+    /// Time in heart rate and power zones as Strava computed it, for when the
+    /// streams can't be bucketed locally (e.g. power, which isn't streamed).
+    pub async fn get_activity_zones(&self, activity_id: u64) -> error::Result<Vec<ActivityZone>> {
+        let response = self
+            .send_authorized(|c| {
+                c.get(format!("{}/activities/{}/zones", self.api_url, activity_id))
+            })
+            .await?;
+        let text = Self::check(response, SCOPE_ACTIVITY_READ)
+            .await?
+            .text()
+            .await?;
+        Ok(de::list::<ActivityZone>(&text, self.lenient)?)
+    }
+
+    /// Downloads a photo from the CDN URL returned by `get_activity_photos`.
+    pub async fn get_photo(&self, url: &str) -> error::Result<Vec<u8>> {
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
     }
 
     pub fn config_path(&self) -> &PathBuf {
@@ -186,6 +756,144 @@ impl StravaClient {
     }
 }
 
+impl StravaApi for StravaClient {
+    async fn get_athlete(&self) -> error::Result<Athlete> {
+        StravaClient::get_athlete(self).await
+    }
+
+    async fn get_detailed_athlete(&self) -> error::Result<DetailedAthlete> {
+        StravaClient::get_detailed_athlete(self).await
+    }
+
+    async fn get_athlete_stats(&self, athlete_id: u64) -> error::Result<AthleteStats> {
+        StravaClient::get_athlete_stats(self, athlete_id).await
+    }
+
+    async fn get_activities(
+        &self,
+        page: u32,
+        per_page: u32,
+        range: DateRange,
+    ) -> error::Result<Page<Activity>> {
+        StravaClient::get_activities(self, page, per_page, range).await
+    }
+
+    async fn get_activity(&self, activity_id: u64) -> error::Result<DetailedActivity> {
+        StravaClient::get_activity(self, activity_id).await
+    }
+
+    async fn update_activity(
+        &self,
+        activity_id: u64,
+        update: &UpdatableActivity,
+    ) -> error::Result<DetailedActivity> {
+        StravaClient::update_activity(self, activity_id, update).await
+    }
+
+    async fn delete_activity(&self, activity_id: u64) -> error::Result<()> {
+        StravaClient::delete_activity(self, activity_id).await
+    }
+
+    async fn create_upload(
+        &self,
+        path: &Path,
+        data_type: UploadDataType,
+        name: Option<&str>,
+    ) -> error::Result<Upload> {
+        StravaClient::create_upload(self, path, data_type, name).await
+    }
+
+    async fn get_upload(&self, upload_id: u64) -> error::Result<Upload> {
+        StravaClient::get_upload(self, upload_id).await
+    }
+
+    async fn get_gear(&self, gear_id: &str) -> error::Result<Gear> {
+        StravaClient::get_gear(self, gear_id).await
+    }
+
+    async fn get_clubs(&self) -> error::Result<Vec<SummaryClub>> {
+        StravaClient::get_clubs(self).await
+    }
+
+    async fn get_club_activities(
+        &self,
+        club_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> error::Result<Page<ClubActivity>> {
+        StravaClient::get_club_activities(self, club_id, page, per_page).await
+    }
+
+    async fn get_following_activities(
+        &self,
+        page: u32,
+        per_page: u32,
+    ) -> error::Result<Page<FeedActivity>> {
+        StravaClient::get_following_activities(self, page, per_page).await
+    }
+
+    async fn get_routes(
+        &self,
+        athlete_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> error::Result<Vec<Route>> {
+        StravaClient::get_routes(self, athlete_id, page, per_page).await
+    }
+
+    async fn export_route_gpx(&self, route_id: u64) -> error::Result<Vec<u8>> {
+        StravaClient::export_route_gpx(self, route_id).await
+    }
+
+    async fn get_route_streams(&self, route_id: u64) -> error::Result<StreamSet> {
+        StravaClient::get_route_streams(self, route_id).await
+    }
+
+    async fn get_starred_segments(
+        &self,
+        page: u32,
+        per_page: u32,
+    ) -> error::Result<Vec<SummarySegment>> {
+        StravaClient::get_starred_segments(self, page, per_page).await
+    }
+
+    async fn get_segment(&self, segment_id: u64) -> error::Result<DetailedSegment> {
+        StravaClient::get_segment(self, segment_id).await
+    }
+
+    async fn get_activity_streams(
+        &self,
+        activity_id: u64,
+        keys: &[StreamKey],
+    ) -> error::Result<StreamSet> {
+        StravaClient::get_activity_streams(self, activity_id, keys).await
+    }
+
+    async fn get_activity_kudoers(&self, activity_id: u64) -> error::Result<Vec<SummaryAthlete>> {
+        StravaClient::get_activity_kudoers(self, activity_id).await
+    }
+
+    async fn get_activity_comments(&self, activity_id: u64) -> error::Result<Vec<Comment>> {
+        StravaClient::get_activity_comments(self, activity_id).await
+    }
+
+    async fn get_activity_photos(&self, activity_id: u64) -> error::Result<Vec<Photo>> {
+        StravaClient::get_activity_photos(self, activity_id).await
+    }
+
+    async fn get_activity_zones(&self, activity_id: u64) -> error::Result<Vec<ActivityZone>> {
+        StravaClient::get_activity_zones(self, activity_id).await
+    }
+
+    async fn get_photo(&self, url: &str) -> error::Result<Vec<u8>> {
+        StravaClient::get_photo(self, url).await
+    }
+
+    fn rate_limit(&self) -> Option<RateLimit> {
+        StravaClient::rate_limit(self)
+    }
+}
+
 impl Default for StravaClient {
     fn default() -> Self {
         Self::new().expect("Failed to create default StravaClient")
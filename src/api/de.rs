@@ -0,0 +1,117 @@
+use crate::api::types::Page;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// For fields Strava sometimes sends as `null`; pair with `#[serde(default)]`
+/// so a missing field gets the default too.
+pub fn null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDate {
+    Text(String),
+    Epoch(i64),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawCount {
+    Number(u32),
+    Text(String),
+}
+
+/// Counts Strava sometimes sends as text, e.g. `"1,024"`; pair with
+/// `#[serde(default)]`. Text without digits reads as no count.
+pub fn count<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<RawCount>::deserialize(deserializer)? {
+        None => None,
+        Some(RawCount::Number(count)) => Some(count),
+        Some(RawCount::Text(text)) => text
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+            .parse()
+            .ok(),
+    })
+}
+
+/// Dates as RFC 3339, without an offset (taken as UTC) or as epoch seconds.
+pub fn datetime<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match RawDate::deserialize(deserializer)? {
+        RawDate::Text(text) => parse_datetime(&text)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid date '{}'", text))),
+        RawDate::Epoch(seconds) => DateTime::from_timestamp(seconds, 0)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp {}", seconds))),
+    }
+}
+
+pub fn parse_datetime(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .map(|date| date.and_utc())
+}
+
+/// Streams keyed by type. Route streams come as a list in which every stream
+/// names its `type`; activity streams fetched with `key_by_type` already are.
+pub fn keyed_streams<T: DeserializeOwned>(body: &str) -> serde_json::Result<T> {
+    match serde_json::from_str(body)? {
+        Value::Array(streams) => {
+            let keyed = streams
+                .into_iter()
+                .filter_map(|stream| {
+                    let key = stream.get("type")?.as_str()?.to_string();
+                    Some((key, stream))
+                })
+                .collect();
+            serde_json::from_value(Value::Object(keyed))
+        }
+        keyed => serde_json::from_value(keyed),
+    }
+}
+
+/// Parses a JSON array entry by entry. In lenient mode entries that don't
+/// fit `T` are logged and skipped, so one odd activity doesn't cost the
+/// whole page; otherwise the first one fails the parse.
+pub fn list<T: DeserializeOwned>(body: &str, lenient: bool) -> serde_json::Result<Vec<T>> {
+    page(body, lenient).map(|page| page.items)
+}
+
+/// Like [`list`], keeping the number of entries in the body so callers
+/// paging through a list can tell a short page from skipped entries.
+pub fn page<T: DeserializeOwned>(body: &str, lenient: bool) -> serde_json::Result<Page<T>> {
+    if !lenient {
+        return serde_json::from_str::<Vec<T>>(body).map(Page::from);
+    }
+    let entries: Vec<Value> = serde_json::from_str(body)?;
+    let received = entries.len();
+    let items = entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let id = entry.get("id").cloned();
+            serde_json::from_value(entry)
+                .inspect_err(|e| tracing::warn!(index, ?id, "skipping malformed entry: {}", e))
+                .ok()
+        })
+        .collect();
+    Ok(Page { items, received })
+}
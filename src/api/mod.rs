@@ -1,2 +1,127 @@
 pub mod client;
+pub mod de;
+pub mod oauth;
+pub mod polyline;
 pub mod types;
+
+use crate::api::types::{
+    Activity, ActivityZone, Athlete, AthleteStats, ClubActivity, Comment, DateRange,
+    DetailedActivity, DetailedAthlete, DetailedSegment, FeedActivity, Gear, Page, Photo, Route,
+    StreamKey, StreamSet, SummaryAthlete, SummaryClub, SummarySegment, UpdatableActivity, Upload,
+    UploadDataType,
+};
+use crate::error::{RateLimit, Result};
+use std::future::Future;
+use std::path::Path;
+
+/// The Strava calls the interactive UI makes, so the worker can run against
+/// something other than the real API in tests. `client::StravaClient` is the
+/// implementation that talks to Strava.
+pub trait StravaApi: Clone + Send + Sync + 'static {
+    fn get_athlete(&self) -> impl Future<Output = Result<Athlete>> + Send;
+
+    fn get_detailed_athlete(&self) -> impl Future<Output = Result<DetailedAthlete>> + Send;
+
+    fn get_athlete_stats(
+        &self,
+        athlete_id: u64,
+    ) -> impl Future<Output = Result<AthleteStats>> + Send;
+
+    fn get_activities(
+        &self,
+        page: u32,
+        per_page: u32,
+        range: DateRange,
+    ) -> impl Future<Output = Result<Page<Activity>>> + Send;
+
+    fn get_activity(
+        &self,
+        activity_id: u64,
+    ) -> impl Future<Output = Result<DetailedActivity>> + Send;
+
+    fn update_activity(
+        &self,
+        activity_id: u64,
+        update: &UpdatableActivity,
+    ) -> impl Future<Output = Result<DetailedActivity>> + Send;
+
+    fn delete_activity(&self, activity_id: u64) -> impl Future<Output = Result<()>> + Send;
+
+    fn create_upload(
+        &self,
+        path: &Path,
+        data_type: UploadDataType,
+        name: Option<&str>,
+    ) -> impl Future<Output = Result<Upload>> + Send;
+
+    fn get_upload(&self, upload_id: u64) -> impl Future<Output = Result<Upload>> + Send;
+
+    fn get_gear(&self, gear_id: &str) -> impl Future<Output = Result<Gear>> + Send;
+
+    fn get_clubs(&self) -> impl Future<Output = Result<Vec<SummaryClub>>> + Send;
+
+    fn get_club_activities(
+        &self,
+        club_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> impl Future<Output = Result<Page<ClubActivity>>> + Send;
+
+    fn get_following_activities(
+        &self,
+        page: u32,
+        per_page: u32,
+    ) -> impl Future<Output = Result<Page<FeedActivity>>> + Send;
+
+    fn get_routes(
+        &self,
+        athlete_id: u64,
+        page: u32,
+        per_page: u32,
+    ) -> impl Future<Output = Result<Vec<Route>>> + Send;
+
+    fn export_route_gpx(&self, route_id: u64) -> impl Future<Output = Result<Vec<u8>>> + Send;
+
+    fn get_route_streams(&self, route_id: u64) -> impl Future<Output = Result<StreamSet>> + Send;
+
+    fn get_starred_segments(
+        &self,
+        page: u32,
+        per_page: u32,
+    ) -> impl Future<Output = Result<Vec<SummarySegment>>> + Send;
+
+    fn get_segment(&self, segment_id: u64) -> impl Future<Output = Result<DetailedSegment>> + Send;
+
+    fn get_activity_streams(
+        &self,
+        activity_id: u64,
+        keys: &[StreamKey],
+    ) -> impl Future<Output = Result<StreamSet>> + Send;
+
+    fn get_activity_kudoers(
+        &self,
+        activity_id: u64,
+    ) -> impl Future<Output = Result<Vec<SummaryAthlete>>> + Send;
+
+    fn get_activity_comments(
+        &self,
+        activity_id: u64,
+    ) -> impl Future<Output = Result<Vec<Comment>>> + Send;
+
+    fn get_activity_photos(
+        &self,
+        activity_id: u64,
+    ) -> impl Future<Output = Result<Vec<Photo>>> + Send;
+
+    fn get_activity_zones(
+        &self,
+        activity_id: u64,
+    ) -> impl Future<Output = Result<Vec<ActivityZone>>> + Send;
+
+    fn get_photo(&self, url: &str) -> impl Future<Output = Result<Vec<u8>>> + Send;
+
+    /// The remaining rate limit as of the last response, if known.
+    fn rate_limit(&self) -> Option<RateLimit> {
+        None
+    }
+}
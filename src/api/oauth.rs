@@ -0,0 +1,152 @@
+use crate::api::types::TokenResponse;
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::process::{Command, Stdio};
+
+pub const AUTHORIZE_URL: &str = "https://www.strava.com/oauth/authorize";
+pub const TOKEN_URL: &str = "https://www.strava.com/oauth/token";
+pub const DEFAULT_REDIRECT_PORT: u16 = 42424;
+pub const SCOPE: &str = "read,activity:read_all,activity:write";
+
+/// PKCE (RFC 7636) verifier/challenge pair for the authorization code flow.
+#[derive(Debug, Clone)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn generate() -> Self {
+        Self::from_verifier(random_string(64))
+    }
+
+    pub fn from_verifier(verifier: impl Into<String>) -> Self {
+        let verifier = verifier.into();
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CallbackParams {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+pub fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+pub fn redirect_uri(port: u16) -> String {
+    format!("http://localhost:{}", port)
+}
+
+pub fn authorize_url(client_id: &str, redirect_uri: &str, pkce: &Pkce, state: &str) -> String {
+    format!(
+        "{}?client_id={}&response_type=code&redirect_uri={}&scope={}&state={}&code_challenge={}&code_challenge_method=S256",
+        AUTHORIZE_URL, client_id, redirect_uri, SCOPE, state, pkce.challenge
+    )
+}
+
+// Parses the query of the callback request line, e.g. "GET /?code=abc&state=xyz HTTP/1.1"
+pub fn parse_callback(request: &str) -> Option<CallbackParams> {
+    let query_start = request.find("GET /?")?;
+    let query_part = &request[query_start + 6..];
+    let query = &query_part[..query_part.find(" HTTP")?];
+
+    let mut params = CallbackParams::default();
+    for param in query.split('&') {
+        if let Some((key, value)) = param.split_once('=') {
+            match key {
+                "code" => params.code = Some(value.to_string()),
+                "state" => params.state = Some(value.to_string()),
+                "error" => params.error = Some(value.to_string()),
+                _ => {}
+            }
+        }
+    }
+    Some(params)
+}
+
+impl CallbackParams {
+    /// The authorization code, or why Strava didn't send one (e.g.
+    /// "access_denied" when the user clicked Deny). None for callbacks of
+    /// another attempt, whose state doesn't match, and for ones with neither.
+    pub fn outcome(&self, state: &str) -> Option<Result<String>> {
+        if self.state.as_deref() != Some(state) {
+            return None;
+        }
+        match (&self.error, &self.code) {
+            (Some(error), _) => Some(Err(anyhow!("Authorization failed: {}", error))),
+            (None, Some(code)) => Some(Ok(code.clone())),
+            (None, None) => None,
+        }
+    }
+}
+
+pub async fn exchange_code(
+    token_url: &str,
+    client_id: &str,
+    client_secret: Option<&str>,
+    code: &str,
+    pkce: &Pkce,
+) -> Result<TokenResponse> {
+    let mut params = vec![
+        ("client_id", client_id),
+        ("code", code),
+        ("code_verifier", pkce.verifier.as_str()),
+        ("grant_type", "authorization_code"),
+    ];
+    if let Some(secret) = client_secret {
+        params.push(("client_secret", secret));
+    }
+
+    let response = reqwest::Client::new()
+        .post(token_url)
+        .form(&params)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(anyhow!("Token exchange failed ({}): {}", status, text));
+    }
+
+    Ok(response.json::<TokenResponse>().await?)
+}
+
+// Returns false if no browser could be launched, so callers can fall back to printing the URL
+pub fn open_browser(url: &str) -> bool {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut c = Command::new("open");
+        c.arg(url);
+        c
+    } else if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", "", url]);
+        c
+    } else {
+        let mut c = Command::new("xdg-open");
+        c.arg(url);
+        c
+    };
+
+    command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .is_ok()
+}
@@ -0,0 +1,70 @@
+// Decoder for Google's encoded polyline format used by Strava's `map` fields.
+// See https://developers.google.com/maps/documentation/utilities/polylinealgorithm
+
+/// Decodes an encoded polyline into `(latitude, longitude)` pairs. Decoding
+/// stops at the first malformed coordinate, returning the points read so far.
+pub fn decode(encoded: &str) -> Vec<(f64, f64)> {
+    let mut points = Vec::new();
+    let mut bytes = encoded.bytes();
+    let (mut lat, mut lng) = (0i64, 0i64);
+
+    while let Some(dlat) = next_value(&mut bytes) {
+        let Some(dlng) = next_value(&mut bytes) else {
+            break;
+        };
+        lat += dlat;
+        lng += dlng;
+        points.push((lat as f64 / 1e5, lng as f64 / 1e5));
+    }
+
+    points
+}
+
+fn next_value(bytes: &mut impl Iterator<Item = u8>) -> Option<i64> {
+    let mut result = 0i64;
+    let mut shift = 0;
+
+    loop {
+        let byte = bytes.next()?.checked_sub(63)? as i64;
+        result |= (byte & 0x1f) << shift;
+        shift += 5;
+        if byte < 0x20 {
+            break;
+        }
+        if shift > 60 {
+            return None;
+        }
+    }
+
+    Some(if result & 1 != 0 {
+        !(result >> 1)
+    } else {
+        result >> 1
+    })
+}
+
+/// Encodes `(latitude, longitude)` pairs at the same 1e-5 precision Strava uses.
+pub fn encode(points: &[(f64, f64)]) -> String {
+    let mut encoded = String::new();
+    let (mut lat, mut lng) = (0i64, 0i64);
+    for &(point_lat, point_lng) in points {
+        let (next_lat, next_lng) = (
+            (point_lat * 1e5).round() as i64,
+            (point_lng * 1e5).round() as i64,
+        );
+        push_value(&mut encoded, next_lat - lat);
+        push_value(&mut encoded, next_lng - lng);
+        lat = next_lat;
+        lng = next_lng;
+    }
+    encoded
+}
+
+fn push_value(encoded: &mut String, value: i64) {
+    let mut value = if value < 0 { !(value << 1) } else { value << 1 };
+    while value >= 0x20 {
+        encoded.push((((value & 0x1f) | 0x20) as u8 + 63) as char);
+        value >>= 5;
+    }
+    encoded.push((value as u8 + 63) as char);
+}
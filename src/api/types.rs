@@ -1,16 +1,70 @@
+use crate::api::de;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Athlete {
     pub id: u64,
     pub username: Option<String>,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub firstname: String,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub lastname: String,
     pub city: Option<String>,
     pub country: Option<String>,
     pub profile: Option<String>,
     pub profile_medium: Option<String>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub bikes: Vec<Gear>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub shoes: Vec<Gear>,
+}
+
+// Everything /athlete returns, for the profile view. Weight is in kilograms,
+// follower and friend counts are what Strava shows on the athlete's page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailedAthlete {
+    #[serde(flatten)]
+    pub athlete: Athlete,
+    pub state: Option<String>,
+    pub sex: Option<String>,
+    pub premium: Option<bool>,
+    pub summit: Option<bool>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub follower_count: Option<u32>,
+    pub friend_count: Option<u32>,
+    // "feet" or "meters"
+    pub measurement_preference: Option<String>,
+    pub ftp: Option<u32>,
+    pub weight: Option<f64>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub clubs: Vec<SummaryClub>,
+}
+
+// Summary gear from the athlete endpoint, or detailed gear from /gear/{id}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gear {
+    pub id: String,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub name: String,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub primary: bool,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub distance: f64,
+    pub brand_name: Option<String>,
+    pub model_name: Option<String>,
+    pub description: Option<String>,
+    pub retired: Option<bool>,
+}
+
+impl Gear {
+    // Strava prefixes bike ids with "b" and shoe ids with "g"
+    pub fn is_bike(&self) -> bool {
+        self.id.starts_with('b')
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,22 +111,38 @@ pub struct ActivityStats {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Activity {
     pub id: u64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub name: String,
-    #[serde(rename = "type")]
+    // Missing or empty types show up as a generic workout
+    #[serde(rename = "type", default = "workout", deserialize_with = "sport")]
     pub activity_type: String,
+    #[serde(default = "workout", deserialize_with = "sport")]
     pub sport_type: String,
+    #[serde(deserialize_with = "de::datetime")]
     pub start_date: DateTime<Utc>,
+    #[serde(deserialize_with = "de::datetime")]
     pub start_date_local: DateTime<Utc>,
+    // e.g. "(GMT+01:00) Europe/Berlin"
+    #[serde(default, deserialize_with = "de::null_default")]
     pub timezone: String,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub distance: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub moving_time: u32,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub elapsed_time: u32,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub total_elevation_gain: f64,
     pub average_speed: Option<f64>,
     pub max_speed: Option<f64>,
     pub average_heartrate: Option<f64>,
     pub max_heartrate: Option<f64>,
+    pub average_watts: Option<f64>,
+    // Strava's normalized power estimate
+    pub weighted_average_watts: Option<f64>,
     pub calories: Option<f64>,
+    // Strava's relative effort
+    pub suffer_score: Option<f64>,
     pub description: Option<String>,
     pub kudos_count: Option<u32>,
     pub comment_count: Option<u32>,
@@ -80,8 +150,71 @@ pub struct Activity {
     pub pr_count: Option<u32>,
     pub private: Option<bool>,
     pub commute: Option<bool>,
+    pub trainer: Option<bool>,
     pub manual: Option<bool>,
     pub gear_id: Option<String>,
+    pub map: Option<PolylineMap>,
+}
+
+fn workout() -> String {
+    "Workout".to_string()
+}
+
+fn sport<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let sport: Option<String> = Deserialize::deserialize(deserializer)?;
+    Ok(sport
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(workout))
+}
+
+/// Strava's `after`/`before` activity filters, as epoch seconds. Strava
+/// returns oldest-first pages when `after` is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DateRange {
+    pub after: Option<i64>,
+    pub before: Option<i64>,
+}
+
+impl DateRange {
+    pub fn is_empty(&self) -> bool {
+        self.after.is_none() && self.before.is_none()
+    }
+}
+
+/// One page of a list. Lenient parsing skips malformed entries, so whether
+/// another page follows depends on how many entries Strava sent, not on how
+/// many are left.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub received: usize,
+}
+
+impl<T> Page<T> {
+    pub fn is_last(&self, per_page: u32) -> bool {
+        self.received < per_page as usize
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.received.saturating_sub(self.items.len())
+    }
+}
+
+impl<T> From<Vec<T>> for Page<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self {
+            received: items.len(),
+            items,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolylineMap {
+    pub id: Option<String>,
+    // Full-resolution polyline, only present on detailed activities
+    pub polyline: Option<String>,
+    pub summary_polyline: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,20 +226,117 @@ pub struct DetailedActivity {
     pub splits_standard: Option<Vec<Split>>,
     pub laps: Option<Vec<Lap>>,
     pub best_efforts: Option<Vec<BestEffort>>,
+    // Degrees Celsius, from devices with a thermometer
+    pub average_temp: Option<f64>,
+    pub device_name: Option<String>,
+    // For Strava's embeddable activity widget
+    pub embed_token: Option<String>,
+}
+
+// Body for PUT /activities/{id}; unset fields are left unchanged by Strava
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdatableActivity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commute: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trainer: Option<bool>,
+    // "none" removes the gear from the activity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gear_id: Option<String>,
+    // Mutes the activity so it doesn't show up in followers' feeds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hide_from_home: Option<bool>,
+}
+
+/// File formats accepted by POST /uploads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadDataType {
+    Fit,
+    FitGz,
+    Tcx,
+    TcxGz,
+    Gpx,
+    GpxGz,
+}
+
+impl UploadDataType {
+    pub const ALL: [UploadDataType; 6] = [
+        UploadDataType::Fit,
+        UploadDataType::FitGz,
+        UploadDataType::Tcx,
+        UploadDataType::TcxGz,
+        UploadDataType::Gpx,
+        UploadDataType::GpxGz,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UploadDataType::Fit => "fit",
+            UploadDataType::FitGz => "fit.gz",
+            UploadDataType::Tcx => "tcx",
+            UploadDataType::TcxGz => "tcx.gz",
+            UploadDataType::Gpx => "gpx",
+            UploadDataType::GpxGz => "gpx.gz",
+        }
+    }
+
+    /// Guesses the type from the file name, e.g. `ride.fit` or `run.gpx.gz`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|t| name.ends_with(&format!(".{}", t.as_str())))
+    }
+}
+
+impl FromStr for UploadDataType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|t| t.as_str()).collect();
+                format!("unknown data type '{}' (expected {})", s, names.join(", "))
+            })
+    }
+}
+
+// Status of an upload; activity_id is set once Strava has processed the file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Upload {
+    pub id: u64,
+    pub external_id: Option<String>,
+    pub error: Option<String>,
+    pub status: String,
+    pub activity_id: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SegmentEffort {
     pub id: u64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub name: String,
     pub activity: Reference,
     pub athlete: Reference,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub elapsed_time: u32,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub moving_time: u32,
+    #[serde(deserialize_with = "de::datetime")]
     pub start_date: DateTime<Utc>,
+    #[serde(deserialize_with = "de::datetime")]
     pub start_date_local: DateTime<Utc>,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub distance: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub average_speed: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub max_speed: f64,
     pub average_heartrate: Option<f64>,
     pub max_heartrate: Option<f64>,
@@ -116,52 +346,356 @@ pub struct SegmentEffort {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Split {
+    #[serde(default, deserialize_with = "de::null_default")]
     pub distance: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub elapsed_time: u32,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub elevation_difference: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub moving_time: u32,
     pub split: u32,
+    pub average_speed: Option<f64>,
+    pub average_heartrate: Option<f64>,
+    // Only on runs
+    pub average_grade_adjusted_speed: Option<f64>,
     pub pace_zone: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Lap {
     pub id: u64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub name: String,
     pub activity: Reference,
     pub athlete: Reference,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub elapsed_time: u32,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub moving_time: u32,
+    #[serde(deserialize_with = "de::datetime")]
     pub start_date: DateTime<Utc>,
+    #[serde(deserialize_with = "de::datetime")]
     pub start_date_local: DateTime<Utc>,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub distance: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub average_speed: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub max_speed: f64,
     pub average_heartrate: Option<f64>,
     pub max_heartrate: Option<f64>,
+    pub total_elevation_gain: Option<f64>,
+    pub average_watts: Option<f64>,
     pub lap_index: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BestEffort {
     pub id: u64,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub name: String,
     pub activity: Reference,
     pub athlete: Reference,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub elapsed_time: u32,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub moving_time: u32,
+    #[serde(deserialize_with = "de::datetime")]
     pub start_date: DateTime<Utc>,
+    #[serde(deserialize_with = "de::datetime")]
     pub start_date_local: DateTime<Utc>,
+    #[serde(default, deserialize_with = "de::null_default")]
     pub distance: f64,
     pub pr_rank: Option<u32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummarySegment {
+    pub id: u64,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub name: String,
+    pub activity_type: Option<String>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub distance: f64,
+    pub average_grade: Option<f64>,
+    pub maximum_grade: Option<f64>,
+    pub elevation_high: Option<f64>,
+    pub elevation_low: Option<f64>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    #[serde(default)]
+    pub starred: bool,
+    // Older responses use athlete_pr_effort, newer ones athlete_segment_stats
+    pub athlete_pr_effort: Option<SegmentStats>,
+    pub athlete_segment_stats: Option<SegmentStats>,
+}
+
+impl SummarySegment {
+    pub fn my_stats(&self) -> Option<&SegmentStats> {
+        self.athlete_segment_stats
+            .as_ref()
+            .or(self.athlete_pr_effort.as_ref())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentStats {
+    pub pr_elapsed_time: Option<u32>,
+    pub pr_date: Option<String>,
+    pub pr_activity_id: Option<u64>,
+    pub effort_count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailedSegment {
+    #[serde(flatten)]
+    pub segment: SummarySegment,
+    pub effort_count: Option<u32>,
+    pub athlete_count: Option<u32>,
+    pub star_count: Option<u32>,
+    pub total_elevation_gain: Option<f64>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub hazardous: bool,
+    pub local_legend: Option<LocalLegend>,
+}
+
+// Not part of Strava's published schema, but returned by the segment endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalLegend {
+    pub athlete_id: Option<u64>,
+    pub title: Option<String>,
+    pub effort_description: Option<String>,
+    // The legend's efforts in the last 90 days
+    #[serde(default, deserialize_with = "de::count")]
+    pub effort_count: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reference {
     pub id: u64,
     pub resource_state: u8,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryAthlete {
+    pub id: Option<u64>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub firstname: String,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub lastname: String,
+}
+
+// An activity from the feed of athletes the user follows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedActivity {
+    pub id: u64,
+    pub athlete: SummaryAthlete,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub name: String,
+    #[serde(default = "workout", deserialize_with = "sport")]
+    pub sport_type: String,
+    pub start_date: DateTime<Utc>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub distance: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub moving_time: u32,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub total_elevation_gain: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub kudos_count: u32,
+    // Whether the user already gave kudos
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub has_kudoed: bool,
+}
+
+// A route the athlete created or starred on Strava
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Route {
+    pub id: u64,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub distance: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub elevation_gain: f64,
+    // 1 for rides, 2 for runs
+    #[serde(rename = "type")]
+    pub route_type: Option<u32>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub starred: bool,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub private: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub map: Option<PolylineMap>,
+}
+
+impl Route {
+    pub fn sport(&self) -> &'static str {
+        match self.route_type {
+            Some(1) => "Ride",
+            Some(2) => "Run",
+            _ => "Workout",
+        }
+    }
+
+    /// A file name from the route's name, e.g. `havel-loop.gpx`
+    pub fn gpx_file_name(&self) -> String {
+        let mut slug = String::new();
+        for c in self.name.to_lowercase().chars() {
+            if c.is_alphanumeric() {
+                slug.push(c);
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug = slug.trim_end_matches('-');
+        if slug.is_empty() {
+            format!("route-{}.gpx", self.id)
+        } else {
+            format!("{}.gpx", slug)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryClub {
+    pub id: u64,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub name: String,
+    pub sport_type: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub member_count: Option<u32>,
+}
+
+// Club feeds omit ids and dates, so these can't be opened or cached like own activities
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClubActivity {
+    pub athlete: SummaryAthlete,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub name: String,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub distance: f64,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub moving_time: u32,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub elapsed_time: u32,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub total_elevation_gain: f64,
+    #[serde(default = "workout", deserialize_with = "sport")]
+    pub sport_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Photo {
+    pub unique_id: Option<String>,
+    pub caption: Option<String>,
+    // Keyed by the requested size in pixels, e.g. "600"
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub urls: BTreeMap<String, String>,
+}
+
+impl Photo {
+    /// The largest of the returned sizes.
+    pub fn url(&self) -> Option<&str> {
+        self.urls
+            .iter()
+            .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or(0))
+            .map(|(_, url)| url.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: u64,
+    pub activity_id: u64,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub text: String,
+    pub athlete: SummaryAthlete,
+    #[serde(deserialize_with = "de::datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Time spent in each heart rate or power zone, as computed by Strava.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityZone {
+    /// "heartrate" or "power"
+    #[serde(rename = "type")]
+    pub zone_type: String,
+    pub score: Option<f64>,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub sensor_based: bool,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub custom_zones: bool,
+    #[serde(default, deserialize_with = "de::null_default")]
+    pub distribution_buckets: Vec<ZoneBucket>,
+}
+
+impl ActivityZone {
+    pub fn total_time(&self) -> f64 {
+        self.distribution_buckets.iter().map(|b| b.time).sum()
+    }
+}
+
+/// One zone; the open-ended top zone has a `max` of -1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZoneBucket {
+    pub min: f64,
+    pub max: f64,
+    /// Seconds
+    pub time: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKey {
+    Time,
+    Distance,
+    Heartrate,
+    Altitude,
+    VelocitySmooth,
+    Cadence,
+    Watts,
+    Latlng,
+}
+
+impl StreamKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamKey::Time => "time",
+            StreamKey::Distance => "distance",
+            StreamKey::Heartrate => "heartrate",
+            StreamKey::Altitude => "altitude",
+            StreamKey::VelocitySmooth => "velocity_smooth",
+            StreamKey::Cadence => "cadence",
+            StreamKey::Watts => "watts",
+            StreamKey::Latlng => "latlng",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stream<T> {
+    pub data: Vec<T>,
+    pub series_type: Option<String>,
+    pub original_size: Option<u32>,
+    pub resolution: Option<String>,
+}
+
+// Streams as returned with `key_by_type=true`; only requested keys are present
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StreamSet {
+    pub time: Option<Stream<u32>>,
+    pub distance: Option<Stream<f64>>,
+    pub heartrate: Option<Stream<f64>>,
+    pub altitude: Option<Stream<f64>>,
+    pub velocity_smooth: Option<Stream<f64>>,
+    pub cadence: Option<Stream<f64>>,
+    pub watts: Option<Stream<f64>>,
+    pub latlng: Option<Stream<[f64; 2]>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenResponse {
     pub access_token: String,
@@ -0,0 +1,523 @@
+use crate::api::de;
+use crate::api::types::{Activity, BestEffort, DetailedActivity, StreamSet};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keeps every local file in `dir` instead of the user's data directory, so
+/// the demo never touches real data. Only the first call counts.
+pub fn use_data_dir(dir: PathBuf) {
+    let _ = DATA_DIR.set(dir);
+}
+
+fn data_file(name: &str) -> Result<PathBuf> {
+    if let Some(dir) = DATA_DIR.get() {
+        return Ok(dir.join(name));
+    }
+    let proj_dirs = ProjectDirs::from("com", "strava-tui", "strava-tui")
+        .ok_or_else(|| anyhow!("Could not determine data directory"))?;
+    Ok(proj_dirs.data_dir().join(name))
+}
+
+/// Every activity seen so far, persisted as `activities.json` in the data
+/// directory so statistics can cover more than the pages loaded this session.
+#[derive(Debug, Default)]
+pub struct ActivityCache {
+    activities: BTreeMap<u64, Activity>,
+}
+
+impl ActivityCache {
+    pub fn path() -> Result<PathBuf> {
+        data_file("activities.json")
+    }
+
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        // Entries written by older versions that no longer parse are dropped
+        let activities: Vec<Activity> = de::list(&content, true)
+            .map_err(|e| anyhow!("Failed to parse activity cache: {}", e))?;
+        let mut cache = Self::default();
+        cache.merge(activities);
+        Ok(cache)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let activities: Vec<&Activity> = self.activities.values().collect();
+        fs::write(path, serde_json::to_string(&activities)?)?;
+        Ok(())
+    }
+
+    /// Inserts or replaces activities by id and returns how many were new.
+    pub fn merge(&mut self, activities: impl IntoIterator<Item = Activity>) -> usize {
+        let mut added = 0;
+        for activity in activities {
+            if self.activities.insert(activity.id, activity).is_none() {
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Merges activities fresh from Strava, counting new and edited ones.
+    pub fn update(&mut self, activities: impl IntoIterator<Item = Activity>) -> SyncChanges {
+        let mut changes = SyncChanges::default();
+        for activity in activities {
+            let fresh = serde_json::to_value(&activity).ok();
+            match self.activities.insert(activity.id, activity) {
+                None => changes.added += 1,
+                Some(old) if serde_json::to_value(&old).ok() != fresh => changes.updated += 1,
+                Some(_) => {}
+            }
+        }
+        changes
+    }
+
+    /// Drops cached activities starting at or after `since` (all of them
+    /// without) that Strava no longer listed, i.e. deleted ones.
+    pub fn remove_missing(&mut self, seen: &BTreeSet<u64>, since: Option<DateTime<Utc>>) -> usize {
+        let before = self.activities.len();
+        self.activities.retain(|id, activity| {
+            seen.contains(id) || since.is_some_and(|since| activity.start_date < since)
+        });
+        before - self.activities.len()
+    }
+
+    pub fn remove(&mut self, activity_id: u64) -> bool {
+        self.activities.remove(&activity_id).is_some()
+    }
+
+    pub fn contains(&self, activity_id: u64) -> bool {
+        self.activities.contains_key(&activity_id)
+    }
+
+    pub fn newest(&self) -> Option<&Activity> {
+        self.activities.values().max_by_key(|a| a.start_date)
+    }
+
+    pub fn len(&self) -> usize {
+        self.activities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.activities.is_empty()
+    }
+
+    /// Newest first, matching the order of Strava's activity list.
+    pub fn activities(&self) -> Vec<Activity> {
+        let mut activities: Vec<Activity> = self.activities.values().cloned().collect();
+        activities.sort_by_key(|a| std::cmp::Reverse(a.start_date));
+        activities
+    }
+}
+
+/// Detailed activities and streams as last downloaded, one file per activity
+/// under `details/` so opening an activity again costs no API requests.
+#[derive(Debug, Clone)]
+pub struct DetailCache {
+    dir: PathBuf,
+}
+
+// A cached response with the revision of the activity it was fetched for
+#[derive(Serialize, Deserialize)]
+struct Revisioned<T> {
+    revision: String,
+    data: T,
+}
+
+impl DetailCache {
+    pub fn open() -> Result<Self> {
+        Ok(Self::in_dir(data_file("details")?))
+    }
+
+    pub fn in_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Strava's activity list has no `updated_at`, so an entry is matched
+    /// against the summary fields that editing or cropping an activity changes.
+    pub fn revision(activity: &Activity) -> String {
+        format!(
+            "{}|{}|{}|{}|{}|{}|{}|{:?}|{:?}|{:?}|{:?}",
+            activity.name,
+            activity.sport_type,
+            activity.start_date.timestamp(),
+            activity.distance,
+            activity.moving_time,
+            activity.elapsed_time,
+            activity.total_elevation_gain,
+            activity.gear_id,
+            activity.private,
+            activity.commute,
+            activity.trainer
+        )
+    }
+
+    /// The cached detail, unless the activity changed since it was fetched.
+    pub fn detail(&self, activity: &Activity) -> Option<DetailedActivity> {
+        self.read(&format!("{}.json", activity.id), activity)
+    }
+
+    pub fn streams(&self, activity: &Activity) -> Option<StreamSet> {
+        self.read(&format!("{}.streams.json", activity.id), activity)
+    }
+
+    pub fn save_detail(&self, detail: &DetailedActivity) -> Result<()> {
+        let activity = &detail.activity;
+        self.write(&format!("{}.json", activity.id), activity, detail)
+    }
+
+    pub fn save_streams(&self, activity: &Activity, streams: &StreamSet) -> Result<()> {
+        self.write(&format!("{}.streams.json", activity.id), activity, streams)
+    }
+
+    pub fn remove(&self, activity_id: u64) {
+        for name in [
+            format!("{}.json", activity_id),
+            format!("{}.streams.json", activity_id),
+        ] {
+            let _ = fs::remove_file(self.dir.join(name));
+        }
+    }
+
+    // Unreadable or outdated entries are misses and get fetched again
+    fn read<T: DeserializeOwned>(&self, name: &str, activity: &Activity) -> Option<T> {
+        let content = fs::read_to_string(self.dir.join(name)).ok()?;
+        let entry: Revisioned<T> = serde_json::from_str(&content).ok()?;
+        (entry.revision == Self::revision(activity)).then_some(entry.data)
+    }
+
+    fn write<T: Serialize>(&self, name: &str, activity: &Activity, data: &T) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = Revisioned {
+            revision: Self::revision(activity),
+            data,
+        };
+        fs::write(self.dir.join(name), serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
+
+/// Best efforts (400m, 1k, 5k, ...) per activity, persisted as
+/// `best_efforts.json`. They only come with detailed activities, so an
+/// activity that has been fetched in detail is recorded even without efforts.
+#[derive(Debug, Default)]
+pub struct BestEffortCache {
+    efforts: BTreeMap<u64, Vec<BestEffort>>,
+}
+
+impl BestEffortCache {
+    pub fn path() -> Result<PathBuf> {
+        data_file("best_efforts.json")
+    }
+
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        let efforts = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse best effort cache: {}", e))?;
+        Ok(Self { efforts })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(&self.efforts)?)?;
+        Ok(())
+    }
+
+    pub fn insert(&mut self, activity_id: u64, efforts: Vec<BestEffort>) {
+        self.efforts.insert(activity_id, efforts);
+    }
+
+    pub fn contains(&self, activity_id: u64) -> bool {
+        self.efforts.contains_key(&activity_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &Vec<BestEffort>)> {
+        self.efforts.iter().map(|(id, efforts)| (*id, efforts))
+    }
+}
+
+/// Local labels such as "race" or "easy" per activity, persisted as
+/// `tags.json`. Strava has no tags, so they never leave this machine.
+#[derive(Debug, Default)]
+pub struct TagStore {
+    tags: BTreeMap<u64, BTreeSet<String>>,
+}
+
+impl TagStore {
+    pub fn path() -> Result<PathBuf> {
+        data_file("tags.json")
+    }
+
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        let tags =
+            serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse tags: {}", e))?;
+        Ok(Self { tags })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(&self.tags)?)?;
+        Ok(())
+    }
+
+    /// Splits what was typed on commas and whitespace into lowercase tags,
+    /// dropping a leading `#`.
+    pub fn parse(input: &str) -> BTreeSet<String> {
+        input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(|tag| tag.trim_start_matches('#').to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+
+    /// Replaces the tags of an activity; an empty set removes them.
+    pub fn set(&mut self, activity_id: u64, tags: BTreeSet<String>) {
+        if tags.is_empty() {
+            self.tags.remove(&activity_id);
+        } else {
+            self.tags.insert(activity_id, tags);
+        }
+    }
+
+    pub fn get(&self, activity_id: u64) -> Option<&BTreeSet<String>> {
+        self.tags.get(&activity_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &BTreeSet<String>)> {
+        self.tags.iter().map(|(id, tags)| (*id, tags))
+    }
+}
+
+/// Private notes per activity, persisted as `notes.json`. Separate from the
+/// Strava description and never uploaded.
+#[derive(Debug, Default)]
+pub struct NoteStore {
+    notes: BTreeMap<u64, String>,
+}
+
+impl NoteStore {
+    pub fn path() -> Result<PathBuf> {
+        data_file("notes.json")
+    }
+
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        let notes =
+            serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse notes: {}", e))?;
+        Ok(Self { notes })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(&self.notes)?)?;
+        Ok(())
+    }
+
+    /// Replaces the note of an activity; a blank one removes it.
+    pub fn set(&mut self, activity_id: u64, note: String) {
+        if note.trim().is_empty() {
+            self.notes.remove(&activity_id);
+        } else {
+            self.notes.insert(activity_id, note);
+        }
+    }
+
+    pub fn get(&self, activity_id: u64) -> Option<&str> {
+        self.notes.get(&activity_id).map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &str)> {
+        self.notes.iter().map(|(id, note)| (*id, note.as_str()))
+    }
+}
+
+/// Files from the watched folder that were uploaded, persisted as
+/// `uploads.json` so each is sent once, however often the folder comes and
+/// goes as a watch is plugged in.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UploadLog {
+    // When the folder was first watched; files older than that aren't uploaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    // By file name, the activity made from it, or None if Strava rejected it
+    #[serde(default)]
+    files: BTreeMap<String, Option<u64>>,
+}
+
+impl UploadLog {
+    pub fn path() -> Result<PathBuf> {
+        data_file("uploads.json")
+    }
+
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse upload log: {}", e))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, file: &str) -> bool {
+        self.files.contains_key(file)
+    }
+
+    pub fn record(&mut self, file: String, activity_id: Option<u64>) {
+        self.files.insert(file, activity_id);
+    }
+}
+
+/// Activities a sync added, found edited or found deleted on Strava.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncChanges {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+impl std::ops::AddAssign for SyncChanges {
+    fn add_assign(&mut self, other: Self) {
+        self.added += other.added;
+        self.updated += other.updated;
+        self.removed += other.removed;
+    }
+}
+
+/// Where `sportfrei sync` got to, persisted as `sync.json` so an interrupted
+/// download resumes at the next page instead of starting over.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncState {
+    pub per_page: u32,
+    pub next_page: u32,
+    pub complete: bool,
+    // When the last sync started, for the next delta sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sync: Option<DateTime<Utc>>,
+}
+
+impl SyncState {
+    pub fn new(per_page: u32) -> Self {
+        Self {
+            per_page,
+            next_page: 1,
+            complete: false,
+            last_sync: None,
+        }
+    }
+
+    pub fn path() -> Result<PathBuf> {
+        data_file("sync.json")
+    }
+
+    pub fn load() -> Result<Option<Self>> {
+        Self::load_from(&Self::path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        let state = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse sync state: {}", e))?;
+        Ok(Some(state))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Picks up an unfinished sync with the same page size; anything else
+    /// starts from the first page.
+    pub fn resume(previous: Option<Self>, per_page: u32) -> Self {
+        match previous {
+            Some(state) if !state.complete && state.per_page == per_page => state,
+            _ => Self::new(per_page),
+        }
+    }
+}
@@ -0,0 +1,937 @@
+use crate::analytics;
+use crate::api::client::StravaClient;
+use crate::api::types::{Activity, ActivityStats, DateRange, UploadDataType};
+use crate::cache::{ActivityCache, DetailCache, NoteStore, SyncChanges, SyncState, TagStore};
+use crate::config::{Config, Units};
+use crate::export::{self, ArchivedActivity, Column};
+use crate::fit;
+use crate::fitness::HeartRateRange;
+use crate::goals::{Goal, GoalMetric, GoalPeriod};
+use crate::i18n::Locale;
+use crate::import;
+use crate::metrics::{self, TrainingMetrics};
+use crate::plan::Plan;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, Utc};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+
+// Strava rejects larger pages
+const MAX_PER_PAGE: u32 = 200;
+// Pages fetched at once by `sync`; Strava allows 100 reads per 15 minutes
+const DEFAULT_SYNC_CONCURRENCY: usize = 4;
+const MAX_SYNC_CONCURRENCY: usize = 8;
+// How far before the last sync a delta sync looks again, for late uploads
+// and edits to recent activities
+const SYNC_RECHECK_DAYS: i64 = 30;
+// A scraper that doesn't send its request by then is dropped
+const SCRAPE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Terminal client for Strava. Runs the interactive UI when no command is given.
+#[derive(Debug, Parser)]
+#[command(
+    name = "sportfrei",
+    version,
+    about,
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Explore the UI with made-up data, without a Strava account
+    #[arg(long)]
+    pub demo: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List recent activities
+    Activities {
+        /// Number of activities to fetch
+        #[arg(long, default_value_t = 10)]
+        last: u32,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+        /// Shorthand for --format json
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+        /// Comma-separated columns for csv/json output, e.g. date,name,distance,pace
+        #[arg(long, value_delimiter = ',')]
+        columns: Vec<Column>,
+        /// Write to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Show athlete totals
+    Stats {
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a single activity with splits, laps and efforts as JSON
+    Export {
+        /// Strava activity id
+        id: u64,
+    },
+    /// Print a shareable card of one activity with its figures and a map thumbnail
+    Card {
+        /// Strava activity id
+        id: u64,
+        /// Color the card for terminals
+        #[arg(long)]
+        ansi: bool,
+        /// Write to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Upload a FIT, TCX or GPX file as a new activity
+    Upload {
+        file: PathBuf,
+        /// Activity name; Strava picks one when omitted
+        #[arg(long)]
+        name: Option<String>,
+        /// fit, fit.gz, tcx, tcx.gz, gpx or gpx.gz; guessed from the file name by default
+        #[arg(long)]
+        data_type: Option<UploadDataType>,
+    },
+    /// Download the whole activity history into the local cache
+    Sync {
+        /// Pages downloaded at the same time (at most 8)
+        #[arg(long, default_value_t = DEFAULT_SYNC_CONCURRENCY)]
+        concurrency: usize,
+        /// Download everything again instead of resuming or checking recent activities only
+        #[arg(long)]
+        restart: bool,
+    },
+    /// Write cached activities and planned workouts as an iCalendar (.ics) file
+    Calendar {
+        /// Write to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Event title, e.g. "{sport} {distance}"; placeholders are {name}, {sport}, {distance} and {duration}
+        #[arg(long)]
+        event_name: Option<String>,
+        /// Leave out the workouts from plan.toml
+        #[arg(long)]
+        no_plan: bool,
+    },
+    /// Write every activity with descriptions, tags and notes to one archive,
+    /// syncing the cache with Strava first
+    ExportAll {
+        #[arg(long, value_enum, default_value_t = ArchiveFormat::Json)]
+        format: ArchiveFormat,
+        /// File to write, or the directory for gpx-dir
+        #[arg(long, short)]
+        output: PathBuf,
+        /// Export the cache as it is, without asking Strava for new activities
+        #[arg(long)]
+        no_sync: bool,
+    },
+    /// Add the activities of Strava's "Download your data" ZIP to the local cache
+    Import {
+        /// The export archive, e.g. export_12345678.zip
+        archive: PathBuf,
+    },
+    /// Use the FIT file from the recording device as a cached activity's streams
+    Attach {
+        /// The Strava activity id
+        activity_id: u64,
+        /// The FIT file, optionally .gz
+        file: PathBuf,
+    },
+    /// Serve weekly volume, load and streak to Prometheus or append them to a CSV file
+    #[command(group(ArgGroup::new("target").required(true).args(["listen", "csv"])))]
+    Metrics {
+        /// Address to serve /metrics on, e.g. 127.0.0.1:9464
+        #[arg(long)]
+        listen: Option<String>,
+        /// Append a row to this CSV file instead
+        #[arg(long)]
+        csv: Option<PathBuf>,
+        /// Minutes between CSV rows and syncs; without it a single row is written
+        #[arg(long)]
+        interval: Option<u64>,
+        /// Sync the activity cache first, and again every --interval minutes
+        #[arg(long)]
+        sync: bool,
+    },
+    /// List, add or remove distance and time goals
+    Goals {
+        #[command(subcommand)]
+        action: Option<GoalAction>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GoalAction {
+    /// Show goals with progress from the local activity cache
+    List,
+    /// Add a goal, e.g. `goals add month 100 --sport Run`
+    Add {
+        period: GoalPeriod,
+        /// Distance in the configured units, or hours with --metric time
+        target: f64,
+        #[arg(long, value_enum, default_value_t = GoalMetric::Distance)]
+        metric: GoalMetric,
+        /// Strava sport type, e.g. Run or Ride; all sports when omitted
+        #[arg(long)]
+        sport: Option<String>,
+    },
+    /// Remove a goal by its number in `goals list`
+    Remove { number: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ArchiveFormat {
+    /// The full Strava objects
+    Json,
+    /// One row per activity with every column
+    Csv,
+    /// A GPX file per activity with a map
+    GpxDir,
+}
+
+pub async fn run(command: Command, client: &StravaClient, units: Units) -> Result<()> {
+    match command {
+        Command::Activities {
+            last,
+            format,
+            json,
+            columns,
+            output,
+        } => {
+            let activities = fetch_recent(client, last).await?;
+            let format = if json { OutputFormat::Json } else { format };
+            let text = render_activities(&activities, format, &columns, units)?;
+            match output {
+                Some(path) => fs::write(path, text)?,
+                None => print!("{}", text),
+            }
+        }
+        Command::Stats { json } => {
+            let athlete = client.get_athlete().await?;
+            let stats = client.get_athlete_stats(athlete.id).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                println!("{} {}", athlete.firstname, athlete.lastname);
+                for (label, totals) in [
+                    ("Runs (last 4 weeks)", &stats.recent_run_totals),
+                    ("Runs (this year)", &stats.ytd_run_totals),
+                    ("Runs (all time)", &stats.all_run_totals),
+                    ("Rides (last 4 weeks)", &stats.recent_ride_totals),
+                    ("Rides (this year)", &stats.ytd_ride_totals),
+                    ("Rides (all time)", &stats.all_ride_totals),
+                ] {
+                    println!("{:<22}{}", label, format_totals(totals, units));
+                }
+            }
+        }
+        Command::Export { id } => {
+            let detail = client.get_activity(id).await?;
+            println!("{}", serde_json::to_string_pretty(&detail)?);
+        }
+        Command::Card { id, ansi, output } => {
+            let detail = client.get_activity(id).await?;
+            let card = export::to_card(&detail.activity, units, ansi);
+            match output {
+                Some(path) => fs::write(path, card)?,
+                None => print!("{}", card),
+            }
+        }
+        Command::Upload {
+            file,
+            name,
+            data_type,
+        } => {
+            let data_type = data_type
+                .or_else(|| UploadDataType::from_path(&file))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "can't tell the format of {}; pass --data-type",
+                        file.display()
+                    )
+                })?;
+            println!("Uploading {}...", file.display());
+            let upload = client
+                .upload_activity(&file, data_type, name.as_deref())
+                .await?;
+            if let Some(id) = upload.activity_id {
+                println!(
+                    "Created activity {}: https://www.strava.com/activities/{}",
+                    id, id
+                );
+            }
+        }
+        Command::Sync {
+            concurrency,
+            restart,
+        } => run_sync(client, concurrency, restart).await?,
+        Command::ExportAll {
+            format,
+            output,
+            no_sync,
+        } => {
+            if !no_sync {
+                run_sync(client, DEFAULT_SYNC_CONCURRENCY, false).await?;
+            }
+            run_export_all(format, &output, units)?;
+        }
+        Command::Import { archive } => run_import(&archive)?,
+        Command::Attach { activity_id, file } => run_attach(activity_id, &file)?,
+        Command::Metrics {
+            listen,
+            csv,
+            interval,
+            sync,
+        } => {
+            let client = sync.then_some(client);
+            run_metrics(listen, csv, interval, client, &Config::load()?).await?
+        }
+        Command::Goals { action } => run_goals(action, &mut Config::load()?)?,
+        Command::Calendar {
+            output,
+            event_name,
+            no_plan,
+        } => run_calendar(output, event_name, no_plan, &Config::load()?)?,
+    }
+    Ok(())
+}
+
+// After the first complete sync, only the activities started since the
+// last one (minus a margin for late uploads and recent edits) are fetched
+async fn run_sync(client: &StravaClient, concurrency: usize, restart: bool) -> Result<()> {
+    let previous = if restart { None } else { SyncState::load()? };
+    let started = Utc::now();
+    let mut cache = ActivityCache::load()?;
+    let changes = match previous.as_ref().filter(|state| state.complete) {
+        Some(SyncState {
+            last_sync: Some(last_sync),
+            ..
+        }) => {
+            let since = *last_sync - chrono::Duration::days(SYNC_RECHECK_DAYS);
+            delta_sync(client, &mut cache, since).await?
+        }
+        _ => {
+            let state = SyncState::resume(previous, MAX_PER_PAGE);
+            full_sync(client, &mut cache, state, concurrency).await?
+        }
+    };
+    SyncState {
+        complete: true,
+        last_sync: Some(started),
+        ..SyncState::new(MAX_PER_PAGE)
+    }
+    .save()?;
+    println!(
+        "Done: {} activities in the cache ({} new, {} updated, {} deleted on Strava)",
+        cache.len(),
+        changes.added,
+        changes.updated,
+        changes.removed
+    );
+    Ok(())
+}
+
+// Fetches pages in batches of `concurrency` and merges them in page order,
+// saving after every batch so an interruption loses at most one batch.
+// Rate limit responses are retried with backoff by the client.
+async fn full_sync(
+    client: &StravaClient,
+    cache: &mut ActivityCache,
+    mut state: SyncState,
+    concurrency: usize,
+) -> Result<SyncChanges> {
+    let concurrency = concurrency.clamp(1, MAX_SYNC_CONCURRENCY) as u32;
+    // Deletions only show when every page was seen in one go, with no
+    // entries skipped as malformed
+    let resumed = state.next_page > 1;
+    if resumed {
+        println!("Resuming at page {}", state.next_page);
+    }
+    let mut seen = BTreeSet::new();
+    let mut skipped = 0;
+    let mut changes = SyncChanges::default();
+
+    while !state.complete {
+        let first = state.next_page;
+        let mut tasks = JoinSet::new();
+        for page in first..first + concurrency {
+            let client = client.clone();
+            tasks.spawn(async move {
+                let result = client
+                    .get_activities(page, MAX_PER_PAGE, DateRange::default())
+                    .await;
+                (page, result)
+            });
+        }
+        let mut pages = BTreeMap::new();
+        while let Some(joined) = tasks.join_next().await {
+            let (page, result) = joined?;
+            pages.insert(page, result);
+        }
+
+        let mut failure = None;
+        for (page, result) in pages {
+            let batch = match result {
+                Ok(batch) => batch,
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            };
+            state.complete = batch.is_last(MAX_PER_PAGE);
+            state.next_page = page + 1;
+            skipped += batch.skipped();
+            seen.extend(batch.items.iter().map(|a| a.id));
+            changes += cache.update(batch.items);
+            if state.complete {
+                break;
+            }
+        }
+        if state.next_page > first {
+            cache.save()?;
+            state.save()?;
+            print!(
+                "\rPage {}: {} activities downloaded, {} new",
+                state.next_page - 1,
+                seen.len(),
+                changes.added
+            );
+            std::io::stdout().flush()?;
+        }
+
+        if let Some(e) = failure {
+            println!();
+            return Err(anyhow::Error::from(e).context(format!(
+                "sync stopped at page {}; run it again to resume",
+                state.next_page
+            )));
+        }
+    }
+    println!();
+
+    if !resumed && skipped == 0 {
+        changes.removed = cache.remove_missing(&seen, None);
+        cache.save()?;
+    }
+    Ok(changes)
+}
+
+// Strava filters by start date only, so everything started since `since` is
+// compared with the cache: new ids are added, changed ones replaced and
+// cached ones that are no longer listed removed
+async fn delta_sync(
+    client: &StravaClient,
+    cache: &mut ActivityCache,
+    since: DateTime<Utc>,
+) -> Result<SyncChanges> {
+    println!("Checking activities since {}", since.format("%Y-%m-%d"));
+    let range = DateRange {
+        after: Some(since.timestamp()),
+        before: None,
+    };
+    let mut fetched = Vec::new();
+    let mut skipped = 0;
+    for page in 1.. {
+        let batch = client.get_activities(page, MAX_PER_PAGE, range).await?;
+        let done = batch.is_last(MAX_PER_PAGE);
+        skipped += batch.skipped();
+        fetched.extend(batch.items);
+        if done {
+            break;
+        }
+    }
+
+    let seen: BTreeSet<u64> = fetched.iter().map(|a| a.id).collect();
+    let mut changes = cache.update(fetched);
+    // A skipped entry isn't missing, so nothing is removed until it parses
+    if skipped == 0 {
+        changes.removed = cache.remove_missing(&seen, Some(since));
+    }
+    cache.save()?;
+    Ok(changes)
+}
+
+// Like goals, the calendar comes from local files only
+pub fn run_calendar(
+    output: Option<PathBuf>,
+    event_name: Option<String>,
+    no_plan: bool,
+    config: &Config,
+) -> Result<()> {
+    let activities = ActivityCache::load()?.activities();
+    let plan = if no_plan {
+        Plan::default()
+    } else {
+        Plan::load()?
+    };
+    let template = event_name
+        .or_else(|| config.calendar_event_name.clone())
+        .unwrap_or_else(|| export::DEFAULT_EVENT_NAME.to_string());
+    let ics = export::to_ics(
+        &activities,
+        &plan.workouts,
+        &template,
+        config.units,
+        chrono::Utc::now(),
+    );
+    match output {
+        Some(path) => {
+            fs::write(&path, ics)?;
+            println!(
+                "Wrote {} activities and {} planned workouts to {}",
+                activities.len(),
+                plan.workouts.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", ics),
+    }
+    Ok(())
+}
+
+// Reads the local files only; syncing is up to the caller. Tracks come from
+// cached streams where an activity was opened, otherwise from its map.
+pub fn run_export_all(format: ArchiveFormat, output: &Path, units: Units) -> Result<()> {
+    let mut activities = ActivityCache::load()?.activities();
+    let tags = TagStore::load()?;
+    let notes = NoteStore::load()?;
+    let details = DetailCache::open()?;
+
+    if format == ArchiveFormat::GpxDir {
+        fs::create_dir_all(output)?;
+        let mut written = 0;
+        for activity in &activities {
+            let streams = details.streams(activity);
+            if let Some(gpx) = export::to_gpx(activity, streams.as_ref()) {
+                fs::write(output.join(export::gpx_file_name(activity)), gpx)?;
+                written += 1;
+            }
+        }
+        println!(
+            "Wrote {} GPX tracks to {} ({} activities without a map left out)",
+            written,
+            output.display(),
+            activities.len() - written
+        );
+        return Ok(());
+    }
+
+    // The activity list has no descriptions, only the details opened before
+    for activity in &mut activities {
+        if activity.description.is_none() {
+            activity.description = details
+                .detail(activity)
+                .and_then(|d| d.activity.description);
+        }
+    }
+    let archive: Vec<ArchivedActivity> = activities
+        .iter()
+        .map(|activity| ArchivedActivity {
+            activity,
+            tags: tags
+                .get(activity.id)
+                .map(|tags| tags.iter().map(String::as_str).collect())
+                .unwrap_or_default(),
+            note: notes.get(activity.id),
+        })
+        .collect();
+    let text = match format {
+        ArchiveFormat::Csv => export::to_archive_csv(&archive, units),
+        _ => export::to_archive_json(&archive)?,
+    };
+    fs::write(output, text)?;
+    println!("Wrote {} activities to {}", archive.len(), output.display());
+    Ok(())
+}
+
+// Activities already cached came from the API, which knows more about them
+// (kudos, gear, time zone), so only the missing ones are added. Private
+// notes go into the local notes unless one was written there.
+pub fn run_import(archive: &Path) -> Result<()> {
+    let import = import::read_archive(archive)?;
+    let mut cache = ActivityCache::load()?;
+    let mut notes = NoteStore::load()?;
+    let details = DetailCache::open()?;
+    let total = import.activities.len();
+    let mut with_map = 0;
+    let mut with_streams = 0;
+    let mut new = Vec::new();
+    for imported in import.activities {
+        if let Some(note) = imported.note {
+            if notes.get(imported.activity.id).is_none() {
+                notes.set(imported.activity.id, note);
+            }
+        }
+        if !cache.contains(imported.activity.id) {
+            with_map += usize::from(imported.activity.map.is_some());
+            // Opening the activity then needs no stream request
+            if let Some(streams) = &imported.streams {
+                details.save_streams(&imported.activity, streams)?;
+                with_streams += 1;
+            }
+            new.push(imported.activity);
+        }
+    }
+    let added = cache.merge(new);
+    cache.save()?;
+    notes.save()?;
+    println!(
+        "Imported {} of {} activities ({} already cached, {} with a map, {} with FIT streams)",
+        added,
+        total,
+        total - added,
+        with_map,
+        with_streams
+    );
+    if import.skipped > 0 {
+        println!(
+            "Skipped {} rows without an id or a readable date",
+            import.skipped
+        );
+    }
+    Ok(())
+}
+
+// The streams are stored like downloaded ones, so they are replaced when the
+// activity is edited on Strava
+pub fn run_attach(activity_id: u64, file: &Path) -> Result<()> {
+    let activity = ActivityCache::load()?
+        .activities()
+        .into_iter()
+        .find(|a| a.id == activity_id)
+        .ok_or_else(|| {
+            anyhow!(
+                "activity {} isn't cached; run `sportfrei sync` first",
+                activity_id
+            )
+        })?;
+    let records = fit::read_file(file)?;
+    if records.is_empty() {
+        return Err(anyhow!("{} has no recorded samples", file.display()));
+    }
+    let streams = fit::to_streams(&records);
+    DetailCache::open()?.save_streams(&activity, &streams)?;
+    let recorded: Vec<&str> = [
+        ("heart rate", streams.heartrate.is_some()),
+        ("power", streams.watts.is_some()),
+        ("cadence", streams.cadence.is_some()),
+        ("GPS", streams.latlng.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, present)| present.then_some(name))
+    .collect();
+    println!(
+        "Attached {} samples to {} ({})",
+        records.len(),
+        activity.name,
+        if recorded.is_empty() {
+            "time only".to_string()
+        } else {
+            recorded.join(", ")
+        }
+    );
+    Ok(())
+}
+
+/// The metrics of the cached activities that count towards the dashboard's
+/// totals, with weeks starting on the locale's first weekday.
+pub fn collect_metrics(config: &Config) -> Result<TrainingMetrics> {
+    let activities: Vec<Activity> = ActivityCache::load()?
+        .activities()
+        .into_iter()
+        .filter(|a| !(config.hide_commutes && a.commute == Some(true)))
+        .filter(|a| !(config.hide_private && a.private == Some(true)))
+        .filter(|a| !(config.exclude_indoor && analytics::is_indoor(a)))
+        .collect();
+    let locale = config
+        .locale
+        .as_deref()
+        .map_or_else(Locale::detect, Locale::from_locale);
+    let today = Local::now().date_naive();
+    let heart_rate =
+        HeartRateRange::resolve(config.resting_heartrate, config.max_heartrate, &activities);
+    Ok(TrainingMetrics::collect(
+        &activities,
+        heart_rate,
+        locale.week_start(today),
+        today,
+    ))
+}
+
+// Without a client the metrics come from the cache as it is, which works
+// before authorizing
+pub async fn run_metrics(
+    listen: Option<String>,
+    csv: Option<PathBuf>,
+    interval: Option<u64>,
+    client: Option<&StravaClient>,
+    config: &Config,
+) -> Result<()> {
+    // The first tick completes right away
+    let mut ticks = interval
+        .map(|minutes| tokio::time::interval(std::time::Duration::from_secs(minutes.max(1) * 60)));
+
+    let Some(address) = listen else {
+        let path = csv.ok_or_else(|| anyhow!("either --listen or --csv is needed"))?;
+        loop {
+            if let Some(ticks) = &mut ticks {
+                ticks.tick().await;
+            }
+            if let Some(client) = client {
+                sync_for_metrics(client).await;
+            }
+            append_metrics_row(&path, config)?;
+            if ticks.is_none() {
+                return Ok(());
+            }
+        }
+    };
+
+    let listener = TcpListener::bind(&address)
+        .await
+        .with_context(|| format!("can't listen on {}", address))?;
+    println!(
+        "Serving metrics on http://{}/metrics",
+        listener.local_addr()?
+    );
+    if let (Some(client), None) = (client, &ticks) {
+        sync_for_metrics(client).await;
+    }
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                if let Err(e) = serve_metrics(stream, config).await {
+                    eprintln!("Failed to answer a scrape: {}", e);
+                }
+            }
+            _ = async { ticks.as_mut()?.tick().await; Some(()) }, if client.is_some() && ticks.is_some() => {
+                if let Some(client) = client {
+                    sync_for_metrics(client).await;
+                }
+            }
+        }
+    }
+}
+
+// A failed sync leaves the cache as it was, which still gives metrics
+async fn sync_for_metrics(client: &StravaClient) {
+    if let Err(e) = run_sync(client, DEFAULT_SYNC_CONCURRENCY, false).await {
+        eprintln!("Sync failed: {:#}", e);
+    }
+}
+
+fn append_metrics_row(path: &Path, config: &Config) -> Result<()> {
+    let metrics = collect_metrics(config)?;
+    let new = fs::metadata(path).map_or(true, |m| m.len() == 0);
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("can't open {}", path.display()))?;
+    if new {
+        writeln!(file, "{}", metrics::CSV_HEADER)?;
+    }
+    writeln!(file, "{}", metrics.csv_row(Utc::now()))?;
+    Ok(())
+}
+
+// Just enough HTTP for a Prometheus scrape: GET /metrics, one request per
+// connection
+async fn serve_metrics(mut stream: TcpStream, config: &Config) -> Result<()> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 8192 {
+        let read = tokio::time::timeout(SCRAPE_TIMEOUT, stream.read(&mut buffer)).await??;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics" | "/")) => match collect_metrics(config) {
+            Ok(metrics) => ("200 OK", metrics.to_prometheus()),
+            Err(e) => ("500 Internal Server Error", format!("{:#}\n", e)),
+        },
+        _ => (
+            "404 Not Found",
+            "Metrics are served on /metrics\n".to_string(),
+        ),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+// Goals only touch the config and the local cache, so they work before authorizing
+pub fn run_goals(action: Option<GoalAction>, config: &mut Config) -> Result<()> {
+    match action.unwrap_or(GoalAction::List) {
+        GoalAction::List => {
+            if config.goals.is_empty() {
+                println!("No goals yet. Add one with `sportfrei goals add month 100 --sport Run`.");
+                return Ok(());
+            }
+            let cache = ActivityCache::load()?;
+            let activities = cache.activities();
+            let today = chrono::Local::now().date_naive();
+            for (i, goal) in config.goals.iter().enumerate() {
+                let progress = goal.progress(&activities, today, config.units);
+                println!(
+                    "{}. {:<32} {:>8.1} done ({:>3.0}%), on pace for {:.1}",
+                    i + 1,
+                    goal.label(config.units),
+                    progress.done,
+                    progress.ratio(goal) * 100.0,
+                    progress.projected
+                );
+            }
+        }
+        GoalAction::Add {
+            period,
+            target,
+            metric,
+            sport,
+        } => {
+            if target <= 0.0 {
+                return Err(anyhow!("goal target must be positive"));
+            }
+            let goal = Goal {
+                period,
+                metric,
+                target,
+                sport,
+            };
+            println!("Added goal: {}", goal.label(config.units));
+            config.goals.push(goal);
+            config.save()?;
+        }
+        GoalAction::Remove { number } => {
+            if number == 0 || number > config.goals.len() {
+                return Err(anyhow!("no goal number {}", number));
+            }
+            let goal = config.goals.remove(number - 1);
+            println!("Removed goal: {}", goal.label(config.units));
+            config.save()?;
+        }
+    }
+    Ok(())
+}
+
+pub fn render_activities(
+    activities: &[Activity],
+    format: OutputFormat,
+    columns: &[Column],
+    units: Units,
+) -> Result<String> {
+    let mut text = match format {
+        OutputFormat::Table => activities
+            .iter()
+            .map(|a| format_activity(a, units))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        // Without explicit columns JSON keeps the full Strava objects
+        OutputFormat::Json if columns.is_empty() => serde_json::to_string_pretty(activities)?,
+        OutputFormat::Json => export::to_json(activities, columns, units)?,
+        OutputFormat::Csv if columns.is_empty() => {
+            return Ok(export::to_csv(activities, &Column::DEFAULT, units));
+        }
+        OutputFormat::Csv => return Ok(export::to_csv(activities, columns, units)),
+    };
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+async fn fetch_recent(client: &StravaClient, count: u32) -> Result<Vec<Activity>> {
+    let per_page = count.clamp(1, MAX_PER_PAGE);
+    let mut activities = Vec::new();
+    let mut page = 1;
+
+    while (activities.len() as u32) < count {
+        let batch = client
+            .get_activities(page, per_page, DateRange::default())
+            .await?;
+        let done = batch.is_last(per_page);
+        activities.extend(batch.items);
+        if done {
+            break;
+        }
+        page += 1;
+    }
+
+    activities.truncate(count as usize);
+    Ok(activities)
+}
+
+pub fn format_activity(activity: &Activity, units: Units) -> String {
+    let distance = units.distance(activity.distance);
+    let pace = if distance > 0.0 {
+        let secs = activity.moving_time as f64 / distance;
+        format!(
+            "{}:{:02} /{}",
+            (secs / 60.0) as u32,
+            (secs % 60.0) as u32,
+            units.distance_unit()
+        )
+    } else {
+        "---".to_string()
+    };
+
+    format!(
+        "{:<11} {:<12} {:<30} {:>7.2} {:<2} {:>8} {:>10}",
+        activity.start_date_local.format("%Y-%m-%d"),
+        activity.sport_type,
+        activity.name.chars().take(30).collect::<String>(),
+        distance,
+        units.distance_unit(),
+        format_duration(activity.moving_time),
+        pace
+    )
+}
+
+fn format_totals(totals: &ActivityStats, units: Units) -> String {
+    format!(
+        "{:>5} activities {:>9.1} {} {:>10} {:>7.0} {}",
+        totals.count,
+        units.distance(totals.distance),
+        units.distance_unit(),
+        format_duration(totals.moving_time),
+        units.elevation(totals.elevation_gain),
+        units.elevation_unit()
+    )
+}
+
+fn format_duration(seconds: u32) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
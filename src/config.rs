@@ -0,0 +1,414 @@
+use crate::analytics::EfficiencyMetric;
+use crate::goals::Goal;
+use crate::i18n::{Language, TimeFormat};
+use crate::secrets;
+use crate::ui::columns::{ActivityColumn, SportIcons};
+use anyhow::{anyhow, Result};
+use directories::{ProjectDirs, UserDirs};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    pub fn distance(&self, meters: f64) -> f64 {
+        match self {
+            Units::Metric => meters / 1000.0,
+            Units::Imperial => meters / 1609.344,
+        }
+    }
+
+    pub fn distance_unit(&self) -> &'static str {
+        match self {
+            Units::Metric => "km",
+            Units::Imperial => "mi",
+        }
+    }
+
+    pub fn elevation(&self, meters: f64) -> f64 {
+        match self {
+            Units::Metric => meters,
+            Units::Imperial => meters * 3.28084,
+        }
+    }
+
+    pub fn elevation_unit(&self) -> &'static str {
+        match self {
+            Units::Metric => "m",
+            Units::Imperial => "ft",
+        }
+    }
+
+    pub fn speed(&self, meters_per_second: f64) -> f64 {
+        match self {
+            Units::Metric => meters_per_second * 3.6,
+            Units::Imperial => meters_per_second * 2.236936,
+        }
+    }
+
+    pub fn speed_unit(&self) -> &'static str {
+        match self {
+            Units::Metric => "km/h",
+            Units::Imperial => "mph",
+        }
+    }
+
+    /// Climb per distance unit from meters climbed per meter covered
+    pub fn climb_density(&self, ratio: f64) -> f64 {
+        match self {
+            Units::Metric => ratio * 1000.0,
+            Units::Imperial => ratio * 5280.0,
+        }
+    }
+
+    pub fn climb_density_unit(&self) -> &'static str {
+        match self {
+            Units::Metric => "m/km",
+            Units::Imperial => "ft/mi",
+        }
+    }
+
+    pub fn temperature(&self, celsius: f64) -> f64 {
+        match self {
+            Units::Metric => celsius,
+            Units::Imperial => celsius * 1.8 + 32.0,
+        }
+    }
+
+    pub fn temperature_unit(&self) -> &'static str {
+        match self {
+            Units::Metric => "°C",
+            Units::Imperial => "°F",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultView {
+    #[default]
+    Dashboard,
+    Activities,
+}
+
+/// Which time the duration, pace and speed columns are based on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeBasis {
+    #[default]
+    Moving,
+    Elapsed,
+    /// Elapsed time for rides, where stops are part of the outing, and moving
+    /// time for everything else
+    BySport,
+}
+
+impl TimeBasis {
+    pub fn next(self) -> Self {
+        match self {
+            TimeBasis::Moving => TimeBasis::Elapsed,
+            TimeBasis::Elapsed => TimeBasis::BySport,
+            TimeBasis::BySport => TimeBasis::Moving,
+        }
+    }
+
+    pub fn uses_elapsed(self, sport_type: &str) -> bool {
+        match self {
+            TimeBasis::Moving => false,
+            TimeBasis::Elapsed => true,
+            TimeBasis::BySport => sport_type.contains("Ride"),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeBasis::Moving => "moving time",
+            TimeBasis::Elapsed => "elapsed time",
+            TimeBasis::BySport => "elapsed time for rides",
+        }
+    }
+}
+
+/// Backoff for transient API failures (429, 5xx, timeouts). Each retry waits
+/// a random time between half and all of `base_delay_ms * 2^attempt`, capped
+/// at `max_delay_ms`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 8000,
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay_ms
+            .saturating_mul(1u64 << attempt.min(32))
+            .min(self.max_delay_ms);
+        Duration::from_millis(delay)
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What auto-refresh raises a desktop notification for. All off by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub new_activities: bool,
+    /// Kudos on activities of the last few days
+    pub kudos: bool,
+    /// Personal records from the local cache and Strava's segment PRs
+    pub records: bool,
+}
+
+impl NotificationConfig {
+    pub fn any(&self) -> bool {
+        self.new_activities || self.kudos || self.records
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Contents of `config.toml`. Keys this version doesn't know about are kept
+/// in `extra` so saving never drops a user's customizations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub units: Units,
+    // "en" or "de"; the locale decides when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    // Dates, decimals and the first weekday, e.g. "de_DE" or "en_US"; the environment decides when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    // "12h" or "24h"; follows the locale when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<TimeFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+    // Fetch the next page once the selection is this close to the end (default 5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefetch_rows: Option<usize>,
+    // Stop paging the activities list after this many pages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_pages: Option<u32>,
+    // Activities table columns in display order; empty keeps the defaults
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub activity_columns: Vec<ActivityColumn>,
+    #[serde(default)]
+    pub default_view: DefaultView,
+    // Check for new uploads every this many minutes while the UI is open; off when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_refresh_minutes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_port: Option<u16>,
+    // Where API and token requests go instead of Strava, e.g. a self-hosted proxy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_url: Option<String>,
+    #[serde(default, skip_serializing_if = "RetryConfig::is_default")]
+    pub retry: RetryConfig,
+    // Fail a whole page on a malformed entry instead of logging and skipping it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_parsing: bool,
+    // Keep client_secret and refresh_token in this file instead of the OS keyring
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub plaintext_secrets: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Goal>,
+    // Heart rates for the training load of activities without a relative effort score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resting_heartrate: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_heartrate: Option<u32>,
+    // What the RelPerf column shows
+    #[serde(default)]
+    pub efficiency_metric: EfficiencyMetric,
+    // Leave these out of the activities table and dashboard figures; C and P toggle at runtime
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hide_commutes: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hide_private: bool,
+    // Leave trainer and virtual activities out of the dashboard figures and goals; I toggles
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exclude_indoor: bool,
+    // Moving or elapsed time for duration, pace and speed; E toggles at runtime
+    #[serde(default)]
+    pub time_basis: TimeBasis,
+    // Icon in front of activity names: none, emoji, nerd_font or ascii
+    #[serde(default)]
+    pub sport_icons: SportIcons,
+    // Title of `sportfrei calendar` events; see export::DEFAULT_EVENT_NAME
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar_event_name: Option<String>,
+    // Desktop notifications for what auto-refresh finds; needs auto_refresh_minutes
+    #[serde(default, skip_serializing_if = "NotificationConfig::is_default")]
+    pub notifications: NotificationConfig,
+    // Where route GPX files are saved; the Downloads folder when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_dir: Option<PathBuf>,
+    // Upload new FIT files written here while the UI is open, e.g. where a watch mounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_dir: Option<PathBuf>,
+    // Rhai script with custom columns and dashboard cards; relative to this file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<PathBuf>,
+    // Custom color themes by name; see ui::theme::Theme::resolve
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub themes: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+impl Config {
+    pub fn path() -> Result<PathBuf> {
+        let proj_dirs = ProjectDirs::from("com", "strava-tui", "strava-tui")
+            .ok_or_else(|| anyhow!("Could not determine config directory"))?;
+        Ok(proj_dirs.config_dir().join("config.toml"))
+    }
+
+    pub fn exists() -> bool {
+        Self::path().map(|p| p.exists()).unwrap_or(false)
+    }
+
+    // Falls back to the working directory where the platform has no Downloads folder
+    pub fn download_dir(&self) -> PathBuf {
+        self.download_dir
+            .clone()
+            .or_else(|| UserDirs::new().and_then(|d| d.download_dir().map(Path::to_path_buf)))
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    // Relative paths are taken from the config file's directory, where the
+    // script usually sits next to config.toml
+    pub fn script_path(&self) -> Option<PathBuf> {
+        let script = self.script.as_ref()?;
+        if script.is_absolute() {
+            return Some(script.clone());
+        }
+        let dir = Self::path().ok()?.parent()?.to_path_buf();
+        Some(dir.join(script))
+    }
+
+    // A missing file yields the default config so first runs can start setup.
+    // Unlike load_from, this also resolves secrets from the keyring and moves
+    // any plaintext ones left by older versions into it.
+    pub fn load() -> Result<Self> {
+        Self::load_at(&Self::path()?)
+    }
+
+    /// Like `load`, for a config file somewhere other than the default path.
+    pub fn load_at(path: &Path) -> Result<Self> {
+        let mut config = Self::load_from(path)?;
+        if config.plaintext_secrets {
+            return Ok(config);
+        }
+
+        let plaintext = config.client_secret.is_some() || config.refresh_token.is_some();
+        if config.client_secret.is_none() {
+            config.client_secret = secrets::get(secrets::CLIENT_SECRET);
+        }
+        if config.refresh_token.is_none() {
+            config.refresh_token = secrets::get(secrets::REFRESH_TOKEN);
+        }
+        if plaintext && path.exists() {
+            config.save_to_keyring(path)?;
+        }
+        Ok(config)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| anyhow!("Failed to parse config: {}", e))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_at(&Self::path()?)
+    }
+
+    /// Like `save`, for a config file somewhere other than the default path.
+    /// Unlike save_to, secrets go to the keyring unless `plaintext_secrets`.
+    pub fn save_at(&self, path: &Path) -> Result<()> {
+        if self.plaintext_secrets {
+            self.save_to(path)
+        } else {
+            self.save_to_keyring(path)
+        }
+    }
+
+    // Falls back to the plaintext file when no keyring is available (e.g. headless Linux)
+    fn save_to_keyring(&self, path: &Path) -> Result<()> {
+        let stored = [
+            (secrets::CLIENT_SECRET, &self.client_secret),
+            (secrets::REFRESH_TOKEN, &self.refresh_token),
+        ]
+        .into_iter()
+        .try_for_each(|(name, value)| match value {
+            Some(value) => secrets::set(name, value),
+            None => {
+                secrets::delete(name);
+                Ok(())
+            }
+        });
+
+        if let Err(e) = stored {
+            tracing::warn!("keyring unavailable, keeping secrets in config file: {}", e);
+            return self.save_to(path);
+        }
+
+        let mut public = self.clone();
+        public.client_secret = None;
+        public.refresh_token = None;
+        public.save_to(path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn has_credentials(&self) -> bool {
+        self.client_id.as_deref().is_some_and(|s| !s.is_empty())
+            && self.refresh_token.as_deref().is_some_and(|s| !s.is_empty())
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+// Meteorological seasons of the northern hemisphere, by first month
+const SEASONS: [(&str, u32); 5] = [
+    ("spring", 3),
+    ("summer", 6),
+    ("autumn", 9),
+    ("fall", 9),
+    ("winter", 12),
+];
+
+/// Resolves what a user typed into the period it names, as `[start, end)`.
+/// Besides `YYYY`, `YYYY-MM` and `YYYY-MM-DD` this understands `today`,
+/// `yesterday`, `this`/`last` week, month, year or season (`last summer`),
+/// month names (`june`, `jun 2023`) and `N days/weeks/months/years ago`.
+/// Relative inputs never point into the future.
+pub fn parse_fuzzy(input: &str, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+    let input = input.trim().to_lowercase();
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let invalid = || {
+        format!(
+            "Can't read '{}' (try 2023-06, last summer or 6 months ago)",
+            input.trim()
+        )
+    };
+    let day = |date: NaiveDate| date.succ_opt().map(|next| (date, next));
+
+    let period = match words[..] {
+        [] => None,
+        ["today"] => day(today),
+        ["yesterday"] => today.pred_opt().and_then(day),
+        [which @ ("this" | "last"), unit] => relative(unit, which == "last", today),
+        [count, unit, "ago"] => {
+            let count = match count {
+                "a" | "an" | "one" => 1,
+                n => n.parse::<u32>().map_err(|_| invalid())?,
+            };
+            ago(count, unit, today).and_then(day)
+        }
+        [name] if month_number(name).is_some() => month_name(name, None, today),
+        [name, year] if month_number(name).is_some() => {
+            let year = year.parse::<i32>().map_err(|_| invalid())?;
+            month_name(name, Some(year), today)
+        }
+        [season, year] if season_month(season).is_some() => {
+            let year = year.parse::<i32>().map_err(|_| invalid())?;
+            season_month(season).and_then(|month| season_in(year, month))
+        }
+        [date] => numeric(date),
+        _ => None,
+    };
+    period.ok_or_else(invalid)
+}
+
+fn numeric(input: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let numbers: Vec<u32> = input
+        .split('-')
+        .map(|p| p.parse::<u32>().ok())
+        .collect::<Option<_>>()?;
+    match numbers[..] {
+        [year] => {
+            let start = NaiveDate::from_ymd_opt(year as i32, 1, 1)?;
+            Some((start, start.with_year(year as i32 + 1)?))
+        }
+        [year, month] => {
+            let start = NaiveDate::from_ymd_opt(year as i32, month, 1)?;
+            Some((start, start.checked_add_months(Months::new(1))?))
+        }
+        [year, month, day] => {
+            let start = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+            Some((start, start.succ_opt()?))
+        }
+        _ => None,
+    }
+}
+
+fn ago(count: u32, unit: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match unit.trim_end_matches('s') {
+        "day" => today.checked_sub_signed(Duration::days(count as i64)),
+        "week" => today.checked_sub_signed(Duration::weeks(count as i64)),
+        "month" => today.checked_sub_months(Months::new(count)),
+        "year" => today.checked_sub_months(Months::new(count.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
+fn relative(unit: &str, last: bool, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    match unit {
+        "week" => {
+            let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            let start = if last {
+                monday - Duration::weeks(1)
+            } else {
+                monday
+            };
+            Some((start, start + Duration::weeks(1)))
+        }
+        "month" => {
+            let first = today.with_day(1)?;
+            let start = if last {
+                first.checked_sub_months(Months::new(1))?
+            } else {
+                first
+            };
+            Some((start, start.checked_add_months(Months::new(1))?))
+        }
+        "year" => {
+            let year = today.year() - i32::from(last);
+            numeric(&year.to_string())
+        }
+        season => {
+            let month = season_month(season)?;
+            // The season containing today, or the most recent one that started
+            let mut year = today.year();
+            let (mut start, _) = season_in(year, month)?;
+            while start > today {
+                year -= 1;
+                start = season_in(year, month)?.0;
+            }
+            if last {
+                let (_, end) = season_in(year, month)?;
+                if end > today {
+                    year -= 1;
+                }
+            }
+            season_in(year, month)
+        }
+    }
+}
+
+// Three months starting in `month` of `year`, so winter 2023 runs into 2024
+fn season_in(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+    Some((start, start.checked_add_months(Months::new(3))?))
+}
+
+fn season_month(name: &str) -> Option<u32> {
+    SEASONS
+        .iter()
+        .find(|(season, _)| *season == name)
+        .map(|&(_, month)| month)
+}
+
+// Full names and abbreviations of at least three letters
+fn month_number(name: &str) -> Option<u32> {
+    if name.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|month| month.starts_with(name))
+        .map(|i| i as u32 + 1)
+}
+
+// Without a year, the latest such month that isn't in the future
+fn month_name(name: &str, year: Option<i32>, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let month = month_number(name)?;
+    let year = year.unwrap_or(if month > today.month() {
+        today.year() - 1
+    } else {
+        today.year()
+    });
+    numeric(&format!("{}-{}", year, month))
+}
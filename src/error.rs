@@ -0,0 +1,176 @@
+use crate::api::types::ErrorResponse;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A failed Strava request, classified by what the caller can do about it:
+/// re-authorize, wait and retry, or just tell the user.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The refresh token was revoked or the app deauthorized
+    #[error("Strava no longer accepts SportFrei's authorization ({0}). Re-authorize to continue.")]
+    Auth(String),
+    /// Authorized, but without the scope this request needs
+    #[error("Strava didn't grant the '{0}' permission. Re-authorize and allow it.")]
+    MissingScope(String),
+    #[error("Strava's rate limit is used up{}", until(.reset))]
+    RateLimited { reset: Option<DateTime<Utc>> },
+    #[error("Not found on Strava: {0}")]
+    NotFound(String),
+    #[error("Strava API error {status}: {message}")]
+    Api { status: u16, message: String },
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Unexpected response from Strava: {0}")]
+    Parse(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+fn until(reset: &Option<DateTime<Utc>>) -> String {
+    reset
+        .map(|reset| {
+            format!(
+                " until {}",
+                reset.with_timezone(&chrono::Local).format("%H:%M")
+            )
+        })
+        .unwrap_or_default()
+}
+
+impl Error {
+    /// Only a new OAuth flow fixes these.
+    pub fn needs_reauth(&self) -> bool {
+        matches!(self, Error::Auth(_) | Error::MissingScope(_))
+    }
+
+    /// Worth trying again later without changing anything.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::RateLimited { .. } | Error::Network(_) => true,
+            Error::Api { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Classifies an unsuccessful response. `scope` is what the request
+    /// needs, reported when Strava answers 403 without saying more.
+    pub fn from_response(
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &str,
+        scope: &str,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let fault = serde_json::from_str::<ErrorResponse>(body).ok();
+        let message = fault
+            .as_ref()
+            .and_then(|f| f.message.clone())
+            .unwrap_or_else(|| body.trim().to_string());
+        // e.g. {"field": "activity:read_permission", "code": "missing"}
+        let missing_scope = fault
+            .iter()
+            .flat_map(|f| f.errors.iter().flatten())
+            .find_map(|e| e.field.strip_suffix("_permission"))
+            .map(str::to_string);
+
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN if missing_scope.is_some() => {
+                Error::MissingScope(missing_scope.unwrap_or_default())
+            }
+            StatusCode::UNAUTHORIZED => Error::Auth(message),
+            StatusCode::FORBIDDEN => Error::MissingScope(scope.to_string()),
+            StatusCode::NOT_FOUND => Error::NotFound(message),
+            StatusCode::TOO_MANY_REQUESTS => Error::RateLimited {
+                reset: rate_limit_reset(headers, now),
+            },
+            _ => Error::Api {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            Error::Parse(e.to_string())
+        } else {
+            Error::Network(e.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Parse(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Other(e.into())
+    }
+}
+
+// "15-minute,daily" numbers of a rate limit header
+fn header_pair(headers: &HeaderMap, name: &str) -> Option<Vec<u32>> {
+    headers
+        .get(name)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .map(|n| n.trim().parse().ok())
+        .collect()
+}
+
+/// Requests left in Strava's overall rate limit, as of the last response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// In the current 15-minute window
+    pub short_remaining: u32,
+    pub daily_remaining: u32,
+}
+
+impl RateLimit {
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let limit = header_pair(headers, "x-ratelimit-limit")?;
+        let usage = header_pair(headers, "x-ratelimit-usage")?;
+        let remaining = |i: usize| Some(limit.get(i)?.saturating_sub(*usage.get(i)?));
+        Some(Self {
+            short_remaining: remaining(0)?,
+            daily_remaining: remaining(1)?,
+        })
+    }
+}
+
+/// When the exhausted limit resets. Strava sends `limit` and `usage` as
+/// "15-minute,daily" pairs; the short window resets every quarter hour, the
+/// daily one at midnight UTC.
+pub fn rate_limit_reset(headers: &HeaderMap, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let pair = |name: &str| header_pair(headers, name);
+    let mut reset = None;
+    for prefix in ["x-ratelimit", "x-readratelimit"] {
+        let (Some(limit), Some(usage)) = (
+            pair(&format!("{}-limit", prefix)),
+            pair(&format!("{}-usage", prefix)),
+        ) else {
+            continue;
+        };
+        let exhausted =
+            |i: usize| matches!((limit.get(i), usage.get(i)), (Some(l), Some(u)) if u >= l);
+        let at = if exhausted(1) {
+            now.duration_trunc(Duration::days(1)).ok()? + Duration::days(1)
+        } else if exhausted(0) {
+            now.duration_trunc(Duration::minutes(15)).ok()? + Duration::minutes(15)
+        } else {
+            continue;
+        };
+        reset = reset.max(Some(at));
+    }
+    reset
+}
@@ -0,0 +1,583 @@
+use crate::analytics;
+use crate::api::polyline;
+use crate::api::types::{Activity, StreamSet};
+use crate::config::Units;
+use crate::plan::PlannedWorkout;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    Date,
+    Name,
+    Sport,
+    Distance,
+    MovingTime,
+    ElapsedTime,
+    Elevation,
+    Pace,
+    Speed,
+    HeartRate,
+    MaxHeartRate,
+    Calories,
+    Kudos,
+    Gear,
+}
+
+impl Column {
+    pub const ALL: [Column; 15] = [
+        Column::Id,
+        Column::Date,
+        Column::Name,
+        Column::Sport,
+        Column::Distance,
+        Column::MovingTime,
+        Column::ElapsedTime,
+        Column::Elevation,
+        Column::Pace,
+        Column::Speed,
+        Column::HeartRate,
+        Column::MaxHeartRate,
+        Column::Calories,
+        Column::Kudos,
+        Column::Gear,
+    ];
+
+    pub const DEFAULT: [Column; 9] = [
+        Column::Id,
+        Column::Date,
+        Column::Name,
+        Column::Sport,
+        Column::Distance,
+        Column::MovingTime,
+        Column::Elevation,
+        Column::Pace,
+        Column::HeartRate,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Date => "date",
+            Column::Name => "name",
+            Column::Sport => "sport",
+            Column::Distance => "distance",
+            Column::MovingTime => "moving_time",
+            Column::ElapsedTime => "elapsed_time",
+            Column::Elevation => "elevation",
+            Column::Pace => "pace",
+            Column::Speed => "speed",
+            Column::HeartRate => "heartrate",
+            Column::MaxHeartRate => "max_heartrate",
+            Column::Calories => "calories",
+            Column::Kudos => "kudos",
+            Column::Gear => "gear",
+        }
+    }
+
+    // Unit-dependent columns carry the unit in the header so spreadsheets stay unambiguous
+    fn header(&self, units: Units) -> String {
+        match self {
+            Column::Distance => format!("distance_{}", units.distance_unit()),
+            Column::Elevation => format!("elevation_{}", units.elevation_unit()),
+            Column::Pace => format!("pace_per_{}", units.distance_unit()),
+            Column::Speed => format!("speed_{}", units.speed_unit().replace('/', "")),
+            Column::MovingTime | Column::ElapsedTime => format!("{}_s", self.name()),
+            _ => self.name().to_string(),
+        }
+    }
+
+    fn value(&self, activity: &Activity, units: Units) -> Value {
+        let round = |v: f64, digits: i32| {
+            let factor = 10f64.powi(digits);
+            Value::from((v * factor).round() / factor)
+        };
+        match self {
+            Column::Id => Value::from(activity.id),
+            Column::Date => Value::from(activity.start_date_local.to_rfc3339()),
+            Column::Name => Value::from(activity.name.clone()),
+            Column::Sport => Value::from(activity.sport_type.clone()),
+            Column::Distance => round(units.distance(activity.distance), 3),
+            Column::MovingTime => Value::from(activity.moving_time),
+            Column::ElapsedTime => Value::from(activity.elapsed_time),
+            Column::Elevation => round(units.elevation(activity.total_elevation_gain), 1),
+            Column::Pace => {
+                let distance = units.distance(activity.distance);
+                if distance > 0.0 {
+                    let secs = activity.moving_time as f64 / distance;
+                    Value::from(format!(
+                        "{}:{:02}",
+                        (secs / 60.0) as u32,
+                        (secs % 60.0) as u32
+                    ))
+                } else {
+                    Value::Null
+                }
+            }
+            Column::Speed => activity
+                .average_speed
+                .map_or(Value::Null, |s| round(units.speed(s), 2)),
+            Column::HeartRate => activity.average_heartrate.map_or(Value::Null, Value::from),
+            Column::MaxHeartRate => activity.max_heartrate.map_or(Value::Null, Value::from),
+            Column::Calories => activity.calories.map_or(Value::Null, Value::from),
+            Column::Kudos => activity.kudos_count.map_or(Value::Null, Value::from),
+            Column::Gear => activity.gear_id.clone().map_or(Value::Null, Value::from),
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+        Column::ALL
+            .into_iter()
+            .find(|c| c.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Column::ALL.iter().map(|c| c.name()).collect();
+                anyhow!(
+                    "unknown column '{}' (expected one of: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+pub fn to_json(activities: &[Activity], columns: &[Column], units: Units) -> Result<String> {
+    let rows: Vec<Value> = activities
+        .iter()
+        .map(|activity| {
+            let row: Map<String, Value> = columns
+                .iter()
+                .map(|c| (c.header(units), c.value(activity, units)))
+                .collect();
+            Value::Object(row)
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&rows)?)
+}
+
+pub fn to_csv(activities: &[Activity], columns: &[Column], units: Units) -> String {
+    let mut out = columns
+        .iter()
+        .map(|c| c.header(units))
+        .collect::<Vec<_>>()
+        .join(",");
+    out.push('\n');
+
+    for activity in activities {
+        let line = columns
+            .iter()
+            .map(|c| csv_field(c.value(activity, units)))
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out
+}
+
+fn csv_field(value: Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_escape(&s),
+        other => other.to_string(),
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// One activity of `export-all` with what only SportFrei knows about it.
+#[derive(Debug, Serialize)]
+pub struct ArchivedActivity<'a> {
+    #[serde(flatten)]
+    pub activity: &'a Activity,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<&'a str>,
+}
+
+/// The full Strava objects plus descriptions, tags and notes.
+pub fn to_archive_json(archive: &[ArchivedActivity]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(archive)?)
+}
+
+/// Every column, then description, tags (space separated) and note.
+pub fn to_archive_csv(archive: &[ArchivedActivity], units: Units) -> String {
+    let mut headers: Vec<String> = Column::ALL.iter().map(|c| c.header(units)).collect();
+    headers.extend(["description", "tags", "note"].map(String::from));
+    let mut out = headers.join(",");
+    out.push('\n');
+
+    for entry in archive {
+        let mut fields: Vec<String> = Column::ALL
+            .iter()
+            .map(|c| csv_field(c.value(entry.activity, units)))
+            .collect();
+        fields.push(csv_escape(
+            entry.activity.description.as_deref().unwrap_or_default(),
+        ));
+        fields.push(csv_escape(&entry.tags.join(" ")));
+        fields.push(csv_escape(entry.note.unwrap_or_default()));
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+
+    out
+}
+
+/// File name of an activity's track in a `gpx-dir` export, e.g.
+/// `2024-01-15-12345678.gpx`, so a directory listing sorts by date.
+pub fn gpx_file_name(activity: &Activity) -> String {
+    format!(
+        "{}-{}.gpx",
+        activity.start_date_local.format("%Y-%m-%d"),
+        activity.id
+    )
+}
+
+/// The activity as a GPX track: every recorded point with elevation and time
+/// when its streams are at hand, otherwise the simplified line of its map.
+/// None for activities without a location, like manual entries.
+pub fn to_gpx(activity: &Activity, streams: Option<&StreamSet>) -> Option<String> {
+    let mut points = Vec::new();
+    if let Some((streams, latlng)) = streams.and_then(|s| Some((s, s.latlng.as_ref()?))) {
+        for (i, [lat, lon]) in latlng.data.iter().enumerate() {
+            let mut point = format!("<trkpt lat=\"{:.6}\" lon=\"{:.6}\">", lat, lon);
+            if let Some(ele) = streams.altitude.as_ref().and_then(|s| s.data.get(i)) {
+                point.push_str(&format!("<ele>{:.1}</ele>", ele));
+            }
+            if let Some(offset) = streams.time.as_ref().and_then(|s| s.data.get(i)) {
+                let time = activity.start_date + Duration::seconds(*offset as i64);
+                point.push_str(&format!(
+                    "<time>{}</time>",
+                    time.format("%Y-%m-%dT%H:%M:%SZ")
+                ));
+            }
+            point.push_str("</trkpt>");
+            points.push(point);
+        }
+    } else {
+        let line = activity.map.as_ref()?.summary_polyline.as_deref()?;
+        points.extend(
+            polyline::decode(line)
+                .into_iter()
+                .map(|(lat, lon)| format!("<trkpt lat=\"{:.5}\" lon=\"{:.5}\"/>", lat, lon)),
+        );
+    }
+    if points.is_empty() {
+        return None;
+    }
+
+    let mut gpx = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <gpx version=\"1.1\" creator=\"SportFrei\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n\
+         <metadata><time>{}</time></metadata>\n\
+         <trk><name>{}</name><type>{}</type><trkseg>\n",
+        activity.start_date.format("%Y-%m-%dT%H:%M:%SZ"),
+        xml_escape(&activity.name),
+        xml_escape(&activity.sport_type)
+    );
+    for point in points {
+        gpx.push_str(&point);
+        gpx.push('\n');
+    }
+    gpx.push_str("</trkseg></trk>\n</gpx>\n");
+    Some(gpx)
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Title of calendar events unless `calendar_event_name` says otherwise.
+/// Placeholders: `{name}`, `{sport}`, `{distance}`, `{duration}`.
+pub const DEFAULT_EVENT_NAME: &str = "{sport}: {name}";
+// RFC 5545 limits lines to 75 octets, continued on lines starting with a space
+const ICS_LINE_OCTETS: usize = 75;
+
+fn event_name(template: &str, activity: &Activity, units: Units) -> String {
+    template
+        .replace("{name}", &activity.name)
+        .replace("{sport}", &activity.sport_type)
+        .replace(
+            "{distance}",
+            &format!(
+                "{:.1} {}",
+                units.distance(activity.distance),
+                units.distance_unit()
+            ),
+        )
+        .replace("{duration}", &format_hms(activity.moving_time))
+}
+
+fn format_hms(seconds: u32) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+/// Activities as timed events and planned workouts as all-day events in one
+/// iCalendar file. `stamp` is when the file was generated.
+pub fn to_ics(
+    activities: &[Activity],
+    plan: &[PlannedWorkout],
+    event_name_template: &str,
+    units: Units,
+    stamp: DateTime<Utc>,
+) -> String {
+    let utc = |time: DateTime<Utc>| time.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//SportFrei//Activities//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for activity in activities {
+        let end = activity.start_date + Duration::seconds(activity.elapsed_time as i64);
+        let url = format!("https://www.strava.com/activities/{}", activity.id);
+        let description = format!(
+            "{:.2} {} in {} moving, {:.0} {} elevation\n{}",
+            units.distance(activity.distance),
+            units.distance_unit(),
+            format_hms(activity.moving_time),
+            units.elevation(activity.total_elevation_gain),
+            units.elevation_unit(),
+            url
+        );
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:activity-{}@sportfrei", activity.id),
+            format!("DTSTAMP:{}", utc(stamp)),
+            format!("DTSTART:{}", utc(activity.start_date)),
+            format!("DTEND:{}", utc(end)),
+            format!(
+                "SUMMARY:{}",
+                ics_escape(&event_name(event_name_template, activity, units))
+            ),
+            format!("DESCRIPTION:{}", ics_escape(&description)),
+            format!("URL:{}", url),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    for (i, workout) in plan.iter().enumerate() {
+        let target = workout.target_label(units);
+        let summary = match &workout.name {
+            Some(name) => format!("Planned: {} ({})", name, target),
+            None => format!("Planned: {}", target),
+        };
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:plan-{}-{}@sportfrei", workout.date.format("%Y%m%d"), i),
+            format!("DTSTAMP:{}", utc(stamp)),
+            format!("DTSTART;VALUE=DATE:{}", workout.date.format("%Y%m%d")),
+            format!("SUMMARY:{}", ics_escape(&summary)),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_ics_line(line))
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// Splits long lines without breaking a UTF-8 character
+fn fold_ics_line(line: &str) -> String {
+    let mut out = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > ICS_LINE_OCTETS {
+            out.push_str("\r\n ");
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+// Inside width of the activity card, and the size of its map thumbnail
+const CARD_WIDTH: usize = 40;
+const CARD_MAP_HEIGHT: usize = 10;
+
+/// A boxed text card of one activity for pasting into chats: name, sport and
+/// date, the main figures, a map thumbnail drawn with `*` (`S` start, `F`
+/// finish) and the link. `ansi` adds colors for terminals.
+pub fn to_card(activity: &Activity, units: Units, ansi: bool) -> String {
+    let paint = |text: String, code: &str| {
+        if ansi {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text
+        }
+    };
+    let border = format!("+{}+", "-".repeat(CARD_WIDTH + 2));
+    let line = |text: String, code: &str| {
+        let text: String = text.chars().take(CARD_WIDTH).collect();
+        let padding = CARD_WIDTH - text.chars().count();
+        format!("| {}{} |", paint(text, code), " ".repeat(padding))
+    };
+
+    let distance = units.distance(activity.distance);
+    let mut figures = vec![
+        (
+            "Distance",
+            format!("{:.2} {}", distance, units.distance_unit()),
+        ),
+        ("Time", format_hms(activity.moving_time)),
+    ];
+    if analytics::is_on_foot(&activity.sport_type) && distance > 0.0 {
+        let secs = activity.moving_time as f64 / distance;
+        figures.push((
+            "Pace",
+            format!(
+                "{}:{:02} /{}",
+                (secs / 60.0) as u32,
+                (secs % 60.0) as u32,
+                units.distance_unit()
+            ),
+        ));
+    } else if let Some(speed) = activity.average_speed {
+        figures.push((
+            "Speed",
+            format!("{:.1} {}", units.speed(speed), units.speed_unit()),
+        ));
+    }
+    figures.push((
+        "Elevation",
+        format!(
+            "{:.0} {}",
+            units.elevation(activity.total_elevation_gain),
+            units.elevation_unit()
+        ),
+    ));
+    if let Some(heartrate) = activity.average_heartrate {
+        figures.push(("Heart rate", format!("{:.0} bpm", heartrate)));
+    }
+
+    let mut lines = vec![
+        border.clone(),
+        line(activity.name.clone(), "1"),
+        line(
+            format!(
+                "{} - {}",
+                activity.sport_type,
+                activity.start_date_local.format("%Y-%m-%d %H:%M")
+            ),
+            "2",
+        ),
+        line(String::new(), ""),
+    ];
+    for (label, value) in figures {
+        lines.push(line(format!("{:<11}{}", label, value), "36"));
+    }
+    let track = activity
+        .map
+        .as_ref()
+        .and_then(|map| map.polyline.as_deref().or(map.summary_polyline.as_deref()))
+        .map(polyline::decode)
+        .unwrap_or_default();
+    if track.len() > 1 {
+        lines.push(line(String::new(), ""));
+        for row in map_thumbnail(&track, CARD_WIDTH, CARD_MAP_HEIGHT) {
+            lines.push(line(row, "33"));
+        }
+    }
+    lines.push(line(String::new(), ""));
+    lines.push(line(format!("strava.com/activities/{}", activity.id), "2"));
+    lines.push(border);
+
+    let mut card = lines.join("\n");
+    card.push('\n');
+    card
+}
+
+// Draws the track into a `width` x `height` character grid, keeping its
+// shape: longitude is scaled by latitude, and a character cell is about
+// twice as tall as it is wide
+fn map_thumbnail(track: &[(f64, f64)], width: usize, height: usize) -> Vec<String> {
+    let mid_lat = track.iter().map(|p| p.0).sum::<f64>() / track.len() as f64;
+    let points: Vec<(f64, f64)> = track
+        .iter()
+        .map(|&(lat, lng)| (lng * mid_lat.to_radians().cos(), lat))
+        .collect();
+    let (min_x, max_x) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+        (lo.min(p.0), hi.max(p.0))
+    });
+    let (min_y, max_y) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+        (lo.min(p.1), hi.max(p.1))
+    });
+    let span_x = (max_x - min_x).max(f64::EPSILON);
+    let span_y = (max_y - min_y).max(f64::EPSILON);
+    let scale = ((width - 1) as f64 / span_x).min((height - 1) as f64 * 2.0 / span_y);
+    // Centered in the grid
+    let offset_x = ((width - 1) as f64 - span_x * scale) / 2.0;
+    let offset_y = ((height - 1) as f64 - span_y * scale / 2.0) / 2.0;
+    let cell = |p: &(f64, f64)| {
+        (
+            offset_x + (p.0 - min_x) * scale,
+            offset_y + (max_y - p.1) * scale / 2.0,
+        )
+    };
+
+    let mut grid = vec![vec![' '; width]; height];
+    let mut plot = |x: f64, y: f64, c: char| {
+        let (col, row) = (x.round() as usize, y.round() as usize);
+        if row < height && col < width {
+            grid[row][col] = c;
+        }
+    };
+    // Sampled finely enough between points that the line has no gaps
+    for pair in points.windows(2) {
+        let (x0, y0) = cell(&pair[0]);
+        let (x1, y1) = cell(&pair[1]);
+        let steps = ((x1 - x0).abs().max((y1 - y0).abs()) * 2.0).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            plot(x0 + (x1 - x0) * t, y0 + (y1 - y0) * t, '*');
+        }
+    }
+    let (x, y) = cell(&points[points.len() - 1]);
+    plot(x, y, 'F');
+    let (x, y) = cell(&points[0]);
+    plot(x, y, 'S');
+
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+        .collect()
+}
@@ -0,0 +1,275 @@
+use crate::api::types::{Stream, StreamSet};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+// FIT timestamps count seconds from 1989-12-31 00:00 UTC
+const FIT_EPOCH: i64 = 631_065_600;
+const RECORD_MESSAGE: u16 = 20;
+const SEMICIRCLES_PER_DEGREE: f64 = 2_147_483_648.0 / 180.0;
+
+/// One sample of a FIT file's `record` messages, with the units of Strava's
+/// streams: degrees, meters, m/s, bpm, rpm and watts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FitRecord {
+    pub timestamp: Option<DateTime<Utc>>,
+    pub position: Option<[f64; 2]>,
+    pub altitude: Option<f64>,
+    pub distance: Option<f64>,
+    pub speed: Option<f64>,
+    pub heartrate: Option<f64>,
+    pub cadence: Option<f64>,
+    pub power: Option<f64>,
+}
+
+/// Decodes a FIT file, unpacking it first when it ends in `.gz`.
+pub fn read_file(path: &Path) -> Result<Vec<FitRecord>> {
+    let mut data = fs::read(path).with_context(|| format!("can't read {}", path.display()))?;
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        let mut unpacked = Vec::new();
+        GzDecoder::new(data.as_slice()).read_to_end(&mut unpacked)?;
+        data = unpacked;
+    }
+    decode(&data).with_context(|| format!("can't decode {}", path.display()))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FieldDefinition {
+    number: u8,
+    size: usize,
+    base_type: u8,
+}
+
+#[derive(Debug, Clone)]
+struct Definition {
+    global: u16,
+    big_endian: bool,
+    fields: Vec<FieldDefinition>,
+    // Developer fields are skipped, so only their total size matters
+    developer_size: usize,
+}
+
+/// The `record` messages of a FIT file, the per-second samples a device
+/// writes while recording. Other messages are skipped; a file cut off in the
+/// middle of a message keeps the samples before it.
+pub fn decode(data: &[u8]) -> Result<Vec<FitRecord>> {
+    if data.len() < 12 || &data[8..12] != b".FIT" {
+        bail!("not a FIT file");
+    }
+    let mut records = Vec::new();
+    let mut rest = data;
+    // Some devices chain several FIT files into one
+    while rest.len() >= 12 {
+        let header_size = rest[0] as usize;
+        if header_size < 12 || rest.len() < header_size || &rest[8..12] != b".FIT" {
+            break;
+        }
+        let data_size = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+        let end = (header_size + data_size).min(rest.len());
+        decode_messages(&rest[header_size..end], &mut records);
+        // Skips the file's CRC
+        rest = &rest[(end + 2).min(rest.len())..];
+    }
+    Ok(records)
+}
+
+fn decode_messages(mut data: &[u8], records: &mut Vec<FitRecord>) {
+    let mut definitions: [Option<Definition>; 16] = Default::default();
+    let mut last_timestamp: Option<u32> = None;
+    while let Some((&header, body)) = data.split_first() {
+        data = body;
+        // Compressed timestamp headers carry the low five bits of the time
+        let (local, time_offset) = if header & 0x80 != 0 {
+            ((header >> 5) & 0x03, Some(u32::from(header & 0x1f)))
+        } else if header & 0x40 != 0 {
+            let Some((definition, rest)) = read_definition(data, header & 0x20 != 0) else {
+                return;
+            };
+            definitions[(header & 0x0f) as usize] = Some(definition);
+            data = rest;
+            continue;
+        } else {
+            (header & 0x0f, None)
+        };
+
+        let Some(definition) = &definitions[local as usize] else {
+            return;
+        };
+        let size =
+            definition.fields.iter().map(|f| f.size).sum::<usize>() + definition.developer_size;
+        if data.len() < size {
+            return;
+        }
+        let (message, rest) = data.split_at(size);
+        data = rest;
+
+        let mut timestamp = time_offset.and_then(|offset| {
+            let last = last_timestamp?;
+            let mut time = (last & !0x1f) + offset;
+            if offset < last & 0x1f {
+                time += 0x20;
+            }
+            Some(time)
+        });
+        let mut record = FitRecord::default();
+        let (mut lat, mut lng) = (None, None);
+        let mut offset = 0;
+        for field in &definition.fields {
+            let bytes = &message[offset..offset + field.size];
+            offset += field.size;
+            let Some(value) = read_value(bytes, field.base_type, definition.big_endian) else {
+                continue;
+            };
+            if field.number == 253 {
+                timestamp = Some(value as u32);
+                continue;
+            }
+            if definition.global != RECORD_MESSAGE {
+                continue;
+            }
+            match field.number {
+                0 => lat = Some(value / SEMICIRCLES_PER_DEGREE),
+                1 => lng = Some(value / SEMICIRCLES_PER_DEGREE),
+                2 => {
+                    record.altitude.get_or_insert(value / 5.0 - 500.0);
+                }
+                3 => record.heartrate = Some(value),
+                4 => record.cadence = Some(value),
+                5 => record.distance = Some(value / 100.0),
+                6 => {
+                    record.speed.get_or_insert(value / 1000.0);
+                }
+                7 => record.power = Some(value),
+                // The enhanced fields have more range and win over the plain ones
+                73 => record.speed = Some(value / 1000.0),
+                78 => record.altitude = Some(value / 5.0 - 500.0),
+                _ => {}
+            }
+        }
+        if timestamp.is_some() {
+            last_timestamp = timestamp;
+        }
+        if definition.global == RECORD_MESSAGE {
+            record.position = lat.zip(lng).map(|(lat, lng)| [lat, lng]);
+            record.timestamp =
+                timestamp.and_then(|t| DateTime::from_timestamp(FIT_EPOCH + i64::from(t), 0));
+            records.push(record);
+        }
+    }
+}
+
+fn read_definition(data: &[u8], developer: bool) -> Option<(Definition, &[u8])> {
+    let (fixed, mut rest) = data.split_at_checked(5)?;
+    let big_endian = fixed[1] == 1;
+    let global = if big_endian {
+        u16::from_be_bytes([fixed[2], fixed[3]])
+    } else {
+        u16::from_le_bytes([fixed[2], fixed[3]])
+    };
+    let (fields, after) = rest.split_at_checked(fixed[4] as usize * 3)?;
+    rest = after;
+    let fields = fields
+        .chunks(3)
+        .map(|f| FieldDefinition {
+            number: f[0],
+            size: f[1] as usize,
+            base_type: f[2],
+        })
+        .collect();
+    let mut developer_size = 0;
+    if developer {
+        let (&count, after) = rest.split_first()?;
+        let (developer_fields, after) = after.split_at_checked(count as usize * 3)?;
+        developer_size = developer_fields.chunks(3).map(|f| f[1] as usize).sum();
+        rest = after;
+    }
+    Some((
+        Definition {
+            global,
+            big_endian,
+            fields,
+            developer_size,
+        },
+        rest,
+    ))
+}
+
+// A numeric field, or None for FIT's "invalid" marker and for types that
+// aren't numbers. Arrays are read as their first element.
+fn read_value(bytes: &[u8], base_type: u8, big_endian: bool) -> Option<f64> {
+    let width = match base_type & 0x1f {
+        0x00..=0x02 | 0x0a | 0x0d => 1,
+        0x03 | 0x04 | 0x0b => 2,
+        0x05 | 0x06 | 0x0c => 4,
+        _ => return None,
+    };
+    let bytes = bytes.get(..width)?;
+    let mut raw = [0u8; 4];
+    raw[..width].copy_from_slice(bytes);
+    let value = if big_endian {
+        bytes.iter().fold(0u32, |v, b| (v << 8) | u32::from(*b))
+    } else {
+        u32::from_le_bytes(raw)
+    };
+    match base_type & 0x1f {
+        0x01 => (value != 0x7f).then(|| f64::from(value as u8 as i8)),
+        0x03 => (value != 0x7fff).then(|| f64::from(value as u16 as i16)),
+        0x05 => (value != 0x7fff_ffff).then(|| f64::from(value as i32)),
+        0x0a..=0x0c => (value != 0).then(|| f64::from(value)),
+        _ => {
+            let invalid = u32::MAX >> (32 - width * 8);
+            (value != invalid).then(|| f64::from(value))
+        }
+    }
+}
+
+/// The records as streams like the API's, one sample per record. Gaps are
+/// filled with the previous value (or the first one, at the start), and a
+/// stream the device didn't record at all is left out.
+pub fn to_streams(records: &[FitRecord]) -> StreamSet {
+    let start = records.iter().find_map(|r| r.timestamp);
+    let time = start.map(|start| {
+        filled(records, |r| {
+            r.timestamp.map(|t| (t - start).num_seconds().max(0) as u32)
+        })
+    });
+    StreamSet {
+        time: time.flatten().map(stream),
+        distance: filled(records, |r| r.distance).map(stream),
+        heartrate: filled(records, |r| r.heartrate).map(stream),
+        altitude: filled(records, |r| r.altitude).map(stream),
+        velocity_smooth: filled(records, |r| r.speed).map(stream),
+        cadence: filled(records, |r| r.cadence).map(stream),
+        watts: filled(records, |r| r.power).map(stream),
+        latlng: filled(records, |r| r.position).map(stream),
+    }
+}
+
+fn filled<T: Copy>(
+    records: &[FitRecord],
+    value: impl Fn(&FitRecord) -> Option<T>,
+) -> Option<Vec<T>> {
+    let first = records.iter().find_map(&value)?;
+    let mut last = first;
+    Some(
+        records
+            .iter()
+            .map(|r| {
+                last = value(r).unwrap_or(last);
+                last
+            })
+            .collect(),
+    )
+}
+
+fn stream<T>(data: Vec<T>) -> Stream<T> {
+    Stream {
+        original_size: Some(data.len() as u32),
+        data,
+        series_type: Some("time".to_string()),
+        resolution: Some("high".to_string()),
+    }
+}
@@ -0,0 +1,102 @@
+use crate::api::types::Activity;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Days over which fitness (chronic training load) is averaged
+pub const FITNESS_DAYS: f64 = 42.0;
+/// Days over which fatigue (acute training load) is averaged
+pub const FATIGUE_DAYS: f64 = 7.0;
+// Used for TRIMP when the config doesn't set a resting heart rate
+const DEFAULT_RESTING_HEARTRATE: f64 = 60.0;
+
+/// Heart rate range used to derive TRIMP for activities without a relative
+/// effort score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeartRateRange {
+    pub resting: f64,
+    pub max: f64,
+}
+
+impl HeartRateRange {
+    /// Uses the configured values where set. Without a configured maximum the
+    /// highest heart rate recorded in `activities` stands in for it.
+    pub fn resolve<'a>(
+        resting: Option<u32>,
+        max: Option<u32>,
+        activities: impl IntoIterator<Item = &'a Activity>,
+    ) -> Option<Self> {
+        let resting = resting.map_or(DEFAULT_RESTING_HEARTRATE, f64::from);
+        let max = match max {
+            Some(max) => f64::from(max),
+            None => activities
+                .into_iter()
+                .filter_map(|a| a.max_heartrate)
+                .reduce(f64::max)?,
+        };
+        (max > resting).then_some(Self { resting, max })
+    }
+}
+
+/// Training load of one activity: Strava's relative effort when present,
+/// otherwise Banister's TRIMP from the average heart rate.
+pub fn training_load(activity: &Activity, heart_rate: Option<HeartRateRange>) -> Option<f64> {
+    if let Some(score) = activity.suffer_score {
+        return Some(score);
+    }
+    let range = heart_rate?;
+    let average = activity.average_heartrate?;
+    let reserve = ((average - range.resting) / (range.max - range.resting)).clamp(0.0, 1.0);
+    let minutes = activity.moving_time as f64 / 60.0;
+    Some(minutes * reserve * 0.64 * (1.92 * reserve).exp())
+}
+
+/// Fitness, fatigue and form at the end of one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitnessDay {
+    pub date: NaiveDate,
+    pub load: f64,
+    /// Chronic training load
+    pub fitness: f64,
+    /// Acute training load
+    pub fatigue: f64,
+    /// Training stress balance: yesterday's fitness minus yesterday's
+    /// fatigue, so today's workout doesn't count against today's form
+    pub form: f64,
+}
+
+/// Day-by-day training load model from the first activity with a known load
+/// up to `until`. Empty when no activity has one.
+pub fn fitness_history<'a>(
+    activities: impl IntoIterator<Item = &'a Activity>,
+    heart_rate: Option<HeartRateRange>,
+    until: NaiveDate,
+) -> Vec<FitnessDay> {
+    let mut loads: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for activity in activities {
+        if let Some(load) = training_load(activity, heart_rate) {
+            *loads
+                .entry(activity.start_date_local.date_naive())
+                .or_insert(0.0) += load;
+        }
+    }
+    let Some(&first) = loads.keys().next() else {
+        return Vec::new();
+    };
+
+    let mut days = Vec::new();
+    let (mut fitness, mut fatigue) = (0.0, 0.0);
+    for date in first.iter_days().take_while(|d| *d <= until) {
+        let load = loads.get(&date).copied().unwrap_or(0.0);
+        let form = fitness - fatigue;
+        fitness += (load - fitness) / FITNESS_DAYS;
+        fatigue += (load - fatigue) / FATIGUE_DAYS;
+        days.push(FitnessDay {
+            date,
+            load,
+            fitness,
+            fatigue,
+            form,
+        });
+    }
+    days
+}
@@ -0,0 +1,126 @@
+use crate::api::types::Activity;
+use crate::config::Units;
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalPeriod {
+    Week,
+    Month,
+    Year,
+}
+
+impl GoalPeriod {
+    /// First and last day of the period containing `today`.
+    pub fn bounds(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let start = match self {
+            GoalPeriod::Week => {
+                today - Duration::days(today.weekday().num_days_from_monday() as i64)
+            }
+            GoalPeriod::Month => today.with_day(1).unwrap_or(today),
+            GoalPeriod::Year => today.with_ordinal(1).unwrap_or(today),
+        };
+        let next = match self {
+            GoalPeriod::Week => Some(start + Duration::weeks(1)),
+            GoalPeriod::Month => start.checked_add_months(chrono::Months::new(1)),
+            GoalPeriod::Year => start.with_year(start.year() + 1),
+        };
+        let end = next.and_then(|d| d.pred_opt()).unwrap_or(today);
+        (start, end)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GoalPeriod::Week => "Weekly",
+            GoalPeriod::Month => "Monthly",
+            GoalPeriod::Year => "Annual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalMetric {
+    Distance,
+    Time,
+}
+
+/// A distance or time target for a week, month or year. Distance targets are
+/// in the configured units (km or mi), time targets in hours.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub period: GoalPeriod,
+    pub metric: GoalMetric,
+    pub target: f64,
+    // Matches Strava's sport_type, e.g. "Run"; all sports when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sport: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub done: f64,
+    /// Where the period ends if the pace so far is kept up
+    pub projected: f64,
+}
+
+impl GoalProgress {
+    pub fn ratio(&self, goal: &Goal) -> f64 {
+        if goal.target > 0.0 {
+            (self.done / goal.target).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+impl Goal {
+    pub fn label(&self, units: Units) -> String {
+        let unit = match self.metric {
+            GoalMetric::Distance => units.distance_unit(),
+            GoalMetric::Time => "h",
+        };
+        format!(
+            "{} {} {}{}",
+            self.period.name(),
+            self.sport.as_deref().unwrap_or("all sports"),
+            self.target,
+            unit
+        )
+    }
+
+    pub fn matches(&self, activity: &Activity) -> bool {
+        self.sport
+            .as_deref()
+            .is_none_or(|s| activity.sport_type.eq_ignore_ascii_case(s))
+    }
+
+    pub fn progress<'a>(
+        &self,
+        activities: impl IntoIterator<Item = &'a Activity>,
+        today: NaiveDate,
+        units: Units,
+    ) -> GoalProgress {
+        let (start, end) = self.period.bounds(today);
+        let done: f64 = activities
+            .into_iter()
+            .filter(|a| {
+                let date = a.start_date_local.date_naive();
+                date >= start && date <= today && self.matches(a)
+            })
+            .map(|a| match self.metric {
+                GoalMetric::Distance => units.distance(a.distance),
+                GoalMetric::Time => a.moving_time as f64 / 3600.0,
+            })
+            .sum();
+
+        let elapsed = (today - start).num_days() + 1;
+        let total = (end - start).num_days() + 1;
+        GoalProgress {
+            done,
+            projected: done * total as f64 / elapsed as f64,
+        }
+    }
+}
@@ -0,0 +1,775 @@
+use chrono::{Datelike, Duration, IsoWeek, NaiveDate, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt::Display;
+
+/// Languages the UI can be shown in, set with `language` in the config or
+/// taken from the locale. Texts are looked up by their English wording, so
+/// anything a catalog lacks stays English.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+impl Language {
+    /// The language of `LC_ALL`, `LC_MESSAGES` or `LANG`, English if none
+    /// of them names a supported one.
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// e.g. `de_DE.UTF-8` or `de-AT`
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let code = locale
+            .split(['_', '-', '.', '@'])
+            .next()?
+            .to_ascii_lowercase();
+        match code.as_str() {
+            "en" => Some(Language::English),
+            "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    pub fn tr(self, text: &'static str) -> &'static str {
+        match self {
+            Language::English => text,
+            Language::German => german(text).unwrap_or(text),
+        }
+    }
+
+    /// Translates `template` and fills its `{}` placeholders in order.
+    pub fn trf(self, template: &'static str, args: &[&dyn Display]) -> String {
+        fill(self.tr(template), args)
+    }
+}
+
+/// How dates are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateStyle {
+    /// 2024-06-30
+    #[default]
+    Iso,
+    /// 30.06.2024
+    Dotted,
+    /// 30/06/2024
+    DayMonth,
+    /// 06/30/2024
+    MonthDay,
+}
+
+/// The clock times are shown with, set with `time_format` in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimeFormat {
+    #[default]
+    #[serde(rename = "24h")]
+    TwentyFourHour,
+    #[serde(rename = "12h")]
+    TwelveHour,
+}
+
+/// Regional conventions for dates, decimals and weeks, set with `locale` in
+/// the config or taken from `LC_ALL`, `LC_TIME` or `LANG`. Regions without
+/// a known convention get ISO dates and weeks starting on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    pub date_style: DateStyle,
+    pub decimal_comma: bool,
+    pub first_weekday: Weekday,
+    pub time_format: TimeFormat,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            date_style: DateStyle::Iso,
+            decimal_comma: false,
+            first_weekday: Weekday::Mon,
+            time_format: TimeFormat::TwentyFourHour,
+        }
+    }
+}
+
+impl Locale {
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// e.g. `de_DE.UTF-8`, `en-GB` or just `de`
+    pub fn from_locale(locale: &str) -> Self {
+        let mut parts = locale
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .split(['_', '-']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts.next().unwrap_or_default().to_ascii_uppercase();
+        let (date_style, decimal_comma, first_weekday) = match (language.as_str(), region.as_str())
+        {
+            ("de", "CH") => (DateStyle::Dotted, false, Weekday::Mon),
+            ("de", _) => (DateStyle::Dotted, true, Weekday::Mon),
+            ("en", "US" | "CA" | "PH") => (DateStyle::MonthDay, false, Weekday::Sun),
+            ("en", "GB" | "IE" | "AU" | "NZ" | "IN") => (DateStyle::DayMonth, false, Weekday::Mon),
+            _ => return Self::default(),
+        };
+        let time_format = match region.as_str() {
+            "US" | "CA" | "PH" | "AU" | "NZ" | "IN" => TimeFormat::TwelveHour,
+            _ => TimeFormat::TwentyFourHour,
+        };
+        Self {
+            date_style,
+            decimal_comma,
+            first_weekday,
+            time_format,
+        }
+    }
+
+    /// Day and month, e.g. `30.06.` or `06/30`
+    pub fn short_date(&self, date: NaiveDate) -> String {
+        let format = match self.date_style {
+            DateStyle::Iso => "%m-%d",
+            DateStyle::Dotted => "%d.%m.",
+            DateStyle::DayMonth => "%d/%m",
+            DateStyle::MonthDay => "%m/%d",
+        };
+        date.format(format).to_string()
+    }
+
+    pub fn date(&self, date: NaiveDate) -> String {
+        let format = match self.date_style {
+            DateStyle::Iso => "%Y-%m-%d",
+            DateStyle::Dotted => "%d.%m.%Y",
+            DateStyle::DayMonth => "%d/%m/%Y",
+            DateStyle::MonthDay => "%m/%d/%Y",
+        };
+        date.format(format).to_string()
+    }
+
+    /// Hours and minutes, e.g. `18:05` or `6:05 PM`
+    pub fn time(&self, time: impl Timelike) -> String {
+        match self.time_format {
+            TimeFormat::TwentyFourHour => format!("{:02}:{:02}", time.hour(), time.minute()),
+            TimeFormat::TwelveHour => {
+                let (pm, hour) = time.hour12();
+                let suffix = if pm { "PM" } else { "AM" };
+                format!("{}:{:02} {}", hour, time.minute(), suffix)
+            }
+        }
+    }
+
+    /// `value` with `places` decimals and the local decimal separator.
+    pub fn decimal(&self, value: f64, places: usize) -> String {
+        let formatted = format!("{:.*}", places, value);
+        if self.decimal_comma {
+            formatted.replace('.', ",")
+        } else {
+            formatted
+        }
+    }
+
+    /// The first day of the week `date` falls in.
+    pub fn week_start(&self, date: NaiveDate) -> NaiveDate {
+        date - Duration::days(date.weekday().days_since(self.first_weekday) as i64)
+    }
+
+    /// Numbered like the ISO week its Monday falls in, so a week starting
+    /// on Sunday takes the number of the ISO week that follows.
+    pub fn week(&self, date: NaiveDate) -> IsoWeek {
+        let monday = Weekday::Mon.days_since(self.first_weekday) as i64;
+        (self.week_start(date) + Duration::days(monday)).iso_week()
+    }
+
+    /// The days of a week in display order.
+    pub fn weekdays(&self) -> [Weekday; 7] {
+        let mut day = self.first_weekday;
+        [(); 7].map(|_| {
+            let current = day;
+            day = day.succ();
+            current
+        })
+    }
+}
+
+/// The English short name the catalogs translate, e.g. `Mon`.
+pub fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Mon",
+        Weekday::Tue => "Tue",
+        Weekday::Wed => "Wed",
+        Weekday::Thu => "Thu",
+        Weekday::Fri => "Fri",
+        Weekday::Sat => "Sat",
+        Weekday::Sun => "Sun",
+    }
+}
+
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+fn german(text: &str) -> Option<&'static str> {
+    Some(match text {
+        // Views and navigation
+        "Dashboard" => "Übersicht",
+        "Activities" => "Aktivitäten",
+        "Segments" => "Segmente",
+        "Heatmap" => "Heatmap",
+        "Routes" => "Strecken",
+        "Plan" => "Plan",
+        "Next view" => "Nächste Ansicht",
+        "Copy Strava link" => "Strava-Link kopieren",
+        "Copy summary" => "Zusammenfassung kopieren",
+        "Link copied" => "Link kopiert",
+        "Summary copied" => "Zusammenfassung kopiert",
+        "{} {} in {} · {} · {} {} elevation" => "{} {} in {} · {} · {} {} Höhenmeter",
+        "Previous view" => "Vorherige Ansicht",
+        "Activity Details" => "Aktivitätsdetails",
+        "Training" => "Training",
+        "Gear" => "Ausrüstung",
+        "Achievements" => "Erfolge",
+        "Starred Segments" => "Favorisierte Segmente",
+        "Clubs" => "Clubs",
+        "Progress" => "Fortschritt",
+        "Activity Calendar" => "Aktivitätskalender",
+        "Fitness & Freshness" => "Fitness & Frische",
+        "Repeated Routes" => "Wiederholte Strecken",
+        "Training Plan" => "Trainingsplan",
+        "Sport Breakdown" => "Sportarten",
+        "Athlete Profile" => "Athletenprofil",
+        "Profile" => "Profil",
+        "Athlete profile" => "Athletenprofil",
+        "Following Feed" => "Feed der Gefolgten",
+        "Social" => "Sozial",
+        "Activities of athletes you follow" => "Aktivitäten von Athleten, denen du folgst",
+        "Give kudos on strava.com" => "Kudos auf strava.com geben",
+        "Next activity without your kudos" => "Nächste Aktivität ohne deine Kudos",
+        "Saved Routes" => "Gespeicherte Routen",
+        "Saved routes" => "Gespeicherte Routen",
+        "Download the route as GPX" => "Route als GPX herunterladen",
+        "Refreshing..." => "Aktualisiere...",
+        "[D]ashboard" => "[D]ashboard",
+        "[A]ctivities" => "[A]ktivitäten",
+        "[T]raining" => "[T]raining",
+        "Pr[o]gress" => "F[o]rtschritt",
+        "[Y]ear" => "Jahr [Y]",
+        "[F]itness" => "[F]itness",
+        "[R]outes" => "[R]outen",
+        "[W]orkout plan" => "Plan [W]",
+        "[B]ikes" => "Räder [B]",
+        "Sport [B]reakdown" => "Sportarten [B]",
+        "[P]Rs" => "[P]Bs",
+        "[S]egments" => "[S]egmente",
+        "[C]lubs" => "[C]lubs",
+        " [?]Help [Q]uit " => " [?]Hilfe [Q]Beenden ",
+        " {} errors - [L]og " => " {} Fehler - [L]og ",
+        "1 new activity" => "1 neue Aktivität",
+        "{} of {} activities" => "{} von {} Aktivitäten",
+        "{} activities" => "{} Aktivitäten",
+        "API {}/15 min, {} today" => "API {}/15 Min., {} heute",
+        "synced {}" => "synchronisiert {}",
+        "{} new activities" => "{} neue Aktivitäten",
+
+        // Desktop notifications
+        "New activity: {}" => "Neue Aktivität: {}",
+        "1 new kudos" => "1 neues Kudos",
+        "{} new kudos" => "{} neue Kudos",
+        "on {} ({} in total)" => "für {} ({} insgesamt)",
+        "New personal record" => "Neue persönliche Bestleistung",
+        "1 segment PR" => "1 Segment-PR",
+        "{} segment PRs" => "{} Segment-PRs",
+
+        // Watched folder uploads
+        "Uploading {}" => "Lade {} hoch",
+        "Strava is processing {}" => "Strava verarbeitet {}",
+        "Uploaded {}: {}, {} {}" => "{} hochgeladen: {}, {} {}",
+        "Failed to upload {}" => "Hochladen von {} fehlgeschlagen",
+
+        // Key bindings
+        "Keys - ?/Esc close" => "Tasten - ?/Esc schließen",
+        "Year heatmap" => "Jahreskalender",
+        "Fitness & freshness" => "Fitness & Frische",
+        "Repeated routes" => "Wiederholte Strecken",
+        "Training plan" => "Trainingsplan",
+        "Bikes & shoes" => "Räder & Schuhe",
+        "Breakdown by sport type" => "Aufteilung nach Sportart",
+        "PRs & achievements" => "Bestzeiten & Erfolge",
+        "Starred segments" => "Favorisierte Segmente",
+        "Hide / show commutes" => "Pendelfahrten aus- / einblenden",
+        "Hide / show private activities" => "Private Aktivitäten aus- / einblenden",
+        "Count / leave out indoor activities" => "Indoor-Aktivitäten mitzählen / weglassen",
+        "Moving / elapsed time" => "Bewegungs- / Gesamtzeit",
+        "12 / 24-hour clock" => "12- / 24-Stunden-Uhr",
+        "Recent errors" => "Letzte Fehler",
+        "This help" => "Diese Hilfe",
+        "Quit" => "Beenden",
+        "Next activity" => "Nächste Aktivität",
+        "Previous activity" => "Vorherige Aktivität",
+        "Open activity" => "Aktivität öffnen",
+        "Go to date (gg: first activity)" => "Zu Datum springen (gg: erste Aktivität)",
+        "First activity" => "Erste Aktivität",
+        "Last activity" => "Letzte Aktivität",
+        "Page down" => "Seite runter",
+        "Page up" => "Seite hoch",
+        "Scroll columns left" => "Spalten nach links",
+        "Scroll columns right" => "Spalten nach rechts",
+        "Filter by date range" => "Nach Zeitraum filtern",
+        "Sort" => "Sortieren",
+        "Group by week / month / off" => "Nach Woche / Monat / nicht gruppieren",
+        "Tag activity" => "Aktivität taggen",
+        "Filter by tag (cycles)" => "Nach Tag filtern (reihum)",
+        "Previous tab" => "Vorheriger Reiter",
+        "Next tab" => "Nächster Reiter",
+        "Edit activity" => "Aktivität bearbeiten",
+        "Cycle splits" => "Splits wechseln",
+        "Splits / laps" => "Splits / Runden",
+        "Write notes" => "Notizen schreiben",
+        "Mute activity" => "Aktivität stummschalten",
+        "Delete activity" => "Aktivität löschen",
+        "Back to the previous view" => "Zurück zur vorherigen Ansicht",
+        "Weeks / months" => "Wochen / Monate",
+        "Next segment" => "Nächstes Segment",
+        "Previous segment" => "Vorheriges Segment",
+        "Next route" => "Nächste Strecke",
+        "Previous route" => "Vorherige Strecke",
+        "Previous month" => "Vorheriger Monat",
+        "Next month" => "Nächster Monat",
+        "Next club activity" => "Nächste Club-Aktivität",
+        "Previous club activity" => "Vorherige Club-Aktivität",
+        "Previous club" => "Vorheriger Club",
+        "Next club" => "Nächster Club",
+
+        // Popups
+        "Go to Date" => "Gehe zu Datum",
+        "2023-06 · last summer · 6 months ago · Enter go · Esc cancel · g first activity" => {
+            "2023-06 · last summer · 6 months ago · Enter los · Esc abbrechen · g erste Aktivität"
+        }
+        "Jump to Date Range" => "Zeitraum wählen",
+        "YYYY[-MM[-DD]] · empty = open · Tab switch · Enter go · Esc cancel" => {
+            "JJJJ[-MM[-TT]] · leer = offen · Tab wechseln · Enter los · Esc abbrechen"
+        }
+        "From:" => "Von:",
+        "To:" => "Bis:",
+        "Start must be before end" => "Der Beginn muss vor dem Ende liegen",
+        "{} to {}" => "{} bis {}",
+        "start" => "Anfang",
+        "now" => "heute",
+        "up to {}" => "bis {}",
+        "Tags for {}" => "Tags für {}",
+        "activity" => "Aktivität",
+        "activities" => "Aktivitäten",
+        "race workout easy · space or comma separated · Enter save · Esc cancel" => {
+            "race workout easy · durch Leerzeichen oder Komma getrennt · Enter speichern · Esc abbrechen"
+        }
+        "No errors so far" => "Bisher keine Fehler",
+        "Recent Errors ({}) - L/Esc close" => "Letzte Fehler ({}) - L/Esc schließen",
+        "Mute Activity" => "Aktivität stummschalten",
+        "Hide '{}' from your followers' feeds?" => "'{}' im Feed deiner Follower ausblenden?",
+        "Delete Activity" => "Aktivität löschen",
+        "Delete '{}' from Strava? This can't be undone." => {
+            "'{}' von Strava löschen? Das kann nicht rückgängig gemacht werden."
+        }
+        "Authorization" => "Autorisierung",
+        "Strava no longer accepts SportFrei's authorization. Re-authorize in the browser now?" => {
+            "Strava akzeptiert die Autorisierung von SportFrei nicht mehr. Jetzt im Browser neu autorisieren?"
+        }
+        "[y] Yes   [n/Esc] No" => "[y] Ja   [n/Esc] Nein",
+
+        // Dashboard
+        "Welcome, {}!" => "Willkommen, {}!",
+        "Welcome! (loading athlete...)" => "Willkommen! (lade Athlet...)",
+        "Biggest Distance (all time)\n\n{} {} {}\n(runs + rides, last 4 weeks: {} {})" => {
+            "Größte Distanz (gesamt)\n\n{} {} {}\n(Läufe + Fahrten, letzte 4 Wochen: {} {})"
+        }
+        "Best Pace (last 30 days)\n\n{} /{} {}\n(vs {} best of {} cached)" => {
+            "Beste Pace (letzte 30 Tage)\n\n{} /{} {}\n(vs. {} Bestwert aus {} gespeicherten)"
+        }
+        "\nYear to date: {} runs, {} rides" => "\nDieses Jahr: {} Läufe, {} Fahrten",
+        "\nYear to date: loading..." => "\nDieses Jahr: lade...",
+        "This Month\n\n{} {}\n(vs {} last month){}" => {
+            "Dieser Monat\n\n{} {}\n(vs. {} im Vormonat){}"
+        }
+        "Best Pace" => "Beste Pace",
+        "Activities this month" => "Aktivitäten diesen Monat",
+        "{} ({} cached)" => "{} ({} gespeichert)",
+        "{} ({} cached, {})" => "{} ({} gespeichert, {})",
+        "Biggest Distance ({})\n\n{} {} {}\n(period before: {} {})" => {
+            "Größte Distanz ({})\n\n{} {} {}\n(Zeitraum davor: {} {})"
+        }
+        "Best Pace ({})\n\n{} /{} {}\n(period before: {})" => {
+            "Beste Pace ({})\n\n{} /{} {}\n(Zeitraum davor: {})"
+        }
+        "{}\n\n{} {}\n(period before: {})" => "{}\n\n{} {}\n(Zeitraum davor: {})",
+        "Climbing ({})" => "Höhenmeter ({})",
+        "this month" => "dieser Monat",
+        "(period before: {})" => "(Zeitraum davor: {})",
+        "(vs {} last month)" => "(vs. {} im Vormonat)",
+        "{} {} over {} {}" => "{} {} auf {} {}",
+        "7 days" => "7 Tage",
+        "30 days" => "30 Tage",
+        "90 days" => "90 Tage",
+        "Year to date" => "Dieses Jahr",
+        "All time" => "Gesamt",
+        "Shorter time range" => "Kürzerer Zeitraum",
+        "Longer time range" => "Längerer Zeitraum",
+        "no commutes or private" => "ohne Pendel- und private",
+        "no commutes" => "ohne Pendelfahrten",
+        "no indoor" => "ohne Indoor",
+        "of which indoor" => "davon indoor",
+        "Indoor activities are left out of the totals" => {
+            "Indoor-Aktivitäten werden in den Summen weggelassen"
+        }
+        "Indoor activities count towards the totals" => "Indoor-Aktivitäten zählen zu den Summen",
+        "no private" => "ohne private",
+        "moving time" => "Bewegungszeit",
+        "elapsed time" => "Gesamtzeit",
+        "elapsed time for rides" => "Gesamtzeit für Radfahrten",
+        "Durations, pace and speed use {}" => "Dauer, Pace und Tempo nach {}",
+        "Race predictions (from {} in {} on {})" => "Wettkampfprognosen (aus {} in {} am {})",
+        "Goals (projection assumes the current pace)" => {
+            "Ziele (Hochrechnung mit dem aktuellen Tempo)"
+        }
+        "on track ({})" => "im Plan ({})",
+        "behind ({} projected)" => "im Rückstand ({} erwartet)",
+        "Half-Marathon" => "Halbmarathon",
+        "Marathon" => "Marathon",
+
+        // Activities
+        "Loading activities..." => "Lade Aktivitäten...",
+        "Loading more activities..." => "Lade weitere Aktivitäten...",
+        "end of activities" => "Ende der Aktivitäten",
+        "✗ Page {} failed: {} - r to retry" => {
+            "✗ Seite {} fehlgeschlagen: {} - r für neuen Versuch"
+        }
+        "Refresh, or retry a failed page" => {
+            "Aktualisieren oder fehlgeschlagene Seite erneut laden"
+        }
+        "No activities loaded" => "Keine Aktivitäten geladen",
+        "No activity selected" => "Keine Aktivität ausgewählt",
+        "No cached activities yet" => "Noch keine gespeicherten Aktivitäten",
+        "No activities in this date range" => "Keine Aktivitäten in diesem Zeitraum",
+        "No activities found" => "Keine Aktivitäten gefunden",
+        "Date" => "Datum",
+        "Name" => "Name",
+        "Sport" => "Sport",
+        "Distance" => "Distanz",
+        "Elev" => "Höhe",
+        "Climb" => "Steigung",
+        "Duration" => "Dauer",
+        "Elapsed" => "Gesamt",
+        "Pace" => "Pace",
+        "GAP" => "GAP",
+        "Speed" => "Tempo",
+        "Max" => "Max",
+        "HR" => "HF",
+        "MaxHR" => "MaxHF",
+        "Cal" => "kcal",
+        "RelPerf" => "RelLeist",
+        "Watts" => "Watt",
+        "Kudos" => "Kudos",
+        "Cmts" => "Komm.",
+        "★ holds a personal record" => "★ enthält eine persönliche Bestzeit",
+        "◀ {} hidden" => "◀ {} ausgeblendet",
+        "{} hidden ▶" => "{} ausgeblendet ▶",
+        " - sorted by {} {}" => " - sortiert nach {} {}",
+        " - {} (oldest first)" => " - {} (älteste zuerst)",
+        "←/→ column, ↑/↓ direction, Enter done" => "←/→ Spalte, ↑/↓ Richtung, Enter fertig",
+        "h/l scroll, j/k nav, s sort, f dates, w group, # tag" => {
+            "h/l scrollen, j/k navigieren, s sortieren, f Zeitraum, w gruppieren, # Tag"
+        }
+        "Activities ({} total){}{}{} - {})" => "Aktivitäten ({} insgesamt){}{}{} - {})",
+        "Week {}, {}" => "Woche {}, {}",
+
+        // Activity details
+        "Details (Esc to go back)" => "Details (Esc zurück)",
+        "Overview" => "Überblick",
+        "Map" => "Karte",
+        "Photos" => "Fotos",
+        "Notes" => "Notizen",
+        "Kudos & Comments" => "Kudos & Kommentare",
+        "Zones" => "Zonen",
+        "Recorded with {}" => "Aufgezeichnet mit {}",
+        "Loading zones..." => "Lade Zonen...",
+        "No heart rate or power zones for this activity" => {
+            "Keine Herzfrequenz- oder Leistungszonen für diese Aktivität"
+        }
+        "computed from the heart rate stream" => "aus dem Herzfrequenzverlauf berechnet",
+        "from Strava" => "von Strava",
+        "Heart Rate Zones" => "Herzfrequenzzonen",
+        "Power Zones" => "Leistungszonen",
+        " Score {} " => " Wertung {} ",
+        "{}\n\nType: {}\nDate: {}\nDistance: {} {}\nMoving Time: {}h {}m\nElevation Gain: {} {}\nAverage Speed: {} {}" => {
+            "{}\n\nTyp: {}\nDatum: {}\nDistanz: {} {}\nBewegungszeit: {}h {}m\nHöhenmeter: {} {}\nDurchschnittstempo: {} {}"
+        }
+        "Pace: {} /{} (grade adjusted {} /{})" => "Pace: {} /{} (steigungsbereinigt {} /{})",
+        "Decoupling: {}% ({})" => "Entkopplung: {}% ({})",
+        "Decoupling: - (pace too uneven)" => "Entkopplung: - (Tempo zu ungleichmäßig)",
+        "well coupled, good aerobic base" => "gut gekoppelt, solide aerobe Basis",
+        "some drift" => "etwas Drift",
+        "heavy drift, beyond aerobic endurance" => "starker Drift, jenseits der aeroben Ausdauer",
+        "Edit Activity" => "Aktivität bearbeiten",
+        "Description" => "Beschreibung",
+        "Commute" => "Pendelfahrt",
+        "Trainer" => "Rollentrainer",
+        "Gear ID" => "Ausrüstungs-ID",
+        "Tab/↑↓ move · Space toggle · Enter save · Esc cancel" => {
+            "Tab/↑↓ wechseln · Leertaste umschalten · Enter speichern · Esc abbrechen"
+        }
+        "Saving..." => "Speichere...",
+        "Map (green: start, red: finish)" => "Karte (grün: Start, rot: Ziel)",
+        "No GPS data for this activity" => "Keine GPS-Daten für diese Aktivität",
+        "Notes - Enter new line, Ctrl+S save, Esc cancel" => {
+            "Notizen - Enter neue Zeile, Strg+S speichern, Esc abbrechen"
+        }
+        "Notes (only on this machine) - n edit" => "Notizen (nur auf diesem Rechner) - n bearbeiten",
+        "No notes yet. Press n to write down how it felt." => {
+            "Noch keine Notizen. Mit n festhalten, wie es sich angefühlt hat."
+        }
+        "Loading photos..." => "Lade Fotos...",
+        "No photos" => "Keine Fotos",
+        "Photos ({})" => "Fotos ({})",
+        "Loading kudos and comments..." => "Lade Kudos und Kommentare...",
+        "Kudos ({})" => "Kudos ({})",
+        "No kudos yet" => "Noch keine Kudos",
+        "Comments ({})" => "Kommentare ({})",
+        "No comments yet" => "Noch keine Kommentare",
+        "Splits (km) - m: toggle, z: laps" => "Splits (km) - m: wechseln, z: Runden",
+        "Splits (mi) - m: toggle, z: laps" => "Splits (mi) - m: wechseln, z: Runden",
+        "Laps - z: splits" => "Runden - z: Splits",
+        "Power" => "Leistung",
+        "Splits" => "Splits",
+        "Loading splits..." => "Lade Splits...",
+        "No splits for this activity" => "Keine Splits für diese Aktivität",
+        "Loading streams..." => "Lade Streams...",
+        "Streams" => "Streams",
+        "No {} data" => "Keine {}-Daten",
+        "Heart Rate" => "Herzfrequenz",
+        "Elevation" => "Höhe",
+        "Elevation ({}-{} {})" => "Höhe ({}-{} {})",
+        "Grade" => "Steigung",
+        "steepest {}%" => "steilste {}%",
+        "Untitled" => "Ohne Titel",
+
+        // Progress, calendar and plan
+        "Progress - {} cached activities, to date = through day {}" => {
+            "Fortschritt - {} gespeicherte Aktivitäten, bis heute = bis Tag {}"
+        }
+        "{} - distance ({})" => "{} - Distanz ({})",
+        "Monthly distance ({})" => "Monatsdistanz ({})",
+        "Month" => "Monat",
+        "This month" => "Dieser Monat",
+        "To date" => "Bis heute",
+        "Total" => "Gesamt",
+        " (last year)" => " (Vorjahr)",
+        "Mon" => "Mo",
+        "Tue" => "Di",
+        "Wed" => "Mi",
+        "Thu" => "Do",
+        "Fri" => "Fr",
+        "Sat" => "Sa",
+        "Sun" => "So",
+        "Less" => "Weniger",
+        "More" => "Mehr",
+        "    {} active days, {}h total | Current streak: {} days | Longest streak: {} days" => {
+            "    {} aktive Tage, {}h gesamt | Aktuelle Serie: {} Tage | Längste Serie: {} Tage"
+        }
+        "Activity calendar - last {} weeks (moving time per day)" => {
+            "Aktivitätskalender - letzte {} Wochen (Bewegungszeit pro Tag)"
+        }
+        "Training plan - {} (h/l month)" => "Trainingsplan - {} (h/l Monat)",
+        "No planned workouts. Add [[workout]] entries with date, sport and a distance \
+         or duration in minutes to plan.toml next to config.toml." => {
+            "Keine geplanten Einheiten. Trage [[workout]]-Einträge mit date, sport und distance \
+             oder Dauer in Minuten in plan.toml neben config.toml ein."
+        }
+        "{} of {} due done · {} partial · {} missed · {} upcoming | compliance {}%" => {
+            "{} von {} fälligen erledigt · {} teilweise · {} verpasst · {} anstehend | Erfüllung {}%"
+        }
+        "{} planned, none due yet" => "{} geplant, noch keine fällig",
+        "✓ done · ◐ partly · ✗ missed · planned" => "✓ erledigt · ◐ teilweise · ✗ verpasst · geplant",
+
+        // Fitness and routes
+        "Fitness" => "Fitness",
+        "Fatigue" => "Ermüdung",
+        "Form" => "Form",
+        "Load from relative effort, or TRIMP with heart rates {}-{} bpm" => {
+            "Belastung aus Relative Effort oder TRIMP mit Herzfrequenzen {}-{} bpm"
+        }
+        "Load from relative effort; set max_heartrate to include heart rate only activities" => {
+            "Belastung aus Relative Effort; max_heartrate setzen, um Aktivitäten nur mit Herzfrequenz einzubeziehen"
+        }
+        "No relative effort or heart rate data in the cached activities" => {
+            "Keine Relative-Effort- oder Herzfrequenzdaten in den gespeicherten Aktivitäten"
+        }
+        "No route done twice yet - activities need a map and a similar distance, start and end" => {
+            "Noch keine Strecke doppelt gelaufen - Aktivitäten brauchen eine Karte und ähnliche Distanz, Start und Ziel"
+        }
+        "Repeated Routes ({}) - j/k select" => "Wiederholte Strecken ({}) - j/k auswählen",
+        "Route" => "Strecke",
+        "Last" => "Zuletzt",
+        "Best" => "Beste",
+        "best" => "beste",
+        "best {}" => "beste {}",
+        "{} repeats, newest first" => "{} Wiederholungen, neueste zuerst",
+        "Time" => "Zeit",
+        "vs best" => "vs. Beste",
+        "Moving time (min) per repeat" => "Bewegungszeit (min) pro Wiederholung",
+
+        // Training, sports and gear
+        "Weekly Training" => "Wochentraining",
+        "Monthly Training" => "Monatstraining",
+        "w: monthly" => "w: monatlich",
+        "w: weekly" => "w: wöchentlich",
+        "Period" => "Zeitraum",
+        "Count" => "Anzahl",
+        "Dist" => "Dist.",
+        "Sports" => "Sportarten",
+        "Sports ({} types in {} cached activities, by moving time)" => {
+            "Sportarten ({} Arten in {} gespeicherten Aktivitäten, nach Bewegungszeit)"
+        }
+        "Avg pace/speed" => "Ø Pace/Tempo",
+        "Longest" => "Längste",
+        "Latest" => "Neueste",
+        "Gear (activity counts cover loaded activities)" => {
+            "Ausrüstung (Aktivitätszahlen aus geladenen Aktivitäten)"
+        }
+        "No bikes or shoes on this account" => "Keine Räder oder Schuhe in diesem Konto",
+        "Bike" => "Rad",
+        "Shoes" => "Schuhe",
+        "Type" => "Typ",
+        "Brand/Model" => "Marke/Modell",
+        "Status" => "Status",
+        "primary" => "Standard",
+        "retired" => "ausgemustert",
+        "Totals" => "Summen",
+
+        // Achievements, segments and clubs
+        "This month: {} PRs and {} achievements in {} activities" => {
+            "Diesen Monat: {} Bestzeiten und {} Erfolge in {} Aktivitäten"
+        }
+        "No PRs or achievements in loaded activities yet" => {
+            "Noch keine Bestzeiten oder Erfolge in den geladenen Aktivitäten"
+        }
+        "PR" => "PB",
+        "2nd" => "2.",
+        "3rd" => "3.",
+        "Activity" => "Aktivität",
+        "PRs" => "PBs",
+        "Achv" => "Erfolge",
+        "Top-3 efforts" => "Top-3-Leistungen",
+        "Personal Bests ({} of {} cached runs scanned)" => {
+            "Persönliche Bestzeiten ({} von {} gespeicherten Läufen durchsucht)"
+        }
+        "Loading starred segments..." => "Lade favorisierte Segmente...",
+        "No starred segments - star some on Strava" => {
+            "Keine favorisierten Segmente - markiere welche auf Strava"
+        }
+        "Starred Segments ({})" => "Favorisierte Segmente ({})",
+        "Segment" => "Segment",
+        "Efforts" => "Versuche",
+        "My PR" => "Meine PB",
+        "Local Legend" => "Local Legend",
+        "You!" => "Du!",
+        "Loading segment details..." => "Lade Segmentdetails...",
+        "You are the Local Legend with {} efforts in the last 90 days" => {
+            "Du bist Local Legend mit {} Versuchen in den letzten 90 Tagen"
+        }
+        "You are the Local Legend" => "Du bist Local Legend",
+        "Local Legend: {} with {} efforts in the last 90 days" => {
+            "Local Legend: {} mit {} Versuchen in den letzten 90 Tagen"
+        }
+        "Local Legend: {}" => "Local Legend: {}",
+        "No Local Legend yet" => "Noch keine Local Legend",
+        "My efforts: {}, PR {} on {}" => "Meine Versuche: {}, PB {} am {}",
+        "My efforts: {}" => "Meine Versuche: {}",
+        "{} efforts by {} athletes, starred {} times" => {
+            "{} Versuche von {} Athleten, {} mal favorisiert"
+        }
+        "Elevation gain {} {}" => "Höhenmeter {} {}",
+        "flagged as hazardous" => "als gefährlich markiert",
+        "loading..." => "lade...",
+        "Loading clubs..." => "Lade Clubs...",
+        "You are not a member of any clubs" => "Du bist in keinem Club",
+        "Clubs (h/l to switch)" => "Clubs (h/l wechseln)",
+        "{} - {} members" => "{} - {} Mitglieder",
+        "No recent club activities" => "Keine aktuellen Club-Aktivitäten",
+        "Loading club activities..." => "Lade Club-Aktivitäten...",
+        "Club activities failed to load - r to retry" => {
+            "Club-Aktivitäten konnten nicht geladen werden - r für neuen Versuch"
+        }
+        "Loading profile..." => "Lade Profil...",
+        "Location" => "Ort",
+        "Member since" => "Mitglied seit",
+        "Subscription" => "Abo",
+        "Summit" => "Summit",
+        "Free" => "Kostenlos",
+        "Weight" => "Gewicht",
+        "FTP" => "FTP",
+        "Followers" => "Follower",
+        "Following" => "Folgt",
+        "Units on Strava" => "Einheiten auf Strava",
+        "Metric" => "Metrisch",
+        "Imperial" => "Imperial",
+        "{} bikes, {} shoes" => "{} Räder, {} Schuhe",
+        "Followers: {}   Following: {}" => "Follower: {}   Folgt: {}",
+        "Athletes you follow (K kudos, n next without kudos)" => {
+            "Athleten, denen du folgst (K Kudos, n nächste ohne Kudos)"
+        }
+        "Strava doesn't share the feed of athletes you follow with this app" => {
+            "Strava gibt den Feed der Athleten, denen du folgst, nicht an diese App heraus"
+        }
+        "No recent activities from athletes you follow" => {
+            "Keine aktuellen Aktivitäten von Athleten, denen du folgst"
+        }
+        "Loading feed..." => "Lade Feed...",
+        "Opened in the browser - give kudos there" => "Im Browser geöffnet - gib dort Kudos",
+        "No more activities without your kudos" => "Keine weiteren Aktivitäten ohne deine Kudos",
+        "Loading routes..." => "Lade Routen...",
+        "No saved routes - create or star one on strava.com" => {
+            "Keine gespeicherten Routen - erstelle oder markiere eine auf strava.com"
+        }
+        "Saved Routes ({}) - x downloads the GPX" => "Gespeicherte Routen ({}) - x lädt das GPX",
+        "Created" => "Erstellt",
+        "Downloading {}..." => "Lade {} herunter...",
+        "Saved {}" => "{} gespeichert",
+        "Loading elevation..." => "Lade Höhenprofil...",
+        "No elevation data for this route" => "Keine Höhendaten für diese Route",
+        "Estimated moving time" => "Geschätzte Bewegungszeit",
+        "{} /{} on the flat" => "{} /{} in der Ebene",
+        "{} {} on the flat" => "{} {} in der Ebene",
+        "plus {} {}/h climbing" => "plus {} {}/h bergauf",
+        "from {} activities in the last {} days" => "aus {} Aktivitäten der letzten {} Tage",
+        "Not enough {} activities in the last {} days to estimate" => {
+            "Zu wenige {}-Aktivitäten in den letzten {} Tagen für eine Schätzung"
+        }
+        "Athlete" => "Athlet",
+        "(no URL)" => "(keine URL)",
+        "Preview" => "Vorschau",
+        "Loaded" => "Geladen",
+        "Done" => "Erledigt",
+        "Last {} days" => "Letzte {} Tage",
+        _ => return None,
+    })
+}
@@ -0,0 +1,274 @@
+use crate::analytics;
+use crate::api::polyline;
+use crate::api::types::{Activity, PolylineMap, StreamSet};
+use crate::fit;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use csv::StringRecord;
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+// Strava's own summary lines have a few hundred points
+const MAX_MAP_POINTS: usize = 500;
+
+/// An activity read from Strava's "Download your data" archive.
+#[derive(Debug, Clone)]
+pub struct ImportedActivity {
+    pub activity: Activity,
+    /// The private note, which the API doesn't return
+    pub note: Option<String>,
+    /// Full-resolution streams from the activity's FIT file
+    pub streams: Option<StreamSet>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BulkImport {
+    pub activities: Vec<ImportedActivity>,
+    /// Rows of activities.csv without an id or a readable date
+    pub skipped: usize,
+}
+
+/// Reads `activities.csv` from the export ZIP, with maps from the GPX and FIT
+/// files it points to. Activities recorded as FIT files also get their
+/// streams; the others fetch them from the API when opened.
+pub fn read_archive(path: &Path) -> Result<BulkImport> {
+    let file = File::open(path).with_context(|| format!("can't open {}", path.display()))?;
+    read_archive_from(file)
+}
+
+pub fn read_archive_from<R: Read + Seek>(reader: R) -> Result<BulkImport> {
+    let mut zip = ZipArchive::new(reader).context("not a ZIP archive")?;
+    let csv = read_entry(&mut zip, "activities.csv")?.ok_or_else(|| {
+        anyhow!("no activities.csv in the archive; is it Strava's \"Download your data\" export?")
+    })?;
+
+    let mut reader = csv::Reader::from_reader(csv.as_slice());
+    let columns = Columns::new(reader.headers()?);
+    let mut import = BulkImport::default();
+    for record in reader.records() {
+        let Some(row) = columns.activity(&record?) else {
+            import.skipped += 1;
+            continue;
+        };
+        let mut activity = row.activity;
+        let mut streams = None;
+        if let Some(file) = &row.file {
+            if let Some(data) = read_entry(&mut zip, file)? {
+                let points = if is_fit(file) {
+                    // A damaged file costs the activity its track, not the import
+                    streams = fit::decode(&data)
+                        .ok()
+                        .map(|records| fit::to_streams(&records));
+                    streams
+                        .as_ref()
+                        .and_then(|s| s.latlng.as_ref())
+                        .map(|latlng| latlng.data.iter().map(|p| (p[0], p[1])).collect())
+                        .unwrap_or_default()
+                } else if file.trim_end_matches(".gz").ends_with(".gpx") {
+                    gpx_points(&String::from_utf8_lossy(&data))
+                } else {
+                    Vec::new()
+                };
+                activity.map = track_map(&points);
+            }
+        }
+        import.activities.push(ImportedActivity {
+            activity,
+            note: row.note,
+            streams,
+        });
+    }
+    Ok(import)
+}
+
+// The entry whose path ends in `name`, as the archive's top folder varies,
+// unpacked when it's gzipped
+fn read_entry<R: Read + Seek>(zip: &mut ZipArchive<R>, name: &str) -> Result<Option<Vec<u8>>> {
+    let Some(index) = (0..zip.len()).find(|&i| {
+        zip.name_for_index(i)
+            .is_some_and(|entry| entry == name || entry.ends_with(&format!("/{}", name)))
+    }) else {
+        return Ok(None);
+    };
+    let mut data = Vec::new();
+    zip.by_index(index)?.read_to_end(&mut data)?;
+    if name.ends_with(".gz") {
+        let mut unpacked = Vec::new();
+        GzDecoder::new(data.as_slice()).read_to_end(&mut unpacked)?;
+        data = unpacked;
+    }
+    Ok(Some(data))
+}
+
+fn is_fit(file: &str) -> bool {
+    file.trim_end_matches(".gz")
+        .to_ascii_lowercase()
+        .ends_with(".fit")
+}
+
+// A simplified line through the recorded track points. TCX files are left
+// without a map.
+fn track_map(points: &[(f64, f64)]) -> Option<PolylineMap> {
+    if points.is_empty() {
+        return None;
+    }
+    let step = points.len().div_ceil(MAX_MAP_POINTS);
+    let mut line: Vec<(f64, f64)> = points.iter().step_by(step).copied().collect();
+    if line.last() != points.last() {
+        line.extend(points.last());
+    }
+    Some(PolylineMap {
+        id: None,
+        polyline: None,
+        summary_polyline: Some(polyline::encode(&line)),
+    })
+}
+
+/// Latitude and longitude of every `<trkpt>` in a GPX document.
+pub fn gpx_points(gpx: &str) -> Vec<(f64, f64)> {
+    gpx.split("<trkpt")
+        .skip(1)
+        .filter_map(|point| {
+            let tag = &point[..point.find('>')?];
+            Some((attribute(tag, "lat")?, attribute(tag, "lon")?))
+        })
+        .collect()
+}
+
+fn attribute(tag: &str, name: &str) -> Option<f64> {
+    let start = tag.find(&format!("{}=", name))? + name.len() + 1;
+    let quote = tag[start..].chars().next()?;
+    let value = &tag[start + 1..];
+    value[..value.find(quote)?].parse().ok()
+}
+
+/// Strava's sport type from the label in activities.csv, e.g. "Virtual Ride"
+/// or "E-Bike Ride".
+pub fn sport_type(label: &str) -> String {
+    let compact: String = label.chars().filter(|c| c.is_alphanumeric()).collect();
+    analytics::SPORT_TYPES
+        .iter()
+        .find(|sport| sport.eq_ignore_ascii_case(&compact))
+        .map_or_else(
+            || match compact.as_str() {
+                "" => "Workout".to_string(),
+                "RockClimb" => "RockClimbing".to_string(),
+                "HIIT" => "HighIntensityIntervalTraining".to_string(),
+                _ => compact,
+            },
+            |sport| sport.to_string(),
+        )
+}
+
+// "Jan 15, 2024, 8:30:00 AM" in UTC; newer exports put a narrow space before AM
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.replace(['\u{202f}', '\u{a0}'], " ");
+    ["%b %d, %Y, %I:%M:%S %p", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text.trim(), format).ok())
+        .map(|date| date.and_utc())
+}
+
+struct ParsedRow {
+    activity: Activity,
+    note: Option<String>,
+    // Path of the recorded file inside the archive
+    file: Option<String>,
+}
+
+// activities.csv repeats some headers: the first Distance is in km, the
+// second in meters, and older exports only have the first
+struct Columns {
+    headers: Vec<String>,
+}
+
+impl Columns {
+    fn new(headers: &StringRecord) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.trim().to_string()).collect(),
+        }
+    }
+
+    fn last(&self, name: &str) -> Option<usize> {
+        self.headers.iter().rposition(|h| h == name)
+    }
+
+    fn text(&self, record: &StringRecord, name: &str) -> Option<String> {
+        let value = record.get(self.last(name)?)?.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    }
+
+    fn number(&self, record: &StringRecord, name: &str) -> Option<f64> {
+        self.text(record, name)?.replace(',', "").parse().ok()
+    }
+
+    fn distance(&self, record: &StringRecord) -> f64 {
+        let columns: Vec<usize> = (0..self.headers.len())
+            .filter(|&i| self.headers[i] == "Distance")
+            .collect();
+        let value = |i: usize| {
+            record
+                .get(i)
+                .and_then(|v| v.trim().replace(',', "").parse::<f64>().ok())
+        };
+        match columns.as_slice() {
+            [] => None,
+            [km] => value(*km).map(|km| km * 1000.0),
+            [.., meters] => value(*meters),
+        }
+        .unwrap_or(0.0)
+    }
+
+    fn activity(&self, record: &StringRecord) -> Option<ParsedRow> {
+        let id = self.text(record, "Activity ID")?.parse().ok()?;
+        let start = parse_date(&self.text(record, "Activity Date")?)?;
+        let sport = sport_type(&self.text(record, "Activity Type").unwrap_or_default());
+        let seconds = |name| self.number(record, name).map(|s| s.round() as u32);
+        let elapsed_time = seconds("Elapsed Time").unwrap_or(0);
+        let file = self.text(record, "Filename");
+        let activity = Activity {
+            id,
+            name: self.text(record, "Activity Name").unwrap_or_default(),
+            activity_type: sport.clone(),
+            sport_type: sport,
+            start_date: start,
+            // The export has no time zones
+            start_date_local: start,
+            timezone: String::new(),
+            distance: self.distance(record),
+            moving_time: seconds("Moving Time").unwrap_or(elapsed_time),
+            elapsed_time,
+            total_elevation_gain: self.number(record, "Elevation Gain").unwrap_or(0.0),
+            average_speed: self.number(record, "Average Speed"),
+            max_speed: self.number(record, "Max Speed"),
+            average_heartrate: self.number(record, "Average Heart Rate"),
+            max_heartrate: self.number(record, "Max Heart Rate"),
+            average_watts: self.number(record, "Average Watts"),
+            weighted_average_watts: self.number(record, "Weighted Average Power"),
+            calories: self.number(record, "Calories"),
+            suffer_score: self.number(record, "Relative Effort"),
+            description: self.text(record, "Activity Description"),
+            kudos_count: None,
+            comment_count: None,
+            achievement_count: None,
+            pr_count: None,
+            private: None,
+            commute: self
+                .text(record, "Commute")
+                .map(|c| c.eq_ignore_ascii_case("true") || c == "1"),
+            trainer: None,
+            manual: Some(file.is_none()),
+            // The export names the gear instead of giving its id
+            gear_id: None,
+            map: None,
+        };
+        Some(ParsedRow {
+            activity,
+            note: self.text(record, "Activity Private Note"),
+            file,
+        })
+    }
+}
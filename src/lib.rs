@@ -1,2 +1,22 @@
+pub mod analytics;
 pub mod api;
+pub mod cache;
+pub mod cli;
+pub mod config;
+pub mod dates;
+pub mod demo;
+pub mod error;
+pub mod export;
+pub mod fit;
+pub mod fitness;
+pub mod goals;
+pub mod i18n;
+pub mod import;
+pub mod metrics;
+pub mod notify;
+pub mod plan;
+pub mod script;
+pub mod secrets;
 pub mod ui;
+pub mod watch;
+pub mod worker;
@@ -1,20 +1,45 @@
 use anyhow::{anyhow, Result};
+use chrono::Utc;
+use clap::Parser;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    cursor::MoveTo,
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
     execute,
+    style::Print,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
 use sportfrei::api::client::StravaClient;
-use sportfrei::ui::app::{App, View};
+use sportfrei::api::oauth;
+use sportfrei::api::types::UploadDataType;
+use sportfrei::cache::{
+    self, ActivityCache, BestEffortCache, DetailCache, NoteStore, TagStore, UploadLog,
+};
+use sportfrei::cli::{self, Cli, Command};
+use sportfrei::config::{Config, DefaultView};
+use sportfrei::demo::Demo;
+use sportfrei::error::Error;
+use sportfrei::i18n::{Language, Locale};
+use sportfrei::notify;
+use sportfrei::plan::Plan;
+use sportfrei::script::Scripts;
+use sportfrei::ui::app::{App, DetailTab, Effect, UploadState, View, DEFAULT_PREFETCH_ROWS};
+use sportfrei::ui::columns::ActivityColumn;
+use sportfrei::ui::graphics::GraphicsProtocol;
+use sportfrei::ui::theme::Theme;
+use sportfrei::watch::{self, FolderWatch};
+use sportfrei::worker::{Request, Response, Worker};
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::io::{self, BufRead, BufReader, Write};
 use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
-
-const REDIRECT_URI: &str = "http://localhost:42424";
-const OAUTH_URL: &str = "https://www.strava.com/oauth/authorize";
-const TOKEN_URL: &str = "https://www.strava.com/oauth/token";
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
 
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     enable_raw_mode()?;
@@ -31,106 +56,56 @@ fn restore_terminal() -> Result<()> {
     Ok(())
 }
 
-fn get_config_path() -> String {
-    if let Some(proj_dirs) = directories::ProjectDirs::from("com", "strava-tui", "strava-tui") {
-        proj_dirs
-            .config_dir()
-            .join("config.toml")
-            .to_string_lossy()
-            .to_string()
-    } else {
-        "~/.config/strava-tui/config.toml".to_string()
-    }
+// The environment variable wins over the config file so one-off setups need no edits
+fn redirect_port(config: &Config) -> u16 {
+    std::env::var("SPORTFREI_REDIRECT_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .or(config.redirect_port)
+        .unwrap_or(oauth::DEFAULT_REDIRECT_PORT)
 }
 
-fn config_exists() -> bool {
-    if let Some(proj_dirs) = directories::ProjectDirs::from("com", "strava-tui", "strava-tui") {
-        proj_dirs.config_dir().join("config.toml").exists()
-    } else {
-        std::path::Path::new("~/.config/strava-tui/config.toml").exists()
-    }
-}
-
-fn read_config() -> Result<(Option<String>, Option<String>, Option<String>)> {
-    let config_path = get_config_path();
-    if !std::path::Path::new(&config_path).exists() {
-        return Ok((None, None, None));
-    }
-
-    let content = std::fs::read_to_string(&config_path)?;
-
-    let client_id = content.find("client_id = \"").and_then(|start| {
-        let rest = &content[start + 12..];
-        rest.find('"').map(|end| rest[..end].to_string())
-    });
-
-    let client_secret = content.find("client_secret = \"").and_then(|start| {
-        let rest = &content[start + 17..];
-        rest.find('"').map(|end| rest[..end].to_string())
-    });
-
-    let refresh_token = content.find("refresh_token = \"").and_then(|start| {
-        let rest = &content[start + 16..];
-        rest.find('"').map(|end| rest[..end].to_string())
-    });
-
-    Ok((client_id, client_secret, refresh_token))
-}
-
-fn save_config(client_id: &str, client_secret: &str, refresh_token: &str) -> Result<()> {
-    let config_path = get_config_path();
-    let content = format!(
-        "client_id = \"{}\"\nclient_secret = \"{}\"\nrefresh_token = \"{}\"\n",
-        client_id, client_secret, refresh_token
-    );
-
-    if let Some(parent) = std::path::Path::new(&config_path).parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    std::fs::write(&config_path, content)?;
-    Ok(())
-}
-
-fn run_oauth_flow() -> Result<StravaClient> {
-    let (stored_client_id, stored_client_secret, _) = read_config()?;
-    let mut client_id = stored_client_id.unwrap_or_default();
-    let mut client_secret = stored_client_secret.unwrap_or_default();
+fn run_oauth_flow(runtime: &Runtime) -> Result<StravaClient> {
+    let mut config = Config::load()?;
+    let mut client_id = config.client_id.clone().unwrap_or_default();
+    let mut client_secret = config.client_secret.clone().filter(|s| !s.is_empty());
 
     if client_id.is_empty() {
         println!("\n=== SportFrei Setup ===\n");
         println!("No client ID found. Please enter your Strava Client ID:\n");
         client_id = prompt_for_input("Client ID")?;
-    }
 
-    if client_secret.is_empty() {
-        if client_id.is_empty() {
-            println!("\n=== SportFrei Setup ===\n");
-        }
-        println!("No client secret found. Please enter your Strava Client Secret:\n");
-        client_secret = prompt_for_input("Client Secret")?;
+        println!("\nIf your Strava app requires it, enter the Client Secret.");
+        println!("Leave it empty to authorize with PKCE only.\n");
+        client_secret = prompt_for_optional_input("Client Secret")?;
     }
 
-    // Build OAuth URL
-    let auth_url = format!(
-        "{}?client_id={}&response_type=code&redirect_uri={}&scope=read,activity:read_all",
-        OAUTH_URL, client_id, REDIRECT_URI
-    );
+    let port = redirect_port(&config);
+    let redirect_uri = oauth::redirect_uri(port);
+    let pkce = oauth::Pkce::generate();
+    let state = oauth::random_string(16);
+    let auth_url = oauth::authorize_url(&client_id, &redirect_uri, &pkce, &state);
 
     println!("=== SportFrei OAuth ===\n");
-    println!("Please open the following URL in your browser:\n");
+    if oauth::open_browser(&auth_url) {
+        println!("Opening your browser to authorize SportFrei.");
+        println!("If nothing happens, open the following URL manually:\n");
+    } else {
+        println!("Please open the following URL in your browser:\n");
+    }
     println!("{}\n", auth_url);
     println!("Then authorize the application.\n");
     println!("Waiting for authorization...\n");
 
     // Start HTTP server to receive the callback
-    let listener = TcpListener::bind("127.0.0.1:42424")?;
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
     listener.set_nonblocking(true)?;
 
-    let mut code: Option<String> = None;
+    let mut outcome: Option<Result<String>> = None;
     let start = std::time::Instant::now();
     let timeout = std::time::Duration::from_secs(300); // 5 minutes timeout
 
-    while code.is_none() && start.elapsed() < timeout {
+    while outcome.is_none() && start.elapsed() < timeout {
         match listener.accept() {
             Ok((mut stream, _)) => {
                 let mut reader = BufReader::new(&stream);
@@ -148,27 +123,14 @@ fn run_oauth_flow() -> Result<StravaClient> {
                     }
                 }
 
-                // Parse query string from URL
-                if let Some(query_start) = request.find("GET /?") {
-                    let query_part = &request[query_start + 5..];
-                    if let Some(query_end) = query_part.find(" HTTP") {
-                        let query = &query_part[..query_end];
-
-                        // Parse code parameter
-                        for param in query.split('&') {
-                            if let Some(stripped) = param.strip_prefix("code=") {
-                                code = Some(stripped.to_string());
-                                break;
-                            }
-                        }
-                    }
-                }
+                // Only accept callbacks carrying the state we generated
+                outcome = oauth::parse_callback(&request).and_then(|params| params.outcome(&state));
 
                 // Send response
-                let response = if code.is_some() {
-                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n<html><body><h1>Authorized!</h1><p>You can close this window and return to the terminal.</p></body></html>"
-                } else {
-                    "HTTP/1.1 400 Bad Request\r\nContent-Type: text/html\r\n\r\n<html><body><h1>Error</h1><p>No authorization code received.</p></body></html>"
+                let response = match outcome {
+                    Some(Ok(_)) => "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n<html><body><h1>Authorized!</h1><p>You can close this window and return to the terminal.</p></body></html>",
+                    Some(Err(_)) => "HTTP/1.1 403 Forbidden\r\nContent-Type: text/html\r\n\r\n<html><body><h1>Not authorized</h1><p>SportFrei was not given access. You can close this window and return to the terminal.</p></body></html>",
+                    None => "HTTP/1.1 400 Bad Request\r\nContent-Type: text/html\r\n\r\n<html><body><h1>Error</h1><p>No authorization code received.</p></body></html>",
                 };
 
                 stream.write_all(response.as_bytes())?;
@@ -183,32 +145,37 @@ fn run_oauth_flow() -> Result<StravaClient> {
         }
     }
 
-    let code = code.ok_or_else(|| anyhow!("Authorization timed out"))?;
+    let code = outcome.ok_or_else(|| anyhow!("Authorization timed out"))??;
     println!("Authorization received! Exchanging for token...\n");
 
-    // Exchange code for token
-    let client = reqwest::blocking::Client::new();
-    let params = [
-        ("client_id", client_id.as_str()),
-        ("client_secret", client_secret.as_str()),
-        ("code", code.as_str()),
-        ("grant_type", "authorization_code"),
-    ];
-
-    let response = client
-        .post(TOKEN_URL)
-        .form(&params)
-        .send()?
-        .json::<sportfrei::api::types::TokenResponse>()?;
+    let response = runtime.block_on(oauth::exchange_code(
+        config.token_url.as_deref().unwrap_or(oauth::TOKEN_URL),
+        &client_id,
+        client_secret.as_deref(),
+        &code,
+        &pkce,
+    ))?;
 
-    // Save config with refresh token
-    save_config(&client_id, &client_secret, &response.refresh_token)?;
+    // Save config with refresh token, keeping any other settings
+    config.client_id = Some(client_id);
+    config.client_secret = client_secret;
+    config.refresh_token = Some(response.refresh_token);
+    config.save()?;
 
     println!("Token saved! Starting SportFrei...\n");
 
     StravaClient::new()
 }
 
+fn prompt_for_optional_input(prompt: &str) -> Result<Option<String>> {
+    print!("{}: ", prompt);
+    io::stdout().flush()?;
+    let mut value = String::new();
+    io::stdin().read_line(&mut value)?;
+    let value = value.trim().to_string();
+    Ok((!value.is_empty()).then_some(value))
+}
+
 fn prompt_for_input(prompt: &str) -> Result<String> {
     loop {
         print!("{}: ", prompt);
@@ -226,143 +193,995 @@ fn prompt_for_input(prompt: &str) -> Result<String> {
     }
 }
 
-fn load_more_activities(
-    client: &StravaClient,
-    page: u32,
-    per_page: u32,
-) -> Result<Vec<sportfrei::api::types::Activity>> {
-    let activities = client.get_activities(page, per_page)?;
-    Ok(activities)
+// Everything kept on disk between runs
+struct LocalStore {
+    cache: ActivityCache,
+    details: DetailCache,
+    efforts: BestEffortCache,
+    tags: TagStore,
+    notes: NoteStore,
+    // The folder whose new FIT files are uploaded, with its upload log
+    watch: Option<FolderWatch>,
+}
+
+// Remembers an upload Strava is done with, so the file isn't sent again
+fn finish_upload(
+    app: &mut App,
+    watch: &mut Option<FolderWatch>,
+    path: &Path,
+    activity_id: Option<u64>,
+) {
+    if let Some(watch) = watch {
+        watch.finish(path, activity_id);
+        if let Err(e) = watch.log().save() {
+            app.report_error(format!("Failed to save upload log: {}", e));
+        }
+    }
+}
+
+// How the UI loop ended
+enum Exit {
+    Quit,
+    // Strava revoked the authorization and the user chose to renew it
+    Reauthorize,
 }
 
-fn run_tui(app: &mut App, client: StravaClient) -> Result<()> {
+fn run_tui(
+    app: &mut App,
+    worker: Worker,
+    store: &mut LocalStore,
+    per_page: Option<u32>,
+    auto_refresh: Option<Duration>,
+    download_dir: &Path,
+    shutdown: &AtomicBool,
+) -> Result<Exit> {
+    let LocalStore {
+        cache,
+        details,
+        efforts,
+        tags,
+        notes,
+        watch,
+    } = store;
     let mut terminal = setup_terminal()?;
 
-    // Get terminal size to determine initial load count
-    let size = terminal.size()?;
     // Account for header (3 lines) and footer (3 lines), each activity takes 1 line
-    let activities_per_page = (size.height - 6).max(10) as u32;
+    let page_size =
+        |height: u16| per_page.unwrap_or_else(|| height.saturating_sub(6).max(10) as u32);
+    let mut activities_per_page = page_size(terminal.size()?.height);
+    // Strava pages by number, so a new size only applies once the list restarts
+    let mut resized_per_page = activities_per_page;
 
-    // Initial load - load enough to fill the screen
-    // Always load at least activities_per_page items
-    let new_activities = client.get_activities(1, activities_per_page)?;
-    app.add_activities(new_activities, activities_per_page);
+    let mut pending_streams: Option<u64> = None;
+    let mut pending_detail: Option<u64> = None;
+    // Opened on the first copy and kept: on X11 the copied text is served by
+    // this handle and would be gone once it is dropped
+    let mut clipboard: Option<arboard::Clipboard> = None;
+    let mut pending_social: Option<u64> = None;
+    let mut pending_photos: Option<u64> = None;
+    let mut pending_zones: Option<u64> = None;
+    // The photo currently on screen, by activity and area
+    let mut placed_photo: Option<(u64, Rect)> = None;
+    // Never cleared, so a gear id that fails to load isn't retried every frame
+    let mut requested_gear: HashSet<String> = HashSet::new();
+    // Details fetched in the background for PR highlights and best efforts
+    let mut requested_details: HashSet<u64> = HashSet::new();
+    let mut requested_starred = false;
+    let mut requested_segments: HashSet<u64> = HashSet::new();
+    let mut requested_clubs = false;
+    let mut requested_profile = false;
+    let mut requested_saved_routes = false;
+    let mut requested_route_streams: HashSet<u64> = HashSet::new();
 
-    let mut pending_load: Option<u32> = None;
-    let mut loading = false;
+    let mut last_poll = Instant::now();
+    let mut polling = false;
+    let mut last_scan: Option<Instant> = None;
+    // Uploads Strava is still processing: when to ask again, and how often it was asked
+    let mut upload_polls: Vec<(Instant, PathBuf, u64)> = Vec::new();
+    let mut upload_attempts: HashMap<PathBuf, u32> = HashMap::new();
+    let mut exit = Exit::Quit;
+
+    // Stats follow once the athlete id is known; the first activity page is
+    // requested by the loop like any other page
+    worker.send(Request::Athlete);
 
     loop {
+        if shutdown.load(Ordering::Relaxed) {
+            break;
+        }
+
         let _ = terminal.draw(|f| {
             app.render(f);
         });
+        if let Err(e) = draw_photo(&mut terminal, app, &mut placed_photo) {
+            app.report_error(format!("Failed to show photo: {}", e));
+        }
 
-        // Handle background loading
-        if let Some(page) = pending_load.take() {
-            match load_more_activities(&client, page, activities_per_page) {
-                Ok(new_activities) => {
-                    app.add_activities(new_activities, activities_per_page);
+        // Apply any responses that finished loading in the background
+        while let Some(response) = worker.try_recv() {
+            match response {
+                // A page for a range the user has since changed
+                Response::Page { range, .. } if range != app.date_range() => {}
+                Response::Page { result, page, .. } => match result {
+                    Ok(new_activities) => {
+                        if cache.merge(new_activities.items.iter().cloned()) > 0 {
+                            if let Err(e) = cache.save() {
+                                app.report_error(format!("Failed to save activity cache: {}", e));
+                            }
+                        }
+                        app.add_activities(new_activities, activities_per_page);
+                    }
+                    Err(e) => {
+                        app.set_load_error(page, e.to_string());
+                        app.report_api_error(
+                            format!("Failed to load activities page {}", page),
+                            &e,
+                        );
+                    }
+                },
+                Response::Athlete { result } => match result {
+                    Ok(athlete) => {
+                        worker.send(Request::Stats {
+                            athlete_id: athlete.id,
+                        });
+                        app.set_athlete(*athlete);
+                    }
+                    Err(e) => app.report_api_error("Failed to load athlete".to_string(), &e),
+                },
+                Response::Stats { result } => match result {
+                    Ok(stats) => app.set_stats(*stats),
+                    Err(e) => app.report_api_error("Failed to load stats".to_string(), &e),
+                },
+                Response::NewActivities { result } => {
+                    polling = false;
+                    match result {
+                        Ok(activities) => {
+                            if app.notifications().any() {
+                                let cached = cache.activities();
+                                for event in
+                                    notify::detect(&cached, &activities, app.notifications())
+                                {
+                                    let (title, body) = app.notification(&event);
+                                    notify::show(title, body);
+                                }
+                            }
+                            // Recent activities come back with their current kudos
+                            let changes = cache.update(activities.iter().cloned());
+                            if changes.added + changes.updated > 0 {
+                                if let Err(e) = cache.save() {
+                                    app.report_error(format!(
+                                        "Failed to save activity cache: {}",
+                                        e
+                                    ));
+                                }
+                            }
+                            app.insert_new_activities(activities);
+                        }
+                        Err(e) => app
+                            .report_api_error("Failed to check for new activities".to_string(), &e),
+                    }
                 }
-                Err(e) => {
-                    app.set_load_error();
-                    eprintln!("Failed to load more activities: {}", e);
+                Response::Upload { path, result } => {
+                    let file = watch::file_name(&path);
+                    match result {
+                        Ok(upload) => match (upload.error.as_deref(), upload.activity_id) {
+                            // Duplicates and broken files fail the same way every time
+                            (Some(error), _) => {
+                                upload_attempts.remove(&path);
+                                finish_upload(app, watch, &path, None);
+                                app.upload_rejected(&file, error);
+                            }
+                            (None, Some(activity_id)) => {
+                                upload_attempts.remove(&path);
+                                finish_upload(app, watch, &path, Some(activity_id));
+                                worker.send(Request::UploadedActivity { path, activity_id });
+                            }
+                            (None, None) => {
+                                let attempts = upload_attempts.entry(path.clone()).or_default();
+                                *attempts += 1;
+                                if *attempts > watch::UPLOAD_POLL_ATTEMPTS {
+                                    upload_attempts.remove(&path);
+                                    app.upload_rejected(&file, &upload.status);
+                                } else {
+                                    app.set_upload(file, UploadState::Processing);
+                                    upload_polls.push((
+                                        Instant::now() + watch::UPLOAD_POLL_INTERVAL,
+                                        path,
+                                        upload.id,
+                                    ));
+                                }
+                            }
+                        },
+                        Err(e) => {
+                            upload_attempts.remove(&path);
+                            app.upload_error(&file, &e);
+                        }
+                    }
+                }
+                Response::UploadedActivity {
+                    path,
+                    activity_id,
+                    result,
+                } => match result {
+                    Ok(detail) => {
+                        if cache.merge([detail.activity.clone()]) > 0 {
+                            if let Err(e) = cache.save() {
+                                app.report_error(format!("Failed to save activity cache: {}", e));
+                            }
+                        }
+                        if let Err(e) = details.save_detail(&detail) {
+                            app.report_error(format!("Failed to cache details: {}", e));
+                        }
+                        app.upload_finished(&watch::file_name(&path), detail.activity);
+                    }
+                    Err(e) => {
+                        app.clear_upload(&watch::file_name(&path));
+                        app.report_api_error(
+                            format!("Failed to load uploaded activity {}", activity_id),
+                            &e,
+                        );
+                    }
+                },
+                Response::Refreshed { result } => match result {
+                    Ok((athlete, stats, activities)) => {
+                        if cache.merge(activities.iter().cloned()) > 0 {
+                            if let Err(e) = cache.save() {
+                                app.report_error(format!("Failed to save activity cache: {}", e));
+                            }
+                        }
+                        app.merge_refresh(athlete, *stats, activities);
+                    }
+                    Err(e) => {
+                        app.set_refreshing(false);
+                        app.report_api_error("Failed to refresh".to_string(), &e);
+                    }
+                },
+                Response::Streams {
+                    activity_id,
+                    result,
+                } => {
+                    pending_streams = None;
+                    match result {
+                        Ok(streams) => {
+                            if let Some(activity) = app.find_activity(activity_id) {
+                                if let Err(e) = details.save_streams(activity, &streams) {
+                                    app.report_error(format!("Failed to cache streams: {}", e));
+                                }
+                            }
+                            app.set_activity_streams(activity_id, *streams);
+                        }
+                        Err(e) => {
+                            app.report_api_error(
+                                format!("Failed to load streams for {}", activity_id),
+                                &e,
+                            );
+                        }
+                    }
+                }
+                Response::Social {
+                    activity_id,
+                    result,
+                } => {
+                    pending_social = None;
+                    match result {
+                        Ok((kudoers, comments)) => {
+                            app.set_activity_social(activity_id, kudoers, comments)
+                        }
+                        // Empty, so the loop doesn't ask again for the same activity
+                        Err(e) => {
+                            app.set_activity_social(activity_id, Vec::new(), Vec::new());
+                            app.report_api_error(
+                                format!("Failed to load kudos/comments for {}", activity_id),
+                                &e,
+                            );
+                        }
+                    }
+                }
+                Response::Zones {
+                    activity_id,
+                    result,
+                } => {
+                    pending_zones = None;
+                    match result {
+                        Ok(zones) => app.set_activity_zones(activity_id, zones),
+                        // Strava only computes zones for subscribers' activities
+                        Err(Error::NotFound(_)) | Err(Error::Api { status: 402, .. }) => {
+                            app.set_activity_zones(activity_id, Vec::new())
+                        }
+                        Err(e) => {
+                            app.set_activity_zones(activity_id, Vec::new());
+                            app.report_api_error(
+                                format!("Failed to load zones for {}", activity_id),
+                                &e,
+                            );
+                        }
+                    }
+                }
+                Response::Photos {
+                    activity_id,
+                    result,
+                } => {
+                    pending_photos = None;
+                    match result {
+                        Ok((photos, image)) => app.set_activity_photos(activity_id, photos, image),
+                        Err(e) => {
+                            app.set_activity_photos(activity_id, Vec::new(), None);
+                            app.report_api_error(
+                                format!("Failed to load photos for {}", activity_id),
+                                &e,
+                            );
+                        }
+                    }
+                }
+                Response::Updated { result } => match result {
+                    Ok(detail) => {
+                        cache.merge([detail.activity.clone()]);
+                        if let Err(e) = cache.save() {
+                            app.report_error(format!("Failed to save activity cache: {}", e));
+                        }
+                        app.apply_activity_update(*detail);
+                    }
+                    Err(e) if app.is_editing() => app.set_edit_error(e.to_string()),
+                    Err(e) => app.report_api_error("Failed to update activity".to_string(), &e),
+                },
+                Response::Deleted {
+                    activity_id,
+                    result,
+                } => match result {
+                    Ok(()) => {
+                        if cache.remove(activity_id) {
+                            if let Err(e) = cache.save() {
+                                app.report_error(format!("Failed to save activity cache: {}", e));
+                            }
+                        }
+                        details.remove(activity_id);
+                        app.remove_activity(activity_id);
+                    }
+                    Err(e) => app
+                        .report_api_error(format!("Failed to delete activity {}", activity_id), &e),
+                },
+                Response::Gear { gear_id, result } => match result {
+                    Ok(gear) => app.set_gear(gear),
+                    Err(e) => app.report_api_error(format!("Failed to load gear {}", gear_id), &e),
+                },
+                Response::StarredSegments { result } => match result {
+                    Ok(segments) => app.set_starred_segments(segments),
+                    Err(e) => {
+                        app.report_api_error("Failed to load starred segments".to_string(), &e)
+                    }
+                },
+                Response::Segment { segment_id, result } => match result {
+                    Ok(segment) => app.set_segment_detail(*segment),
+                    Err(e) => {
+                        app.report_api_error(format!("Failed to load segment {}", segment_id), &e)
+                    }
+                },
+                Response::Clubs { result } => match result {
+                    Ok(clubs) => app.set_clubs(clubs),
+                    Err(e) => app.report_api_error("Failed to load clubs".to_string(), &e),
+                },
+                Response::Profile { result } => match result {
+                    Ok(profile) => app.set_profile(*profile),
+                    Err(e) => app.report_api_error("Failed to load profile".to_string(), &e),
+                },
+                Response::SavedRoutes { result } => match result {
+                    Ok(routes) => app.set_saved_routes(routes),
+                    Err(e) => app.report_api_error("Failed to load saved routes".to_string(), &e),
+                },
+                Response::RouteGpx { route_id, result } => {
+                    let name = app
+                        .saved_route(route_id)
+                        .map(|r| r.gpx_file_name())
+                        .unwrap_or_else(|| format!("route-{}.gpx", route_id));
+                    let path = download_dir.join(name);
+                    match result {
+                        Ok(gpx) => match fs::create_dir_all(download_dir)
+                            .and_then(|_| fs::write(&path, gpx))
+                        {
+                            Ok(()) => app.show_notice(app.trf("Saved {}", &[&path.display()])),
+                            Err(e) => app.report_error(format!(
+                                "Failed to save {}: {}",
+                                path.display(),
+                                e
+                            )),
+                        },
+                        Err(e) => app
+                            .report_api_error(format!("Failed to download route {}", route_id), &e),
+                    }
+                }
+                Response::RouteStreams { route_id, result } => match result {
+                    Ok(streams) => app.set_route_streams(route_id, *streams),
+                    Err(e) => app.report_api_error(
+                        format!("Failed to load elevation of route {}", route_id),
+                        &e,
+                    ),
+                },
+                Response::Following { page, result } => match result {
+                    Ok(activities) => {
+                        app.add_following_activities(page, activities, activities_per_page)
+                    }
+                    Err(Error::NotFound(_)) => app.set_following_unavailable(),
+                    Err(e) => {
+                        app.set_following_load_error();
+                        app.report_api_error("Failed to load the following feed".to_string(), &e);
+                    }
+                },
+                Response::ClubActivities {
+                    club_id,
+                    page,
+                    result,
+                } => match result {
+                    Ok(activities) => {
+                        app.add_club_activities(club_id, page, activities, activities_per_page)
+                    }
+                    Err(e) => {
+                        app.set_club_load_error(club_id);
+                        app.report_api_error(
+                            format!("Failed to load activities for club {}", club_id),
+                            &e,
+                        );
+                    }
+                },
+                Response::RateLimit(limit) => app.set_rate_limit(limit),
+                Response::Detail {
+                    activity_id,
+                    result,
+                } => {
+                    if pending_detail == Some(activity_id) {
+                        pending_detail = None;
+                    }
+                    match result {
+                        Ok(detail) => {
+                            efforts.insert(
+                                activity_id,
+                                detail.best_efforts.clone().unwrap_or_default(),
+                            );
+                            if let Err(e) = efforts.save() {
+                                app.report_error(format!("Failed to save best efforts: {}", e));
+                            }
+                            if let Err(e) = details.save_detail(&detail) {
+                                app.report_error(format!("Failed to cache details: {}", e));
+                            }
+                            app.set_activity_detail(*detail);
+                        }
+                        Err(e) => {
+                            app.report_api_error(
+                                format!("Failed to load details for {}", activity_id),
+                                &e,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // Kudos and comments are only fetched once their tab is opened
+        if app.current_view() == View::ActivityDetail && app.detail_tab() == DetailTab::Social {
+            if let Some(id) = app.get_selected_activity().map(|a| a.id) {
+                if !app.has_social_for(id) && pending_social != Some(id) {
+                    pending_social = Some(id);
+                    worker.send(Request::Social { activity_id: id });
+                }
+            }
+        }
+
+        // Strava's zones are only needed where the streams have no heart rate
+        if app.current_view() == View::ActivityDetail && app.detail_tab() == DetailTab::Zones {
+            if let Some(id) = app.zones_needed() {
+                if pending_zones != Some(id) {
+                    pending_zones = Some(id);
+                    worker.send(Request::Zones { activity_id: id });
+                }
+            }
+        }
+
+        if app.current_view() == View::ActivityDetail && app.detail_tab() == DetailTab::Photos {
+            if let Some(id) = app.get_selected_activity().map(|a| a.id) {
+                if !app.has_photos_for(id) && pending_photos != Some(id) {
+                    pending_photos = Some(id);
+                    worker.send(Request::Photos {
+                        activity_id: id,
+                        download: app.graphics_protocol().is_some(),
+                    });
                 }
             }
-            loading = false;
         }
 
-        // Check if we should load more (but not if already loading)
-        if !loading && app.should_load_more() {
-            loading = true;
+        if app.current_view() == View::Gear {
+            for gear_id in app.gear_ids() {
+                if !app.has_gear_for(&gear_id) && requested_gear.insert(gear_id.clone()) {
+                    worker.send(Request::Gear { gear_id });
+                }
+            }
+        }
+
+        if app.current_view() == View::Achievements {
+            let needed = app.achievement_details_needed();
+            for activity_id in needed.into_iter().chain(app.best_effort_details_needed()) {
+                if requested_details.insert(activity_id) {
+                    worker.send(Request::Detail { activity_id });
+                }
+            }
+        }
+
+        if app.current_view() == View::Segments {
+            if !requested_starred {
+                requested_starred = true;
+                worker.send(Request::StarredSegments);
+            }
+            for segment_id in app.segment_details_needed() {
+                if requested_segments.insert(segment_id) {
+                    worker.send(Request::Segment { segment_id });
+                }
+            }
+        }
+
+        if app.current_view() == View::Clubs {
+            if !requested_clubs {
+                requested_clubs = true;
+                worker.send(Request::Clubs);
+            }
+            if let Some(club_id) = app.selected_club_id() {
+                if app.should_load_club_activities() {
+                    app.set_club_loading(true);
+                    worker.send(Request::ClubActivities {
+                        club_id,
+                        page: app.club_activity_page() + 1,
+                        per_page: activities_per_page,
+                    });
+                }
+            }
+        }
+
+        // The social view shows the follower counts from the profile
+        if matches!(app.current_view(), View::Profile | View::Social) && !requested_profile {
+            requested_profile = true;
+            worker.send(Request::Profile);
+        }
+
+        if app.current_view() == View::SavedRoutes {
+            if !requested_saved_routes {
+                if let Some(athlete_id) = app.athlete_id() {
+                    requested_saved_routes = true;
+                    worker.send(Request::SavedRoutes { athlete_id });
+                }
+            }
+            // The profile of whichever route is selected, once each
+            if let Some(route_id) = app.selected_saved_route_id() {
+                if !app.has_route_streams_for(route_id) && requested_route_streams.insert(route_id)
+                {
+                    worker.send(Request::RouteStreams { route_id });
+                }
+            }
+        }
+
+        if app.current_view() == View::Social && app.should_load_following() {
+            app.set_following_loading(true);
+            worker.send(Request::Following {
+                page: app.following_page() + 1,
+                per_page: activities_per_page,
+            });
+        }
+
+        if let Some(watch) = watch.as_mut() {
+            if last_scan.is_none_or(|scanned| scanned.elapsed() >= watch::SCAN_INTERVAL) {
+                last_scan = Some(Instant::now());
+                for path in watch.scan() {
+                    if let Some(data_type) = UploadDataType::from_path(&path) {
+                        app.set_upload(watch::file_name(&path), UploadState::Uploading);
+                        worker.send(Request::Upload { path, data_type });
+                    }
+                }
+            }
+        }
+        let now = Instant::now();
+        let (due, waiting) = upload_polls.drain(..).partition(|(at, ..)| *at <= now);
+        upload_polls = waiting;
+        for (_, path, upload_id) in due {
+            worker.send(Request::UploadStatus { path, upload_id });
+        }
+
+        if let Some(interval) = auto_refresh {
+            if !polling && !app.is_rate_limited() && last_poll.elapsed() >= interval {
+                if let Some(newest) = cache.newest() {
+                    let mut after = newest.start_date;
+                    if app.notifications().kudos {
+                        after -= chrono::Duration::days(notify::KUDOS_WINDOW_DAYS);
+                    }
+                    polling = true;
+                    worker.send(Request::NewActivities {
+                        after: after.timestamp(),
+                    });
+                }
+                last_poll = Instant::now();
+            }
+        }
+
+        // Check if we should load more (should_load_more is false while loading)
+        if app.should_load_more() {
+            if app.activity_page() == 0 {
+                activities_per_page = resized_per_page;
+            }
             app.set_loading(true);
-            pending_load = Some(app.activity_page() + 1);
+            worker.send(Request::Page {
+                page: app.activity_page() + 1,
+                per_page: activities_per_page,
+                range: app.date_range(),
+            });
         }
 
         // Use poll to not block indefinitely
-        if event::poll(Duration::from_millis(100)).unwrap() {
-            if let Event::Key(key) = event::read().unwrap() {
+        if event::poll(Duration::from_millis(100))? {
+            let event = event::read()?;
+            if let Event::Resize(_, height) = event {
+                // The next draw reflows the layout; a taller activities table
+                // makes should_load_more fetch until it is filled
+                resized_per_page = page_size(height);
+                // The resize cleared the screen, photo included
+                placed_photo = None;
+            }
+            if let Event::Key(key) = event {
                 if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') => {
-                            restore_terminal().unwrap();
-                            break;
-                        }
-                        KeyCode::Char('d') => {
-                            app.set_view(View::Dashboard);
-                        }
-                        KeyCode::Char('a') => {
-                            app.set_view(View::Activities);
+                    match app.handle_key(key) {
+                        Some(Effect::Quit) => break,
+                        Some(Effect::Open(activity)) => {
+                            let id = activity.id;
+                            // What was downloaded before loads from disk unless the activity changed
+                            if !app.has_streams_for(id) {
+                                if let Some(streams) = details.streams(&activity) {
+                                    app.set_activity_streams(id, streams);
+                                } else if pending_streams != Some(id) {
+                                    pending_streams = Some(id);
+                                    worker.send(Request::Streams { activity_id: id });
+                                }
+                            }
+                            if !app.has_detail_for(id) {
+                                if let Some(detail) = details.detail(&activity) {
+                                    app.set_activity_detail(detail);
+                                } else if pending_detail != Some(id) {
+                                    pending_detail = Some(id);
+                                    worker.send(Request::Detail { activity_id: id });
+                                }
+                            }
                         }
-                        KeyCode::Char('j') | KeyCode::Down => {
-                            app.select_next_activity();
+                        Some(Effect::Refresh) => {
+                            worker.send(Request::Refresh {
+                                per_page: activities_per_page,
+                                range: app.date_range(),
+                            });
                         }
-                        KeyCode::Char('k') | KeyCode::Up => {
-                            app.select_prev_activity();
+                        Some(Effect::DownloadRoute(route_id)) => {
+                            worker.send(Request::RouteGpx { route_id });
                         }
-                        KeyCode::Char('h') | KeyCode::Left => {
-                            if app.current_view() == View::Activities {
-                                app.scroll_left();
+                        Some(Effect::SaveTags(activity_id, activity_tags)) => {
+                            tags.set(activity_id, activity_tags);
+                            if let Err(e) = tags.save() {
+                                app.report_error(format!("Failed to save tags: {}", e));
                             }
                         }
-                        KeyCode::Char('l') | KeyCode::Right => {
-                            if app.current_view() == View::Activities {
-                                app.scroll_right();
+                        Some(Effect::SaveNote(activity_id, note)) => {
+                            notes.set(activity_id, note);
+                            if let Err(e) = notes.save() {
+                                app.report_error(format!("Failed to save notes: {}", e));
                             }
                         }
-                        KeyCode::Enter => {
-                            if app.current_view() == View::Activities
-                                && app.get_selected_activity().is_some()
-                            {
-                                app.set_view(View::ActivityDetail);
+                        Some(Effect::Update(activity_id, update)) => {
+                            worker.send(Request::Update {
+                                activity_id,
+                                update,
+                            });
+                        }
+                        Some(Effect::Delete(activity_id)) => {
+                            worker.send(Request::Delete { activity_id });
+                        }
+                        Some(Effect::Reauthorize) => {
+                            exit = Exit::Reauthorize;
+                            break;
+                        }
+                        Some(Effect::Browse(url, notice)) => {
+                            if oauth::open_browser(&url) {
+                                app.show_notice(app.tr(notice).to_string());
+                            } else {
+                                app.report_error(format!("Failed to open a browser for {}", url));
                             }
                         }
-                        KeyCode::Esc => {
-                            if app.current_view() == View::ActivityDetail {
-                                app.set_view(View::Activities);
+                        Some(Effect::Copy(text, notice)) => {
+                            let copied = match clipboard.as_mut() {
+                                Some(clipboard) => clipboard.set_text(text),
+                                None => arboard::Clipboard::new()
+                                    .and_then(|c| clipboard.insert(c).set_text(text)),
+                            };
+                            match copied {
+                                Ok(()) => app.show_notice(app.tr(notice).to_string()),
+                                Err(e) => app.report_error(format!(
+                                    "Failed to copy to the clipboard: {}",
+                                    e
+                                )),
                             }
                         }
-                        _ => {}
+                        None => {}
                     }
                 }
             }
         }
     }
 
+    if placed_photo.is_some() {
+        clear_photo(&mut terminal, app)?;
+    }
+    restore_terminal()?;
+    Ok(exit)
+}
+
+/// Draws the photo the last frame made room for. Images live outside
+/// ratatui's buffer, so a changed or removed photo forces a full redraw
+/// before the next one is placed.
+fn draw_photo(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &App,
+    placed: &mut Option<(u64, Rect)>,
+) -> Result<()> {
+    let Some(protocol) = app.graphics_protocol() else {
+        return Ok(());
+    };
+    let placement = app.photo_placement();
+    if placement.map(|(id, area, _)| (id, area)) == *placed {
+        return Ok(());
+    }
+    if placed.take().is_some() {
+        return clear_photo(terminal, app);
+    }
+    if let Some((id, area, image)) = placement {
+        // Marked first so an image that fails to decode isn't retried every frame
+        *placed = Some((id, area));
+        let sequence = protocol.encode(image, area.width, area.height)?;
+        execute!(
+            terminal.backend_mut(),
+            MoveTo(area.x, area.y),
+            Print(sequence)
+        )?;
+    }
     Ok(())
 }
 
+fn clear_photo(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &App) -> Result<()> {
+    if let Some(protocol) = app.graphics_protocol() {
+        execute!(terminal.backend_mut(), Print(protocol.clear()))?;
+    }
+    terminal.clear()?;
+    Ok(())
+}
+
+/// Restores the terminal before the default hook prints the panic, so the
+/// message is readable and the shell is usable afterwards.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        default_hook(info);
+    }));
+}
+
+// Replaces the default SIGINT/SIGTERM handling, so only spawn it while the UI runs
+async fn wait_for_shutdown(shutdown: Arc<AtomicBool>) {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+            return;
+        };
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+
+    shutdown.store(true, Ordering::Relaxed);
+}
+
 fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
-    restore_terminal()?;
+    let cli = Cli::parse();
+    let runtime = Runtime::new()?;
 
-    let client = if config_exists() {
-        match StravaClient::new() {
-            Ok(c) => c,
-            Err(_) => {
-                println!("Config exists but failed to load. Re-running OAuth flow...\n");
-                run_oauth_flow()?
-            }
+    // Subcommands are meant for scripts, so they never start the interactive OAuth setup
+    if let Some(command) = cli.command {
+        let mut config = Config::load()?;
+        match command {
+            Command::Goals { action } => return cli::run_goals(action, &mut config),
+            Command::Calendar {
+                output,
+                event_name,
+                no_plan,
+            } => return cli::run_calendar(output, event_name, no_plan, &config),
+            Command::ExportAll {
+                format,
+                output,
+                no_sync: true,
+            } => return cli::run_export_all(format, &output, config.units),
+            Command::Import { archive } => return cli::run_import(&archive),
+            Command::Attach { activity_id, file } => return cli::run_attach(activity_id, &file),
+            Command::Metrics {
+                listen,
+                csv,
+                interval,
+                sync: false,
+            } => return runtime.block_on(cli::run_metrics(listen, csv, interval, None, &config)),
+            _ => {}
         }
-    } else {
-        run_oauth_flow()?
-    };
+        if !config.has_credentials() {
+            return Err(anyhow!(
+                "Not authorized yet. Run `sportfrei` without arguments once to connect your Strava account."
+            ));
+        }
+        let client = StravaClient::from_config(&config, Config::path()?)?;
+        return runtime.block_on(cli::run(command, &client, config.units));
+    }
 
-    println!("Loading athlete data...");
+    restore_terminal()?;
 
-    let athlete = client.get_athlete()?;
-    let stats = client.get_athlete_stats(athlete.id)?;
+    let demo_dir = std::env::temp_dir().join(format!("sportfrei-demo-{}", process::id()));
+    let (mut worker, config, plan) = if cli.demo {
+        start_demo(&demo_dir)?
+    } else {
+        let client = if Config::exists() {
+            match StravaClient::new() {
+                Ok(c) => c,
+                Err(_) => {
+                    println!("Config exists but failed to load. Re-running OAuth flow...\n");
+                    run_oauth_flow(&runtime)?
+                }
+            }
+        } else {
+            run_oauth_flow(&runtime)?
+        };
+        // Re-read so settings written by the OAuth flow are picked up
+        (Worker::start(client)?, Config::load()?, Plan::load())
+    };
 
-    // Activities will be loaded in run_tui() based on terminal size
-    let activities = vec![];
-    let per_page = 30; // Will be recalculated in run_tui
+    let mut store = LocalStore {
+        // A corrupt cache only costs history, so start fresh rather than fail
+        cache: ActivityCache::load().unwrap_or_default(),
+        details: DetailCache::open()?,
+        efforts: BestEffortCache::load().unwrap_or_default(),
+        // Tags and notes exist nowhere else, so a broken file stops here instead of being overwritten
+        tags: TagStore::load()?,
+        notes: NoteStore::load()?,
+        // A broken log starts over from now, which skips files rather than uploading them twice
+        watch: config
+            .watch_dir
+            .clone()
+            .map(|dir| FolderWatch::new(dir, UploadLog::load().unwrap_or_default(), Utc::now())),
+    };
 
     let mut app = App::new();
-    app.set_data(athlete, stats, activities, per_page);
+    app.set_language(config.language.unwrap_or_else(Language::detect));
+    let mut locale = config
+        .locale
+        .as_deref()
+        .map_or_else(Locale::detect, Locale::from_locale);
+    if let Some(time_format) = config.time_format {
+        locale.time_format = time_format;
+    }
+    app.set_locale(locale);
+    // A broken theme shouldn't keep the app from starting
+    match Theme::resolve(config.theme.as_deref(), &config.themes) {
+        Ok(theme) => app.set_theme(theme),
+        Err(e) => app.report_error(format!("{}; using the dark theme", e)),
+    }
+    app.set_units(config.units);
+    app.set_graphics_protocol(GraphicsProtocol::detect());
+    app.set_activity_columns(config.activity_columns.clone());
+    // Keeps when watching started for the next run
+    if let Some(watch) = &store.watch {
+        if let Err(e) = watch.log().save() {
+            app.report_error(format!("Failed to save upload log: {}", e));
+        }
+    }
+    if let Some(path) = config.script_path() {
+        match Scripts::load(&path) {
+            Ok(scripts) => {
+                let missing: Vec<&str> = config
+                    .activity_columns
+                    .iter()
+                    .filter_map(|column| match column {
+                        ActivityColumn::Script(name) if !scripts.has_function(name) => {
+                            Some(name.as_str())
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                if !missing.is_empty() {
+                    app.report_error(format!(
+                        "{} defines no column {}",
+                        path.display(),
+                        missing.join(", ")
+                    ));
+                }
+                app.set_scripts(scripts);
+            }
+            Err(e) => app.report_error(format!("Failed to load {}: {:#}", path.display(), e)),
+        }
+    }
+    app.set_sport_icons(config.sport_icons);
+    app.set_goals(config.goals.clone());
+    app.set_heart_rates(config.resting_heartrate, config.max_heartrate);
+    app.set_efficiency_metric(config.efficiency_metric);
+    app.set_paging(
+        config.prefetch_rows.unwrap_or(DEFAULT_PREFETCH_ROWS),
+        config.max_pages,
+    );
+    match plan {
+        Ok(plan) => app.set_plan(plan.workouts),
+        Err(e) => app.report_error(e.to_string()),
+    }
+    app.set_hidden(config.hide_commutes, config.hide_private);
+    app.set_exclude_indoor(config.exclude_indoor);
+    app.set_notifications(config.notifications.clone());
+    app.set_time_basis(config.time_basis);
+    app.merge_history(store.cache.activities());
+    app.merge_best_efforts(store.efforts.iter());
+    app.set_tags(store.tags.iter().map(|(id, tags)| (id, tags.clone())));
+    app.set_notes(store.notes.iter().map(|(id, note)| (id, note.to_string())));
+    app.set_view(match config.default_view {
+        DefaultView::Dashboard => View::Dashboard,
+        DefaultView::Activities => View::Activities,
+    });
 
-    if let Err(e) = run_tui(&mut app, client) {
-        let _ = restore_terminal();
-        eprintln!("Error: {}", e);
+    install_panic_hook();
+    let shutdown = Arc::new(AtomicBool::new(false));
+    runtime.spawn(wait_for_shutdown(shutdown.clone()));
+
+    let auto_refresh = config
+        .auto_refresh_minutes
+        .map(|minutes| Duration::from_secs(minutes.max(1) * 60));
+    loop {
+        match run_tui(
+            &mut app,
+            worker,
+            &mut store,
+            config.per_page,
+            auto_refresh,
+            &config.download_dir(),
+            &shutdown,
+        ) {
+            Ok(Exit::Quit) => break,
+            // The UI picks up where it left off with the new authorization
+            Ok(Exit::Reauthorize) => match run_oauth_flow(&runtime).and_then(Worker::start) {
+                Ok(renewed) => worker = renewed,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    break;
+                }
+            },
+            Err(e) => {
+                let _ = restore_terminal();
+                eprintln!("Error: {}", e);
+                break;
+            }
+        }
+    }
+    if cli.demo {
+        let _ = fs::remove_dir_all(&demo_dir);
     }
 
     Ok(())
 }
+
+// The demo keeps its local files in a throwaway directory and only reads the
+// config for display settings, so trying it leaves the real setup untouched
+fn start_demo(dir: &Path) -> Result<(Worker, Config, Result<Plan>)> {
+    fs::create_dir_all(dir)?;
+    cache::use_data_dir(dir.to_path_buf());
+
+    let demo = Demo::load(Utc::now())?;
+    let mut config = Config::load_from(&Config::path()?)?;
+    config.goals = demo.goals.clone();
+    let plan = Plan {
+        workouts: demo.plan.clone(),
+    };
+    Ok((Worker::demo(demo)?, config, Ok(plan)))
+}
@@ -0,0 +1,166 @@
+use crate::analytics::{self, SportTotals};
+use crate::api::types::Activity;
+use crate::fitness::{self, FitnessDay, HeartRateRange};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Columns of [`TrainingMetrics::csv_row`], in meters and seconds
+pub const CSV_HEADER: &str = "time,week_activities,week_distance_m,week_moving_time_s,\
+week_elevation_m,load,fitness,fatigue,form,current_streak,longest_streak,activities";
+
+/// Aggregated training figures for graphing outside the UI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingMetrics {
+    /// Totals per sport type since the start of the current week
+    pub week: Vec<SportTotals>,
+    /// Today's load, fitness, fatigue and form
+    pub fitness: Option<FitnessDay>,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub activities: usize,
+    pub last_activity: Option<DateTime<Utc>>,
+}
+
+impl TrainingMetrics {
+    pub fn collect(
+        activities: &[Activity],
+        heart_rate: Option<HeartRateRange>,
+        week_start: NaiveDate,
+        today: NaiveDate,
+    ) -> Self {
+        let day = |a: &Activity| a.start_date_local.date_naive();
+        let active: BTreeSet<NaiveDate> = activities.iter().map(day).collect();
+        let (current_streak, longest_streak) = analytics::streaks(&active, today);
+        Self {
+            week: analytics::sport_breakdown(
+                activities
+                    .iter()
+                    .filter(|a| (week_start..=today).contains(&day(a))),
+            ),
+            fitness: fitness::fitness_history(activities, heart_rate, today).pop(),
+            current_streak,
+            longest_streak,
+            activities: activities.len(),
+            last_activity: activities.iter().map(|a| a.start_date).max(),
+        }
+    }
+
+    /// The metrics in Prometheus' text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let week = |value: fn(&SportTotals) -> f64| {
+            self.week
+                .iter()
+                .map(|totals| (Some(totals.sport.as_str()), value(totals)))
+                .collect::<Vec<_>>()
+        };
+        gauge(
+            &mut out,
+            "week_activities",
+            "Activities since the start of the week",
+            &week(|t| f64::from(t.count)),
+        );
+        gauge(
+            &mut out,
+            "week_distance_meters",
+            "Distance since the start of the week",
+            &week(|t| t.distance),
+        );
+        gauge(
+            &mut out,
+            "week_moving_time_seconds",
+            "Moving time since the start of the week",
+            &week(|t| t.moving_time as f64),
+        );
+        gauge(
+            &mut out,
+            "week_elevation_meters",
+            "Elevation gain since the start of the week",
+            &week(|t| t.elevation),
+        );
+        if let Some(day) = &self.fitness {
+            for (name, help, value) in [
+                (
+                    "training_load",
+                    "Training load of today's activities",
+                    day.load,
+                ),
+                ("fitness", "Chronic training load (CTL)", day.fitness),
+                ("fatigue", "Acute training load (ATL)", day.fatigue),
+                ("form", "Training stress balance (TSB)", day.form),
+            ] {
+                gauge(&mut out, name, help, &[(None, value)]);
+            }
+        }
+        gauge(
+            &mut out,
+            "streak_days",
+            "Consecutive active days up to today",
+            &[(None, f64::from(self.current_streak))],
+        );
+        gauge(
+            &mut out,
+            "longest_streak_days",
+            "Longest run of consecutive active days",
+            &[(None, f64::from(self.longest_streak))],
+        );
+        gauge(
+            &mut out,
+            "activities",
+            "Activities in the local cache",
+            &[(None, self.activities as f64)],
+        );
+        if let Some(last) = self.last_activity {
+            gauge(
+                &mut out,
+                "last_activity_timestamp_seconds",
+                "Start of the newest activity",
+                &[(None, last.timestamp() as f64)],
+            );
+        }
+        out
+    }
+
+    /// One line under [`CSV_HEADER`], with the week summed over all sports.
+    /// The sums are folded from zero, as an empty `sum()` of floats is -0.
+    pub fn csv_row(&self, time: DateTime<Utc>) -> String {
+        let fitness = |value: fn(&FitnessDay) -> f64| {
+            self.fitness
+                .as_ref()
+                .map(|day| format!("{:.1}", value(day)))
+                .unwrap_or_default()
+        };
+        format!(
+            "{},{},{:.0},{},{:.0},{},{},{},{},{},{},{}",
+            time.format("%Y-%m-%dT%H:%M:%SZ"),
+            self.week.iter().map(|t| t.count).sum::<u32>(),
+            self.week.iter().fold(0.0, |sum, t| sum + t.distance),
+            self.week.iter().map(|t| t.moving_time).sum::<u64>(),
+            self.week.iter().fold(0.0, |sum, t| sum + t.elevation),
+            fitness(|d| d.load),
+            fitness(|d| d.fitness),
+            fitness(|d| d.fatigue),
+            fitness(|d| d.form),
+            self.current_streak,
+            self.longest_streak,
+            self.activities
+        )
+    }
+}
+
+// A gauge with its HELP and TYPE lines, the samples labelled by sport type
+fn gauge(out: &mut String, name: &str, help: &str, samples: &[(Option<&str>, f64)]) {
+    let _ = writeln!(out, "# HELP sportfrei_{} {}", name, help);
+    let _ = writeln!(out, "# TYPE sportfrei_{} gauge", name);
+    for (sport, value) in samples {
+        match sport {
+            Some(sport) => {
+                let _ = writeln!(out, "sportfrei_{}{{sport=\"{}\"}} {}", name, sport, value);
+            }
+            None => {
+                let _ = writeln!(out, "sportfrei_{} {}", name, value);
+            }
+        }
+    }
+}
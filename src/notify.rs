@@ -0,0 +1,104 @@
+use crate::analytics;
+use crate::api::types::Activity;
+use crate::config::NotificationConfig;
+use std::collections::{BTreeMap, HashMap};
+use std::thread;
+
+/// How far before the newest cached activity auto-refresh looks again, so
+/// kudos on recent activities are noticed
+pub const KUDOS_WINDOW_DAYS: i64 = 3;
+
+/// Something auto-refresh found that is worth a desktop notification.
+#[derive(Debug, Clone)]
+pub enum Event {
+    NewActivity(Activity),
+    Kudos {
+        activity: Activity,
+        /// Kudos since the previous refresh
+        new: u32,
+    },
+    /// A record of [`analytics::RECORDS`] taken over from an older activity
+    Record {
+        record: &'static str,
+        activity: Activity,
+    },
+    SegmentPrs {
+        activity: Activity,
+        count: u32,
+    },
+}
+
+/// The enabled events between the cached activities and a fresh poll.
+/// Records only count when they beat an earlier activity, so the first
+/// run or ride in the cache doesn't announce a record.
+pub fn detect(
+    cached: &[Activity],
+    fetched: &[Activity],
+    config: &NotificationConfig,
+) -> Vec<Event> {
+    let known: HashMap<u64, &Activity> = cached.iter().map(|a| (a.id, a)).collect();
+    let new: Vec<&Activity> = fetched
+        .iter()
+        .filter(|a| !known.contains_key(&a.id))
+        .collect();
+    let mut events = Vec::new();
+
+    if config.new_activities {
+        events.extend(new.iter().map(|&a| Event::NewActivity(a.clone())));
+    }
+    if config.records && !new.is_empty() {
+        let before: BTreeMap<&str, u64> = analytics::personal_records(cached)
+            .into_iter()
+            .map(|(kind, holder)| (kind.name, holder.id))
+            .collect();
+        for (kind, holder) in analytics::personal_records(cached.iter().chain(new.iter().copied()))
+        {
+            if before.contains_key(kind.name) && !known.contains_key(&holder.id) {
+                events.push(Event::Record {
+                    record: kind.name,
+                    activity: holder.clone(),
+                });
+            }
+        }
+        for activity in &new {
+            if let Some(count) = activity.pr_count.filter(|&count| count > 0) {
+                events.push(Event::SegmentPrs {
+                    activity: (*activity).clone(),
+                    count,
+                });
+            }
+        }
+    }
+    if config.kudos {
+        for activity in fetched {
+            let Some(previous) = known.get(&activity.id) else {
+                continue;
+            };
+            let now = activity.kudos_count.unwrap_or(0);
+            let before = previous.kudos_count.unwrap_or(0);
+            if now > before {
+                events.push(Event::Kudos {
+                    activity: activity.clone(),
+                    new: now - before,
+                });
+            }
+        }
+    }
+    events
+}
+
+/// Shows a desktop notification. Talking to the notification daemon can
+/// take a moment, so it happens off the UI thread; failures only get logged,
+/// as a missing daemon shouldn't disturb the UI.
+pub fn show(title: String, body: String) {
+    thread::spawn(move || {
+        if let Err(e) = notify_rust::Notification::new()
+            .appname("SportFrei")
+            .summary(&title)
+            .body(&body)
+            .show()
+        {
+            tracing::warn!("Failed to show a desktop notification: {}", e);
+        }
+    });
+}
@@ -0,0 +1,133 @@
+use crate::api::types::Activity;
+use crate::config::Units;
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use directories::ProjectDirs;
+use serde::{Deserialize, Deserializer};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// A workout counts as done once this share of its target is covered
+const DONE_RATIO: f64 = 0.9;
+
+/// One planned workout. Distance is in the configured units (km or mi),
+/// duration in minutes; with neither, any matching activity completes it.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PlannedWorkout {
+    #[serde(deserialize_with = "plan_date")]
+    pub date: NaiveDate,
+    // Matches Strava's sport_type, e.g. "Run"
+    pub sport: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub distance: Option<f64>,
+    #[serde(default)]
+    pub duration: Option<u32>,
+}
+
+// Bare TOML dates (date = 2024-03-18) as well as quoted ones
+fn plan_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum DateValue {
+        Toml(toml::value::Datetime),
+        Text(String),
+    }
+    let text = match DateValue::deserialize(deserializer)? {
+        DateValue::Toml(date) => date.to_string(),
+        DateValue::Text(text) => text,
+    };
+    text.parse().map_err(serde::de::Error::custom)
+}
+
+/// Planned workouts from `plan.toml` next to the config file, one
+/// `[[workout]]` table each.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Plan {
+    #[serde(default, rename = "workout")]
+    pub workouts: Vec<PlannedWorkout>,
+}
+
+impl Plan {
+    pub fn path() -> Result<PathBuf> {
+        let proj_dirs = ProjectDirs::from("com", "strava-tui", "strava-tui")
+            .ok_or_else(|| anyhow!("Could not determine config directory"))?;
+        Ok(proj_dirs.config_dir().join("plan.toml"))
+    }
+
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        let mut plan: Plan =
+            toml::from_str(&content).map_err(|e| anyhow!("Failed to parse plan: {}", e))?;
+        plan.workouts.sort_by_key(|w| w.date);
+        Ok(plan)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compliance {
+    Done,
+    /// Share of the target covered so far
+    Partial(f64),
+    Missed,
+    /// Today or later and nothing done yet
+    Planned,
+}
+
+impl PlannedWorkout {
+    pub fn matches(&self, activity: &Activity) -> bool {
+        activity.start_date_local.date_naive() == self.date
+            && activity.sport_type.eq_ignore_ascii_case(&self.sport)
+    }
+
+    /// Compares the target against every matching activity on the day, so
+    /// a workout split in two still counts.
+    pub fn compliance<'a>(
+        &self,
+        activities: impl IntoIterator<Item = &'a Activity>,
+        today: NaiveDate,
+        units: Units,
+    ) -> Compliance {
+        let (mut count, mut distance, mut minutes) = (0, 0.0, 0.0);
+        for activity in activities.into_iter().filter(|a| self.matches(a)) {
+            count += 1;
+            distance += units.distance(activity.distance);
+            minutes += activity.moving_time as f64 / 60.0;
+        }
+        if count == 0 {
+            return if self.date < today {
+                Compliance::Missed
+            } else {
+                Compliance::Planned
+            };
+        }
+
+        let ratio = match (self.distance, self.duration) {
+            (Some(target), _) if target > 0.0 => distance / target,
+            (_, Some(target)) if target > 0 => minutes / target as f64,
+            _ => 1.0,
+        };
+        if ratio >= DONE_RATIO {
+            Compliance::Done
+        } else {
+            Compliance::Partial(ratio)
+        }
+    }
+
+    /// Short target such as "Run 10 km" or "Ride 90 min".
+    pub fn target_label(&self, units: Units) -> String {
+        match (self.distance, self.duration) {
+            (Some(distance), _) => format!("{} {} {}", self.sport, distance, units.distance_unit()),
+            (None, Some(minutes)) => format!("{} {} min", self.sport, minutes),
+            (None, None) => self.sport.clone(),
+        }
+    }
+}
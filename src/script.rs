@@ -0,0 +1,150 @@
+use crate::api::types::Activity;
+use crate::cache::DetailCache;
+use anyhow::{anyhow, Context, Result};
+use rhai::{Dynamic, Engine, Scope, AST};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+// Enough for a loop over a few thousand activities, not for a runaway one
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+/// Functions named like this are dashboard cards
+pub const CARD_PREFIX: &str = "card_";
+
+// A script's result, or the message it failed with
+type Outcome<T> = Result<T, String>;
+// Results by key, with the revision or fingerprint of their input
+type Memo<K, R, T> = RefCell<HashMap<K, (R, Outcome<T>)>>;
+
+/// Custom activity columns and dashboard cards written in Rhai
+/// (<https://rhai.rs>). A column is a function taking one activity, as a
+/// map with the fields of Strava's summary, and returning a number, text or
+/// `()` for none. A card is a function named `card_<title>` taking the array
+/// of activities in the dashboard's range and returning its text.
+///
+/// Results are kept until the activity (or, for cards, any activity in the
+/// range) changes, so scripts don't run on every frame.
+pub struct Scripts {
+    engine: Engine,
+    ast: AST,
+    // By column and activity id
+    cells: Memo<(String, u64), String, Option<String>>,
+    // By card, fingerprinted by the activities it was given
+    cards: Memo<String, u64, String>,
+}
+
+impl Scripts {
+    pub fn load(path: &Path) -> Result<Self> {
+        let source =
+            fs::read_to_string(path).with_context(|| format!("can't read {}", path.display()))?;
+        Self::compile(&source)
+    }
+
+    pub fn compile(source: &str) -> Result<Self> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        // print() would write over the UI
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+        let ast = engine.compile(source).map_err(|e| anyhow!("{}", e))?;
+        Ok(Self {
+            engine,
+            ast,
+            cells: RefCell::new(HashMap::new()),
+            cards: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Whether the script has a function `name` taking one argument.
+    pub fn has_function(&self, name: &str) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|f| f.name == name && f.params.len() == 1)
+    }
+
+    /// Names of the card functions, in alphabetical order.
+    pub fn cards(&self) -> Vec<String> {
+        let mut cards: Vec<String> = self
+            .ast
+            .iter_functions()
+            .filter(|f| f.name.starts_with(CARD_PREFIX) && f.params.len() == 1)
+            .map(|f| f.name.to_string())
+            .collect();
+        cards.sort();
+        cards.dedup();
+        cards
+    }
+
+    /// The text of column `name` for `activity`, None when the script
+    /// returns `()`. Errors carry the script's message.
+    pub fn column(&self, name: &str, activity: &Activity) -> Outcome<Option<String>> {
+        let revision = DetailCache::revision(activity);
+        let key = (name.to_string(), activity.id);
+        if let Some((cached, value)) = self.cells.borrow().get(&key) {
+            if *cached == revision {
+                return value.clone();
+            }
+        }
+        let value = self
+            .call(name, to_map(activity))
+            .map(|value| (!value.is_unit()).then(|| display(value)));
+        self.cells
+            .borrow_mut()
+            .insert(key, (revision, value.clone()));
+        value
+    }
+
+    /// The text of card `name` for `activities`.
+    pub fn card(&self, name: &str, activities: &[&Activity]) -> Outcome<String> {
+        let mut hasher = DefaultHasher::new();
+        for activity in activities {
+            activity.id.hash(&mut hasher);
+            DetailCache::revision(activity).hash(&mut hasher);
+        }
+        let fingerprint = hasher.finish();
+        if let Some((cached, text)) = self.cards.borrow().get(name) {
+            if *cached == fingerprint {
+                return text.clone();
+            }
+        }
+        let array: rhai::Array = activities.iter().map(|a| to_map(a)).collect();
+        let text = self.call(name, array.into()).map(display);
+        self.cards
+            .borrow_mut()
+            .insert(name.to_string(), (fingerprint, text.clone()));
+        text
+    }
+
+    fn call(&self, name: &str, argument: Dynamic) -> Outcome<Dynamic> {
+        self.engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, name, (argument,))
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// The title of a card function: `card_long_runs` becomes "Long runs".
+pub fn card_title(name: &str) -> String {
+    let title = name.trim_start_matches(CARD_PREFIX).replace('_', " ");
+    let mut chars = title.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => title,
+    }
+}
+
+fn to_map(activity: &Activity) -> Dynamic {
+    rhai::serde::to_dynamic(activity).unwrap_or_default()
+}
+
+// Floats get one decimal, as most figures in the table do
+fn display(value: Dynamic) -> String {
+    match value.as_float() {
+        Ok(number) => format!("{:.1}", number),
+        Err(_) => value.to_string(),
+    }
+}
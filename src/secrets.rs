@@ -0,0 +1,26 @@
+// Credentials kept in the OS keychain (macOS Keychain, Windows Credential
+// Manager, Secret Service on Linux) instead of config.toml.
+use anyhow::Result;
+use keyring::Entry;
+
+const SERVICE: &str = "sportfrei";
+
+pub const CLIENT_SECRET: &str = "client_secret";
+pub const REFRESH_TOKEN: &str = "refresh_token";
+
+/// Returns `None` both when nothing is stored and when no keyring is available.
+pub fn get(name: &str) -> Option<String> {
+    Entry::new(SERVICE, name).ok()?.get_password().ok()
+}
+
+pub fn set(name: &str, value: &str) -> Result<()> {
+    Entry::new(SERVICE, name)?.set_password(value)?;
+    Ok(())
+}
+
+// Missing entries and unavailable keyrings are both fine when deleting
+pub fn delete(name: &str) {
+    if let Ok(entry) = Entry::new(SERVICE, name) {
+        let _ = entry.delete_credential();
+    }
+}
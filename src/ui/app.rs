@@ -1,19 +1,218 @@
-use crate::api::types::{Activity, Athlete, AthleteStats};
+mod achievements;
+mod activities;
+mod clubs;
+mod dashboard;
+mod detail;
+mod freshness;
+mod gear;
+mod heatmap;
+mod overlay;
+mod plan;
+mod profile;
+mod progress;
+mod routes;
+mod saved_routes;
+mod segments;
+mod social;
+mod sports;
+mod training;
+
+pub use dashboard::DashboardRange;
+
+use crate::analytics::{self, EfficiencyMetric};
+use crate::api::types::{
+    Activity, ActivityZone, Athlete, AthleteStats, BestEffort, ClubActivity, Comment, DateRange,
+    DetailedActivity, DetailedAthlete, DetailedSegment, Gear, Page, Photo, Route, StreamSet,
+    SummaryAthlete, SummaryClub, SummarySegment, UpdatableActivity,
+};
+use crate::cache::TagStore;
+use crate::config::{NotificationConfig, TimeBasis, Units};
+use crate::dates;
+use crate::error::{Error, RateLimit};
+use crate::fitness::HeartRateRange;
+use crate::goals::Goal;
+use crate::i18n::{Language, Locale, TimeFormat};
+use crate::notify;
+use crate::plan::PlannedWorkout;
+use crate::script::Scripts;
+use crate::ui::columns::{ActivityColumn, SportIcons};
+use crate::ui::graphics::GraphicsProtocol;
+use crate::ui::keymap::{self, Action};
+use crate::ui::theme::Theme;
+use chrono::{DateTime, NaiveDate, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Style};
-use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap};
 use ratatui::Frame;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+// Rows before the end of the activities list at which the next page loads
+pub const DEFAULT_PREFETCH_ROWS: usize = 5;
+// How long the footer banner shows a new error before it fades out
+const STATUS_TIMEOUT: Duration = Duration::from_secs(6);
+const ERROR_LOG_CAPACITY: usize = 50;
+// How many views Esc can walk back through
+const VIEW_HISTORY_CAPACITY: usize = 50;
+// Status bar spinner, one frame per 100 ms
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
 pub struct App {
     athlete: Option<Athlete>,
     stats: Option<AthleteStats>,
     activities: Vec<Activity>,
     current_view: View,
+    // Views left for another, most recent last
+    view_history: Vec<View>,
     selected_activity_index: usize,
     activity_page: u32,
+    prefetch_rows: usize,
+    max_pages: Option<u32>,
     is_loading: bool,
+    is_refreshing: bool,
     has_more_activities: bool,
+    // The activities page that failed to load and why; paging waits for a retry
+    failed_page: Option<(u32, String)>,
     scroll_offset: u32,
+    activity_streams: Option<(u64, StreamSet)>,
+    // Zones from Strava, for activities whose streams can't be bucketed
+    activity_zones: Option<(u64, Vec<ActivityZone>)>,
+    // Grade adjusted speed of every activity whose streams were loaded
+    grade_adjusted: HashMap<u64, f64>,
+    sort_key: Option<SortKey>,
+    sort_direction: SortDirection,
+    sort_mode: bool,
+    units: Units,
+    activity_detail: Option<DetailedActivity>,
+    splits_mode: SplitsMode,
+    training_period: TrainingPeriod,
+    dashboard_range: DashboardRange,
+    // Group header rows in the activities table, off when None
+    activity_grouping: Option<TrainingPeriod>,
+    hide_commutes: bool,
+    time_basis: TimeBasis,
+    hide_private: bool,
+    // Leaves trainer and virtual activities out of the dashboard figures and
+    // goals, without taking them out of the list
+    exclude_indoor: bool,
+    // What auto-refresh raises desktop notifications for
+    notifications: NotificationConfig,
+    // Local tags per activity; the list can be narrowed to one of them
+    tags: BTreeMap<u64, BTreeSet<String>>,
+    tag_filter: Option<String>,
+    tag_editor: Option<TagEditor>,
+    // Private notes per activity, kept next to the tags
+    notes: BTreeMap<u64, String>,
+    note_editor: Option<NoteEditor>,
+    // Loaded activities taken out of the list by the filters above
+    hidden_activities: Vec<Activity>,
+    detail_tab: DetailTab,
+    activity_social: Option<ActivitySocial>,
+    activity_photos: Option<ActivityPhotos>,
+    graphics: Option<GraphicsProtocol>,
+    // Where the photo goes; images are drawn by the event loop after each frame
+    photo_area: Option<Rect>,
+    edit_form: Option<EditForm>,
+    // The action and the name of the activity it applies to
+    confirm: Option<(ConfirmAction, String)>,
+    // Asked once; a declined re-authorization isn't offered again
+    reauth_declined: bool,
+    // Paging and polling wait until Strava's rate limit resets
+    rate_limited_until: Option<DateTime<Utc>>,
+    rate_limit: Option<RateLimit>,
+    // When activities last arrived from Strava
+    last_synced: Option<DateTime<chrono::Local>>,
+    // Drives the spinner animation
+    started: Instant,
+    gear: BTreeMap<String, Gear>,
+    status: Option<(String, Instant)>,
+    // Informational counterpart to `status`, e.g. new activities found by auto-refresh
+    notice: Option<(String, Instant)>,
+    // Files from the watched folder on their way to Strava, by file name
+    uploads: Vec<(String, UploadState)>,
+    error_log: VecDeque<(chrono::DateTime<chrono::Local>, String)>,
+    show_error_log: bool,
+    show_help: bool,
+    date_range: DateRange,
+    range_label: Option<String>,
+    range_form: Option<RangeForm>,
+    date_jump: Option<DateJump>,
+    // Cached plus loaded activities; dashboard figures use this rather than the current list
+    history: BTreeMap<u64, Activity>,
+    highlights: BTreeMap<u64, Vec<EffortHighlight>>,
+    starred_segments: Option<Vec<SummarySegment>>,
+    segment_details: BTreeMap<u64, DetailedSegment>,
+    selected_segment_index: usize,
+    selected_route_index: usize,
+    plan: Vec<PlannedWorkout>,
+    // Month shown in the plan view, relative to the current one
+    plan_month_offset: i32,
+    clubs: Option<Vec<SummaryClub>>,
+    selected_club_index: usize,
+    profile: Option<DetailedAthlete>,
+    following: social::FollowingFeed,
+    saved_routes: Option<Vec<Route>>,
+    selected_saved_route: usize,
+    route_streams: HashMap<u64, StreamSet>,
+    club_feed: ClubFeed,
+    goals: Vec<Goal>,
+    resting_heartrate: Option<u32>,
+    efficiency_metric: EfficiencyMetric,
+    max_heartrate: Option<u32>,
+    // Per activity; an empty list marks a detail fetched without efforts
+    best_efforts: BTreeMap<u64, Vec<BestEffort>>,
+    theme: Theme,
+    language: Language,
+    locale: Locale,
+    columns: Vec<ActivityColumn>,
+    // The user's script with custom columns and dashboard cards
+    scripts: Option<Scripts>,
+    sport_icons: SportIcons,
+    // Keeps the selected row in view; the offset is maintained by ratatui
+    table_state: TableState,
+    // Rows visible in the last rendered activities table, for PageUp/PageDown
+    table_page_rows: usize,
+}
+
+// The views named in the footer, in order, until the width runs out
+const FOOTER_VIEWS: [&str; 13] = [
+    "[D]ashboard",
+    "[A]ctivities",
+    "[T]raining",
+    "Pr[o]gress",
+    "[Y]ear",
+    "[F]itness",
+    "[R]outes",
+    "[W]orkout plan",
+    "[B]ikes",
+    "Sport [B]reakdown",
+    "[P]Rs",
+    "[S]egments",
+    "[C]lubs",
+];
+// Strava's best effort names for the distances shown as personal bests
+const PERSONAL_BEST_EFFORTS: [&str; 6] = ["400m", "1k", "5k", "10k", "Half-Marathon", "Marathon"];
+
+// Recent activities of the selected club, paged like the own activity list
+#[derive(Default)]
+struct ClubFeed {
+    activities: Vec<ClubActivity>,
+    page: u32,
+    loading: bool,
+    exhausted: bool,
+    // The next page failed and waits for a retry
+    failed: bool,
+    selected: usize,
+}
+
+// A best effort or segment effort ranked in the athlete's top three
+struct EffortHighlight {
+    name: String,
+    rank: u32,
+    elapsed_time: u32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,170 +220,2246 @@ pub enum View {
     Dashboard,
     Activities,
     ActivityDetail,
+    Training,
+    Gear,
+    Achievements,
+    Segments,
+    Clubs,
+    Progress,
+    Heatmap,
+    Fitness,
+    Routes,
+    Plan,
+    Sports,
+    Profile,
+    Social,
+    SavedRoutes,
+}
+
+impl View {
+    pub fn title(&self) -> &'static str {
+        match self {
+            View::Dashboard => "Dashboard",
+            View::Activities => "Activities",
+            View::ActivityDetail => "Activity Details",
+            View::Training => "Training",
+            View::Gear => "Gear",
+            View::Achievements => "Achievements",
+            View::Segments => "Starred Segments",
+            View::Clubs => "Clubs",
+            View::Progress => "Progress",
+            View::Heatmap => "Activity Calendar",
+            View::Fitness => "Fitness & Freshness",
+            View::Routes => "Repeated Routes",
+            View::Plan => "Training Plan",
+            View::Sports => "Sport Breakdown",
+            View::Profile => "Athlete Profile",
+            View::Social => "Following Feed",
+            View::SavedRoutes => "Saved Routes",
+        }
+    }
+
+    /// The views in the tab bar, in order. Activity details open from the
+    /// activities list instead.
+    pub const TABS: [View; 16] = [
+        View::Dashboard,
+        View::Activities,
+        View::Segments,
+        View::Gear,
+        View::Clubs,
+        View::Training,
+        View::Progress,
+        View::Heatmap,
+        View::Fitness,
+        View::Routes,
+        View::Plan,
+        View::Sports,
+        View::Achievements,
+        View::Profile,
+        View::Social,
+        View::SavedRoutes,
+    ];
+
+    /// Short name in the tab bar
+    pub fn tab_title(&self) -> &'static str {
+        match self {
+            View::Dashboard => "Dashboard",
+            View::Activities | View::ActivityDetail => "Activities",
+            View::Training => "Training",
+            View::Gear => "Gear",
+            View::Achievements => "PRs",
+            View::Segments => "Segments",
+            View::Clubs => "Clubs",
+            View::Progress => "Progress",
+            View::Heatmap => "Heatmap",
+            View::Fitness => "Fitness",
+            View::Routes => "Routes",
+            View::Plan => "Plan",
+            View::Sports => "Sports",
+            View::Profile => "Profile",
+            View::Social => "Social",
+            View::SavedRoutes => "Saved routes",
+        }
+    }
+
+    // Position in the tab bar; details count as the activities tab
+    fn tab(&self) -> usize {
+        let view = match self {
+            View::ActivityDetail => View::Activities,
+            view => *view,
+        };
+        View::TABS.iter().position(|&tab| tab == view).unwrap_or(0)
+    }
+
+    // The module that draws the view and handles its keys
+    fn screen(&self) -> &'static dyn Screen {
+        match self {
+            View::Dashboard => &dashboard::Dashboard,
+            View::Activities => &activities::Activities,
+            View::ActivityDetail => &detail::ActivityDetail,
+            View::Training => &training::Training,
+            View::Gear => &gear::Gear,
+            View::Achievements => &achievements::Achievements,
+            View::Segments => &segments::Segments,
+            View::Clubs => &clubs::Clubs,
+            View::Progress => &progress::Progress,
+            View::Heatmap => &heatmap::Heatmap,
+            View::Fitness => &freshness::Fitness,
+            View::Routes => &routes::Routes,
+            View::Plan => &plan::Plan,
+            View::Sports => &sports::Sports,
+            View::Profile => &profile::Profile,
+            View::Social => &social::Social,
+            View::SavedRoutes => &saved_routes::SavedRoutes,
+        }
+    }
+}
+
+/// The part of the UI behind one view: how it is drawn and what the keys
+/// bound to it do.
+pub trait Screen {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect);
+    /// Handles an action bound in the view. Global actions never get here,
+    /// except j/k, which move through the activities unless the view binds
+    /// them itself.
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Down => app.select_next_activity(),
+            Action::Up => app.select_prev_activity(),
+            _ => {}
+        }
+        None
+    }
+}
+
+/// What a key press leaves to the event loop, which owns the worker and the
+/// local caches.
+#[derive(Debug, Clone)]
+pub enum Effect {
+    Quit,
+    /// Load streams and details of the activity just opened
+    Open(Box<Activity>),
+    /// Fetch athlete stats and the newest activities again
+    Refresh,
+    /// Put text on the system clipboard, then show the notice
+    Copy(String, &'static str),
+    /// Open the URL in the browser, then show the notice
+    Browse(String, &'static str),
+    /// Download the saved route's GPX into the download directory
+    DownloadRoute(u64),
+    /// Keep the activity's tags in the tag store
+    SaveTags(u64, BTreeSet<String>),
+    /// Keep the activity's notes in the note store
+    SaveNote(u64, String),
+    /// Send the changes to the activity to Strava
+    Update(u64, UpdatableActivity),
+    /// Delete the activity on Strava
+    Delete(u64),
+    /// Leave the UI to authorize again in the browser
+    Reauthorize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrainingPeriod {
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Date,
+    Distance,
+    Elevation,
+    Pace,
+    HeartRate,
+}
+
+impl SortKey {
+    const ALL: [SortKey; 5] = [
+        SortKey::Date,
+        SortKey::Distance,
+        SortKey::Elevation,
+        SortKey::Pace,
+        SortKey::HeartRate,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortKey::Date => "Date",
+            SortKey::Distance => "Distance",
+            SortKey::Elevation => "Elev",
+            SortKey::Pace => "Pace",
+            SortKey::HeartRate => "HR",
+        }
+    }
+
+    fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|k| *k == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|k| *k == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    // Activities without a value for the key (no HR, zero distance) sort last
+    fn value(&self, activity: &Activity) -> Option<f64> {
+        match self {
+            SortKey::Date => Some(activity.start_date_local.timestamp() as f64),
+            SortKey::Distance => Some(activity.distance),
+            SortKey::Elevation => Some(activity.total_elevation_gain),
+            SortKey::Pace => (activity.distance > 0.0)
+                .then(|| activity.moving_time as f64 / (activity.distance / 1000.0)),
+            SortKey::HeartRate => activity.average_heartrate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailTab {
+    Overview,
+    Map,
+    Photos,
+    Notes,
+    Zones,
+    Social,
+}
+
+impl DetailTab {
+    const ALL: [DetailTab; 6] = [
+        DetailTab::Overview,
+        DetailTab::Map,
+        DetailTab::Photos,
+        DetailTab::Notes,
+        DetailTab::Zones,
+        DetailTab::Social,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            DetailTab::Overview => "Overview",
+            DetailTab::Map => "Map",
+            DetailTab::Photos => "Photos",
+            DetailTab::Notes => "Notes",
+            DetailTab::Zones => "Zones",
+            DetailTab::Social => "Kudos & Comments",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|t| t == self).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditField {
+    Name,
+    Description,
+    Commute,
+    Trainer,
+    GearId,
+}
+
+impl EditField {
+    const ALL: [EditField; 5] = [
+        EditField::Name,
+        EditField::Description,
+        EditField::Commute,
+        EditField::Trainer,
+        EditField::GearId,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            EditField::Name => "Name",
+            EditField::Description => "Description",
+            EditField::Commute => "Commute",
+            EditField::Trainer => "Trainer",
+            EditField::GearId => "Gear ID",
+        }
+    }
+}
+
+struct EditForm {
+    activity_id: u64,
+    name: String,
+    description: String,
+    commute: bool,
+    trainer: bool,
+    gear_id: String,
+    field: EditField,
+    saving: bool,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+struct DateJump {
+    input: String,
+    error: Option<String>,
+}
+
+struct TagEditor {
+    activity_id: u64,
+    input: String,
+}
+
+struct NoteEditor {
+    activity_id: u64,
+    text: String,
 }
 
-impl App {
-    pub fn new() -> Self {
-        Self {
-            athlete: None,
-            stats: None,
-            activities: Vec::new(),
-            current_view: View::Dashboard,
-            selected_activity_index: 0,
-            activity_page: 1,
-            is_loading: false,
-            has_more_activities: true,
-            scroll_offset: 0,
+#[derive(Default)]
+struct RangeForm {
+    from: String,
+    to: String,
+    editing_to: bool,
+    error: Option<String>,
+}
+
+// Accepts YYYY, YYYY-MM or YYYY-MM-DD in local time. The end bound covers the
+// whole year/month/day, so it resolves to the start of the following period.
+fn parse_range_bound(input: &str, end: bool) -> Result<Option<NaiveDate>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    let invalid = || {
+        format!(
+            "'{}' is not a date (use YYYY, YYYY-MM or YYYY-MM-DD)",
+            input
+        )
+    };
+    let parts: Vec<&str> = input.split('-').collect();
+    let numbers: Vec<u32> = parts
+        .iter()
+        .map(|p| p.parse::<u32>().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+
+    let date = match numbers[..] {
+        [year] => {
+            let start = NaiveDate::from_ymd_opt(year as i32, 1, 1);
+            if end {
+                NaiveDate::from_ymd_opt(year as i32 + 1, 1, 1)
+            } else {
+                start
+            }
+        }
+        [year, month] => {
+            let start = NaiveDate::from_ymd_opt(year as i32, month, 1);
+            if end {
+                start.and_then(|d| d.checked_add_months(chrono::Months::new(1)))
+            } else {
+                start
+            }
+        }
+        [year, month, day] => {
+            let start = NaiveDate::from_ymd_opt(year as i32, month, day);
+            if end {
+                start.and_then(|d| d.succ_opt())
+            } else {
+                start
+            }
+        }
+        _ => None,
+    };
+    date.map(Some).ok_or_else(invalid)
+}
+
+fn local_epoch(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|dt| dt.and_local_timezone(chrono::Local).earliest())
+        .map(|dt| dt.timestamp())
+        .unwrap_or_else(|| date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp())
+}
+
+impl EditForm {
+    fn text_field_focused(&self) -> bool {
+        !matches!(self.field, EditField::Commute | EditField::Trainer)
+    }
+
+    fn text_mut(&mut self) -> Option<&mut String> {
+        match self.field {
+            EditField::Name => Some(&mut self.name),
+            EditField::Description => Some(&mut self.description),
+            EditField::GearId => Some(&mut self.gear_id),
+            EditField::Commute | EditField::Trainer => None,
+        }
+    }
+}
+
+struct ActivitySocial {
+    activity_id: u64,
+    kudoers: Vec<SummaryAthlete>,
+    comments: Vec<Comment>,
+}
+
+/// Changes that need a confirmation before they are sent to Strava.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmAction {
+    Mute(u64),
+    Delete(u64),
+    Reauthorize,
+}
+
+struct ActivityPhotos {
+    activity_id: u64,
+    photos: Vec<Photo>,
+    // The first photo, only downloaded when the terminal can show images
+    image: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitsMode {
+    Metric,
+    Standard,
+    Laps,
+}
+
+impl SplitsMode {
+    fn next(self) -> Self {
+        match self {
+            SplitsMode::Metric => SplitsMode::Standard,
+            SplitsMode::Standard => SplitsMode::Laps,
+            SplitsMode::Laps => SplitsMode::Metric,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// How far a file from the watched folder got on its way to Strava.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadState {
+    Uploading,
+    /// Sent; Strava is turning it into an activity
+    Processing,
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            athlete: None,
+            stats: None,
+            activities: Vec::new(),
+            current_view: View::Dashboard,
+            view_history: Vec::new(),
+            selected_activity_index: 0,
+            activity_page: 0,
+            prefetch_rows: DEFAULT_PREFETCH_ROWS,
+            max_pages: None,
+            is_loading: false,
+            is_refreshing: false,
+            has_more_activities: true,
+            failed_page: None,
+            scroll_offset: 0,
+            activity_streams: None,
+            activity_zones: None,
+            grade_adjusted: HashMap::new(),
+            sort_key: None,
+            sort_direction: SortDirection::Descending,
+            sort_mode: false,
+            units: Units::Metric,
+            activity_detail: None,
+            splits_mode: SplitsMode::Metric,
+            training_period: TrainingPeriod::Week,
+            dashboard_range: DashboardRange::AllTime,
+            activity_grouping: None,
+            hide_commutes: false,
+            time_basis: TimeBasis::Moving,
+            hide_private: false,
+            exclude_indoor: false,
+            notifications: NotificationConfig::default(),
+            tags: BTreeMap::new(),
+            tag_filter: None,
+            tag_editor: None,
+            notes: BTreeMap::new(),
+            note_editor: None,
+            hidden_activities: Vec::new(),
+            detail_tab: DetailTab::Overview,
+            activity_social: None,
+            activity_photos: None,
+            graphics: None,
+            photo_area: None,
+            edit_form: None,
+            confirm: None,
+            reauth_declined: false,
+            rate_limited_until: None,
+            rate_limit: None,
+            last_synced: None,
+            started: Instant::now(),
+            gear: BTreeMap::new(),
+            status: None,
+            notice: None,
+            uploads: Vec::new(),
+            error_log: VecDeque::new(),
+            show_error_log: false,
+            show_help: false,
+            date_range: DateRange::default(),
+            range_label: None,
+            range_form: None,
+            date_jump: None,
+            history: BTreeMap::new(),
+            highlights: BTreeMap::new(),
+            starred_segments: None,
+            segment_details: BTreeMap::new(),
+            selected_segment_index: 0,
+            selected_route_index: 0,
+            plan: Vec::new(),
+            plan_month_offset: 0,
+            clubs: None,
+            selected_club_index: 0,
+            profile: None,
+            following: social::FollowingFeed::default(),
+            saved_routes: None,
+            selected_saved_route: 0,
+            route_streams: HashMap::new(),
+            club_feed: ClubFeed::default(),
+            goals: Vec::new(),
+            resting_heartrate: None,
+            efficiency_metric: EfficiencyMetric::default(),
+            max_heartrate: None,
+            best_efforts: BTreeMap::new(),
+            theme: Theme::default(),
+            language: Language::default(),
+            locale: Locale::default(),
+            columns: ActivityColumn::DEFAULT.to_vec(),
+            scripts: None,
+            sport_icons: SportIcons::None,
+            table_state: TableState::default(),
+            table_page_rows: 10,
+        }
+    }
+
+    pub fn set_data(
+        &mut self,
+        athlete: Athlete,
+        stats: AthleteStats,
+        activities: Vec<Activity>,
+        per_page: usize,
+    ) {
+        let count = activities.len();
+        self.athlete = Some(athlete);
+        self.stats = Some(stats);
+        self.merge_history(activities.iter().cloned());
+        self.activities = activities;
+        self.hidden_activities.clear();
+        self.activity_page = 1;
+        self.has_more_activities = count >= per_page;
+        self.apply_sort();
+    }
+
+    pub fn set_athlete(&mut self, athlete: Athlete) {
+        self.athlete = Some(athlete);
+    }
+
+    pub fn set_stats(&mut self, stats: AthleteStats) {
+        self.stats = Some(stats);
+    }
+
+    // Recent PR activities whose best efforts haven't been fetched yet, capped
+    // to keep API usage down
+    pub fn achievement_details_needed(&self) -> Vec<u64> {
+        self.achievement_activities()
+            .into_iter()
+            .filter(|a| a.pr_count.unwrap_or(0) > 0 && !self.highlights.contains_key(&a.id))
+            .take(10)
+            .map(|a| a.id)
+            .collect()
+    }
+
+    // Cached runs whose best efforts haven't been fetched yet, newest first and
+    // capped like achievement details
+    pub fn best_effort_details_needed(&self) -> Vec<u64> {
+        let mut runs: Vec<&Activity> = self
+            .history
+            .values()
+            .filter(|a| a.sport_type.contains("Run") && !self.best_efforts.contains_key(&a.id))
+            .collect();
+        runs.sort_by_key(|a| std::cmp::Reverse(a.start_date));
+        runs.into_iter().take(10).map(|a| a.id).collect()
+    }
+
+    pub fn merge_best_efforts<'a>(
+        &mut self,
+        efforts: impl IntoIterator<Item = (u64, &'a Vec<BestEffort>)>,
+    ) {
+        for (activity_id, efforts) in efforts {
+            self.best_efforts.insert(activity_id, efforts.clone());
+        }
+    }
+
+    // Fastest effort per distance across everything fetched so far
+    fn personal_bests(&self) -> Vec<(&'static str, Option<&BestEffort>)> {
+        PERSONAL_BEST_EFFORTS
+            .into_iter()
+            .map(|name| {
+                let best = self
+                    .best_efforts
+                    .values()
+                    .flatten()
+                    .filter(|e| e.name == name)
+                    .min_by_key(|e| e.elapsed_time);
+                (name, best)
+            })
+            .collect()
+    }
+
+    // Records from analytics::RECORDS still held by each cached activity
+    fn records_by_activity(&self) -> BTreeMap<u64, Vec<&'static str>> {
+        let mut records: BTreeMap<u64, Vec<&'static str>> = BTreeMap::new();
+        for (kind, activity) in analytics::personal_records(self.history.values()) {
+            records.entry(activity.id).or_default().push(kind.name);
+        }
+        records
+    }
+
+    fn achievement_activities(&self) -> Vec<&Activity> {
+        let mut activities: Vec<&Activity> = self
+            .history
+            .values()
+            .filter(|a| a.pr_count.unwrap_or(0) > 0 || a.achievement_count.unwrap_or(0) > 0)
+            .collect();
+        activities.sort_by_key(|a| std::cmp::Reverse(a.start_date));
+        activities
+    }
+
+    pub fn merge_history(&mut self, activities: impl IntoIterator<Item = Activity>) {
+        for activity in activities {
+            self.history.insert(activity.id, activity);
+        }
+    }
+
+    /// Activities in the list, without the ones the filters hide
+    pub fn activities(&self) -> &[Activity] {
+        &self.activities
+    }
+
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn set_goals(&mut self, goals: Vec<Goal>) {
+        self.goals = goals;
+    }
+
+    pub fn set_heart_rates(&mut self, resting: Option<u32>, max: Option<u32>) {
+        self.resting_heartrate = resting;
+        self.max_heartrate = max;
+    }
+
+    pub fn set_efficiency_metric(&mut self, metric: EfficiencyMetric) {
+        self.efficiency_metric = metric;
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn set_language(&mut self, language: Language) {
+        self.language = language;
+    }
+
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+    }
+
+    /// Switches between the 24 and 12-hour clock.
+    pub fn toggle_time_format(&mut self) {
+        self.locale.time_format = match self.locale.time_format {
+            TimeFormat::TwentyFourHour => TimeFormat::TwelveHour,
+            TimeFormat::TwelveHour => TimeFormat::TwentyFourHour,
+        };
+    }
+
+    pub fn tr(&self, text: &'static str) -> &'static str {
+        self.language.tr(text)
+    }
+
+    pub fn trf(&self, template: &'static str, args: &[&dyn Display]) -> String {
+        self.language.trf(template, args)
+    }
+
+    // An empty list keeps the default columns
+    pub fn set_activity_columns(&mut self, columns: Vec<ActivityColumn>) {
+        if !columns.is_empty() {
+            self.columns = columns;
+        }
+    }
+
+    pub fn set_scripts(&mut self, scripts: Scripts) {
+        self.scripts = Some(scripts);
+    }
+
+    pub fn set_sport_icons(&mut self, icons: SportIcons) {
+        self.sport_icons = icons;
+    }
+
+    pub fn set_units(&mut self, units: Units) {
+        self.units = units;
+    }
+
+    pub fn units(&self) -> Units {
+        self.units
+    }
+
+    pub fn set_view(&mut self, view: View) {
+        if view != self.current_view {
+            if self.view_history.len() == VIEW_HISTORY_CAPACITY {
+                self.view_history.remove(0);
+            }
+            self.view_history.push(self.current_view);
+        }
+        self.current_view = view;
+    }
+
+    /// Returns to the view shown before the current one. False if there is none.
+    pub fn back(&mut self) -> bool {
+        match self.view_history.pop() {
+            Some(view) => {
+                self.current_view = view;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn current_view(&self) -> View {
+        self.current_view
+    }
+
+    pub fn next_view(&mut self) {
+        let next = (self.current_view.tab() + 1) % View::TABS.len();
+        self.set_view(View::TABS[next]);
+    }
+
+    pub fn prev_view(&mut self) {
+        let prev = (self.current_view.tab() + View::TABS.len() - 1) % View::TABS.len();
+        self.set_view(View::TABS[prev]);
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.is_loading
+    }
+
+    pub fn set_loading(&mut self, loading: bool) {
+        self.is_loading = loading;
+    }
+
+    pub fn set_paging(&mut self, prefetch_rows: usize, max_pages: Option<u32>) {
+        self.prefetch_rows = prefetch_rows;
+        self.max_pages = max_pages;
+    }
+
+    pub fn should_load_more(&self) -> bool {
+        !self.is_loading
+            && !self.is_rate_limited()
+            && self.failed_page.is_none()
+            && self.has_more_activities
+            && self.max_pages.is_none_or(|max| self.activity_page < max)
+            && (self.selected_activity_index
+                >= self.activities.len().saturating_sub(self.prefetch_rows)
+                || self.activities.len() < self.table_page_rows)
+    }
+
+    pub fn add_activities(&mut self, page: Page<Activity>, per_page: u32) {
+        self.has_more_activities = !page.is_last(per_page);
+        let new_activities = page.items;
+        self.merge_history(new_activities.iter().cloned());
+        // Activities added by a refresh shift Strava's pages, so a page can repeat some
+        let known: BTreeSet<u64> = self
+            .activities
+            .iter()
+            .chain(&self.hidden_activities)
+            .map(|a| a.id)
+            .collect();
+        self.activities.extend(
+            new_activities
+                .into_iter()
+                .filter(|a| !known.contains(&a.id)),
+        );
+        self.apply_sort();
+        self.activity_page += 1;
+        self.is_loading = false;
+        self.last_synced = Some(chrono::Local::now());
+    }
+
+    pub fn is_refreshing(&self) -> bool {
+        self.is_refreshing
+    }
+
+    pub fn set_refreshing(&mut self, refreshing: bool) {
+        self.is_refreshing = refreshing;
+    }
+
+    /// Applies a re-fetched first page: known activities are updated in
+    /// place, new ones inserted ahead of them, and the selection stays on
+    /// the same activity.
+    pub fn merge_refresh(
+        &mut self,
+        athlete: Athlete,
+        stats: AthleteStats,
+        first_page: Vec<Activity>,
+    ) {
+        self.athlete = Some(athlete);
+        self.stats = Some(stats);
+        self.merge_history(first_page.iter().cloned());
+
+        let mut insert_at = 0;
+        for activity in first_page {
+            if let Some(index) = self.activities.iter().position(|a| a.id == activity.id) {
+                self.activities[index] = activity;
+                insert_at = index + 1;
+            } else {
+                if insert_at <= self.selected_activity_index && !self.activities.is_empty() {
+                    self.selected_activity_index += 1;
+                }
+                self.activities.insert(insert_at, activity);
+                insert_at += 1;
+            }
+        }
+        self.apply_sort();
+        self.is_refreshing = false;
+        self.last_synced = Some(chrono::Local::now());
+    }
+
+    /// Adds activities found by auto-refresh on top of the list and returns
+    /// how many were new. With a date range active they only go into the
+    /// history, since they're newer than the range.
+    pub fn insert_new_activities(&mut self, activities: Vec<Activity>) -> usize {
+        self.last_synced = Some(chrono::Local::now());
+        let mut new: Vec<Activity> = activities
+            .into_iter()
+            .filter(|a| !self.history.contains_key(&a.id))
+            .collect();
+        if new.is_empty() {
+            return 0;
+        }
+        self.merge_history(new.iter().cloned());
+
+        let count = new.len();
+        if self.date_range.is_empty() {
+            new.sort_by_key(|a| std::cmp::Reverse(a.start_date));
+            if !self.activities.is_empty() {
+                self.selected_activity_index += count;
+            }
+            self.activities.splice(0..0, new);
+            self.apply_sort();
+        }
+        self.show_notice(match count {
+            1 => self.tr("1 new activity").to_string(),
+            n => self.trf("{} new activities", &[&n]),
+        });
+        count
+    }
+
+    pub fn set_upload(&mut self, file: String, state: UploadState) {
+        match self.uploads.iter_mut().find(|(name, _)| *name == file) {
+            Some(upload) => upload.1 = state,
+            None => self.uploads.push((file, state)),
+        }
+    }
+
+    pub fn uploads(&self) -> &[(String, UploadState)] {
+        &self.uploads
+    }
+
+    /// Adds the activity an upload turned into and names it in the header.
+    pub fn upload_finished(&mut self, file: &str, activity: Activity) {
+        self.clear_upload(file);
+        let message = self.trf(
+            "Uploaded {}: {}, {} {}",
+            &[
+                &file,
+                &activity.name,
+                &self
+                    .locale
+                    .decimal(self.units.distance(activity.distance), 1),
+                &self.units.distance_unit(),
+            ],
+        );
+        self.insert_new_activities(vec![activity]);
+        self.show_notice(message);
+    }
+
+    pub fn clear_upload(&mut self, file: &str) {
+        self.uploads.retain(|(name, _)| name != file);
+    }
+
+    /// Strava couldn't make an activity of the file, e.g. a duplicate.
+    pub fn upload_rejected(&mut self, file: &str, reason: &str) {
+        self.clear_upload(file);
+        let context = self.trf("Failed to upload {}", &[&file]);
+        self.report_error(format!("{}: {}", context, reason));
+    }
+
+    pub fn upload_error(&mut self, file: &str, error: &Error) {
+        self.clear_upload(file);
+        self.report_api_error(self.trf("Failed to upload {}", &[&file]), error);
+    }
+
+    /// Title and body of the desktop notification for `event`.
+    pub fn notification(&self, event: &notify::Event) -> (String, String) {
+        match event {
+            notify::Event::NewActivity(activity) => (
+                self.trf("New activity: {}", &[&activity.name]),
+                format!(
+                    "{} · {:.1} {} · {}",
+                    activity.sport_type,
+                    self.units.distance(activity.distance),
+                    self.units.distance_unit(),
+                    Self::format_hms(activity.moving_time as f64)
+                ),
+            ),
+            notify::Event::Kudos { activity, new } => (
+                match new {
+                    1 => self.tr("1 new kudos").to_string(),
+                    n => self.trf("{} new kudos", &[n]),
+                },
+                self.trf(
+                    "on {} ({} in total)",
+                    &[&activity.name, &activity.kudos_count.unwrap_or(0)],
+                ),
+            ),
+            notify::Event::Record { record, activity } => (
+                self.tr("New personal record").to_string(),
+                format!("{}: {}", record, activity.name),
+            ),
+            notify::Event::SegmentPrs { activity, count } => (
+                match count {
+                    1 => self.tr("1 segment PR").to_string(),
+                    n => self.trf("{} segment PRs", &[n]),
+                },
+                activity.name.clone(),
+            ),
+        }
+    }
+
+    /// Shows a short confirmation in the header until it times out.
+    pub fn show_notice(&mut self, message: String) {
+        self.notice = Some((message, Instant::now()));
+    }
+
+    pub fn notice(&self) -> Option<&str> {
+        self.notice
+            .as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < STATUS_TIMEOUT)
+            .map(|(message, _)| message.as_str())
+    }
+
+    pub fn set_load_error(&mut self, page: u32, reason: String) {
+        self.is_loading = false;
+        self.failed_page = Some((page, reason));
+    }
+
+    pub fn failed_page(&self) -> Option<u32> {
+        self.failed_page.as_ref().map(|(page, _)| *page)
+    }
+
+    /// Lets paging request the failed page again. False if nothing failed.
+    pub fn retry_failed_page(&mut self) -> bool {
+        self.failed_page.take().is_some()
+    }
+
+    pub fn activity_page(&self) -> u32 {
+        self.activity_page
+    }
+
+    pub fn scroll_left(&mut self) {
+        if self.scroll_offset > 0 {
+            self.scroll_offset -= 1;
+        }
+    }
+
+    // The first column never scrolls and at least one other stays visible
+    pub fn scroll_right(&mut self) {
+        if (self.scroll_offset as usize) < self.columns.len().saturating_sub(2) {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn scroll_offset(&self) -> u32 {
+        self.scroll_offset
+    }
+
+    pub fn set_activity_streams(&mut self, activity_id: u64, streams: StreamSet) {
+        if let Some(speed) = analytics::grade_adjusted_speed(&streams, 0.0..f64::INFINITY) {
+            self.grade_adjusted.insert(activity_id, speed);
+        }
+        self.activity_streams = Some((activity_id, streams));
+    }
+
+    /// Any activity seen this session or in the cache, filtered or not.
+    pub fn find_activity(&self, activity_id: u64) -> Option<&Activity> {
+        self.history.get(&activity_id)
+    }
+
+    pub fn has_streams_for(&self, activity_id: u64) -> bool {
+        matches!(self.activity_streams, Some((id, _)) if id == activity_id)
+    }
+
+    // Details fetched for the achievements view only contribute highlights and
+    // don't replace the one shown in the detail view
+    pub fn set_activity_detail(&mut self, detail: DetailedActivity) {
+        let best_efforts = detail
+            .best_efforts
+            .iter()
+            .flatten()
+            .map(|e| (&e.name, e.pr_rank, e.elapsed_time));
+        let segment_efforts = detail
+            .segment_efforts
+            .iter()
+            .flatten()
+            .map(|e| (&e.name, e.pr_rank, e.elapsed_time));
+        let highlights = best_efforts
+            .chain(segment_efforts)
+            .filter_map(|(name, rank, elapsed_time)| {
+                rank.filter(|r| (1..=3).contains(r))
+                    .map(|rank| EffortHighlight {
+                        name: name.clone(),
+                        rank,
+                        elapsed_time,
+                    })
+            })
+            .collect();
+        self.highlights.insert(detail.activity.id, highlights);
+        self.best_efforts.insert(
+            detail.activity.id,
+            detail.best_efforts.clone().unwrap_or_default(),
+        );
+
+        if self.get_selected_activity().map(|a| a.id) == Some(detail.activity.id) {
+            self.activity_detail = Some(detail);
+        }
+    }
+
+    pub fn has_detail_for(&self, activity_id: u64) -> bool {
+        self.activity_detail
+            .as_ref()
+            .is_some_and(|d| d.activity.id == activity_id)
+    }
+
+    pub fn splits_mode(&self) -> SplitsMode {
+        self.splits_mode
+    }
+
+    pub fn cycle_splits_mode(&mut self) {
+        self.splits_mode = self.splits_mode.next();
+    }
+
+    /// Switches between the device laps and the splits in the configured units.
+    pub fn toggle_laps(&mut self) {
+        self.splits_mode = match (self.splits_mode, self.units) {
+            (SplitsMode::Laps, Units::Metric) => SplitsMode::Metric,
+            (SplitsMode::Laps, Units::Imperial) => SplitsMode::Standard,
+            _ => SplitsMode::Laps,
+        };
+    }
+
+    pub fn detail_tab(&self) -> DetailTab {
+        self.detail_tab
+    }
+
+    pub fn next_detail_tab(&mut self) {
+        let i = self.detail_tab.index();
+        self.detail_tab = DetailTab::ALL[(i + 1) % DetailTab::ALL.len()];
+    }
+
+    pub fn prev_detail_tab(&mut self) {
+        let i = self.detail_tab.index();
+        self.detail_tab = DetailTab::ALL[(i + DetailTab::ALL.len() - 1) % DetailTab::ALL.len()];
+    }
+
+    pub fn set_notes(&mut self, notes: impl IntoIterator<Item = (u64, String)>) {
+        self.notes = notes.into_iter().collect();
+    }
+
+    pub fn note_of(&self, activity_id: u64) -> Option<&str> {
+        self.notes.get(&activity_id).map(String::as_str)
+    }
+
+    pub fn is_editing_note(&self) -> bool {
+        self.note_editor.is_some()
+    }
+
+    /// Opens the notes tab with the note of the selected activity for editing.
+    pub fn start_note(&mut self) {
+        let Some(activity_id) = self.get_selected_activity().map(|a| a.id) else {
+            return;
+        };
+        let text = self.notes.get(&activity_id).cloned().unwrap_or_default();
+        self.detail_tab = DetailTab::Notes;
+        self.note_editor = Some(NoteEditor { activity_id, text });
+    }
+
+    pub fn cancel_note(&mut self) {
+        self.note_editor = None;
+    }
+
+    pub fn note_input(&mut self, c: char) {
+        if let Some(editor) = self.note_editor.as_mut() {
+            editor.text.push(c);
+        }
+    }
+
+    pub fn note_backspace(&mut self) {
+        if let Some(editor) = self.note_editor.as_mut() {
+            editor.text.pop();
+        }
+    }
+
+    /// Keeps the edited note and returns it for the note store. Trailing
+    /// whitespace is dropped and a blank note removes it.
+    pub fn submit_note(&mut self) -> Option<(u64, String)> {
+        let editor = self.note_editor.take()?;
+        let note = editor.text.trim_end().to_string();
+        if note.is_empty() {
+            self.notes.remove(&editor.activity_id);
+        } else {
+            self.notes.insert(editor.activity_id, note.clone());
+        }
+        Some((editor.activity_id, note))
+    }
+
+    pub fn set_activity_social(
+        &mut self,
+        activity_id: u64,
+        kudoers: Vec<SummaryAthlete>,
+        comments: Vec<Comment>,
+    ) {
+        self.activity_social = Some(ActivitySocial {
+            activity_id,
+            kudoers,
+            comments,
+        });
+    }
+
+    pub fn has_social_for(&self, activity_id: u64) -> bool {
+        self.activity_social
+            .as_ref()
+            .is_some_and(|s| s.activity_id == activity_id)
+    }
+
+    pub fn set_activity_zones(&mut self, activity_id: u64, zones: Vec<ActivityZone>) {
+        self.activity_zones = Some((activity_id, zones));
+    }
+
+    /// The selected activity, once its streams are loaded but turned out not
+    /// to allow computing heart rate zones and Strava's haven't been fetched.
+    pub fn zones_needed(&self) -> Option<u64> {
+        let id = self.get_selected_activity()?.id;
+        let fetched = matches!(self.activity_zones, Some((zones_id, _)) if zones_id == id);
+        (self.has_streams_for(id) && self.stream_zones().is_none() && !fetched).then_some(id)
+    }
+
+    // Heart rate zones from the selected activity's streams
+    fn stream_zones(&self) -> Option<ActivityZone> {
+        let streams = self.selected_streams()?;
+        let heart_rate = HeartRateRange::resolve(
+            self.resting_heartrate,
+            self.max_heartrate,
+            self.history.values(),
+        )?;
+        analytics::heart_rate_zones(streams, heart_rate.max)
+    }
+
+    pub fn set_activity_photos(
+        &mut self,
+        activity_id: u64,
+        photos: Vec<Photo>,
+        image: Option<Vec<u8>>,
+    ) {
+        self.activity_photos = Some(ActivityPhotos {
+            activity_id,
+            photos,
+            image,
+        });
+    }
+
+    pub fn has_photos_for(&self, activity_id: u64) -> bool {
+        self.activity_photos
+            .as_ref()
+            .is_some_and(|p| p.activity_id == activity_id)
+    }
+
+    pub fn set_graphics_protocol(&mut self, graphics: Option<GraphicsProtocol>) {
+        self.graphics = graphics;
+    }
+
+    pub fn graphics_protocol(&self) -> Option<GraphicsProtocol> {
+        self.graphics
+    }
+
+    /// The photo to draw after the last rendered frame, with the activity it
+    /// belongs to and the cells it should cover.
+    pub fn photo_placement(&self) -> Option<(u64, Rect, &[u8])> {
+        let area = self.photo_area?;
+        let photos = self.activity_photos.as_ref()?;
+        Some((photos.activity_id, area, photos.image.as_deref()?))
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.edit_form.is_some()
+    }
+
+    pub fn edit_field(&self) -> Option<EditField> {
+        self.edit_form.as_ref().map(|f| f.field)
+    }
+
+    pub fn start_edit(&mut self) {
+        let Some(activity) = self.get_selected_activity() else {
+            return;
+        };
+        self.edit_form = Some(EditForm {
+            activity_id: activity.id,
+            name: activity.name.clone(),
+            description: activity.description.clone().unwrap_or_default(),
+            commute: activity.commute.unwrap_or(false),
+            trainer: activity.trainer.unwrap_or(false),
+            gear_id: activity.gear_id.clone().unwrap_or_default(),
+            field: EditField::Name,
+            saving: false,
+            error: None,
+        });
+    }
+
+    pub fn request_mute(&mut self) {
+        if let Some(activity) = self.get_selected_activity() {
+            self.confirm = Some((ConfirmAction::Mute(activity.id), activity.name.clone()));
+        }
+    }
+
+    pub fn request_delete(&mut self) {
+        if let Some(activity) = self.get_selected_activity() {
+            self.confirm = Some((ConfirmAction::Delete(activity.id), activity.name.clone()));
+        }
+    }
+
+    pub fn is_confirming(&self) -> bool {
+        self.confirm.is_some()
+    }
+
+    /// Closes the dialog and hands back the confirmed action.
+    pub fn confirm(&mut self) -> Option<ConfirmAction> {
+        self.confirm.take().map(|(action, _)| action)
+    }
+
+    pub fn cancel_confirm(&mut self) {
+        if let Some((ConfirmAction::Reauthorize, _)) = self.confirm.take() {
+            self.reauth_declined = true;
+        }
+    }
+
+    /// Drops a deleted activity from the list and the history.
+    pub fn remove_activity(&mut self, activity_id: u64) {
+        self.activities.retain(|a| a.id != activity_id);
+        self.history.remove(&activity_id);
+        self.selected_activity_index = self
+            .selected_activity_index
+            .min(self.activities.len().saturating_sub(1));
+        if self.current_view == View::ActivityDetail && !self.back() {
+            self.current_view = View::Activities;
+        }
+    }
+
+    pub fn cancel_edit(&mut self) {
+        self.edit_form = None;
+    }
+
+    pub fn edit_next_field(&mut self) {
+        if let Some(form) = self.edit_form.as_mut() {
+            let i = EditField::ALL
+                .iter()
+                .position(|f| *f == form.field)
+                .unwrap_or(0);
+            form.field = EditField::ALL[(i + 1) % EditField::ALL.len()];
+        }
+    }
+
+    pub fn edit_prev_field(&mut self) {
+        if let Some(form) = self.edit_form.as_mut() {
+            let i = EditField::ALL
+                .iter()
+                .position(|f| *f == form.field)
+                .unwrap_or(0);
+            form.field = EditField::ALL[(i + EditField::ALL.len() - 1) % EditField::ALL.len()];
+        }
+    }
+
+    // Space toggles checkboxes; everywhere else characters go into the focused text field
+    pub fn edit_input(&mut self, c: char) {
+        let Some(form) = self.edit_form.as_mut() else {
+            return;
+        };
+        match form.field {
+            EditField::Commute if c == ' ' => form.commute = !form.commute,
+            EditField::Trainer if c == ' ' => form.trainer = !form.trainer,
+            _ => {
+                if let Some(text) = form.text_mut() {
+                    text.push(c);
+                }
+            }
+        }
+    }
+
+    pub fn edit_backspace(&mut self) {
+        if let Some(text) = self.edit_form.as_mut().and_then(|f| f.text_mut()) {
+            text.pop();
+        }
+    }
+
+    // Marks the form as saving and returns the changes to send, if any
+    pub fn submit_edit(&mut self) -> Option<(u64, UpdatableActivity)> {
+        let activity = self
+            .activities
+            .iter()
+            .find(|a| Some(a.id) == self.edit_form.as_ref().map(|f| f.activity_id))?
+            .clone();
+        let form = self.edit_form.as_mut()?;
+        if form.saving {
+            return None;
+        }
+
+        let changed = |new: &str, old: Option<&str>| {
+            (new != old.unwrap_or_default()).then(|| new.to_string())
+        };
+        let update = UpdatableActivity {
+            name: changed(&form.name, Some(&activity.name)),
+            description: changed(&form.description, activity.description.as_deref()),
+            commute: (form.commute != activity.commute.unwrap_or(false)).then_some(form.commute),
+            trainer: (form.trainer != activity.trainer.unwrap_or(false)).then_some(form.trainer),
+            gear_id: changed(&form.gear_id, activity.gear_id.as_deref()).map(|g| {
+                if g.is_empty() {
+                    "none".to_string()
+                } else {
+                    g
+                }
+            }),
+            hide_from_home: None,
+        };
+
+        if update == UpdatableActivity::default() {
+            self.edit_form = None;
+            return None;
+        }
+
+        form.saving = true;
+        form.error = None;
+        Some((form.activity_id, update))
+    }
+
+    pub fn apply_activity_update(&mut self, detail: DetailedActivity) {
+        if let Some(activity) = self
+            .activities
+            .iter_mut()
+            .find(|a| a.id == detail.activity.id)
+        {
+            *activity = detail.activity.clone();
+        }
+        self.merge_history([detail.activity.clone()]);
+        if self
+            .edit_form
+            .as_ref()
+            .is_some_and(|f| f.activity_id == detail.activity.id)
+        {
+            self.edit_form = None;
+        }
+        self.activity_detail = Some(detail);
+    }
+
+    pub fn set_edit_error(&mut self, error: String) {
+        if let Some(form) = self.edit_form.as_mut() {
+            form.saving = false;
+            form.error = Some(error);
+        }
+    }
+
+    fn selected_detail(&self) -> Option<&DetailedActivity> {
+        let selected = self.get_selected_activity()?;
+        self.activity_detail
+            .as_ref()
+            .filter(|d| d.activity.id == selected.id)
+    }
+
+    fn selected_streams(&self) -> Option<&StreamSet> {
+        let selected = self.get_selected_activity()?;
+        match &self.activity_streams {
+            Some((id, streams)) if *id == selected.id => Some(streams),
+            _ => None,
+        }
+    }
+
+    pub fn report_error(&mut self, message: String) {
+        if self.error_log.len() == ERROR_LOG_CAPACITY {
+            self.error_log.pop_back();
+        }
+        self.error_log
+            .push_front((chrono::Local::now(), message.clone()));
+        self.status = Some((message, Instant::now()));
+    }
+
+    /// Logs a failed request and reacts to its kind: a used-up rate limit
+    /// pauses paging until it resets, lost authorization offers to
+    /// re-authorize.
+    pub fn report_api_error(&mut self, context: String, error: &Error) {
+        match error {
+            Error::RateLimited { reset } => {
+                // Strava's short window is 15 minutes
+                self.rate_limited_until =
+                    Some(reset.unwrap_or_else(|| Utc::now() + chrono::Duration::minutes(15)));
+            }
+            e if e.needs_reauth() && !self.reauth_declined && self.confirm.is_none() => {
+                self.confirm = Some((ConfirmAction::Reauthorize, String::new()));
+            }
+            _ => {}
+        }
+        self.report_error(format!("{}: {}", context, error));
+    }
+
+    pub fn set_rate_limit(&mut self, limit: RateLimit) {
+        self.rate_limit = Some(limit);
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.rate_limited_until
+            .is_some_and(|until| Utc::now() < until)
+    }
+
+    pub fn status_message(&self) -> Option<&str> {
+        self.status
+            .as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < STATUS_TIMEOUT)
+            .map(|(message, _)| message.as_str())
+    }
+
+    pub fn dismiss_status(&mut self) {
+        self.status = None;
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.error_log.len()
+    }
+
+    pub fn is_error_log_open(&self) -> bool {
+        self.show_error_log
+    }
+
+    pub fn toggle_error_log(&mut self) {
+        self.show_error_log = !self.show_error_log;
+        self.status = None;
+    }
+
+    pub fn is_help_open(&self) -> bool {
+        self.show_help
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    pub fn date_range(&self) -> DateRange {
+        self.date_range
+    }
+
+    pub fn is_picking_range(&self) -> bool {
+        self.range_form.is_some()
+    }
+
+    pub fn start_range_picker(&mut self) {
+        self.range_form = Some(RangeForm::default());
+    }
+
+    pub fn cancel_range_picker(&mut self) {
+        self.range_form = None;
+    }
+
+    pub fn range_next_field(&mut self) {
+        if let Some(form) = self.range_form.as_mut() {
+            form.editing_to = !form.editing_to;
+        }
+    }
+
+    pub fn range_input(&mut self, c: char) {
+        if let Some(form) = self.range_form.as_mut() {
+            if c.is_ascii_digit() || c == '-' {
+                let field = if form.editing_to {
+                    &mut form.to
+                } else {
+                    &mut form.from
+                };
+                field.push(c);
+                form.error = None;
+            }
+        }
+    }
+
+    pub fn range_backspace(&mut self) {
+        if let Some(form) = self.range_form.as_mut() {
+            let field = if form.editing_to {
+                &mut form.to
+            } else {
+                &mut form.from
+            };
+            field.pop();
+        }
+    }
+
+    // Leaving both fields empty clears the range. On success the list is reset
+    // so the UI loop loads page 1 of the new range.
+    pub fn submit_range(&mut self) -> bool {
+        let Some(form) = self.range_form.as_mut() else {
+            return false;
+        };
+        let bounds = parse_range_bound(&form.from, false)
+            .and_then(|from| Ok((from, parse_range_bound(&form.to, true)?)));
+        let (from, to) = match bounds {
+            Ok((Some(from), Some(to))) if from >= to => {
+                form.error = Some(self.language.tr("Start must be before end").to_string());
+                return false;
+            }
+            Ok(bounds) => bounds,
+            Err(e) => {
+                form.error = Some(e);
+                return false;
+            }
+        };
+
+        let label = match (from, to) {
+            (None, None) => None,
+            (from, to) => Some(
+                self.trf(
+                    "{} to {}",
+                    &[
+                        &from.map_or(self.tr("start").to_string(), |d| d.to_string()),
+                        &to.and_then(|d| d.pred_opt())
+                            .map_or(self.tr("now").to_string(), |d| d.to_string()),
+                    ],
+                ),
+            ),
+        };
+        self.range_form = None;
+        self.set_date_range(
+            DateRange {
+                after: from.map(local_epoch),
+                before: to.map(local_epoch),
+            },
+            label,
+        );
+        true
+    }
+
+    pub fn is_jumping_to_date(&self) -> bool {
+        self.date_jump.is_some()
+    }
+
+    pub fn start_date_jump(&mut self) {
+        self.date_jump = Some(DateJump::default());
+    }
+
+    pub fn cancel_date_jump(&mut self) {
+        self.date_jump = None;
+    }
+
+    pub fn date_jump_input(&self) -> &str {
+        self.date_jump
+            .as_ref()
+            .map_or("", |jump| jump.input.as_str())
+    }
+
+    pub fn date_jump_push(&mut self, c: char) {
+        if let Some(jump) = self.date_jump.as_mut() {
+            jump.input.push(c);
+            jump.error = None;
+        }
+    }
+
+    pub fn date_jump_backspace(&mut self) {
+        if let Some(jump) = self.date_jump.as_mut() {
+            jump.input.pop();
+        }
+    }
+
+    // Lists activities up to the end of the named period, newest first, so
+    // the list starts there and older ones are a scroll away
+    pub fn submit_date_jump(&mut self) -> bool {
+        let Some(jump) = self.date_jump.as_mut() else {
+            return false;
+        };
+        let today = chrono::Local::now().date_naive();
+        let (start, end) = match dates::parse_fuzzy(&jump.input, today) {
+            Ok(period) => period,
+            Err(e) => {
+                jump.error = Some(e);
+                return false;
+            }
+        };
+        let label = self.trf("up to {}", &[&end.pred_opt().unwrap_or(start)]);
+        self.date_jump = None;
+        self.set_date_range(
+            DateRange {
+                after: None,
+                before: Some(local_epoch(end)),
+            },
+            Some(label),
+        );
+        true
+    }
+
+    fn set_date_range(&mut self, range: DateRange, label: Option<String>) {
+        self.date_range = range;
+        self.range_label = label;
+        self.activities.clear();
+        self.hidden_activities.clear();
+        self.selected_activity_index = 0;
+        // The next load requests page 1; stale in-flight pages are dropped by range
+        self.activity_page = 0;
+        self.has_more_activities = true;
+        self.is_loading = false;
+        self.failed_page = None;
+    }
+
+    pub fn set_gear(&mut self, gear: Gear) {
+        self.gear.insert(gear.id.clone(), gear);
+    }
+
+    pub fn has_gear_for(&self, gear_id: &str) -> bool {
+        self.gear.contains_key(gear_id)
+    }
+
+    // Includes gear only referenced by activities, e.g. retired shoes the
+    // athlete endpoint no longer lists
+    pub fn gear_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        let athlete_gear = self
+            .athlete
+            .iter()
+            .flat_map(|a| a.bikes.iter().chain(a.shoes.iter()))
+            .map(|g| g.id.as_str());
+        let activity_gear = self.activities.iter().filter_map(|a| a.gear_id.as_deref());
+        for id in athlete_gear.chain(activity_gear) {
+            if !ids.iter().any(|known| known == id) {
+                ids.push(id.to_string());
+            }
+        }
+        ids
+    }
+
+    pub fn set_starred_segments(&mut self, segments: Vec<SummarySegment>) {
+        self.selected_segment_index = 0;
+        self.starred_segments = Some(segments);
+    }
+
+    pub fn has_starred_segments(&self) -> bool {
+        self.starred_segments.is_some()
+    }
+
+    pub fn set_segment_detail(&mut self, segment: DetailedSegment) {
+        self.segment_details.insert(segment.segment.id, segment);
+    }
+
+    // Local legend status is only on the detailed segment endpoint
+    pub fn segment_details_needed(&self) -> Vec<u64> {
+        self.starred_segments
+            .iter()
+            .flatten()
+            .map(|s| s.id)
+            .filter(|id| !self.segment_details.contains_key(id))
+            .collect()
+    }
+
+    pub fn select_next_segment(&mut self) {
+        let count = self.starred_segments.as_ref().map_or(0, Vec::len);
+        self.selected_segment_index =
+            (self.selected_segment_index + 1).min(count.saturating_sub(1));
+    }
+
+    pub fn select_prev_segment(&mut self) {
+        self.selected_segment_index = self.selected_segment_index.saturating_sub(1);
+    }
+
+    // Routes are recomputed from the history, so the index is clamped when drawing
+    pub fn select_next_route(&mut self) {
+        self.selected_route_index += 1;
+    }
+
+    pub fn select_prev_route(&mut self) {
+        self.selected_route_index = self.selected_route_index.saturating_sub(1);
+    }
+
+    pub fn set_plan(&mut self, workouts: Vec<PlannedWorkout>) {
+        self.plan = workouts;
+    }
+
+    pub fn next_plan_month(&mut self) {
+        self.plan_month_offset += 1;
+    }
+
+    pub fn prev_plan_month(&mut self) {
+        self.plan_month_offset -= 1;
+    }
+
+    pub fn set_clubs(&mut self, clubs: Vec<SummaryClub>) {
+        self.clubs = Some(clubs);
+        self.selected_club_index = 0;
+        self.club_feed = ClubFeed::default();
+    }
+
+    pub fn has_clubs(&self) -> bool {
+        self.clubs.is_some()
+    }
+
+    pub fn set_profile(&mut self, profile: DetailedAthlete) {
+        self.profile = Some(profile);
+    }
+
+    pub fn has_profile(&self) -> bool {
+        self.profile.is_some()
+    }
+
+    pub fn athlete_id(&self) -> Option<u64> {
+        self.athlete.as_ref().map(|a| a.id)
+    }
+
+    pub fn selected_club_id(&self) -> Option<u64> {
+        self.clubs
+            .as_ref()
+            .and_then(|c| c.get(self.selected_club_index))
+            .map(|c| c.id)
+    }
+
+    pub fn next_club(&mut self) {
+        let count = self.clubs.as_ref().map_or(0, Vec::len);
+        if self.selected_club_index + 1 < count {
+            self.selected_club_index += 1;
+            self.club_feed = ClubFeed::default();
+        }
+    }
+
+    pub fn prev_club(&mut self) {
+        if self.selected_club_index > 0 {
+            self.selected_club_index -= 1;
+            self.club_feed = ClubFeed::default();
+        }
+    }
+
+    pub fn should_load_club_activities(&self) -> bool {
+        let feed = &self.club_feed;
+        self.selected_club_id().is_some()
+            && !feed.loading
+            && !feed.exhausted
+            && !feed.failed
+            && feed.selected >= feed.activities.len().saturating_sub(5)
+    }
+
+    pub fn club_activity_page(&self) -> u32 {
+        self.club_feed.page
+    }
+
+    pub fn set_club_loading(&mut self, loading: bool) {
+        self.club_feed.loading = loading;
+    }
+
+    // Pages for a club the user has switched away from are dropped
+    pub fn add_club_activities(
+        &mut self,
+        club_id: u64,
+        page: u32,
+        activities: Page<ClubActivity>,
+        per_page: u32,
+    ) {
+        if self.selected_club_id() != Some(club_id) || page != self.club_feed.page + 1 {
+            return;
+        }
+        let feed = &mut self.club_feed;
+        feed.exhausted = activities.is_last(per_page);
+        feed.activities.extend(activities.items);
+        feed.page = page;
+        feed.loading = false;
+    }
+
+    pub fn set_club_load_error(&mut self, club_id: u64) {
+        if self.selected_club_id() == Some(club_id) {
+            self.club_feed.loading = false;
+            self.club_feed.failed = true;
+        }
+    }
+
+    /// Lets paging request the club page that failed again. False if nothing
+    /// failed.
+    pub fn retry_club_activities(&mut self) -> bool {
+        std::mem::take(&mut self.club_feed.failed)
+    }
+
+    pub fn select_next_club_activity(&mut self) {
+        let count = self.club_feed.activities.len();
+        self.club_feed.selected = (self.club_feed.selected + 1).min(count.saturating_sub(1));
+    }
+
+    pub fn select_prev_club_activity(&mut self) {
+        self.club_feed.selected = self.club_feed.selected.saturating_sub(1);
+    }
+
+    fn find_gear(&self, gear_id: &str) -> Option<&Gear> {
+        self.gear.get(gear_id).or_else(|| {
+            self.athlete
+                .iter()
+                .flat_map(|a| a.bikes.iter().chain(a.shoes.iter()))
+                .find(|g| g.id == gear_id)
+        })
+    }
+
+    pub fn sort_key(&self) -> Option<SortKey> {
+        self.sort_key
+    }
+
+    pub fn sort_direction(&self) -> SortDirection {
+        self.sort_direction
+    }
+
+    pub fn is_sort_mode(&self) -> bool {
+        self.sort_mode
+    }
+
+    pub fn toggle_sort_mode(&mut self) {
+        self.sort_mode = !self.sort_mode;
+        if self.sort_mode && self.sort_key.is_none() {
+            self.sort_key = Some(SortKey::Date);
+            self.apply_sort();
+        }
+    }
+
+    pub fn next_sort_key(&mut self) {
+        self.sort_key = Some(self.sort_key.map_or(SortKey::Date, SortKey::next));
+        self.apply_sort();
+    }
+
+    pub fn prev_sort_key(&mut self) {
+        self.sort_key = Some(self.sort_key.map_or(SortKey::Date, SortKey::prev));
+        self.apply_sort();
+    }
+
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort_direction = match self.sort_direction {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        };
+        self.apply_sort();
+    }
+
+    pub fn set_hidden(&mut self, commutes: bool, private: bool) {
+        self.hide_commutes = commutes;
+        self.hide_private = private;
+        self.apply_sort();
+    }
+
+    pub fn toggle_hide_commutes(&mut self) {
+        self.set_hidden(!self.hide_commutes, self.hide_private);
+    }
+
+    pub fn toggle_hide_private(&mut self) {
+        self.set_hidden(self.hide_commutes, !self.hide_private);
+    }
+
+    pub fn set_notifications(&mut self, notifications: NotificationConfig) {
+        self.notifications = notifications;
+    }
+
+    pub fn notifications(&self) -> &NotificationConfig {
+        &self.notifications
+    }
+
+    pub fn set_exclude_indoor(&mut self, exclude: bool) {
+        self.exclude_indoor = exclude;
+    }
+
+    pub fn toggle_exclude_indoor(&mut self) {
+        self.exclude_indoor = !self.exclude_indoor;
+        let notice = if self.exclude_indoor {
+            "Indoor activities are left out of the totals"
+        } else {
+            "Indoor activities count towards the totals"
+        };
+        self.show_notice(self.tr(notice).to_string());
+    }
+
+    pub fn set_time_basis(&mut self, basis: TimeBasis) {
+        self.time_basis = basis;
+    }
+
+    pub fn time_basis(&self) -> TimeBasis {
+        self.time_basis
+    }
+
+    pub fn cycle_time_basis(&mut self) {
+        self.time_basis = self.time_basis.next();
+        self.show_notice(self.trf(
+            "Durations, pace and speed use {}",
+            &[&self.tr(self.time_basis.label())],
+        ));
+    }
+
+    // Moving or elapsed time, whichever the time basis picks for the sport
+    fn shown_time(&self, activity: &Activity) -> u32 {
+        if self.time_basis.uses_elapsed(&activity.sport_type) {
+            activity.elapsed_time
+        } else {
+            activity.moving_time
         }
     }
 
-    pub fn set_data(
-        &mut self,
-        athlete: Athlete,
-        stats: AthleteStats,
-        activities: Vec<Activity>,
-        per_page: usize,
-    ) {
-        let count = activities.len();
-        self.athlete = Some(athlete);
-        self.stats = Some(stats);
-        self.activities = activities;
-        self.activity_page = 1;
-        self.has_more_activities = count >= per_page;
+    fn is_hidden(&self, activity: &Activity) -> bool {
+        (self.hide_commutes && activity.commute == Some(true))
+            || (self.hide_private && activity.private == Some(true))
     }
 
-    pub fn set_view(&mut self, view: View) {
-        self.current_view = view;
+    // Cached activities that count towards dashboard figures and goals
+    fn counted_history(&self) -> impl Iterator<Item = &Activity> {
+        self.history
+            .values()
+            .filter(|a| !self.is_hidden(a))
+            .filter(|a| !self.exclude_indoor || !analytics::is_indoor(a))
     }
 
-    pub fn current_view(&self) -> View {
-        self.current_view
+    // Hidden, or without the tag the list is narrowed to
+    fn is_filtered(&self, activity: &Activity) -> bool {
+        self.is_hidden(activity)
+            || self
+                .tag_filter
+                .as_ref()
+                .is_some_and(|tag| !self.has_tag(activity.id, tag))
     }
 
-    pub fn is_loading(&self) -> bool {
-        self.is_loading
+    fn has_tag(&self, activity_id: u64, tag: &str) -> bool {
+        self.tags
+            .get(&activity_id)
+            .is_some_and(|tags| tags.contains(tag))
     }
 
-    pub fn set_loading(&mut self, loading: bool) {
-        self.is_loading = loading;
+    pub fn set_tags(&mut self, tags: impl IntoIterator<Item = (u64, BTreeSet<String>)>) {
+        self.tags = tags.into_iter().collect();
+        self.apply_sort();
     }
 
-    pub fn should_load_more(&self) -> bool {
-        !self.is_loading
-            && self.has_more_activities
-            && self.selected_activity_index >= self.activities.len().saturating_sub(5)
+    pub fn tags_of(&self, activity_id: u64) -> Option<&BTreeSet<String>> {
+        self.tags.get(&activity_id)
     }
 
-    pub fn add_activities(&mut self, new_activities: Vec<Activity>, per_page: u32) {
-        let count = new_activities.len();
-        self.activities.extend(new_activities);
-        self.activity_page += 1;
-        self.has_more_activities = count >= per_page as usize;
-        self.is_loading = false;
+    pub fn tag_filter(&self) -> Option<&str> {
+        self.tag_filter.as_deref()
     }
 
-    pub fn set_load_error(&mut self) {
-        self.is_loading = false;
+    /// Off, then each tag in use in alphabetical order, then off again.
+    pub fn cycle_tag_filter(&mut self) {
+        self.tag_filter = self
+            .tags
+            .values()
+            .flatten()
+            .filter(|tag| Some(*tag) > self.tag_filter.as_ref())
+            .min()
+            .cloned();
+        self.apply_sort();
     }
 
-    pub fn activity_page(&self) -> u32 {
-        self.activity_page
+    pub fn is_tagging(&self) -> bool {
+        self.tag_editor.is_some()
     }
 
-    pub fn scroll_left(&mut self) {
-        if self.scroll_offset > 0 {
-            self.scroll_offset -= 1;
+    pub fn start_tagging(&mut self) {
+        let Some(activity_id) = self.get_selected_activity().map(|a| a.id) else {
+            return;
+        };
+        let mut input = self
+            .tags
+            .get(&activity_id)
+            .map(|tags| tags.iter().cloned().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+        if !input.is_empty() {
+            input.push(' ');
         }
+        self.tag_editor = Some(TagEditor { activity_id, input });
     }
 
-    pub fn scroll_right(&mut self) {
-        self.scroll_offset += 1;
+    pub fn cancel_tagging(&mut self) {
+        self.tag_editor = None;
     }
 
-    pub fn scroll_offset(&self) -> u32 {
-        self.scroll_offset
+    pub fn tag_input(&mut self, c: char) {
+        if let Some(editor) = self.tag_editor.as_mut() {
+            editor.input.push(c);
+        }
     }
 
-    fn compute_biggest_distance(&self) -> (f64, f64) {
-        let all_time = self
-            .activities
-            .iter()
-            .map(|a| a.distance / 1000.0)
-            .fold(0.0f64, f64::max);
-
-        let recent: f64 = self
-            .activities
-            .iter()
-            .filter(|a| {
-                let thirty_days_ago = chrono::Utc::now() - chrono::Duration::days(30);
-                a.start_date_local > thirty_days_ago
-            })
-            .map(|a| a.distance / 1000.0)
-            .sum();
+    pub fn tag_backspace(&mut self) {
+        if let Some(editor) = self.tag_editor.as_mut() {
+            editor.input.pop();
+        }
+    }
 
-        (all_time, recent)
+    /// Applies the typed tags and returns them for the tag store.
+    pub fn submit_tags(&mut self) -> Option<(u64, BTreeSet<String>)> {
+        let editor = self.tag_editor.take()?;
+        let tags = TagStore::parse(&editor.input);
+        if tags.is_empty() {
+            self.tags.remove(&editor.activity_id);
+        } else {
+            self.tags.insert(editor.activity_id, tags.clone());
+        }
+        // Dropping the filtered tag takes the activity out of the list
+        self.apply_sort();
+        Some((editor.activity_id, tags))
     }
 
-    fn compute_best_pace(&self) -> (String, String) {
-        let all_time_best = self
-            .activities
-            .iter()
-            .filter(|a| a.distance > 0.0 && (a.sport_type == "Run" || a.activity_type == "Run"))
-            .map(|a| a.moving_time as f64 / (a.distance / 1000.0))
-            .fold(f64::INFINITY, f64::min);
+    // Totals over every cached activity with the filtered tag, not only the
+    // loaded ones
+    fn tag_summary(&self) -> Option<String> {
+        let tag = self.tag_filter.as_ref()?;
+        Some(self.subtotal_label(
+            &format!("#{}", tag),
+            self.counted_history().filter(|a| self.has_tag(a.id, tag)),
+        ))
+    }
 
-        let recent_activities: Vec<_> = self
-            .activities
-            .iter()
-            .filter(|a| {
-                let thirty_days_ago = chrono::Utc::now() - chrono::Duration::days(30);
-                a.start_date_local > thirty_days_ago
-            })
-            .filter(|a| a.sport_type == "Run" || a.activity_type == "Run")
-            .filter(|a| a.distance > 0.0)
-            .collect();
+    fn filter_label(&self) -> Option<&'static str> {
+        let label = match (self.hide_commutes, self.hide_private) {
+            (true, true) => "no commutes or private",
+            (true, false) => "no commutes",
+            (false, true) => "no private",
+            (false, false) => return None,
+        };
+        Some(self.tr(label))
+    }
 
-        let recent_best = recent_activities
-            .iter()
-            .map(|a| a.moving_time as f64 / (a.distance / 1000.0))
-            .fold(f64::INFINITY, f64::min);
+    // The list filters plus the ones that only apply to the totals
+    fn volume_filter_label(&self) -> Option<String> {
+        let indoor = self.exclude_indoor.then(|| self.tr("no indoor"));
+        match (self.filter_label(), indoor) {
+            (Some(label), Some(indoor)) => Some(format!("{}, {}", label, indoor)),
+            (label, indoor) => label.or(indoor).map(str::to_string),
+        }
+    }
 
-        let format_pace = |secs: f64| {
-            if secs.is_infinite() || secs == 0.0 {
-                "--:--".to_string()
+    // Moves activities between the list and the hidden ones as the filters
+    // change. Shown again, they go back in date order, oldest first like the
+    // rest when the range lists that way.
+    fn apply_filter(&mut self) {
+        let selected_id = self.get_selected_activity().map(|a| a.id);
+        // A refresh can bring back a hidden activity, replacing the stale copy
+        let listed: BTreeSet<u64> = self.activities.iter().map(|a| a.id).collect();
+        let (restored, still_hidden): (Vec<Activity>, Vec<Activity>) =
+            std::mem::take(&mut self.hidden_activities)
+                .into_iter()
+                .filter(|a| !listed.contains(&a.id))
+                .partition(|a| !self.is_filtered(a));
+        let (newly_hidden, shown): (Vec<Activity>, Vec<Activity>) =
+            std::mem::take(&mut self.activities)
+                .into_iter()
+                .partition(|a| self.is_filtered(a));
+        self.activities = shown;
+        self.hidden_activities = still_hidden;
+        self.hidden_activities.extend(newly_hidden);
+        if !restored.is_empty() {
+            self.activities.extend(restored);
+            if self.date_range.after.is_some() {
+                self.activities.sort_by_key(|a| a.start_date);
             } else {
-                let min = (secs / 60.0) as u32;
-                let rem_sec = (secs % 60.0) as u32;
-                format!("{}:{:02}", min, rem_sec)
+                self.activities
+                    .sort_by_key(|a| std::cmp::Reverse(a.start_date));
+            }
+        }
+
+        let index = selected_id.and_then(|id| self.activities.iter().position(|a| a.id == id));
+        self.selected_activity_index = index
+            .unwrap_or(self.selected_activity_index)
+            .min(self.activities.len().saturating_sub(1));
+    }
+
+    // Filters and re-sorts the loaded activities while keeping the same
+    // activity selected
+    fn apply_sort(&mut self) {
+        self.apply_filter();
+        let Some(key) = self.sort_key else {
+            return;
+        };
+        let selected_id = self.get_selected_activity().map(|a| a.id);
+        let direction = self.sort_direction;
+
+        self.activities
+            .sort_by(|a, b| match (key.value(a), key.value(b)) {
+                (Some(x), Some(y)) => {
+                    let ord = x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal);
+                    match direction {
+                        SortDirection::Ascending => ord,
+                        SortDirection::Descending => ord.reverse(),
+                    }
+                }
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            });
+
+        if let Some(id) = selected_id {
+            if let Some(index) = self.activities.iter().position(|a| a.id == id) {
+                self.selected_activity_index = index;
             }
+        }
+    }
+
+    pub fn training_period(&self) -> TrainingPeriod {
+        self.training_period
+    }
+
+    pub fn toggle_training_period(&mut self) {
+        self.training_period = match self.training_period {
+            TrainingPeriod::Week => TrainingPeriod::Month,
+            TrainingPeriod::Month => TrainingPeriod::Week,
         };
+    }
 
-        (format_pace(all_time_best), format_pace(recent_best))
+    pub fn dashboard_range(&self) -> DashboardRange {
+        self.dashboard_range
     }
 
-    fn compute_monthly_count(&self) -> (u32, u32) {
-        let now = chrono::Utc::now();
-        let this_month = now.format("%Y-%m").to_string();
-        let prev_month = (now - chrono::Duration::days(35))
-            .format("%Y-%m")
-            .to_string();
+    pub fn set_dashboard_range(&mut self, range: DashboardRange) {
+        self.dashboard_range = range;
+    }
 
-        let this_month_count = self
-            .activities
-            .iter()
-            .filter(|a| a.start_date_local.format("%Y-%m").to_string() == this_month)
-            .count() as u32;
+    // Buckets are returned newest first, keyed by ISO week or calendar month
+    pub fn activity_grouping(&self) -> Option<TrainingPeriod> {
+        self.activity_grouping
+    }
 
-        let prev_month_count = self
-            .activities
-            .iter()
-            .filter(|a| a.start_date_local.format("%Y-%m").to_string() == prev_month)
-            .count() as u32;
+    /// Off, weeks, months, off again.
+    pub fn cycle_activity_grouping(&mut self) {
+        self.activity_grouping = match self.activity_grouping {
+            None => Some(TrainingPeriod::Week),
+            Some(TrainingPeriod::Week) => Some(TrainingPeriod::Month),
+            Some(TrainingPeriod::Month) => None,
+        };
+    }
 
-        (this_month_count, prev_month_count)
+    // Consecutive activities in the same week or month with their subtotal
+    // label. A non-date sort can split a period into several groups.
+    fn activity_groups(&self, period: TrainingPeriod) -> Vec<(String, std::ops::Range<usize>)> {
+        let key = |activity: &Activity| {
+            let date = activity.start_date_local;
+            match period {
+                TrainingPeriod::Week => {
+                    let week = self.locale.week(date.date_naive());
+                    self.trf("Week {}, {}", &[&week.week(), &week.year()])
+                }
+                TrainingPeriod::Month => date.format("%B %Y").to_string(),
+            }
+        };
+
+        let mut groups: Vec<(String, std::ops::Range<usize>)> = Vec::new();
+        for (i, activity) in self.activities.iter().enumerate() {
+            let name = key(activity);
+            match groups.last_mut() {
+                Some((last, range)) if *last == name => range.end = i + 1,
+                _ => groups.push((name, i..i + 1)),
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(name, range)| {
+                let label = self.subtotal_label(&name, &self.activities[range.clone()]);
+                (label, range)
+            })
+            .collect()
+    }
+
+    // "<name> — 35.0 km, 7 activities, 3h 30m"
+    fn subtotal_label<'a>(
+        &self,
+        name: &str,
+        activities: impl IntoIterator<Item = &'a Activity>,
+    ) -> String {
+        let (mut count, mut distance, mut moving_time) = (0, 0.0, 0);
+        for activity in activities {
+            count += 1;
+            distance += activity.distance;
+            moving_time += activity.moving_time;
+        }
+        format!(
+            "{} — {} {}, {} {}, {}h {:02}m",
+            name,
+            self.locale.decimal(self.units.distance(distance), 1),
+            self.units.distance_unit(),
+            count,
+            self.tr(if count == 1 { "activity" } else { "activities" }),
+            moving_time / 3600,
+            (moving_time % 3600) / 60
+        )
+    }
+
+    /// Handles a key press. An open overlay takes it; otherwise actions bound
+    /// in the current view are passed on to its screen.
+    pub fn handle_key(&mut self, key: impl Into<KeyEvent>) -> Option<Effect> {
+        let key = key.into();
+        // Raw mode swallows SIGINT, so Ctrl+C arrives as a key press
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(Effect::Quit);
+        }
+        if let Some(overlay) = self.overlay() {
+            return overlay.handle_key(self, key);
+        }
+        let view = self.current_view;
+        match keymap::action(view, key.code)? {
+            Action::Quit => return Some(Effect::Quit),
+            Action::ShowView(view) => self.set_view(view),
+            Action::NextView => self.next_view(),
+            Action::PrevView => self.prev_view(),
+            Action::Back => {
+                self.back();
+            }
+            Action::ToggleErrorLog => self.toggle_error_log(),
+            Action::ToggleHelp => self.toggle_help(),
+            Action::HideCommutes => self.toggle_hide_commutes(),
+            Action::TimeBasis => self.cycle_time_basis(),
+            Action::HidePrivate => self.toggle_hide_private(),
+            Action::ExcludeIndoor => self.toggle_exclude_indoor(),
+            Action::TimeFormat => self.toggle_time_format(),
+            // The paging check picks the failed page up again
+            Action::Refresh if view == View::Activities && self.retry_failed_page() => {}
+            Action::Refresh if view == View::Clubs && self.retry_club_activities() => {}
+            Action::Refresh => {
+                if !self.is_refreshing {
+                    self.is_refreshing = true;
+                    return Some(Effect::Refresh);
+                }
+            }
+            action => return view.screen().handle_key(self, action),
+        }
+        None
     }
 
     pub fn render(&mut self, f: &mut Frame) {
+        self.photo_area = None;
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -196,314 +2471,465 @@ impl App {
 
         self.render_header(f, chunks[0]);
 
-        match self.current_view {
-            View::Dashboard => self.render_dashboard(f, chunks[1]),
-            View::Activities => self.render_activities(f, chunks[1]),
-            View::ActivityDetail => self.render_activity_detail(f, chunks[1]),
-        }
+        self.current_view.screen().render(self, f, chunks[1]);
 
         self.render_footer(f, chunks[2]);
+
+        if let Some(form) = &self.range_form {
+            self.render_range_form(f, chunks[1], form);
+        }
+        if let Some(jump) = &self.date_jump {
+            self.render_date_jump(f, chunks[1], jump);
+        }
+        if let Some(editor) = &self.tag_editor {
+            self.render_tag_editor(f, chunks[1], editor);
+        }
+        if self.show_error_log {
+            self.render_error_log(f, chunks[1]);
+        }
+        if let Some((action, name)) = &self.confirm {
+            self.render_confirm(f, chunks[1], *action, name);
+        }
+        if self.show_help {
+            self.render_help(f, chunks[1]);
+        }
+        // Images would cover the popups
+        if self.range_form.is_some()
+            || self.date_jump.is_some()
+            || self.tag_editor.is_some()
+            || self.edit_form.is_some()
+            || self.confirm.is_some()
+            || self.show_error_log
+            || self.show_help
+        {
+            self.photo_area = None;
+        }
     }
 
-    fn render_header(&self, f: &mut Frame, area: Rect) {
-        let title = match self.current_view {
-            View::Dashboard => "SportFrei - Dashboard",
-            View::Activities => "SportFrei - Activities",
-            View::ActivityDetail => "SportFrei - Activity Details",
+    fn render_date_jump(&self, f: &mut Frame, area: Rect, jump: &DateJump) {
+        let popup = Self::centered_rect(area, area.width.saturating_sub(4).min(60), 7);
+
+        let status = match &jump.error {
+            Some(error) => Line::styled(error.clone(), Style::default().fg(self.theme.error)),
+            None => Line::styled(
+                self.tr(
+                    "2023-06 · last summer · 6 months ago · Enter go · Esc cancel · g first activity",
+                ),
+                Style::default().fg(self.theme.muted),
+            ),
         };
+        let lines = vec![
+            Line::styled(
+                format!("{}_", jump.input),
+                Style::default().fg(self.theme.warning),
+            ),
+            Line::raw(""),
+            status,
+        ];
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.tr("Go to Date"))
+                .border_style(Style::default().fg(self.theme.accent)),
+        );
 
-        let block = Block::new().borders(Borders::ALL).title(title);
-
-        f.render_widget(block, area);
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
     }
 
-    fn render_dashboard(&self, f: &mut Frame, area: Rect) {
-        if self.athlete.is_none() {
-            let paragraph = Paragraph::new("No data available")
-                .style(Style::default().fg(Color::Yellow))
-                .block(Block::new().borders(Borders::ALL).title("Dashboard"));
-            f.render_widget(paragraph, area);
-            return;
-        }
-
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(33),
-                Constraint::Percentage(33),
-                Constraint::Percentage(33),
-            ])
-            .split(area);
-
-        let (all_time_dist, recent_dist) = self.compute_biggest_distance();
-        let (best_pace_all, best_pace_recent) = self.compute_best_pace();
-        let (this_month, prev_month) = self.compute_monthly_count();
+    fn render_tag_editor(&self, f: &mut Frame, area: Rect, editor: &TagEditor) {
+        let popup = Self::centered_rect(area, area.width.saturating_sub(4).min(60), 7);
 
         let name = self
-            .athlete
-            .as_ref()
-            .map(|a| a.firstname.as_str())
-            .unwrap_or("Athlete");
-        let title = format!("Welcome, {}!", name);
+            .activities
+            .iter()
+            .find(|a| a.id == editor.activity_id)
+            .map_or(self.tr("activity"), |a| a.name.as_str());
+        let lines = vec![
+            Line::styled(
+                format!("{}_", editor.input),
+                Style::default().fg(self.theme.warning),
+            ),
+            Line::raw(""),
+            Line::styled(
+                self.tr("race workout easy · space or comma separated · Enter save · Esc cancel"),
+                Style::default().fg(self.theme.muted),
+            ),
+        ];
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.trf("Tags for {}", &[&name]))
+                .border_style(Style::default().fg(self.theme.accent)),
+        );
 
-        let dist_trend = if recent_dist > 0.0 { "↑" } else { "↓" };
-        let dist_color = if recent_dist > 0.0 {
-            Color::Green
-        } else {
-            Color::Red
-        };
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
 
-        let pace_all_secs: f64 = best_pace_all.split(':').fold(0.0, |acc, s| {
-            let parts: Vec<&str> = s.split_whitespace().collect();
-            if parts.is_empty() {
-                return acc;
-            }
-            let n: u32 = parts[0].parse().unwrap_or(0);
-            acc * 60.0 + n as f64
-        });
-        let pace_recent_secs: f64 = best_pace_recent.split(':').fold(0.0, |acc, s| {
-            let parts: Vec<&str> = s.split_whitespace().collect();
-            if parts.is_empty() {
-                return acc;
-            }
-            let n: u32 = parts[0].parse().unwrap_or(0);
-            acc * 60.0 + n as f64
-        });
-        let pace_trend = if pace_recent_secs > 0.0 && pace_recent_secs < pace_all_secs {
-            "↑"
-        } else {
-            "↓"
+    fn render_range_form(&self, f: &mut Frame, area: Rect, form: &RangeForm) {
+        let popup = Self::centered_rect(area, area.width.saturating_sub(4).min(60), 8);
+
+        let field = |label: &str, value: &str, focused: bool| {
+            let style = if focused {
+                Style::default().fg(self.theme.warning)
+            } else {
+                Style::default().fg(self.theme.text)
+            };
+            let cursor = if focused { "_" } else { "" };
+            Line::styled(format!("{:<6} {}{}", label, value, cursor), style)
         };
-        let pace_color = if pace_recent_secs > 0.0 && pace_recent_secs < pace_all_secs {
-            Color::Green
-        } else {
-            Color::Red
+        let status = match &form.error {
+            Some(error) => Line::styled(error.clone(), Style::default().fg(self.theme.error)),
+            None => Line::styled(
+                self.tr("YYYY[-MM[-DD]] · empty = open · Tab switch · Enter go · Esc cancel"),
+                Style::default().fg(self.theme.muted),
+            ),
         };
+        let lines = vec![
+            field(self.tr("From:"), &form.from, !form.editing_to),
+            field(self.tr("To:"), &form.to, form.editing_to),
+            Line::raw(""),
+            status,
+        ];
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.tr("Jump to Date Range"))
+                .border_style(Style::default().fg(self.theme.accent)),
+        );
 
-        let count_trend = if this_month > prev_month {
-            "↑"
-        } else {
-            "↓"
-        };
-        let count_color = if this_month > prev_month {
-            Color::Green
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+
+    fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+        let width = width.min(area.width);
+        let height = height.min(area.height);
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    fn render_error_log(&self, f: &mut Frame, area: Rect) {
+        let popup = Self::centered_rect(area, area.width.saturating_sub(4).min(100), 20);
+
+        let lines: Vec<Line> = if self.error_log.is_empty() {
+            vec![Line::styled(
+                self.tr("No errors so far"),
+                Style::default().fg(self.theme.muted),
+            )]
         } else {
-            Color::Red
+            self.error_log
+                .iter()
+                .map(|(at, message)| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{} ", at.format("%H:%M:%S")),
+                            Style::default().fg(self.theme.muted),
+                        ),
+                        Span::styled(message.clone(), Style::default().fg(self.theme.error)),
+                    ])
+                })
+                .collect()
         };
 
-        let widget1 = format!(
-            "Biggest Distance\n\n{:.1} km {}\n(last 30 days: {:.1} km)",
-            all_time_dist, dist_trend, recent_dist
-        );
-        let widget2 = format!(
-            "Best Pace\n\n{} /km {}\n(vs {})",
-            best_pace_recent, pace_trend, best_pace_all
-        );
-        let widget3 = format!(
-            "This Month\n\n{} {}\n(vs {} last month)",
-            this_month, count_trend, prev_month
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.trf("Recent Errors ({}) - L/Esc close", &[&self.error_log.len()]))
+                .border_style(Style::default().fg(self.theme.error)),
         );
 
-        let block1 = Block::new()
-            .borders(Borders::ALL)
-            .title(title)
-            .border_style(Style::default().fg(Color::Cyan));
-        let block2 = Block::new()
-            .borders(Borders::ALL)
-            .title("Best Pace")
-            .border_style(Style::default().fg(Color::Green));
-        let block3 = Block::new()
-            .borders(Borders::ALL)
-            .title("Activities this month")
-            .border_style(Style::default().fg(Color::Yellow));
-
-        let p1 = Paragraph::new(widget1).style(Style::default().fg(dist_color));
-        let p2 = Paragraph::new(widget2).style(Style::default().fg(pace_color));
-        let p3 = Paragraph::new(widget3).style(Style::default().fg(count_color));
-
-        f.render_widget(block1, chunks[0]);
-        f.render_widget(
-            p1,
-            chunks[0].inner(ratatui::layout::Margin {
-                horizontal: 1,
-                vertical: 1,
-            }),
-        );
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
 
-        f.render_widget(block2, chunks[1]);
-        f.render_widget(
-            p2,
-            chunks[1].inner(ratatui::layout::Margin {
-                horizontal: 1,
-                vertical: 1,
-            }),
-        );
+    fn render_confirm(&self, f: &mut Frame, area: Rect, action: ConfirmAction, name: &str) {
+        let popup = Self::centered_rect(area, area.width.saturating_sub(4).min(60), 6);
+        let (title, question, color) = match action {
+            ConfirmAction::Mute(_) => (
+                self.tr("Mute Activity"),
+                self.trf("Hide '{}' from your followers' feeds?", &[&name]),
+                self.theme.warning,
+            ),
+            ConfirmAction::Delete(_) => (
+                self.tr("Delete Activity"),
+                self.trf("Delete '{}' from Strava? This can't be undone.", &[&name]),
+                self.theme.error,
+            ),
+            ConfirmAction::Reauthorize => (
+                self.tr("Authorization"),
+                self.tr(
+                    "Strava no longer accepts SportFrei's authorization. Re-authorize in the browser now?",
+                )
+                .to_string(),
+                self.theme.warning,
+            ),
+        };
 
-        f.render_widget(block3, chunks[2]);
-        f.render_widget(
-            p3,
-            chunks[2].inner(ratatui::layout::Margin {
-                horizontal: 1,
-                vertical: 1,
-            }),
+        let lines = vec![
+            Line::from(question),
+            Line::from(""),
+            Line::styled(
+                self.tr("[y] Yes   [n/Esc] No"),
+                Style::default().fg(self.theme.muted),
+            ),
+        ];
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(color)),
         );
+
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
     }
 
-    fn get_activity_color(activity: &Activity) -> Color {
-        match activity.sport_type.as_str() {
-            "Run" => Color::Green,
-            "Ride" => Color::Blue,
-            "Swim" => Color::Cyan,
-            "Hike" => Color::Yellow,
-            "Walk" => Color::Yellow,
-            _ => Color::Magenta,
+    fn render_help(&self, f: &mut Frame, area: Rect) {
+        let bindings = keymap::help(self.current_view);
+        // The activities help starts with what RelPerf means
+        let metric = (self.current_view == View::Activities
+            && self.columns.contains(&ActivityColumn::RelPerf))
+        .then_some(self.efficiency_metric);
+        let (width, extra) = if metric.is_some() { (60, 6) } else { (50, 0) };
+        let height = (bindings.len() as u16 + 2 + extra).min(area.height);
+        let popup = Self::centered_rect(area, area.width.saturating_sub(4).min(width), height);
+
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(self.tr("Keys - ?/Esc close"))
+            .border_style(Style::default().fg(self.theme.accent));
+        let inner = block.inner(popup);
+        f.render_widget(Clear, popup);
+        f.render_widget(block, popup);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(extra), Constraint::Min(0)])
+            .split(inner);
+
+        if let Some(metric) = metric {
+            let lines = vec![
+                Line::styled(
+                    format!("RelPerf: {}", metric.name()),
+                    Style::default().fg(self.theme.special),
+                ),
+                Line::styled(metric.formula(), Style::default().fg(self.theme.muted)),
+            ];
+            f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), chunks[0]);
         }
+
+        let rows = bindings.into_iter().map(|(keys, description)| {
+            Row::new(vec![
+                Cell::from(keys).style(Style::default().fg(self.theme.accent)),
+                Cell::from(self.tr(description)),
+            ])
+        });
+        let table = Table::new(rows, [Constraint::Length(8), Constraint::Fill(1)]);
+        f.render_widget(table, chunks[1]);
     }
 
-    fn render_activities(&mut self, f: &mut Frame, area: Rect) {
-        if self.activities.is_empty() {
-            let paragraph = Paragraph::new("No activities found")
-                .style(Style::default().fg(Color::White))
-                .block(Block::new().borders(Borders::ALL).title("Activities"));
-            f.render_widget(paragraph, area);
-            return;
+    fn render_header(&self, f: &mut Frame, area: Rect) {
+        let mut block = Block::new().borders(Borders::ALL).title(format!(
+            "SportFrei - {}",
+            self.tr(self.current_view.title())
+        ));
+        if self.is_refreshing {
+            block = block.title(
+                Line::styled(
+                    format!(" {} ", self.tr("Refreshing...")),
+                    Style::default().fg(self.theme.warning),
+                )
+                .right_aligned(),
+            );
+        } else if let Some(notice) = self.notice() {
+            block = block.title(
+                Line::styled(
+                    format!(" {} ", notice),
+                    Style::default().fg(self.theme.positive),
+                )
+                .right_aligned(),
+            );
         }
 
-        let rows: Vec<Row> = self
-            .activities
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        f.render_widget(self.tab_bar(inner.width), inner);
+    }
+
+    // Numbered tabs for all views. When they don't fit, the bar starts late
+    // enough for the active tab to show.
+    fn tab_bar(&self, width: u16) -> Line<'_> {
+        let active = self.current_view.tab();
+        let labels: Vec<String> = View::TABS
             .iter()
             .enumerate()
-            .map(|(i, activity)| {
-                let selected = i == self.selected_activity_index;
-                let activity_color = Self::get_activity_color(activity);
-
-                let date = activity.start_date_local.format("%m-%d %H:%M").to_string();
-                let name: String = activity.name.chars().take(25).collect();
-                let distance = format!("{:.1}", activity.distance / 1000.0);
-                let elevation = format!("{:.0}", activity.total_elevation_gain);
-
-                let duration = format!(
-                    "{}:{:02}:{:02}",
-                    activity.moving_time / 3600,
-                    (activity.moving_time % 3600) / 60,
-                    activity.moving_time % 60
-                );
-
-                let pace = if activity.distance > 0.0 {
-                    let pace_seconds = activity.moving_time as f64 / (activity.distance / 1000.0);
-                    let pace_min = (pace_seconds / 60.0) as u32;
-                    let pace_rem_sec = (pace_seconds % 60.0) as u32;
-                    format!("{}:{:02}", pace_min, pace_rem_sec)
-                } else {
-                    "--:--".to_string()
-                };
-
-                let hr = activity
-                    .average_heartrate
-                    .map(|h| format!("{:.0}", h))
-                    .unwrap_or_else(|| "---".to_string());
-                let calories = activity
-                    .calories
-                    .map(|c| format!("{:.0}", c))
-                    .unwrap_or_else(|| "---".to_string());
-
-                let rel_perf = if let (Some(avg_speed), Some(avg_hr)) =
-                    (activity.average_speed, activity.average_heartrate)
-                {
-                    if avg_speed > 0.0 {
-                        let rp = (activity.distance / avg_speed) / avg_hr;
-                        format!("{:.0}", rp)
-                    } else {
-                        "---".to_string()
-                    }
-                } else {
-                    "---".to_string()
-                };
-
-                let row_style = if selected {
-                    Style::default().bg(Color::DarkGray).fg(Color::White)
-                } else {
-                    Style::default().fg(Color::White)
-                };
-
-                Row::new(vec![
-                    Cell::from(date).style(row_style),
-                    Cell::from(name).style(row_style.fg(activity_color)),
-                    Cell::from(distance).style(row_style.fg(Color::Cyan)),
-                    Cell::from(elevation).style(row_style),
-                    Cell::from(duration).style(row_style.fg(Color::Green)),
-                    Cell::from(pace).style(row_style.fg(Color::Yellow)),
-                    Cell::from(hr).style(row_style.fg(Color::Red)),
-                    Cell::from(calories).style(row_style),
-                    Cell::from(rel_perf).style(row_style.fg(Color::Magenta)),
-                ])
+            .map(|(i, view)| match i {
+                0..=8 => format!(" {} {} ", i + 1, self.tr(view.tab_title())),
+                _ => format!(" {} ", self.tr(view.tab_title())),
             })
             .collect();
+        let widths: Vec<usize> = labels.iter().map(|l| l.chars().count() + 1).collect();
+        let mut start = 0;
+        while start < active && widths[start..=active].iter().sum::<usize>() + 2 > width as usize {
+            start += 1;
+        }
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(12),
-                Constraint::Length(25),
-                Constraint::Length(8),
-                Constraint::Length(7),
-                Constraint::Length(8),
-                Constraint::Length(7),
-                Constraint::Length(5),
-                Constraint::Length(5),
-                Constraint::Length(7),
-            ],
-        )
-        .header(
-            Row::new(vec![
-                "Date", "Name", "Distance", "Elev", "Duration", "Pace", "HR", "Cal", "RelPerf",
-            ])
-            .style(Style::default().fg(Color::White).bg(Color::Black)),
-        )
-        .block(Block::new().borders(Borders::ALL).title(format!(
-            "Activities ({} total) - h/l scroll, j/k nav)",
-            self.activities.len()
-        )))
-        .row_highlight_style(Style::default().bg(Color::DarkGray).fg(Color::White));
+        let mut spans = Vec::new();
+        if start > 0 {
+            spans.push(Span::styled("‹", Style::default().fg(self.theme.muted)));
+        }
+        for (i, label) in labels.into_iter().enumerate().skip(start) {
+            let style = if i == active {
+                Style::default()
+                    .fg(self.theme.accent)
+                    .bg(self.theme.selection_bg)
+            } else {
+                Style::default().fg(self.theme.text)
+            };
+            spans.push(Span::styled(label, style));
+            spans.push(Span::styled("|", Style::default().fg(self.theme.muted)));
+        }
+        spans.pop();
+        Line::from(spans)
+    }
 
-        f.render_widget(table, area);
+    fn format_hms(seconds: f64) -> String {
+        let seconds = seconds.round() as u64;
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            (seconds % 3600) / 60,
+            seconds % 60
+        )
     }
 
-    fn render_activity_detail(&self, f: &mut Frame, area: Rect) {
-        let activity = self.activities.get(self.selected_activity_index);
+    fn format_pace(seconds: f64) -> String {
+        if !seconds.is_finite() || seconds <= 0.0 {
+            return "--:--".to_string();
+        }
+        let min = (seconds / 60.0) as u32;
+        let rem_sec = (seconds % 60.0) as u32;
+        format!("{}:{:02}", min, rem_sec)
+    }
 
-        let content = if let Some(activity) = activity {
-            format!(
-                "{}\n\nType: {}\nDistance: {:.2} km\nMoving Time: {}h {}m\nElevation Gain: {:.0} m\nAverage Speed: {:.2} km/h",
-                activity.name,
-                activity.activity_type,
-                activity.distance / 1000.0,
-                activity.moving_time / 3600,
-                (activity.moving_time % 3600) / 60,
-                activity.total_elevation_gain,
-                activity.average_speed.unwrap_or(0.0) * 3.6
-            )
+    fn render_footer(&self, f: &mut Frame, area: Rect) {
+        // The error count takes the place of the help hint so both fit on narrow terminals
+        let hint = if self.error_log.is_empty() {
+            Line::raw(self.tr(" [?]Help [Q]uit "))
         } else {
-            "No activity selected".to_string()
+            Line::styled(
+                self.trf(" {} errors - [L]og ", &[&self.error_log.len()]),
+                Style::default().fg(self.theme.error),
+            )
         };
 
-        let paragraph = Paragraph::new(content)
-            .style(Style::default().fg(Color::White))
-            .block(
-                Block::new()
-                    .borders(Borders::ALL)
-                    .title("Details (Esc to go back)"),
-            );
+        // Views that don't fit are left to the help overlay
+        let room = (area.width as usize).saturating_sub(hint.width() + 4);
+        let mut nav = String::new();
+        for view in FOOTER_VIEWS.map(|view| self.tr(view)) {
+            if nav.chars().count() + view.chars().count() + 2 > room {
+                nav.push_str(" …");
+                break;
+            }
+            if !nav.is_empty() {
+                nav.push(' ');
+            }
+            nav.push_str(view);
+        }
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(nav)
+            .title(hint.right_aligned());
 
-        f.render_widget(paragraph, area);
+        // A fresh error takes the place of the status bar until it fades
+        let status = match self.status_message() {
+            Some(message) => Line::styled(message, Style::default().fg(self.theme.error)),
+            None => self.status_bar(),
+        };
+        f.render_widget(Paragraph::new(status).block(block), area);
     }
 
-    fn render_footer(&self, f: &mut Frame, area: Rect) {
-        let nav = "[D]ashboard | [A]ctivities | [Q]uit";
-
-        let block = Block::new().borders(Borders::ALL).title(nav);
+    fn spinner(&self) -> &'static str {
+        SPINNER[(self.started.elapsed().as_millis() / 100) as usize % SPINNER.len()]
+    }
 
-        f.render_widget(block, area);
+    // View, list size, filters and sort, API budget and last sync
+    fn status_bar(&self) -> Line<'_> {
+        let muted = Style::default().fg(self.theme.muted);
+        let mut spans = Vec::new();
+        if self.is_loading || self.is_refreshing || !self.uploads.is_empty() {
+            spans.push(Span::styled(
+                format!("{} ", self.spinner()),
+                Style::default().fg(self.theme.warning),
+            ));
+        }
+        spans.push(Span::styled(
+            self.tr(self.current_view.title()),
+            Style::default().fg(self.theme.accent),
+        ));
+
+        let loaded = self.activities.len();
+        let mut parts = Vec::new();
+        if let Some((file, state)) = self.uploads.first() {
+            let mut part = match state {
+                UploadState::Uploading => self.trf("Uploading {}", &[file]),
+                UploadState::Processing => self.trf("Strava is processing {}", &[file]),
+            };
+            if self.uploads.len() > 1 {
+                part.push_str(&format!(" (+{})", self.uploads.len() - 1));
+            }
+            parts.push(part);
+        }
+        parts.push(match &self.stats {
+            Some(stats) => self.trf(
+                "{} of {} activities",
+                &[
+                    &loaded,
+                    &(stats.all_run_totals.count + stats.all_ride_totals.count),
+                ],
+            ),
+            None => self.trf("{} activities", &[&loaded]),
+        });
+        let mut filters: Vec<String> = Vec::new();
+        if let Some(label) = &self.range_label {
+            filters.push(label.clone());
+        }
+        filters.extend(self.volume_filter_label());
+        if let Some(tag) = &self.tag_filter {
+            filters.push(format!("#{}", tag));
+        }
+        if let Some(key) = self.sort_key {
+            let arrow = match self.sort_direction {
+                SortDirection::Ascending => "▲",
+                SortDirection::Descending => "▼",
+            };
+            filters.push(format!("{} {}", self.tr(key.label()), arrow));
+        }
+        if !filters.is_empty() {
+            parts.push(filters.join(", "));
+        }
+        if let Some(limit) = self.rate_limit {
+            parts.push(self.trf(
+                "API {}/15 min, {} today",
+                &[&limit.short_remaining, &limit.daily_remaining],
+            ));
+        }
+        if let Some(synced) = self.last_synced {
+            parts.push(self.trf("synced {}", &[&self.locale.time(synced)]));
+        }
+        for part in parts {
+            spans.push(Span::styled(" │ ", muted));
+            spans.push(Span::raw(part));
+        }
+        Line::from(spans)
     }
 
     pub fn select_next_activity(&mut self) {
@@ -521,6 +2947,76 @@ impl App {
         self.selected_activity_index = self.selected_activity_index.saturating_sub(1);
     }
 
+    pub fn select_first_activity(&mut self) {
+        self.selected_activity_index = 0;
+    }
+
+    pub fn select_last_activity(&mut self) {
+        self.selected_activity_index = self.activities.len().saturating_sub(1);
+    }
+
+    pub fn page_down_activities(&mut self) {
+        self.selected_activity_index = (self.selected_activity_index + self.table_page_rows)
+            .min(self.activities.len().saturating_sub(1));
+    }
+
+    pub fn page_up_activities(&mut self) {
+        self.selected_activity_index = self
+            .selected_activity_index
+            .saturating_sub(self.table_page_rows);
+    }
+
+    /// Link to the selected activity on Strava
+    pub fn activity_link(&self) -> Option<String> {
+        let activity = self.get_selected_activity()?;
+        Some(format!("https://www.strava.com/activities/{}", activity.id))
+    }
+
+    /// The selected activity in a few lines for pasting into a chat: name,
+    /// sport and date, the main figures and the link
+    pub fn activity_summary(&self) -> Option<String> {
+        let activity = self.get_selected_activity()?;
+        let unit = self.units.distance_unit();
+        let distance = self.units.distance(activity.distance);
+        let pace_or_speed = if analytics::is_on_foot(&activity.sport_type) && distance > 0.0 {
+            format!(
+                "{} /{}",
+                Self::format_pace(activity.moving_time as f64 / distance),
+                unit
+            )
+        } else {
+            let speed = activity
+                .average_speed
+                .unwrap_or(activity.distance / activity.moving_time.max(1) as f64);
+            format!(
+                "{} {}",
+                self.locale.decimal(self.units.speed(speed), 1),
+                self.units.speed_unit()
+            )
+        };
+        let figures = self.trf(
+            "{} {} in {} · {} · {} {} elevation",
+            &[
+                &self.locale.decimal(distance, 2),
+                &unit,
+                &Self::format_hms(activity.moving_time as f64),
+                &pace_or_speed,
+                &self
+                    .locale
+                    .decimal(self.units.elevation(activity.total_elevation_gain), 0),
+                &self.units.elevation_unit(),
+            ],
+        );
+        Some(format!(
+            "{} ({}, {})\n{}\n{}",
+            activity.name,
+            activity.sport_type,
+            self.locale.date(activity.start_date_local.date_naive()),
+            figures,
+            self.activity_link()?
+        ))
+    }
+
     pub fn get_selected_activity(&self) -> Option<&Activity> {
         self.activities.get(self.selected_activity_index)
     }
@@ -0,0 +1,188 @@
+use super::{App, Screen, PERSONAL_BEST_EFFORTS};
+use crate::api::types::Activity;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::Frame;
+
+pub(super) struct Achievements;
+
+impl Screen for Achievements {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_achievements(f, area);
+    }
+}
+
+impl App {
+    fn render_achievements(&self, f: &mut Frame, area: Rect) {
+        let activities = self.achievement_activities();
+        let this_month = chrono::Local::now().format("%Y-%m").to_string();
+        let in_month: Vec<&&Activity> = activities
+            .iter()
+            .filter(|a| a.start_date_local.format("%Y-%m").to_string() == this_month)
+            .collect();
+        let summary = self.trf(
+            "This month: {} PRs and {} achievements in {} activities",
+            &[
+                &in_month
+                    .iter()
+                    .map(|a| a.pr_count.unwrap_or(0))
+                    .sum::<u32>(),
+                &in_month
+                    .iter()
+                    .map(|a| a.achievement_count.unwrap_or(0))
+                    .sum::<u32>(),
+                &in_month.len(),
+            ],
+        );
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(PERSONAL_BEST_EFFORTS.len() as u16 + 3),
+                Constraint::Min(0),
+            ])
+            .split(area);
+        self.render_personal_bests(f, chunks[0]);
+        let area = chunks[1];
+
+        if activities.is_empty() {
+            let paragraph =
+                Paragraph::new(self.tr("No PRs or achievements in loaded activities yet"))
+                    .style(Style::default().fg(self.theme.text))
+                    .block(
+                        Block::new()
+                            .borders(Borders::ALL)
+                            .title(self.tr("Achievements")),
+                    );
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let rows: Vec<Row> = activities
+            .iter()
+            .map(|a| {
+                let highlights = match self.highlights.get(&a.id) {
+                    Some(efforts) if efforts.is_empty() => "---".to_string(),
+                    Some(efforts) => efforts
+                        .iter()
+                        .map(|e| {
+                            let rank = self.tr(match e.rank {
+                                1 => "PR",
+                                2 => "2nd",
+                                _ => "3rd",
+                            });
+                            format!(
+                                "{} {} {}",
+                                e.name,
+                                rank,
+                                Self::format_pace(e.elapsed_time as f64)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    None if a.pr_count.unwrap_or(0) > 0 => self.tr("loading...").to_string(),
+                    None => String::new(),
+                };
+                let style = if a.start_date_local.format("%Y-%m").to_string() == this_month {
+                    Style::default().fg(self.theme.warning)
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                Row::new(vec![
+                    Cell::from(self.locale.date(a.start_date_local.date_naive())),
+                    Cell::from(a.name.chars().take(25).collect::<String>()),
+                    Cell::from(a.sport_type.clone()),
+                    Cell::from(a.pr_count.unwrap_or(0).to_string()),
+                    Cell::from(a.achievement_count.unwrap_or(0).to_string()),
+                    Cell::from(highlights).style(Style::default().fg(self.theme.positive)),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Length(25),
+                Constraint::Length(10),
+                Constraint::Length(4),
+                Constraint::Length(5),
+                Constraint::Min(20),
+            ],
+        )
+        .header(
+            Row::new(
+                ["Date", "Activity", "Sport", "PRs", "Achv", "Top-3 efforts"]
+                    .map(|label| self.tr(label)),
+            )
+            .style(self.theme.header()),
+        )
+        .block(Block::new().borders(Borders::ALL).title(format!(
+            "{} - {}",
+            self.tr("Achievements"),
+            summary
+        )));
+        f.render_widget(table, area);
+    }
+
+    fn render_personal_bests(&self, f: &mut Frame, area: Rect) {
+        let (runs, scanned) = self
+            .history
+            .values()
+            .filter(|a| a.sport_type.contains("Run"))
+            .fold((0, 0), |(runs, scanned), a| {
+                (
+                    runs + 1,
+                    scanned + self.best_efforts.contains_key(&a.id) as usize,
+                )
+            });
+        let rows: Vec<Row> = self
+            .personal_bests()
+            .into_iter()
+            .map(|(name, best)| match best {
+                Some(effort) => {
+                    let activity = self
+                        .history
+                        .get(&effort.activity.id)
+                        .map(|a| a.name.chars().take(30).collect::<String>())
+                        .unwrap_or_default();
+                    let pace = Self::format_pace(
+                        effort.elapsed_time as f64 / self.units.distance(effort.distance),
+                    );
+                    Row::new(vec![
+                        Cell::from(self.tr(name)),
+                        Cell::from(Self::format_hms(effort.elapsed_time as f64))
+                            .style(Style::default().fg(self.theme.positive)),
+                        Cell::from(format!("{} /{}", pace, self.units.distance_unit()))
+                            .style(Style::default().fg(self.theme.warning)),
+                        Cell::from(self.locale.date(effort.start_date_local.date_naive())),
+                        Cell::from(activity),
+                    ])
+                }
+                None => Row::new(vec![Cell::from(self.tr(name)), Cell::from("---")]),
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(14),
+                Constraint::Length(9),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Min(10),
+            ],
+        )
+        .header(
+            Row::new(["Distance", "Time", "Pace", "Date", "Activity"].map(|label| self.tr(label)))
+                .style(self.theme.header()),
+        )
+        .block(Block::new().borders(Borders::ALL).title(self.trf(
+            "Personal Bests ({} of {} cached runs scanned)",
+            &[&scanned, &runs],
+        )));
+        f.render_widget(table, area);
+    }
+}
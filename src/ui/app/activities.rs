@@ -0,0 +1,540 @@
+use super::{App, Effect, Screen, SortDirection, View};
+use crate::analytics;
+use crate::api::types::Activity;
+use crate::config::TimeBasis;
+use crate::ui::columns::ActivityColumn;
+use crate::ui::keymap::Action;
+use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::Frame;
+
+// Placeholder rows below the activities while the next page loads, the
+// first of them holding the spinner
+const SKELETON_ROWS: usize = 3;
+
+pub(super) struct Activities;
+
+impl Screen for Activities {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_activities(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Down => app.select_next_activity(),
+            Action::Up => app.select_prev_activity(),
+            Action::Left => app.scroll_left(),
+            Action::Right => app.scroll_right(),
+            Action::GoToDate => app.start_date_jump(),
+            Action::Home => app.select_first_activity(),
+            Action::Bottom => app.select_last_activity(),
+            Action::PageDown => app.page_down_activities(),
+            Action::PageUp => app.page_up_activities(),
+            Action::DateRange => app.start_range_picker(),
+            Action::Sort => app.toggle_sort_mode(),
+            Action::GroupActivities => app.cycle_activity_grouping(),
+            Action::Tag => app.start_tagging(),
+            Action::FilterTag => app.cycle_tag_filter(),
+            Action::Mute => app.request_mute(),
+            Action::Delete => app.request_delete(),
+            Action::CopyLink => return Some(Effect::Copy(app.activity_link()?, "Link copied")),
+            Action::CopySummary => {
+                return Some(Effect::Copy(app.activity_summary()?, "Summary copied"))
+            }
+            Action::Open => {
+                let activity = app.get_selected_activity().cloned()?;
+                app.set_view(View::ActivityDetail);
+                return Some(Effect::Open(Box::new(activity)));
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl App {
+    fn render_activities(&mut self, f: &mut Frame, area: Rect) {
+        if self.activities.is_empty() {
+            let (message, color) = if let Some(error) = self.load_error_line() {
+                (error, self.theme.error)
+            } else if self.is_loading {
+                (
+                    self.tr("Loading activities...").to_string(),
+                    self.theme.text,
+                )
+            } else if !self.date_range.is_empty() {
+                (
+                    self.tr("No activities in this date range").to_string(),
+                    self.theme.text,
+                )
+            } else {
+                (self.tr("No activities found").to_string(), self.theme.text)
+            };
+            let paragraph = Paragraph::new(message)
+                .style(Style::default().fg(color))
+                .block(
+                    Block::new()
+                        .borders(Borders::ALL)
+                        .title(self.activities_title()),
+                );
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let records = self.records_by_activity();
+        let cells: Vec<Vec<(String, Option<Color>)>> = self
+            .activities
+            .iter()
+            .map(|activity| {
+                self.columns
+                    .iter()
+                    .map(|column| {
+                        let (mut text, color) = self.activity_cell(column, activity);
+                        if *column == ActivityColumn::Name {
+                            if let Some(icon) = self.sport_icons.icon(&activity.sport_type) {
+                                text = format!("{} {}", icon, text);
+                            }
+                            if records.contains_key(&activity.id) {
+                                text = format!("★ {}", text);
+                            }
+                        }
+                        (text, color)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Fit each column to its widest value, capped for free text
+        let widths: Vec<u16> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(c, column)| {
+                cells
+                    .iter()
+                    // Emoji icons take two cells
+                    .map(|row| Line::from(row[c].0.as_str()).width() as u16)
+                    .chain([self.column_title(column).chars().count() as u16])
+                    .max()
+                    .unwrap_or(0)
+                    .min(column.max_width())
+            })
+            .collect();
+
+        // The first column stays put while h/l scroll through the rest, and
+        // only columns that fit are shown
+        let offset = (self.scroll_offset as usize).min(self.columns.len().saturating_sub(2));
+        let available = area.width.saturating_sub(2);
+        let mut visible = vec![0];
+        let mut used = widths[0];
+        for (c, &width) in widths.iter().enumerate().skip(1 + offset) {
+            if visible.len() > 1 && used + 1 + width > available {
+                break;
+            }
+            used += 1 + width;
+            visible.push(c);
+        }
+        let hidden_right = self.columns.len() - offset - visible.len();
+
+        let row_style = Style::default().fg(self.theme.text);
+        let mut rows: Vec<Row> = cells
+            .into_iter()
+            .map(|row| {
+                Row::new(visible.iter().map(|&c| {
+                    let (text, color) = &row[c];
+                    let text: String = text.chars().take(widths[c] as usize).collect();
+                    let style = color.map_or(row_style, |color| row_style.fg(color));
+                    Cell::from(text).style(style)
+                }))
+            })
+            .collect();
+
+        // Group headers become blank rows here; their labels span all columns,
+        // so they're drawn over the table once it's rendered
+        let groups = self
+            .activity_grouping
+            .map(|period| self.activity_groups(period))
+            .unwrap_or_default();
+        let mut group_of_row = vec![None; rows.len()];
+        let mut selected_row = self.selected_activity_index;
+        if !groups.is_empty() {
+            let mut activity_rows = rows.into_iter();
+            rows = Vec::new();
+            group_of_row.clear();
+            for (g, (_, range)) in groups.iter().enumerate() {
+                rows.push(Row::new(Vec::<Cell>::new()));
+                group_of_row.push(Some(g));
+                if range.contains(&self.selected_activity_index) {
+                    selected_row = rows.len() + self.selected_activity_index - range.start;
+                }
+                for _ in range.clone() {
+                    rows.extend(activity_rows.next());
+                    group_of_row.push(None);
+                }
+            }
+        }
+        // Shows that the list goes on while the next page is on its way
+        if self.is_loading {
+            let skeleton = Style::default().fg(self.theme.muted);
+            rows.push(Row::new(Vec::<Cell>::new()));
+            for _ in 1..SKELETON_ROWS {
+                rows.push(Row::new(visible.iter().map(|&c| {
+                    Cell::from("░".repeat(widths[c].saturating_sub(1).max(1) as usize))
+                        .style(skeleton)
+                })));
+            }
+            group_of_row.resize(rows.len(), None);
+        } else if self.failed_page.is_some() || !self.has_more_activities {
+            rows.push(Row::new(Vec::<Cell>::new()));
+            group_of_row.push(None);
+        }
+        let row_count = rows.len();
+
+        let last = visible.len() - 1;
+        let constraints: Vec<Constraint> = visible
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                // Only takes leftover width so narrow terminals keep the leading columns
+                if i == last && i > 0 {
+                    Constraint::Fill(1)
+                } else {
+                    Constraint::Length(widths[c])
+                }
+            })
+            .collect();
+
+        let mut block = Block::new()
+            .borders(Borders::ALL)
+            .title(self.activities_title());
+        if self.columns.contains(&ActivityColumn::Name)
+            && self.activities.iter().any(|a| records.contains_key(&a.id))
+        {
+            block = block.title_bottom(Line::styled(
+                format!(" {} ", self.tr("★ holds a personal record")),
+                Style::default().fg(self.theme.warning),
+            ));
+        }
+        if let Some(summary) = self.tag_summary() {
+            block = block.title_bottom(Line::styled(
+                format!(" {} ", summary),
+                Style::default().fg(self.theme.accent),
+            ));
+        }
+        if offset > 0 || hidden_right > 0 {
+            let mut hints = Vec::new();
+            if offset > 0 {
+                hints.push(self.trf("◀ {} hidden", &[&offset]));
+            }
+            if hidden_right > 0 {
+                hints.push(self.trf("{} hidden ▶", &[&hidden_right]));
+            }
+            block = block.title_bottom(
+                Line::styled(
+                    format!(" {} ", hints.join(" | ")),
+                    Style::default().fg(self.theme.muted),
+                )
+                .right_aligned(),
+            );
+        }
+
+        let table = Table::new(rows, constraints)
+            .header(
+                Row::new(visible.iter().map(|&c| {
+                    let column = &self.columns[c];
+                    let sorted = self.sort_key.is_some_and(|k| k.label() == column.label());
+                    let style = if sorted {
+                        Style::default().fg(self.theme.warning)
+                    } else {
+                        Style::default()
+                    };
+                    Cell::from(self.column_title(column).to_string()).style(style)
+                }))
+                .style(self.theme.header()),
+            )
+            .block(block)
+            // Background only, so cells keep their colors when selected
+            .row_highlight_style(Style::default().bg(self.theme.selection_bg));
+
+        // Borders and the header row
+        let body = area.height.saturating_sub(3).max(1) as usize;
+        self.table_page_rows = body;
+        self.table_state.select(Some(selected_row));
+        // At the last activity, scroll far enough to show what follows it
+        if self.selected_activity_index + 1 >= self.activities.len() {
+            let end = row_count.saturating_sub(body);
+            if self.table_state.offset() < end {
+                *self.table_state.offset_mut() = end;
+            }
+        }
+        if groups.is_empty() {
+            f.render_stateful_widget(table, area, &mut self.table_state);
+        } else {
+            // The first body line always shows the header of the group at the
+            // top, covering the row beneath it, so the selection has to stay below
+            let mut offset = self.table_state.offset();
+            if selected_row <= offset {
+                offset = selected_row.saturating_sub(1);
+            } else if selected_row >= offset + body {
+                offset = selected_row + 1 - body;
+            }
+            offset = offset.min(row_count.saturating_sub(body));
+            *self.table_state.offset_mut() = offset;
+            f.render_stateful_widget(table, area, &mut self.table_state);
+
+            let header_style = self.theme.header().fg(self.theme.accent);
+            let owner = |row: usize| {
+                group_of_row[..=row]
+                    .iter()
+                    .rev()
+                    .find_map(|g| *g)
+                    .unwrap_or(0)
+            };
+            for line in 0..body.min(row_count - offset) {
+                let row = offset + line;
+                let group = match group_of_row[row] {
+                    Some(g) => g,
+                    None if line == 0 => owner(row),
+                    None => continue,
+                };
+                let y = area.y + 2 + line as u16;
+                let label = Paragraph::new(format!(" {}", groups[group].0)).style(header_style);
+                f.render_widget(
+                    label,
+                    Rect::new(area.x + 1, y, area.width.saturating_sub(2), 1),
+                );
+            }
+        }
+
+        // Like group headers, the spinner and end of list lines span all columns
+        let trailer = if self.is_loading {
+            Some((
+                row_count.saturating_sub(SKELETON_ROWS),
+                Paragraph::new(format!(
+                    "{} {}",
+                    self.spinner(),
+                    self.tr("Loading more activities...")
+                ))
+                .style(Style::default().fg(self.theme.warning)),
+            ))
+        } else if let Some(error) = self.load_error_line() {
+            Some((
+                row_count - 1,
+                Paragraph::new(error).style(Style::default().fg(self.theme.error)),
+            ))
+        } else if !self.has_more_activities {
+            Some((
+                row_count - 1,
+                Paragraph::new(format!("— {} —", self.tr("end of activities")))
+                    .style(Style::default().fg(self.theme.muted))
+                    .alignment(Alignment::Center),
+            ))
+        } else {
+            None
+        };
+        let offset = self.table_state.offset();
+        if let Some((row, label)) = trailer.filter(|(row, _)| (offset..offset + body).contains(row))
+        {
+            let y = area.y + 2 + (row - offset) as u16;
+            f.render_widget(
+                label,
+                Rect::new(area.x + 1, y, area.width.saturating_sub(2), 1),
+            );
+        }
+    }
+
+    // Script columns are headed by their function's name
+    fn column_title<'a>(&'a self, column: &'a ActivityColumn) -> &'a str {
+        match column {
+            ActivityColumn::Script(name) => name,
+            _ => self.tr(column.label()),
+        }
+    }
+
+    // Text and, if it differs from the row's, the color of one table cell
+    fn activity_cell(
+        &self,
+        column: &ActivityColumn,
+        activity: &Activity,
+    ) -> (String, Option<Color>) {
+        let number = |value: Option<f64>| {
+            value
+                .map(|v| format!("{:.0}", v))
+                .unwrap_or_else(|| "---".to_string())
+        };
+        let duration = |seconds: u32| {
+            format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                (seconds % 3600) / 60,
+                seconds % 60
+            )
+        };
+        match column {
+            ActivityColumn::Date => (
+                format!(
+                    "{} {}",
+                    self.locale
+                        .short_date(activity.start_date_local.date_naive()),
+                    self.locale.time(activity.start_date_local)
+                ),
+                None,
+            ),
+            ActivityColumn::Name => (
+                activity.name.clone(),
+                Some(self.theme.sport(&activity.sport_type)),
+            ),
+            ActivityColumn::Sport => (
+                activity.sport_type.clone(),
+                Some(self.theme.sport(&activity.sport_type)),
+            ),
+            ActivityColumn::Distance => (
+                self.locale
+                    .decimal(self.units.distance(activity.distance), 1),
+                Some(self.theme.accent),
+            ),
+            ActivityColumn::Elevation => (
+                format!("{:.0}", self.units.elevation(activity.total_elevation_gain)),
+                None,
+            ),
+            ActivityColumn::ClimbDensity => (
+                analytics::climb_density(activity.total_elevation_gain, activity.distance)
+                    .map(|ratio| format!("{:.0}", self.units.climb_density(ratio)))
+                    .unwrap_or_else(|| "---".to_string()),
+                None,
+            ),
+            ActivityColumn::Duration => (
+                duration(self.shown_time(activity)),
+                Some(self.theme.positive),
+            ),
+            ActivityColumn::ElapsedTime => (duration(activity.elapsed_time), None),
+            ActivityColumn::Pace => (
+                Self::format_pace(
+                    self.shown_time(activity) as f64 / self.units.distance(activity.distance),
+                ),
+                Some(self.theme.warning),
+            ),
+            ActivityColumn::Gap => (
+                self.grade_adjusted
+                    .get(&activity.id)
+                    .filter(|_| analytics::is_on_foot(&activity.sport_type))
+                    .map(|&speed| Self::format_pace(1.0 / self.units.distance(speed)))
+                    .unwrap_or_else(|| "---".to_string()),
+                Some(self.theme.warning),
+            ),
+            ActivityColumn::Speed => {
+                let speed = if self.time_basis.uses_elapsed(&activity.sport_type) {
+                    (activity.elapsed_time > 0)
+                        .then(|| activity.distance / activity.elapsed_time as f64)
+                } else {
+                    activity.average_speed
+                };
+                (
+                    speed
+                        .map(|s| self.locale.decimal(self.units.speed(s), 1))
+                        .unwrap_or_else(|| "---".to_string()),
+                    None,
+                )
+            }
+            ActivityColumn::MaxSpeed => (
+                activity
+                    .max_speed
+                    .filter(|s| *s > 0.0)
+                    .map(|s| self.locale.decimal(self.units.speed(s), 1))
+                    .unwrap_or_else(|| "---".to_string()),
+                None,
+            ),
+            ActivityColumn::Heartrate => {
+                (number(activity.average_heartrate), Some(self.theme.error))
+            }
+            ActivityColumn::MaxHeartrate => {
+                (number(activity.max_heartrate), Some(self.theme.error))
+            }
+            ActivityColumn::Calories => (number(activity.calories), None),
+            ActivityColumn::RelPerf => {
+                let streams = self
+                    .activity_streams
+                    .as_ref()
+                    .filter(|(id, _)| *id == activity.id)
+                    .map(|(_, streams)| streams);
+                let metric = self.efficiency_metric;
+                let text = metric
+                    .value(activity, streams, self.units)
+                    .map(|v| format!("{:.*}", metric.precision(), v))
+                    .unwrap_or_else(|| "---".to_string());
+                (text, Some(self.theme.special))
+            }
+            ActivityColumn::Power => (number(activity.average_watts), Some(self.theme.special)),
+            ActivityColumn::Kudos => (number(activity.kudos_count.map(f64::from)), None),
+            ActivityColumn::Comments => (number(activity.comment_count.map(f64::from)), None),
+            ActivityColumn::Gear => (
+                activity
+                    .gear_id
+                    .as_deref()
+                    .map(|id| self.find_gear(id).map_or(id, |g| g.name.as_str()))
+                    .unwrap_or("---")
+                    .to_string(),
+                Some(self.theme.info),
+            ),
+            ActivityColumn::Script(name) => {
+                match self.scripts.as_ref().map(|s| s.column(name, activity)) {
+                    Some(Ok(text)) => (text.unwrap_or_else(|| "---".to_string()), None),
+                    Some(Err(_)) => ("ERR".to_string(), Some(self.theme.error)),
+                    None => ("---".to_string(), None),
+                }
+            }
+        }
+    }
+
+    fn load_error_line(&self) -> Option<String> {
+        let (page, reason) = self.failed_page.as_ref()?;
+        Some(self.trf("✗ Page {} failed: {} - r to retry", &[page, reason]))
+    }
+
+    fn activities_title(&self) -> String {
+        let sort = match self.sort_key {
+            Some(key) => {
+                let arrow = match self.sort_direction {
+                    SortDirection::Ascending => "▲",
+                    SortDirection::Descending => "▼",
+                };
+                self.trf(" - sorted by {} {}", &[&self.tr(key.label()), &arrow])
+            }
+            None => String::new(),
+        };
+        let range = match &self.range_label {
+            Some(label) if self.date_range.after.is_some() => {
+                self.trf(" - {} (oldest first)", &[&label])
+            }
+            Some(label) => format!(" - {}", label),
+            None => String::new(),
+        };
+        let mut filter = self
+            .filter_label()
+            .map(|label| format!(" - {}", label))
+            .unwrap_or_default();
+        if let Some(tag) = &self.tag_filter {
+            filter.push_str(&format!(" - #{}", tag));
+        }
+        if self.time_basis != TimeBasis::Moving {
+            filter.push_str(&format!(" - {}", self.tr(self.time_basis.label())));
+        }
+        let hint = if self.sort_mode {
+            "←/→ column, ↑/↓ direction, Enter done"
+        } else {
+            "h/l scroll, j/k nav, s sort, f dates, w group, # tag"
+        };
+        self.trf(
+            "Activities ({} total){}{}{} - {})",
+            &[
+                &self.activities.len(),
+                &range,
+                &filter,
+                &sort,
+                &self.tr(hint),
+            ],
+        )
+    }
+}
@@ -0,0 +1,143 @@
+use super::{App, Effect, Screen};
+use crate::ui::keymap::Action;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Tabs};
+use ratatui::Frame;
+
+pub(super) struct Clubs;
+
+impl Screen for Clubs {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_clubs(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Down => app.select_next_club_activity(),
+            Action::Up => app.select_prev_club_activity(),
+            Action::Left => app.prev_club(),
+            Action::Right => app.next_club(),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl App {
+    fn render_clubs(&self, f: &mut Frame, area: Rect) {
+        let clubs = match &self.clubs {
+            Some(clubs) if !clubs.is_empty() => clubs,
+            loaded => {
+                let message = if loaded.is_none() {
+                    "Loading clubs..."
+                } else {
+                    "You are not a member of any clubs"
+                };
+                let paragraph = Paragraph::new(self.tr(message))
+                    .style(Style::default().fg(self.theme.text))
+                    .block(Block::new().borders(Borders::ALL).title(self.tr("Clubs")));
+                f.render_widget(paragraph, area);
+                return;
+            }
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let tabs = Tabs::new(clubs.iter().map(|c| c.name.clone()))
+            .select(self.selected_club_index)
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.tr("Clubs (h/l to switch)")),
+            )
+            .highlight_style(Style::default().fg(self.theme.warning));
+        f.render_widget(tabs, chunks[0]);
+
+        let club = &clubs[self.selected_club_index];
+        let title = match club.member_count {
+            Some(members) => self.trf("{} - {} members", &[&club.name, &members]),
+            None => club.name.clone(),
+        };
+        let feed = &self.club_feed;
+        if feed.activities.is_empty() {
+            let message = if feed.failed {
+                "Club activities failed to load - r to retry"
+            } else if feed.exhausted {
+                "No recent club activities"
+            } else {
+                "Loading club activities..."
+            };
+            let paragraph = Paragraph::new(self.tr(message))
+                .style(Style::default().fg(self.theme.text))
+                .block(Block::new().borders(Borders::ALL).title(title));
+            f.render_widget(paragraph, chunks[1]);
+            return;
+        }
+
+        let rows: Vec<Row> = feed
+            .activities
+            .iter()
+            .enumerate()
+            .map(|(i, activity)| {
+                let row_style = if i == feed.selected {
+                    self.theme.selection()
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                let athlete = format!(
+                    "{} {}",
+                    activity.athlete.firstname, activity.athlete.lastname
+                );
+                let duration = format!(
+                    "{}:{:02}:{:02}",
+                    activity.moving_time / 3600,
+                    (activity.moving_time % 3600) / 60,
+                    activity.moving_time % 60
+                );
+                let pace = Self::format_pace(
+                    activity.moving_time as f64 / self.units.distance(activity.distance),
+                );
+
+                Row::new(vec![
+                    Cell::from(athlete.chars().take(20).collect::<String>()).style(row_style),
+                    Cell::from(activity.name.chars().take(25).collect::<String>())
+                        .style(row_style.fg(self.theme.sport(&activity.sport_type))),
+                    Cell::from(format!("{:.1}", self.units.distance(activity.distance)))
+                        .style(row_style.fg(self.theme.accent)),
+                    Cell::from(format!(
+                        "{:.0}",
+                        self.units.elevation(activity.total_elevation_gain)
+                    ))
+                    .style(row_style),
+                    Cell::from(duration).style(row_style.fg(self.theme.positive)),
+                    Cell::from(pace).style(row_style.fg(self.theme.warning)),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(20),
+                Constraint::Length(25),
+                Constraint::Length(8),
+                Constraint::Length(7),
+                Constraint::Length(8),
+                Constraint::Length(7),
+            ],
+        )
+        .header(
+            Row::new(
+                ["Athlete", "Name", "Distance", "Elev", "Duration", "Pace"]
+                    .map(|label| self.tr(label)),
+            )
+            .style(self.theme.header()),
+        )
+        .block(Block::new().borders(Borders::ALL).title(title));
+        f.render_widget(table, chunks[1]);
+    }
+}
@@ -0,0 +1,674 @@
+use super::{App, Effect, Screen};
+use crate::analytics::{self, RaceEstimate};
+use crate::api::types::Activity;
+use crate::script;
+use crate::ui::keymap::Action;
+use chrono::{DateTime, Datelike, Utc};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, LineGauge, Paragraph, Wrap};
+use ratatui::Frame;
+
+// Best efforts older than this don't count towards race predictions
+const RACE_ESTIMATE_DAYS: i64 = 90;
+// Borders and three lines of a script's card
+const SCRIPT_CARD_HEIGHT: u16 = 5;
+
+pub(super) struct Dashboard;
+
+impl Screen for Dashboard {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_dashboard(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Down => app.select_next_activity(),
+            Action::Up => app.select_prev_activity(),
+            Action::Left => app.set_dashboard_range(app.dashboard_range().shorter()),
+            Action::Right => app.set_dashboard_range(app.dashboard_range().longer()),
+            _ => {}
+        }
+        None
+    }
+}
+
+/// The window the dashboard cards cover, picked with h/l
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashboardRange {
+    Week,
+    Month,
+    Quarter,
+    YearToDate,
+    AllTime,
+}
+
+impl DashboardRange {
+    pub const ALL: [DashboardRange; 5] = [
+        DashboardRange::Week,
+        DashboardRange::Month,
+        DashboardRange::Quarter,
+        DashboardRange::YearToDate,
+        DashboardRange::AllTime,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DashboardRange::Week => "7 days",
+            DashboardRange::Month => "30 days",
+            DashboardRange::Quarter => "90 days",
+            DashboardRange::YearToDate => "Year to date",
+            DashboardRange::AllTime => "All time",
+        }
+    }
+
+    pub fn shorter(self) -> Self {
+        let index = Self::ALL.iter().position(|r| *r == self).unwrap_or(0);
+        Self::ALL[index.saturating_sub(1)]
+    }
+
+    pub fn longer(self) -> Self {
+        let index = Self::ALL.iter().position(|r| *r == self).unwrap_or(0);
+        Self::ALL[(index + 1).min(Self::ALL.len() - 1)]
+    }
+
+    // Start of the window ending `now` and the window before it, which the
+    // trend arrows compare against. Year to date compares with the same days
+    // of last year. All time has neither.
+    fn windows(&self, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let days = |n| now - chrono::Duration::days(n);
+        match self {
+            DashboardRange::Week => Some((days(7), days(14))),
+            DashboardRange::Month => Some((days(30), days(60))),
+            DashboardRange::Quarter => Some((days(90), days(180))),
+            DashboardRange::YearToDate => {
+                let start = now
+                    .date_naive()
+                    .with_ordinal(1)?
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+                Some((start, start.checked_sub_months(chrono::Months::new(12))?))
+            }
+            DashboardRange::AllTime => None,
+        }
+    }
+}
+
+// Figures of the cached activities in one dashboard window
+struct WindowFigures {
+    biggest_distance: f64,
+    // Seconds per distance unit, infinite without runs
+    best_pace: f64,
+    count: u32,
+}
+
+impl App {
+    // Strava's own biggest ride covers the full history; cached runs can still beat it
+    fn compute_biggest_distance(&self) -> (f64, f64) {
+        let cached_best = self
+            .counted_history()
+            .map(|a| a.distance)
+            .fold(0.0f64, f64::max);
+        let strava_best = self
+            .stats
+            .as_ref()
+            .and_then(|s| s.biggest_ride_distance)
+            .unwrap_or(0.0);
+
+        // Strava's "recent" totals cover the last four weeks
+        let recent = match &self.stats {
+            Some(stats) => stats.recent_run_totals.distance + stats.recent_ride_totals.distance,
+            None => {
+                let four_weeks_ago = chrono::Utc::now() - chrono::Duration::days(28);
+                self.counted_history()
+                    .filter(|a| a.start_date_local > four_weeks_ago)
+                    .map(|a| a.distance)
+                    .sum()
+            }
+        };
+
+        (
+            self.units.distance(cached_best.max(strava_best)),
+            self.units.distance(recent),
+        )
+    }
+
+    // Strava's stats have no pace, so this uses every cached run
+    fn compute_best_pace(&self) -> (String, String) {
+        let runs: Vec<&Activity> = self
+            .counted_history()
+            .filter(|a| a.distance > 0.0 && (a.sport_type == "Run" || a.activity_type == "Run"))
+            .collect();
+        let pace = |a: &&Activity| a.moving_time as f64 / self.units.distance(a.distance);
+
+        let all_time_best = runs.iter().map(pace).fold(f64::INFINITY, f64::min);
+
+        let thirty_days_ago = chrono::Utc::now() - chrono::Duration::days(30);
+        let recent_best = runs
+            .iter()
+            .filter(|a| a.start_date_local > thirty_days_ago)
+            .map(pace)
+            .fold(f64::INFINITY, f64::min);
+
+        (
+            Self::format_pace(all_time_best),
+            Self::format_pace(recent_best),
+        )
+    }
+
+    // Only the cache knows single activities, so unlike the all-time cards
+    // the windows leave Strava's stats out
+    fn window_figures(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> WindowFigures {
+        let mut figures = WindowFigures {
+            biggest_distance: 0.0,
+            best_pace: f64::INFINITY,
+            count: 0,
+        };
+        for activity in self
+            .counted_history()
+            .filter(|a| a.start_date_local >= from && a.start_date_local < to)
+        {
+            figures.count += 1;
+            figures.biggest_distance = figures.biggest_distance.max(activity.distance);
+            if activity.distance > 0.0
+                && (activity.sport_type == "Run" || activity.activity_type == "Run")
+            {
+                let pace = activity.moving_time as f64 / self.units.distance(activity.distance);
+                figures.best_pace = figures.best_pace.min(pace);
+            }
+        }
+        figures.biggest_distance = self.units.distance(figures.biggest_distance);
+        figures
+    }
+
+    fn compute_monthly_count(&self) -> (u32, u32) {
+        let now = chrono::Utc::now();
+        let this_month = now.format("%Y-%m").to_string();
+        let prev_month = (now - chrono::Duration::days(35))
+            .format("%Y-%m")
+            .to_string();
+
+        let this_month_count = self
+            .counted_history()
+            .filter(|a| a.start_date_local.format("%Y-%m").to_string() == this_month)
+            .count() as u32;
+
+        let prev_month_count = self
+            .counted_history()
+            .filter(|a| a.start_date_local.format("%Y-%m").to_string() == prev_month)
+            .count() as u32;
+
+        (this_month_count, prev_month_count)
+    }
+
+    fn render_dashboard(&self, f: &mut Frame, area: Rect) {
+        let since = chrono::Utc::now() - chrono::Duration::days(RACE_ESTIMATE_DAYS);
+        let estimate = analytics::estimate_races(self.best_efforts.values().flatten(), since);
+
+        let mut constraints = vec![
+            Constraint::Length(1),
+            Constraint::Min(8),
+            Constraint::Length(3),
+        ];
+        if estimate.is_some() {
+            constraints.push(Constraint::Length(3));
+        }
+        let script_cards = self.scripts.as_ref().map(|s| s.cards()).unwrap_or_default();
+        if !script_cards.is_empty() {
+            constraints.push(Constraint::Length(SCRIPT_CARD_HEIGHT));
+        }
+        if !self.goals.is_empty() {
+            constraints.push(Constraint::Length(self.goals.len() as u16 + 2));
+        }
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+        self.render_dashboard_ranges(f, rows[0]);
+        self.render_climbing(f, rows[2]);
+        if let Some(estimate) = &estimate {
+            self.render_race_predictions(f, rows[3], estimate);
+        }
+        if !script_cards.is_empty() {
+            let row = 3 + usize::from(estimate.is_some());
+            self.render_script_cards(f, rows[row], &script_cards);
+        }
+        if !self.goals.is_empty() {
+            self.render_goals(f, rows[rows.len() - 1]);
+        }
+        let area = rows[1];
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ])
+            .split(area);
+
+        // Figures from the cache show right away; athlete and stats fill in as they load
+        let title = match &self.athlete {
+            Some(athlete) => self.trf("Welcome, {}!", &[&athlete.firstname]),
+            None => self.tr("Welcome! (loading athlete...)").to_string(),
+        };
+        let cached = self.history.len();
+        let cards = match self.dashboard_range.windows(chrono::Utc::now()) {
+            Some((start, previous)) => self.range_cards(start, previous),
+            None => self.all_time_cards(),
+        };
+        let [(widget1, dist_color), (widget2, pace_color), (widget3, count_color)] = cards;
+        let counted = match self.dashboard_range {
+            DashboardRange::AllTime => self.tr("Activities this month"),
+            _ => self.tr("Activities"),
+        };
+
+        let block1 = Block::new()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(self.theme.accent));
+        let block2 = Block::new()
+            .borders(Borders::ALL)
+            .title(self.tr("Best Pace"))
+            .border_style(Style::default().fg(self.theme.positive));
+        let block3 = Block::new()
+            .borders(Borders::ALL)
+            .title(match self.volume_filter_label() {
+                Some(label) => self.trf("{} ({} cached, {})", &[&counted, &cached, &label]),
+                None => self.trf("{} ({} cached)", &[&counted, &cached]),
+            })
+            .border_style(Style::default().fg(self.theme.warning));
+
+        let p1 = Paragraph::new(widget1).style(Style::default().fg(dist_color));
+        let p2 = Paragraph::new(widget2).style(Style::default().fg(pace_color));
+        let p3 = Paragraph::new(widget3).style(Style::default().fg(count_color));
+
+        f.render_widget(block1, chunks[0]);
+        f.render_widget(
+            p1,
+            chunks[0].inner(ratatui::layout::Margin {
+                horizontal: 1,
+                vertical: 1,
+            }),
+        );
+
+        f.render_widget(block2, chunks[1]);
+        f.render_widget(
+            p2,
+            chunks[1].inner(ratatui::layout::Margin {
+                horizontal: 1,
+                vertical: 1,
+            }),
+        );
+
+        f.render_widget(block3, chunks[2]);
+        f.render_widget(
+            p3,
+            chunks[2].inner(ratatui::layout::Margin {
+                horizontal: 1,
+                vertical: 1,
+            }),
+        );
+    }
+
+    fn render_dashboard_ranges(&self, f: &mut Frame, area: Rect) {
+        let mut spans = vec![Span::styled(
+            " h/l  ",
+            Style::default().fg(self.theme.muted),
+        )];
+        for range in DashboardRange::ALL {
+            let color = if range == self.dashboard_range {
+                self.theme.accent
+            } else {
+                self.theme.muted
+            };
+            spans.push(Span::styled(
+                format!("{}  ", self.tr(range.label())),
+                Style::default().fg(color),
+            ));
+        }
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    // Cards for a window, each compared with the window before it
+    fn range_cards(&self, start: DateTime<Utc>, previous: DateTime<Utc>) -> [(String, Color); 3] {
+        let current = self.window_figures(start, chrono::Utc::now());
+        let before = self.window_figures(previous, start);
+        let range = self.tr(self.dashboard_range.label());
+        let trend = |better: bool| {
+            if better {
+                ("↑", self.theme.positive)
+            } else {
+                ("↓", self.theme.error)
+            }
+        };
+        let unit = self.units.distance_unit();
+
+        let (dist_trend, dist_color) = trend(current.biggest_distance > before.biggest_distance);
+        let widget1 = self.trf(
+            "Biggest Distance ({})\n\n{} {} {}\n(period before: {} {})",
+            &[
+                &range,
+                &self.locale.decimal(current.biggest_distance, 1),
+                &unit,
+                &dist_trend,
+                &self.locale.decimal(before.biggest_distance, 1),
+                &unit,
+            ],
+        );
+        let (pace_trend, pace_color) = trend(current.best_pace < before.best_pace);
+        let widget2 = self.trf(
+            "Best Pace ({})\n\n{} /{} {}\n(period before: {})",
+            &[
+                &range,
+                &Self::format_pace(current.best_pace),
+                &unit,
+                &pace_trend,
+                &Self::format_pace(before.best_pace),
+            ],
+        );
+        let (count_trend, count_color) = trend(current.count > before.count);
+        let widget3 = self.trf(
+            "{}\n\n{} {}\n(period before: {})",
+            &[&range, &current.count, &count_trend, &before.count],
+        );
+
+        [
+            (widget1, dist_color),
+            (widget2, pace_color),
+            (widget3, count_color),
+        ]
+    }
+
+    fn all_time_cards(&self) -> [(String, Color); 3] {
+        let (all_time_dist, recent_dist) = self.compute_biggest_distance();
+        let (best_pace_all, best_pace_recent) = self.compute_best_pace();
+        let (this_month, prev_month) = self.compute_monthly_count();
+
+        let dist_trend = if recent_dist > 0.0 { "↑" } else { "↓" };
+        let dist_color = if recent_dist > 0.0 {
+            self.theme.positive
+        } else {
+            self.theme.error
+        };
+
+        let pace_all_secs: f64 = best_pace_all.split(':').fold(0.0, |acc, s| {
+            let parts: Vec<&str> = s.split_whitespace().collect();
+            if parts.is_empty() {
+                return acc;
+            }
+            let n: u32 = parts[0].parse().unwrap_or(0);
+            acc * 60.0 + n as f64
+        });
+        let pace_recent_secs: f64 = best_pace_recent.split(':').fold(0.0, |acc, s| {
+            let parts: Vec<&str> = s.split_whitespace().collect();
+            if parts.is_empty() {
+                return acc;
+            }
+            let n: u32 = parts[0].parse().unwrap_or(0);
+            acc * 60.0 + n as f64
+        });
+        let pace_trend = if pace_recent_secs > 0.0 && pace_recent_secs < pace_all_secs {
+            "↑"
+        } else {
+            "↓"
+        };
+        let pace_color = if pace_recent_secs > 0.0 && pace_recent_secs < pace_all_secs {
+            self.theme.positive
+        } else {
+            self.theme.error
+        };
+
+        let count_trend = if this_month > prev_month {
+            "↑"
+        } else {
+            "↓"
+        };
+        let count_color = if this_month > prev_month {
+            self.theme.positive
+        } else {
+            self.theme.error
+        };
+
+        let unit = self.units.distance_unit();
+        let cached = self.history.len();
+        let widget1 = self.trf(
+            "Biggest Distance (all time)\n\n{} {} {}\n(runs + rides, last 4 weeks: {} {})",
+            &[
+                &self.locale.decimal(all_time_dist, 1),
+                &unit,
+                &dist_trend,
+                &self.locale.decimal(recent_dist, 1),
+                &unit,
+            ],
+        );
+        let widget2 = self.trf(
+            "Best Pace (last 30 days)\n\n{} /{} {}\n(vs {} best of {} cached)",
+            &[
+                &best_pace_recent,
+                &unit,
+                &pace_trend,
+                &best_pace_all,
+                &cached,
+            ],
+        );
+        let ytd = self
+            .stats
+            .as_ref()
+            .map(|s| {
+                self.trf(
+                    "\nYear to date: {} runs, {} rides",
+                    &[&s.ytd_run_totals.count, &s.ytd_ride_totals.count],
+                )
+            })
+            .unwrap_or_else(|| self.tr("\nYear to date: loading...").to_string());
+        let widget3 = self.trf(
+            "This Month\n\n{} {}\n(vs {} last month){}",
+            &[&this_month, &count_trend, &prev_month, &ytd],
+        );
+
+        [
+            (widget1, dist_color),
+            (widget2, pace_color),
+            (widget3, count_color),
+        ]
+    }
+
+    // One card per `card_` function of the user's script, given the counted
+    // activities of the selected range, oldest first
+    fn render_script_cards(&self, f: &mut Frame, area: Rect, cards: &[String]) {
+        let Some(scripts) = &self.scripts else {
+            return;
+        };
+        let mut activities: Vec<&Activity> = match self.dashboard_range.windows(Utc::now()) {
+            Some((start, _)) => self
+                .counted_history()
+                .filter(|a| a.start_date_local >= start)
+                .collect(),
+            None => self.counted_history().collect(),
+        };
+        activities.sort_by_key(|a| a.start_date_local);
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, cards.len() as u32); cards.len()])
+            .split(area);
+        for (name, &chunk) in cards.iter().zip(chunks.iter()) {
+            let (text, color) = match scripts.card(name, &activities) {
+                Ok(text) => (text, self.theme.text),
+                Err(e) => (e, self.theme.error),
+            };
+            let block = Block::new()
+                .borders(Borders::ALL)
+                .title(script::card_title(name))
+                .border_style(Style::default().fg(self.theme.special));
+            f.render_widget(
+                Paragraph::new(text)
+                    .style(Style::default().fg(color))
+                    .wrap(Wrap { trim: true })
+                    .block(block),
+                chunk,
+            );
+        }
+    }
+
+    // Elevation gain of the dashboard window against the one before it, or
+    // this calendar month against the last one for all time
+    fn render_climbing(&self, f: &mut Frame, area: Rect) {
+        let elevation = |meters: f64| {
+            format!(
+                "{} {}",
+                self.locale.decimal(self.units.elevation(meters), 0),
+                self.units.elevation_unit()
+            )
+        };
+        let (range, current, before, compared) = match self.dashboard_range.windows(Utc::now()) {
+            Some((start, previous)) => {
+                let current = analytics::climb_totals(
+                    self.counted_history()
+                        .filter(|a| a.start_date_local >= start),
+                );
+                let before = analytics::climb_totals(
+                    self.counted_history()
+                        .filter(|a| a.start_date_local >= previous && a.start_date_local < start),
+                );
+                let compared = self.trf("(period before: {})", &[&elevation(before.elevation)]);
+                (
+                    self.tr(self.dashboard_range.label()),
+                    current,
+                    before,
+                    compared,
+                )
+            }
+            None => {
+                let now = Utc::now();
+                let month = |date: DateTime<Utc>| date.format("%Y-%m").to_string();
+                let this_month = month(now);
+                let prev_month = month(now - chrono::Duration::days(35));
+                let current = analytics::climb_totals(
+                    self.counted_history()
+                        .filter(|a| month(a.start_date_local) == this_month),
+                );
+                let before = analytics::climb_totals(
+                    self.counted_history()
+                        .filter(|a| month(a.start_date_local) == prev_month),
+                );
+                let compared = self.trf("(vs {} last month)", &[&elevation(before.elevation)]);
+                (self.tr("this month"), current, before, compared)
+            }
+        };
+
+        let (trend, color) = if current.elevation > before.elevation {
+            ("↑", self.theme.positive)
+        } else {
+            ("↓", self.theme.error)
+        };
+        let mut spans = vec![
+            Span::styled(
+                format!("{} {}", elevation(current.elevation), trend),
+                Style::default().fg(color),
+            ),
+            Span::raw(format!(" {}", compared)),
+        ];
+        if let Some(density) = current.density() {
+            spans.push(Span::raw(" · "));
+            spans.push(Span::styled(
+                self.trf(
+                    "{} {} over {} {}",
+                    &[
+                        &self.locale.decimal(self.units.climb_density(density), 1),
+                        &self.units.climb_density_unit(),
+                        &self
+                            .locale
+                            .decimal(self.units.distance(current.distance), 1),
+                        &self.units.distance_unit(),
+                    ],
+                ),
+                Style::default().fg(self.theme.accent),
+            ));
+        }
+        let paragraph = Paragraph::new(Line::from(spans)).block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.trf("Climbing ({})", &[&range]))
+                .border_style(Style::default().fg(self.theme.special)),
+        );
+        f.render_widget(paragraph, area);
+    }
+
+    fn render_race_predictions(&self, f: &mut Frame, area: Rect, estimate: &RaceEstimate) {
+        let mut spans = vec![Span::styled(
+            format!("VO2max ~{:.0}", estimate.vdot),
+            Style::default().fg(self.theme.accent),
+        )];
+        for (name, seconds) in &estimate.predictions {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::raw(format!("{} ", name)));
+            spans.push(Span::styled(
+                Self::format_hms(*seconds),
+                Style::default().fg(self.theme.positive),
+            ));
+        }
+        let effort = estimate.effort;
+        let paragraph = Paragraph::new(Line::from(spans)).block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.trf(
+                    "Race predictions (from {} in {} on {})",
+                    &[
+                        &effort.name,
+                        &Self::format_hms(effort.elapsed_time as f64),
+                        &self.locale.date(effort.start_date_local.date_naive()),
+                    ],
+                ))
+                .border_style(Style::default().fg(self.theme.info)),
+        );
+        f.render_widget(paragraph, area);
+    }
+
+    fn render_goals(&self, f: &mut Frame, area: Rect) {
+        let today = chrono::Local::now().date_naive();
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(self.tr("Goals (projection assumes the current pace)"))
+            .border_style(Style::default().fg(self.theme.special));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(1); self.goals.len()])
+            .split(inner);
+        for (goal, row) in self.goals.iter().zip(rows.iter()) {
+            let progress = goal.progress(self.counted_history(), today, self.units);
+            let on_track = progress.projected >= goal.target;
+            let color = if progress.done >= goal.target {
+                self.theme.positive
+            } else if on_track {
+                self.theme.accent
+            } else {
+                self.theme.warning
+            };
+            let label = format!(
+                "{:<28} {:>7.1} / {:<6} {}",
+                goal.label(self.units),
+                progress.done,
+                goal.target,
+                if on_track {
+                    self.trf("on track ({})", &[&format!("{:.0}", progress.projected)])
+                } else {
+                    self.trf(
+                        "behind ({} projected)",
+                        &[&format!("{:.0}", progress.projected)],
+                    )
+                }
+            );
+            let gauge = LineGauge::default()
+                .ratio(progress.ratio(goal))
+                .label(label)
+                .filled_style(Style::default().fg(color))
+                .unfilled_style(Style::default().fg(self.theme.muted));
+            f.render_widget(gauge, *row);
+        }
+    }
+}
@@ -0,0 +1,1118 @@
+use super::{App, DetailTab, EditField, EditForm, Effect, Screen, SplitsMode};
+use crate::analytics;
+use crate::api::polyline;
+use crate::api::types::ActivityZone;
+use crate::config::Units;
+use crate::ui::keymap::Action;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::canvas::{Canvas, Line as CanvasLine, Points};
+use ratatui::widgets::{
+    Axis, Bar, BarChart, BarGroup, Block, Borders, Cell, Chart, Clear, Dataset, GraphType,
+    LegendPosition, Padding, Paragraph, Row, Sparkline, Table, Tabs, Wrap,
+};
+use ratatui::Frame;
+
+// Shortest stretch considered for the steepest climb marker, so GPS noise
+// between two samples doesn't count as a wall
+const CLIMB_WINDOW_METERS: f64 = 200.0;
+// The five split columns with their spacing and borders
+const SPLITS_TABLE_WIDTH: u16 = 37;
+// The grade adjusted pace column with its spacing
+const SPLITS_GAP_WIDTH: u16 = 7;
+// The lap power column with its spacing
+const SPLITS_POWER_WIDTH: u16 = 6;
+// Number, distance, moving time, elevation change, heart rate and grade
+// adjusted speed of a split or lap
+type SplitFields = (u32, f64, u32, f64, Option<f64>, Option<f64>);
+
+pub(super) struct ActivityDetail;
+
+impl Screen for ActivityDetail {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_activity_detail(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Down => app.select_next_activity(),
+            Action::Up => app.select_prev_activity(),
+            Action::Left => app.prev_detail_tab(),
+            Action::Right => app.next_detail_tab(),
+            Action::Edit => app.start_edit(),
+            Action::SplitsMode => app.cycle_splits_mode(),
+            Action::ToggleLaps => app.toggle_laps(),
+            Action::Tag => app.start_tagging(),
+            Action::Note => app.start_note(),
+            Action::Mute => app.request_mute(),
+            Action::Delete => app.request_delete(),
+            Action::CopyLink => return Some(Effect::Copy(app.activity_link()?, "Link copied")),
+            Action::CopySummary => {
+                return Some(Effect::Copy(app.activity_summary()?, "Summary copied"))
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl App {
+    fn render_activity_detail(&mut self, f: &mut Frame, area: Rect) {
+        let activity = self.activities.get(self.selected_activity_index);
+
+        let mut content = if let Some(activity) = activity {
+            self.trf(
+                "{}\n\nType: {}\nDate: {}\nDistance: {} {}\nMoving Time: {}h {}m\nElevation Gain: {} {}\nAverage Speed: {} {}",
+                &[
+                    &activity.name,
+                    &activity.activity_type,
+                    &format!(
+                        "{} {}",
+                        self.locale.date(activity.start_date_local.date_naive()),
+                        self.locale.time(activity.start_date_local)
+                    ),
+                    &self.locale.decimal(self.units.distance(activity.distance), 2),
+                    &self.units.distance_unit(),
+                    &(activity.moving_time / 3600),
+                    &((activity.moving_time % 3600) / 60),
+                    &format!("{:.0}", self.units.elevation(activity.total_elevation_gain)),
+                    &self.units.elevation_unit(),
+                    &self
+                        .locale
+                        .decimal(self.units.speed(activity.average_speed.unwrap_or(0.0)), 2),
+                    &self.units.speed_unit(),
+                ],
+            )
+        } else {
+            self.tr("No activity selected").to_string()
+        };
+        let decoupling = self.selected_streams().and_then(|streams| {
+            let percent = analytics::aerobic_decoupling(streams)?;
+            Some(if analytics::is_steady(streams) {
+                self.trf(
+                    "Decoupling: {}% ({})",
+                    &[
+                        &self.locale.decimal(percent, 1),
+                        &self.tr(analytics::decoupling_label(percent)),
+                    ],
+                )
+            } else {
+                self.tr("Decoupling: - (pace too uneven)").to_string()
+            })
+        });
+        // Flat-equivalent pace for hilly runs, next to the raw pace
+        let grade_adjusted = activity
+            .filter(|a| analytics::is_on_foot(&a.sport_type) && a.distance > 0.0)
+            .and_then(|a| {
+                let gap = self.grade_adjusted.get(&a.id)?;
+                Some(self.trf(
+                    "Pace: {} /{} (grade adjusted {} /{})",
+                    &[
+                        &Self::format_pace(a.moving_time as f64 / self.units.distance(a.distance)),
+                        &self.units.distance_unit(),
+                        &Self::format_pace(1.0 / self.units.distance(*gap)),
+                        &self.units.distance_unit(),
+                    ],
+                ))
+            });
+        // Recording device, weather and energy, known once the details are loaded
+        let recorded = self.selected_detail().and_then(|detail| {
+            let mut parts = Vec::new();
+            if let Some(device) = detail.device_name.as_deref().filter(|d| !d.is_empty()) {
+                parts.push(self.trf("Recorded with {}", &[&device]));
+            }
+            if let Some(celsius) = detail.average_temp {
+                parts.push(format!(
+                    "{:.0} {}",
+                    self.units.temperature(celsius),
+                    self.units.temperature_unit()
+                ));
+            }
+            if let Some(calories) = detail.activity.calories.filter(|c| *c > 0.0) {
+                parts.push(format!("{:.0} kcal", calories));
+            }
+            (!parts.is_empty()).then(|| parts.join(" · "))
+        });
+        let extra: Vec<&String> = grade_adjusted
+            .iter()
+            .chain(&decoupling)
+            .chain(&recorded)
+            .collect();
+        for line in &extra {
+            content.push('\n');
+            content.push_str(line);
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(10 + extra.len() as u16),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        let mut block = Block::new()
+            .borders(Borders::ALL)
+            .title(self.tr("Details (Esc to go back)"));
+        if let Some(names) = activity.and_then(|a| self.records_by_activity().remove(&a.id)) {
+            block = block.title(
+                Line::styled(
+                    format!(" ★ {} ", names.join(", ")),
+                    Style::default().fg(self.theme.warning),
+                )
+                .right_aligned(),
+            );
+        }
+        if let Some(tags) = activity.and_then(|a| self.tags.get(&a.id)) {
+            let tags: Vec<String> = tags.iter().map(|tag| format!("#{}", tag)).collect();
+            block = block.title_bottom(Line::styled(
+                format!(" {} ", tags.join(" ")),
+                Style::default().fg(self.theme.accent),
+            ));
+        }
+        let paragraph = Paragraph::new(content)
+            .style(Style::default().fg(self.theme.text))
+            .block(block);
+
+        f.render_widget(paragraph, chunks[0]);
+
+        if activity.is_none() {
+            return;
+        }
+
+        let tab_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(chunks[1]);
+
+        let tabs = Tabs::new(DetailTab::ALL.iter().map(|t| self.tr(t.title())))
+            .select(self.detail_tab.index())
+            .style(Style::default().fg(self.theme.muted))
+            .highlight_style(Style::default().fg(self.theme.warning));
+        f.render_widget(tabs, tab_chunks[0]);
+
+        match self.detail_tab {
+            DetailTab::Overview => {
+                let panels = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(tab_chunks[1]);
+
+                self.render_stream_graphs(f, panels[0]);
+                self.render_splits(f, panels[1]);
+            }
+            DetailTab::Map => self.render_map(f, tab_chunks[1]),
+            DetailTab::Photos => self.render_photos(f, tab_chunks[1]),
+            DetailTab::Notes => self.render_notes(f, tab_chunks[1]),
+            DetailTab::Zones => self.render_zones(f, tab_chunks[1]),
+            DetailTab::Social => self.render_social(f, tab_chunks[1]),
+        }
+
+        if let Some(form) = &self.edit_form {
+            self.render_edit_form(f, area, form);
+        }
+    }
+
+    fn render_edit_form(&self, f: &mut Frame, area: Rect, form: &EditForm) {
+        let popup = Self::centered_rect(area, area.width.saturating_sub(4).min(70), 11);
+
+        let checkbox = |checked: bool| if checked { "[x]" } else { "[ ]" };
+        let mut lines: Vec<Line> = EditField::ALL
+            .iter()
+            .map(|field| {
+                let value = match field {
+                    EditField::Name => form.name.clone(),
+                    EditField::Description => form.description.clone(),
+                    EditField::Commute => checkbox(form.commute).to_string(),
+                    EditField::Trainer => checkbox(form.trainer).to_string(),
+                    EditField::GearId => form.gear_id.clone(),
+                };
+                let focused = *field == form.field;
+                let cursor = if focused && form.text_field_focused() {
+                    "_"
+                } else {
+                    ""
+                };
+                let style = if focused {
+                    Style::default().fg(self.theme.warning)
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                Line::styled(
+                    format!(
+                        "{:<12} {}{}",
+                        format!("{}:", self.tr(field.label())),
+                        value,
+                        cursor
+                    ),
+                    style,
+                )
+            })
+            .collect();
+
+        lines.push(Line::raw(""));
+        let status = if form.saving {
+            Line::styled(
+                self.tr("Saving..."),
+                Style::default().fg(self.theme.warning),
+            )
+        } else if let Some(error) = &form.error {
+            Line::styled(error.clone(), Style::default().fg(self.theme.error))
+        } else {
+            Line::styled(
+                self.tr("Tab/↑↓ move · Space toggle · Enter save · Esc cancel"),
+                Style::default().fg(self.theme.muted),
+            )
+        };
+        lines.push(status);
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.tr("Edit Activity"))
+                .border_style(Style::default().fg(self.theme.accent)),
+        );
+
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+
+    // Prefers the full-resolution latlng stream, then the detailed and summary polylines
+    fn selected_route(&self) -> Vec<(f64, f64)> {
+        if let Some(latlng) = self.selected_streams().and_then(|s| s.latlng.as_ref()) {
+            if latlng.data.len() >= 2 {
+                return latlng.data.iter().map(|p| (p[0], p[1])).collect();
+            }
+        }
+
+        let detail_map = self.selected_detail().and_then(|d| d.activity.map.as_ref());
+        let summary_map = self.get_selected_activity().and_then(|a| a.map.as_ref());
+        detail_map
+            .and_then(|m| m.polyline.as_deref().or(m.summary_polyline.as_deref()))
+            .or_else(|| summary_map.and_then(|m| m.summary_polyline.as_deref()))
+            .map(polyline::decode)
+            .unwrap_or_default()
+    }
+
+    fn render_map(&self, f: &mut Frame, area: Rect) {
+        let route = self.selected_route();
+        if route.len() < 2 {
+            let paragraph = Paragraph::new(self.tr("No GPS data for this activity"))
+                .style(Style::default().fg(self.theme.muted))
+                .block(Block::new().borders(Borders::ALL).title(self.tr("Map")));
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        // Equirectangular projection: shrink longitude by cos(latitude)
+        let mid_lat = route.iter().map(|p| p.0).sum::<f64>() / route.len() as f64;
+        let scale = mid_lat.to_radians().cos();
+        let points: Vec<(f64, f64)> = route.iter().map(|(lat, lng)| (lng * scale, *lat)).collect();
+
+        let (mut min_x, mut max_x) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut min_y, mut max_y) = (f64::INFINITY, f64::NEG_INFINITY);
+        for (x, y) in &points {
+            min_x = min_x.min(*x);
+            max_x = max_x.max(*x);
+            min_y = min_y.min(*y);
+            max_y = max_y.max(*y);
+        }
+
+        // Braille cells hold 2x4 roughly square dots; pad the bounds to keep the route's shape
+        let dots_w = area.width.saturating_sub(2).max(1) as f64 * 2.0;
+        let dots_h = area.height.saturating_sub(2).max(1) as f64 * 4.0;
+        let mut w = (max_x - min_x).max(1e-6);
+        let mut h = (max_y - min_y).max(1e-6);
+        if w / h < dots_w / dots_h {
+            w = h * dots_w / dots_h;
+        } else {
+            h = w * dots_h / dots_w;
+        }
+        let (cx, cy) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+        let (w, h) = (w * 1.05, h * 1.05);
+
+        let start = [points[0]];
+        let end = [points[points.len() - 1]];
+        let canvas = Canvas::default()
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.tr("Map (green: start, red: finish)")),
+            )
+            .marker(Marker::Braille)
+            .x_bounds([cx - w / 2.0, cx + w / 2.0])
+            .y_bounds([cy - h / 2.0, cy + h / 2.0])
+            .paint(|ctx| {
+                for pair in points.windows(2) {
+                    ctx.draw(&CanvasLine {
+                        x1: pair[0].0,
+                        y1: pair[0].1,
+                        x2: pair[1].0,
+                        y2: pair[1].1,
+                        color: self.theme.accent,
+                    });
+                }
+                ctx.layer();
+                ctx.draw(&Points {
+                    coords: &start,
+                    color: self.theme.positive,
+                });
+                ctx.draw(&Points {
+                    coords: &end,
+                    color: self.theme.error,
+                });
+            });
+
+        f.render_widget(canvas, area);
+    }
+
+    fn render_notes(&self, f: &mut Frame, area: Rect) {
+        let Some(activity) = self.get_selected_activity() else {
+            return;
+        };
+        let editor = self
+            .note_editor
+            .as_ref()
+            .filter(|e| e.activity_id == activity.id);
+
+        let (text, style, title) = match (editor, self.notes.get(&activity.id)) {
+            (Some(editor), _) => (
+                format!("{}_", editor.text),
+                Style::default().fg(self.theme.warning),
+                "Notes - Enter new line, Ctrl+S save, Esc cancel",
+            ),
+            (None, Some(note)) => (
+                note.clone(),
+                Style::default().fg(self.theme.text),
+                "Notes (only on this machine) - n edit",
+            ),
+            (None, None) => (
+                self.tr("No notes yet. Press n to write down how it felt.")
+                    .to_string(),
+                Style::default().fg(self.theme.muted),
+                "Notes (only on this machine) - n edit",
+            ),
+        };
+
+        // Keep the end of a long note, where the cursor is, in view
+        let width = area.width.saturating_sub(2).max(1) as usize;
+        let lines: usize = text
+            .split('\n')
+            .map(|line| line.chars().count().div_ceil(width).max(1))
+            .sum();
+        let scroll = lines.saturating_sub(area.height.saturating_sub(2) as usize);
+
+        let paragraph = Paragraph::new(text)
+            .style(style)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll.min(u16::MAX as usize) as u16, 0))
+            .block(Block::new().borders(Borders::ALL).title(self.tr(title)));
+        f.render_widget(paragraph, area);
+    }
+
+    fn render_photos(&mut self, f: &mut Frame, area: Rect) {
+        let photos = self.get_selected_activity().and_then(|a| {
+            self.activity_photos
+                .as_ref()
+                .filter(|p| p.activity_id == a.id)
+        });
+
+        let Some(photos) = photos else {
+            let paragraph = Paragraph::new(self.tr("Loading photos..."))
+                .style(Style::default().fg(self.theme.warning))
+                .block(Block::new().borders(Borders::ALL).title(self.tr("Photos")));
+            f.render_widget(paragraph, area);
+            return;
+        };
+
+        let lines: Vec<Line> = if photos.photos.is_empty() {
+            vec![Line::styled(
+                self.tr("No photos"),
+                Style::default().fg(self.theme.muted),
+            )]
+        } else {
+            photos
+                .photos
+                .iter()
+                .flat_map(|p| {
+                    [
+                        Line::styled(
+                            p.caption
+                                .clone()
+                                .filter(|c| !c.is_empty())
+                                .unwrap_or_else(|| self.tr("Untitled").to_string()),
+                            Style::default().fg(self.theme.text),
+                        ),
+                        Line::styled(
+                            format!("  {}", p.url().unwrap_or(self.tr("(no URL)"))),
+                            Style::default().fg(self.theme.accent),
+                        ),
+                    ]
+                })
+                .collect()
+        };
+        let title = self.trf("Photos ({})", &[&photos.photos.len()]);
+        let has_image = photos.image.is_some() && self.graphics.is_some();
+
+        let list_area = if has_image {
+            let panels = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .split(area);
+            let block = Block::new().borders(Borders::ALL).title(self.tr("Preview"));
+            self.photo_area = Some(block.inner(panels[1]));
+            f.render_widget(block, panels[1]);
+            panels[0]
+        } else {
+            area
+        };
+
+        let list = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::new().borders(Borders::ALL).title(title));
+        f.render_widget(list, list_area);
+    }
+
+    fn render_social(&self, f: &mut Frame, area: Rect) {
+        let social = self.get_selected_activity().and_then(|a| {
+            self.activity_social
+                .as_ref()
+                .filter(|s| s.activity_id == a.id)
+        });
+
+        let Some(social) = social else {
+            let paragraph = Paragraph::new(self.tr("Loading kudos and comments..."))
+                .style(Style::default().fg(self.theme.warning))
+                .block(
+                    Block::new()
+                        .borders(Borders::ALL)
+                        .title(self.tr("Kudos & Comments")),
+                );
+            f.render_widget(paragraph, area);
+            return;
+        };
+
+        let panels = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(area);
+
+        let kudos = if social.kudoers.is_empty() {
+            self.tr("No kudos yet").to_string()
+        } else {
+            social
+                .kudoers
+                .iter()
+                .map(|a| format!("{} {}", a.firstname, a.lastname))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let kudos = Paragraph::new(kudos)
+            .style(Style::default().fg(self.theme.warning))
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.trf("Kudos ({})", &[&social.kudoers.len()])),
+            );
+        f.render_widget(kudos, panels[0]);
+
+        let comments = if social.comments.is_empty() {
+            self.tr("No comments yet").to_string()
+        } else {
+            social
+                .comments
+                .iter()
+                .map(|c| {
+                    format!(
+                        "{} {} ({} {}):\n  {}",
+                        c.athlete.firstname,
+                        c.athlete.lastname,
+                        self.locale.date(c.created_at.date_naive()),
+                        self.locale.time(c.created_at),
+                        c.text
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+        let comments = Paragraph::new(comments)
+            .style(Style::default().fg(self.theme.text))
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.trf("Comments ({})", &[&social.comments.len()])),
+            );
+        f.render_widget(comments, panels[1]);
+    }
+
+    fn render_zones(&self, f: &mut Frame, area: Rect) {
+        let title = self.tr("Zones");
+        let stream_zones = self.stream_zones();
+        let zones = match (&stream_zones, &self.activity_zones) {
+            (Some(zone), _) => Some(std::slice::from_ref(zone)),
+            (None, Some((id, zones)))
+                if Some(*id) == self.get_selected_activity().map(|a| a.id) =>
+            {
+                Some(zones.as_slice())
+            }
+            _ => None,
+        };
+        let zones: Vec<&ActivityZone> = match zones {
+            Some(zones) => zones.iter().filter(|z| z.total_time() > 0.0).collect(),
+            None => {
+                let paragraph = Paragraph::new(self.tr("Loading zones..."))
+                    .style(Style::default().fg(self.theme.warning))
+                    .block(Block::new().borders(Borders::ALL).title(title));
+                f.render_widget(paragraph, area);
+                return;
+            }
+        };
+        if zones.is_empty() {
+            let paragraph =
+                Paragraph::new(self.tr("No heart rate or power zones for this activity"))
+                    .style(Style::default().fg(self.theme.muted))
+                    .block(Block::new().borders(Borders::ALL).title(title));
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let source = if stream_zones.is_some() {
+            self.tr("computed from the heart rate stream")
+        } else {
+            self.tr("from Strava")
+        };
+        let panels = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, zones.len() as u32); zones.len()])
+            .split(area);
+        for (zone, panel) in zones.into_iter().zip(panels.iter()) {
+            self.render_zone(f, *panel, zone, source);
+        }
+    }
+
+    fn render_zone(&self, f: &mut Frame, area: Rect, zone: &ActivityZone, source: &str) {
+        let (title, unit, color) = match zone.zone_type.as_str() {
+            "power" => (self.tr("Power Zones"), "W", self.theme.warning),
+            _ => (self.tr("Heart Rate Zones"), "bpm", self.theme.error),
+        };
+        let total = zone.total_time();
+        let bars: Vec<Bar> = zone
+            .distribution_buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| {
+                let range = if bucket.max < 0.0 {
+                    format!("{:.0}+", bucket.min)
+                } else {
+                    format!("{:.0}-{:.0}", bucket.min, bucket.max)
+                };
+                Bar::default()
+                    .label(Line::from(format!("Z{} {:>7} {}", i + 1, range, unit)))
+                    .value(bucket.time.round() as u64)
+                    .text_value(format!(
+                        "{} {:.0}%",
+                        Self::format_hms(bucket.time),
+                        bucket.time / total * 100.0
+                    ))
+                    .style(Style::default().fg(color))
+                    .value_style(Style::default().fg(self.theme.header_bg).bg(color))
+            })
+            .collect();
+
+        let mut block = Block::new()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_bottom(Line::styled(
+                format!(" {} ", source),
+                Style::default().fg(self.theme.muted),
+            ))
+            .padding(Padding::top(1));
+        if let Some(score) = zone.score.filter(|s| *s > 0.0) {
+            block = block.title(
+                Line::from(self.trf(" Score {} ", &[&format!("{:.0}", score)])).right_aligned(),
+            );
+        }
+        let chart = BarChart::default()
+            .block(block)
+            .direction(Direction::Horizontal)
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(1)
+            .bar_gap(1);
+        f.render_widget(chart, area);
+    }
+
+    fn render_splits(&self, f: &mut Frame, area: Rect) {
+        let Some(detail) = self.selected_detail() else {
+            let paragraph = Paragraph::new(self.tr("Loading splits..."))
+                .style(Style::default().fg(self.theme.warning))
+                .block(Block::new().borders(Borders::ALL).title(self.tr("Splits")));
+            f.render_widget(paragraph, area);
+            return;
+        };
+
+        // Splits come in fixed km/mile lengths; laps use the configured units.
+        // Only laps carry the average power.
+        let mut watts: Vec<Option<f64>> = Vec::new();
+        let (title, pace_units, mut splits): (&str, Units, Vec<SplitFields>) =
+            match self.splits_mode {
+                SplitsMode::Metric | SplitsMode::Standard => {
+                    let (title, units, splits) = if self.splits_mode == SplitsMode::Metric {
+                        (
+                            "Splits (km) - m: toggle, z: laps",
+                            Units::Metric,
+                            &detail.splits_metric,
+                        )
+                    } else {
+                        (
+                            "Splits (mi) - m: toggle, z: laps",
+                            Units::Imperial,
+                            &detail.splits_standard,
+                        )
+                    };
+                    let splits = splits
+                        .iter()
+                        .flatten()
+                        .map(|split| {
+                            (
+                                split.split,
+                                split.distance,
+                                split.moving_time,
+                                split.elevation_difference,
+                                split.average_heartrate,
+                                split.average_grade_adjusted_speed,
+                            )
+                        })
+                        .collect();
+                    (title, units, splits)
+                }
+                SplitsMode::Laps => {
+                    watts = detail
+                        .laps
+                        .iter()
+                        .flatten()
+                        .map(|lap| lap.average_watts.filter(|w| *w > 0.0))
+                        .collect();
+                    let laps = detail
+                        .laps
+                        .iter()
+                        .flatten()
+                        .map(|lap| {
+                            (
+                                lap.lap_index,
+                                lap.distance,
+                                lap.moving_time,
+                                lap.total_elevation_gain.unwrap_or(0.0),
+                                lap.average_heartrate,
+                                None,
+                            )
+                        })
+                        .collect();
+                    ("Laps - z: splits", self.units, laps)
+                }
+            };
+        let title = self.tr(title);
+
+        if splits.is_empty() {
+            let paragraph = Paragraph::new(self.tr("No splits for this activity"))
+                .style(Style::default().fg(self.theme.muted))
+                .block(Block::new().borders(Borders::ALL).title(title));
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        // Strava only grades splits of runs; the rest comes from the streams
+        if analytics::is_on_foot(&detail.activity.sport_type) {
+            if let Some(streams) = self.selected_streams() {
+                let mut start = 0.0;
+                for split in &mut splits {
+                    let end = start + split.1;
+                    split.5 = split
+                        .5
+                        .or_else(|| analytics::grade_adjusted_speed(streams, start..end));
+                    start = end;
+                }
+            }
+        }
+        let graded = splits.iter().any(|split| split.5.is_some());
+        let powered = watts.iter().any(Option::is_some);
+
+        let rows: Vec<Row> = splits
+            .iter()
+            .enumerate()
+            .map(
+                |(i, &(index, distance, moving_time, elevation, heartrate, gap))| {
+                    let mut row = vec![
+                        Cell::from(index.to_string()),
+                        Cell::from(format!("{:.2}", self.units.distance(distance)))
+                            .style(Style::default().fg(self.theme.accent)),
+                        Cell::from(if distance > 0.0 {
+                            Self::format_pace(moving_time as f64 / pace_units.distance(distance))
+                        } else {
+                            "--:--".to_string()
+                        })
+                        .style(Style::default().fg(self.theme.warning)),
+                    ];
+                    if graded {
+                        let gap = gap
+                            .map(|speed| Self::format_pace(1.0 / pace_units.distance(speed)))
+                            .unwrap_or_else(|| "--:--".to_string());
+                        row.push(Cell::from(gap).style(Style::default().fg(self.theme.special)));
+                    }
+                    row.push(Cell::from(format!(
+                        "{:+.0}",
+                        self.units.elevation(elevation)
+                    )));
+                    row.push(
+                        Cell::from(
+                            heartrate
+                                .map(|h| format!("{:.0}", h))
+                                .unwrap_or_else(|| "---".to_string()),
+                        )
+                        .style(Style::default().fg(self.theme.error)),
+                    );
+                    if powered {
+                        row.push(
+                            Cell::from(
+                                watts[i]
+                                    .map(|w| format!("{:.0}", w))
+                                    .unwrap_or_else(|| "---".to_string()),
+                            )
+                            .style(Style::default().fg(self.theme.warning)),
+                        );
+                    }
+                    Row::new(row)
+                },
+            )
+            .collect();
+
+        let mut widths = vec![
+            Constraint::Length(4),
+            Constraint::Length(7),
+            Constraint::Length(8),
+        ];
+        let mut header = vec![
+            "#".to_string(),
+            self.units.distance_unit().to_string(),
+            format!("/{}", pace_units.distance_unit()),
+        ];
+        let mut table_width = SPLITS_TABLE_WIDTH;
+        if graded {
+            widths.push(Constraint::Length(SPLITS_GAP_WIDTH - 1));
+            header.push("GAP".to_string());
+            table_width += SPLITS_GAP_WIDTH;
+        }
+        widths.extend([Constraint::Length(7), Constraint::Length(5)]);
+        header.extend([
+            format!("Δ{}", self.units.elevation_unit()),
+            self.tr("HR").to_string(),
+        ]);
+        if powered {
+            widths.push(Constraint::Length(SPLITS_POWER_WIDTH - 1));
+            header.push("W".to_string());
+            table_width += SPLITS_POWER_WIDTH;
+        }
+
+        let table = Table::new(rows, widths)
+            .header(Row::new(header).style(self.theme.header()))
+            .block(Block::new().borders(Borders::ALL).title(title));
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(table_width), Constraint::Min(0)])
+            .split(area);
+        f.render_widget(table, chunks[0]);
+
+        // Interval laps are paced by power on the bike
+        if powered {
+            self.render_split_bars(f, chunks[1], "Power", &watts, true, |w| {
+                format!("{:.0} W", w)
+            });
+            return;
+        }
+        let paces: Vec<Option<f64>> = splits
+            .iter()
+            .map(|&(_, distance, moving_time, _, _, _)| {
+                let pace = moving_time as f64 / pace_units.distance(distance);
+                (distance > 0.0 && pace.is_finite() && pace > 0.0).then_some(pace)
+            })
+            .collect();
+        self.render_split_bars(f, chunks[1], "Pace", &paces, false, Self::format_pace);
+    }
+
+    // One bar per split row, lined up with the table next to it. Bars start
+    // just short of the lowest value so small differences stay visible.
+    fn render_split_bars(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        title: &'static str,
+        values: &[Option<f64>],
+        higher_is_better: bool,
+        label: impl Fn(f64) -> String,
+    ) {
+        let known = || values.iter().flatten().copied();
+        let lowest = known().fold(f64::INFINITY, f64::min);
+        let highest = known().fold(0.0, f64::max);
+        let base = (lowest - (highest - lowest).max(10.0) / 3.0).max(0.0);
+        let (best, worst) = if higher_is_better {
+            (highest, lowest)
+        } else {
+            (lowest, highest)
+        };
+
+        let bars: Vec<Bar> = values
+            .iter()
+            .map(|value| {
+                let Some(value) = *value else {
+                    return Bar::default().value(0).text_value(String::new());
+                };
+                let color = if values.len() > 1 && value == best {
+                    self.theme.positive
+                } else if values.len() > 1 && value == worst {
+                    self.theme.error
+                } else {
+                    self.theme.accent
+                };
+                Bar::default()
+                    .value((value - base).round() as u64)
+                    .text_value(label(value))
+                    .style(Style::default().fg(color))
+                    .value_style(Style::default().fg(self.theme.header_bg).bg(color))
+            })
+            .collect();
+
+        let chart = BarChart::default()
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.tr(title))
+                    .padding(Padding::top(1)),
+            )
+            .direction(Direction::Horizontal)
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(1)
+            .bar_gap(0);
+        f.render_widget(chart, area);
+    }
+
+    fn render_stream_graphs(&self, f: &mut Frame, area: Rect) {
+        let Some(streams) = self.selected_streams() else {
+            let paragraph = Paragraph::new(self.tr("Loading streams..."))
+                .style(Style::default().fg(self.theme.warning))
+                .block(Block::new().borders(Borders::ALL).title(self.tr("Streams")));
+            f.render_widget(paragraph, area);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+
+        self.render_sparkline(
+            f,
+            chunks[0],
+            self.tr("Heart Rate"),
+            "bpm",
+            streams.heartrate.as_ref().map(|s| s.data.as_slice()),
+            self.theme.error,
+        );
+        match (&streams.distance, &streams.altitude) {
+            (Some(distance), Some(altitude))
+                if distance.data.len() == altitude.data.len() && distance.data.len() > 1 =>
+            {
+                self.render_elevation_profile(f, chunks[1], &distance.data, &altitude.data)
+            }
+            _ => self.render_sparkline(
+                f,
+                chunks[1],
+                self.tr("Elevation"),
+                "m",
+                streams.altitude.as_ref().map(|s| s.data.as_slice()),
+                self.theme.positive,
+            ),
+        }
+    }
+
+    pub(super) fn render_elevation_profile(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        distance: &[f64],
+        altitude: &[f64],
+    ) {
+        let point = |i: usize| {
+            (
+                self.units.distance(distance[i]),
+                self.units.elevation(altitude[i]),
+            )
+        };
+        // A couple of points per Braille column is all the chart can show
+        let step = (distance.len() / (area.width as usize * 2).max(1)).max(1);
+        let profile: Vec<(f64, f64)> = (0..distance.len())
+            .step_by(step)
+            .chain([distance.len() - 1])
+            .map(point)
+            .collect();
+
+        let (top, _) = altitude
+            .iter()
+            .enumerate()
+            .fold((0, f64::NEG_INFINITY), |best, (i, &a)| {
+                if a > best.1 {
+                    (i, a)
+                } else {
+                    best
+                }
+            });
+        let top_point = [point(top)];
+        let climb = Self::steepest_climb(distance, altitude);
+        let climb_points: Vec<(f64, f64)> = climb
+            .map(|(start, end, _)| (start..=end).map(point).collect())
+            .unwrap_or_default();
+
+        let elevation_unit = self.units.elevation_unit();
+        let mut datasets = vec![Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(self.theme.positive))
+            .data(&profile)];
+        if let Some((_, _, grade)) = climb {
+            datasets.push(
+                Dataset::default()
+                    .name(self.trf("steepest {}%", &[&format!("{:.1}", grade * 100.0)]))
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(self.theme.error))
+                    .data(&climb_points),
+            );
+        }
+        datasets.push(
+            Dataset::default()
+                .name(format!("max {:.0} {}", top_point[0].1, elevation_unit))
+                .marker(Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(Style::default().fg(self.theme.warning))
+                .data(&top_point),
+        );
+
+        let total = point(distance.len() - 1).0;
+        let (low, high) = profile
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &(_, y)| {
+                (lo.min(y), hi.max(y))
+            });
+        let high = high.max(low + 1.0);
+        let unit = self.units.distance_unit();
+        let chart = Chart::new(datasets)
+            .block(Block::new().borders(Borders::ALL).title(self.trf(
+                "Elevation ({}-{} {})",
+                &[
+                    &format!("{:.0}", low),
+                    &format!("{:.0}", high),
+                    &elevation_unit,
+                ],
+            )))
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(self.theme.muted))
+                    .bounds([0.0, total])
+                    .labels([
+                        "0".to_string(),
+                        format!("{:.1}", total / 2.0),
+                        format!("{:.1} {}", total, unit),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(self.theme.muted))
+                    .bounds([low, high])
+                    .labels([format!("{:.0}", low), format!("{:.0}", high)]),
+            )
+            .legend_position(Some(LegendPosition::TopLeft))
+            .hidden_legend_constraints((Constraint::Ratio(1, 1), Constraint::Ratio(1, 1)));
+
+        f.render_widget(chart, area);
+    }
+
+    /// Start, end and grade of the steepest stretch at least
+    /// `CLIMB_WINDOW_METERS` long, if anything climbs at all.
+    fn steepest_climb(distance: &[f64], altitude: &[f64]) -> Option<(usize, usize, f64)> {
+        let mut best: Option<(usize, usize, f64)> = None;
+        let mut end = 0;
+        for start in 0..distance.len() {
+            end = end.max(start);
+            while end < distance.len() && distance[end] - distance[start] < CLIMB_WINDOW_METERS {
+                end += 1;
+            }
+            if end == distance.len() {
+                break;
+            }
+            let grade = (altitude[end] - altitude[start]) / (distance[end] - distance[start]);
+            if grade > 0.0 && best.is_none_or(|(_, _, g)| grade > g) {
+                best = Some((start, end, grade));
+            }
+        }
+        best
+    }
+
+    fn render_sparkline(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        label: &str,
+        unit: &str,
+        data: Option<&[f64]>,
+        color: Color,
+    ) {
+        let data = match data {
+            Some(data) if !data.is_empty() => data,
+            _ => {
+                let paragraph = Paragraph::new(self.trf("No {} data", &[&label.to_lowercase()]))
+                    .style(Style::default().fg(self.theme.muted))
+                    .block(Block::new().borders(Borders::ALL).title(label));
+                f.render_widget(paragraph, area);
+                return;
+            }
+        };
+
+        let min = data.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = data.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let width = area.width.saturating_sub(2) as usize;
+
+        // Offset by the minimum so the profile fills the available height
+        let points: Vec<u64> = Self::downsample(data, width)
+            .iter()
+            .map(|v| (v - min).round() as u64 + 1)
+            .collect();
+
+        let sparkline = Sparkline::default()
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(format!("{} ({:.0}-{:.0} {})", label, min, max, unit)),
+            )
+            .data(&points)
+            .style(Style::default().fg(color));
+
+        f.render_widget(sparkline, area);
+    }
+
+    fn downsample(data: &[f64], width: usize) -> Vec<f64> {
+        if width == 0 || data.len() <= width {
+            return data.to_vec();
+        }
+
+        (0..width)
+            .map(|i| {
+                let start = i * data.len() / width;
+                let end = ((i + 1) * data.len() / width).max(start + 1);
+                let bucket = &data[start..end];
+                bucket.iter().sum::<f64>() / bucket.len() as f64
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,152 @@
+use super::{App, Screen};
+use crate::fitness::{self, HeartRateRange};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Axis, Block, Borders, Chart, Dataset, GraphType, LegendPosition, Paragraph,
+};
+use ratatui::Frame;
+
+// How far back the fitness chart reaches
+const FITNESS_CHART_DAYS: usize = 90;
+
+pub(super) struct Fitness;
+
+impl Screen for Fitness {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_fitness(f, area);
+    }
+}
+
+impl App {
+    fn render_fitness(&self, f: &mut Frame, area: Rect) {
+        let title = self.tr("Fitness & Freshness");
+        let today = chrono::Local::now().date_naive();
+        let heart_rate = HeartRateRange::resolve(
+            self.resting_heartrate,
+            self.max_heartrate,
+            self.history.values(),
+        );
+        let history = fitness::fitness_history(self.history.values(), heart_rate, today);
+        let Some(current) = history.last() else {
+            let paragraph = Paragraph::new(
+                self.tr("No relative effort or heart rate data in the cached activities"),
+            )
+            .style(Style::default().fg(self.theme.muted))
+            .block(Block::new().borders(Borders::ALL).title(title));
+            f.render_widget(paragraph, area);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(0)])
+            .split(area);
+
+        // TrainingPeaks' form zones
+        let (state, state_color) = match current.form {
+            f if f > 25.0 => ("transition, fitness is fading", self.theme.warning),
+            f if f > 5.0 => ("fresh", self.theme.positive),
+            f if f > -10.0 => ("neutral", self.theme.text),
+            f if f > -30.0 => ("optimal training", self.theme.accent),
+            _ => ("overreaching", self.theme.error),
+        };
+        let source = match heart_rate {
+            Some(hr) => self.trf(
+                "Load from relative effort, or TRIMP with heart rates {}-{} bpm",
+                &[&format!("{:.0}", hr.resting), &format!("{:.0}", hr.max)],
+            ),
+            None => self
+                .tr("Load from relative effort; set max_heartrate to include heart rate only activities")
+                .to_string(),
+        };
+        let summary = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled(
+                    format!("{} {:.0}", self.tr("Fitness"), current.fitness),
+                    Style::default().fg(self.theme.info),
+                ),
+                Span::raw(" | "),
+                Span::styled(
+                    format!("{} {:.0}", self.tr("Fatigue"), current.fatigue),
+                    Style::default().fg(self.theme.special),
+                ),
+                Span::raw(" | "),
+                Span::styled(
+                    format!(
+                        "{} {:+.0} ({})",
+                        self.tr("Form"),
+                        current.form,
+                        self.tr(state)
+                    ),
+                    Style::default().fg(state_color),
+                ),
+            ]),
+            Line::styled(source, Style::default().fg(self.theme.muted)),
+        ])
+        .block(Block::new().borders(Borders::ALL).title(title));
+        f.render_widget(summary, chunks[0]);
+
+        let shown = &history[history.len().saturating_sub(FITNESS_CHART_DAYS)..];
+        let series = |value: fn(&fitness::FitnessDay) -> f64| -> Vec<(f64, f64)> {
+            shown
+                .iter()
+                .enumerate()
+                .map(|(i, day)| (i as f64, value(day)))
+                .collect()
+        };
+        let fitness = series(|d| d.fitness);
+        let fatigue = series(|d| d.fatigue);
+        let form = series(|d| d.form);
+        let (low, high) = shown.iter().fold((0.0f64, 1.0f64), |(lo, hi), d| {
+            (lo.min(d.form), hi.max(d.fitness).max(d.fatigue))
+        });
+        let (low, high) = (low.floor(), high.ceil());
+
+        let line = |name: &'static str, color: Color, data| {
+            Dataset::default()
+                .name(name)
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(color))
+                .data(data)
+        };
+        let datasets = vec![
+            line(self.tr("Fitness"), self.theme.info, &fitness),
+            line(self.tr("Fatigue"), self.theme.special, &fatigue),
+            line(self.tr("Form"), self.theme.positive, &form),
+        ];
+        let date = |day: &fitness::FitnessDay| day.date.format("%b %d").to_string();
+        let chart = Chart::new(datasets)
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.trf("Last {} days", &[&shown.len()])),
+            )
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(self.theme.muted))
+                    .bounds([0.0, (shown.len() - 1).max(1) as f64])
+                    .labels([
+                        date(&shown[0]),
+                        date(&shown[shown.len() / 2]),
+                        date(&shown[shown.len() - 1]),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(self.theme.muted))
+                    .bounds([low, high])
+                    .labels([
+                        format!("{:.0}", low),
+                        format!("{:.0}", (low + high) / 2.0),
+                        format!("{:.0}", high),
+                    ]),
+            )
+            .legend_position(Some(LegendPosition::TopLeft))
+            .hidden_legend_constraints((Constraint::Ratio(1, 1), Constraint::Ratio(1, 1)));
+        f.render_widget(chart, chunks[1]);
+    }
+}
@@ -0,0 +1,104 @@
+use super::{App, Screen};
+use crate::api::types;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::Frame;
+
+pub(super) struct Gear;
+
+impl Screen for Gear {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_gear(f, area);
+    }
+}
+
+impl App {
+    fn render_gear(&self, f: &mut Frame, area: Rect) {
+        let mut gear: Vec<&types::Gear> = self
+            .gear_ids()
+            .iter()
+            .filter_map(|id| self.find_gear(id))
+            .collect();
+        if gear.is_empty() {
+            let paragraph = Paragraph::new(self.tr("No bikes or shoes on this account"))
+                .style(Style::default().fg(self.theme.text))
+                .block(Block::new().borders(Borders::ALL).title(self.tr("Gear")));
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        // Bikes first, then shoes; retired gear sinks to the bottom of each group
+        gear.sort_by_key(|g| (!g.is_bike(), g.retired.unwrap_or(false), !g.primary));
+
+        let rows: Vec<Row> = gear
+            .iter()
+            .map(|g| {
+                let (count, loaded_distance) = self
+                    .activities
+                    .iter()
+                    .filter(|a| a.gear_id.as_deref() == Some(g.id.as_str()))
+                    .fold((0u32, 0.0), |(n, d), a| (n + 1, d + a.distance));
+                let model = [g.brand_name.as_deref(), g.model_name.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let status = if g.retired.unwrap_or(false) {
+                    self.tr("retired")
+                } else if g.primary {
+                    self.tr("primary")
+                } else {
+                    ""
+                };
+                let style = if g.retired.unwrap_or(false) {
+                    Style::default().fg(self.theme.muted)
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                Row::new(vec![
+                    Cell::from(self.tr(if g.is_bike() { "Bike" } else { "Shoes" })),
+                    Cell::from(g.name.clone()),
+                    Cell::from(model),
+                    Cell::from(format!("{:.1}", self.units.distance(g.distance)))
+                        .style(style.fg(self.theme.accent)),
+                    Cell::from(count.to_string()),
+                    Cell::from(format!("{:.1}", self.units.distance(loaded_distance))),
+                    Cell::from(status).style(style.fg(self.theme.warning)),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(6),
+                Constraint::Length(24),
+                Constraint::Min(16),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(8),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                self.tr("Type").to_string(),
+                self.tr("Name").to_string(),
+                self.tr("Brand/Model").to_string(),
+                format!("{} {}", self.tr("Total"), self.units.distance_unit()),
+                self.tr("Activities").to_string(),
+                format!("{} {}", self.tr("Loaded"), self.units.distance_unit()),
+                self.tr("Status").to_string(),
+            ])
+            .style(self.theme.header()),
+        )
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.tr("Gear (activity counts cover loaded activities)")),
+        );
+        f.render_widget(table, area);
+    }
+}
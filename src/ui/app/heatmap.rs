@@ -0,0 +1,117 @@
+use super::{App, Screen};
+use crate::analytics;
+use crate::i18n;
+use chrono::{Datelike, NaiveDate};
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub(super) struct Heatmap;
+
+impl Screen for Heatmap {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_heatmap(f, area);
+    }
+}
+
+impl App {
+    fn render_heatmap(&self, f: &mut Frame, area: Rect) {
+        let today = chrono::Local::now().date_naive();
+        let volume = self.compute_daily_volume();
+        let active: BTreeSet<NaiveDate> = volume.keys().copied().collect();
+        let (current, longest) = analytics::streaks(&active, today);
+
+        // Each week is a two-character column; rows run from the locale's first weekday
+        let weeks = (area.width.saturating_sub(6) / 2).clamp(1, 53) as i64;
+        let first_day = self.locale.week_start(today) - chrono::Duration::weeks(weeks - 1);
+        let shown = volume.range(first_day..=today);
+        let max = shown.clone().map(|(_, &t)| t).max().unwrap_or(0).max(1);
+        let active_days = shown.clone().count();
+        let total_time: u32 = shown.map(|(_, &t)| t).sum();
+
+        let mut month_row = String::from("    ");
+        let mut last_month = None;
+        for week in 0..weeks {
+            let start = first_day + chrono::Duration::weeks(week);
+            if last_month != Some(start.month()) {
+                last_month = Some(start.month());
+                let label = start.format("%b").to_string();
+                // Skip labels that would collide with the previous one
+                if month_row.chars().count() <= 4 + week as usize * 2 {
+                    month_row.truncate(4 + week as usize * 2);
+                    month_row.push_str(&label);
+                }
+            }
+        }
+
+        let mut lines = vec![Line::styled(
+            month_row,
+            Style::default().fg(self.theme.text),
+        )];
+        for (row, weekday) in self.locale.weekdays().into_iter().enumerate() {
+            // Every other day is labelled
+            let label = if row % 2 == 0 {
+                self.tr(i18n::weekday_name(weekday))
+            } else {
+                ""
+            };
+            let mut spans = vec![Span::raw(format!("{:<4}", label))];
+            for week in 0..weeks {
+                let day = first_day + chrono::Duration::days(week * 7 + row as i64);
+                if day > today {
+                    spans.push(Span::raw("  "));
+                    continue;
+                }
+                let level = match volume.get(&day) {
+                    None => 0,
+                    Some(&t) => ((t as u64 * 4).div_ceil(max as u64) as usize).clamp(1, 4),
+                };
+                spans.push(Span::styled(
+                    "■ ",
+                    Style::default().fg(self.theme.heatmap[level]),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        lines.push(Line::raw(""));
+        let mut legend = vec![Span::raw(format!("    {} ", self.tr("Less")))];
+        legend.extend(
+            self.theme
+                .heatmap
+                .iter()
+                .map(|&c| Span::styled("■ ", Style::default().fg(c))),
+        );
+        legend.push(Span::raw(self.tr("More")));
+        lines.push(Line::from(legend));
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            self.trf(
+                "    {} active days, {}h total | Current streak: {} days | Longest streak: {} days",
+                &[&active_days, &(total_time / 3600), &current, &longest],
+            ),
+            Style::default().fg(self.theme.warning),
+        ));
+
+        let paragraph =
+            Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title(self.trf(
+                "Activity calendar - last {} weeks (moving time per day)",
+                &[&weeks],
+            )));
+        f.render_widget(paragraph, area);
+    }
+
+    // Moving time per local day over the cached history
+    fn compute_daily_volume(&self) -> BTreeMap<NaiveDate, u32> {
+        let mut days = BTreeMap::new();
+        for activity in self.history.values() {
+            *days
+                .entry(activity.start_date_local.date_naive())
+                .or_insert(0) += activity.moving_time;
+        }
+        days
+    }
+}
@@ -0,0 +1,144 @@
+use super::{App, ConfirmAction, Effect};
+use crate::api::types::UpdatableActivity;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A dialog or editor drawn over the current view. While one is open it gets
+/// every key press and the keymap doesn't apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Overlay {
+    Help,
+    ErrorLog,
+    RangePicker,
+    DateJump,
+    Tags,
+    Note,
+    Confirm,
+    Edit,
+    Sort,
+}
+
+impl Overlay {
+    pub(super) fn handle_key(self, app: &mut App, key: KeyEvent) -> Option<Effect> {
+        match self {
+            Overlay::Help => {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Char('?' | 'q')) {
+                    app.toggle_help();
+                }
+            }
+            Overlay::ErrorLog => {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Char('L' | 'q')) {
+                    app.toggle_error_log();
+                }
+            }
+            Overlay::RangePicker => match key.code {
+                KeyCode::Esc => app.cancel_range_picker(),
+                KeyCode::Enter => {
+                    app.submit_range();
+                }
+                KeyCode::Tab | KeyCode::BackTab | KeyCode::Down | KeyCode::Up => {
+                    app.range_next_field()
+                }
+                KeyCode::Backspace => app.range_backspace(),
+                KeyCode::Char(c) => app.range_input(c),
+                _ => {}
+            },
+            Overlay::DateJump => match key.code {
+                KeyCode::Esc => app.cancel_date_jump(),
+                KeyCode::Enter => {
+                    app.submit_date_jump();
+                }
+                // gg still jumps to the first activity
+                KeyCode::Char('g') if app.date_jump_input().is_empty() => {
+                    app.cancel_date_jump();
+                    app.select_first_activity();
+                }
+                KeyCode::Backspace => app.date_jump_backspace(),
+                KeyCode::Char(c) => app.date_jump_push(c),
+                _ => {}
+            },
+            Overlay::Tags => match key.code {
+                KeyCode::Esc => app.cancel_tagging(),
+                KeyCode::Enter => {
+                    let (activity_id, tags) = app.submit_tags()?;
+                    return Some(Effect::SaveTags(activity_id, tags));
+                }
+                KeyCode::Backspace => app.tag_backspace(),
+                KeyCode::Char(c) => app.tag_input(c),
+                _ => {}
+            },
+            Overlay::Note => {
+                let control = key.modifiers.contains(KeyModifiers::CONTROL);
+                match key.code {
+                    KeyCode::Esc => app.cancel_note(),
+                    KeyCode::Char('s') if control => {
+                        let (activity_id, note) = app.submit_note()?;
+                        return Some(Effect::SaveNote(activity_id, note));
+                    }
+                    KeyCode::Enter => app.note_input('\n'),
+                    KeyCode::Backspace => app.note_backspace(),
+                    KeyCode::Char(_) if control => {}
+                    KeyCode::Char(c) => app.note_input(c),
+                    _ => {}
+                }
+            }
+            Overlay::Confirm => match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    return match app.confirm()? {
+                        ConfirmAction::Mute(activity_id) => Some(Effect::Update(
+                            activity_id,
+                            UpdatableActivity {
+                                hide_from_home: Some(true),
+                                ..Default::default()
+                            },
+                        )),
+                        ConfirmAction::Delete(activity_id) => Some(Effect::Delete(activity_id)),
+                        ConfirmAction::Reauthorize => Some(Effect::Reauthorize),
+                    };
+                }
+                KeyCode::Char('n') | KeyCode::Esc => app.cancel_confirm(),
+                _ => {}
+            },
+            Overlay::Edit => match key.code {
+                KeyCode::Esc => app.cancel_edit(),
+                KeyCode::Enter => {
+                    let (activity_id, update) = app.submit_edit()?;
+                    return Some(Effect::Update(activity_id, update));
+                }
+                KeyCode::Tab | KeyCode::Down => app.edit_next_field(),
+                KeyCode::BackTab | KeyCode::Up => app.edit_prev_field(),
+                KeyCode::Backspace => app.edit_backspace(),
+                KeyCode::Char(c) => app.edit_input(c),
+                _ => {}
+            },
+            Overlay::Sort => match key.code {
+                KeyCode::Char('h') | KeyCode::Left => app.prev_sort_key(),
+                KeyCode::Char('l') | KeyCode::Right => app.next_sort_key(),
+                KeyCode::Char('j' | 'k') | KeyCode::Down | KeyCode::Up => {
+                    app.toggle_sort_direction()
+                }
+                KeyCode::Char('s') | KeyCode::Enter | KeyCode::Esc => app.toggle_sort_mode(),
+                _ => {}
+            },
+        }
+        None
+    }
+}
+
+impl App {
+    // The overlay that takes the keys; the topmost if several are open
+    pub(super) fn overlay(&self) -> Option<Overlay> {
+        let open = [
+            (self.is_help_open(), Overlay::Help),
+            (self.is_error_log_open(), Overlay::ErrorLog),
+            (self.is_picking_range(), Overlay::RangePicker),
+            (self.is_jumping_to_date(), Overlay::DateJump),
+            (self.is_tagging(), Overlay::Tags),
+            (self.is_editing_note(), Overlay::Note),
+            (self.is_confirming(), Overlay::Confirm),
+            (self.is_editing(), Overlay::Edit),
+            (self.is_sort_mode(), Overlay::Sort),
+        ];
+        open.into_iter()
+            .find_map(|(is_open, overlay)| is_open.then_some(overlay))
+    }
+}
@@ -0,0 +1,159 @@
+use super::{App, Effect, Screen};
+use crate::i18n;
+use crate::plan::{Compliance, PlannedWorkout};
+use crate::ui::keymap::Action;
+use chrono::Datelike;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+
+pub(super) struct Plan;
+
+impl Screen for Plan {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_plan(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Left => app.prev_plan_month(),
+            Action::Right => app.next_plan_month(),
+            Action::Down => app.select_next_activity(),
+            Action::Up => app.select_prev_activity(),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl App {
+    fn render_plan(&self, f: &mut Frame, area: Rect) {
+        let today = chrono::Local::now().date_naive();
+        let this_month = today.with_day(1).unwrap_or(today);
+        let offset = chrono::Months::new(self.plan_month_offset.unsigned_abs());
+        let first = if self.plan_month_offset >= 0 {
+            this_month.checked_add_months(offset)
+        } else {
+            this_month.checked_sub_months(offset)
+        }
+        .unwrap_or(this_month);
+        let next = first
+            .checked_add_months(chrono::Months::new(1))
+            .unwrap_or(first);
+        let title = self.trf("Training plan - {} (h/l month)", &[&first.format("%B %Y")]);
+
+        if self.plan.is_empty() {
+            let paragraph = Paragraph::new(self.tr(
+                "No planned workouts. Add [[workout]] entries with date, sport and a distance \
+                 or duration in minutes to plan.toml next to config.toml.",
+            ))
+            .style(Style::default().fg(self.theme.muted))
+            .wrap(Wrap { trim: true })
+            .block(Block::new().borders(Borders::ALL).title(title));
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let workouts: Vec<(&PlannedWorkout, Compliance)> = self
+            .plan
+            .iter()
+            .filter(|w| w.date >= first && w.date < next)
+            .map(|w| (w, w.compliance(self.history.values(), today, self.units)))
+            .collect();
+
+        let (mut done, mut partial, mut missed, mut upcoming) = (0, 0, 0, 0);
+        let mut covered = 0.0;
+        for (_, compliance) in &workouts {
+            match compliance {
+                Compliance::Done => {
+                    done += 1;
+                    covered += 1.0;
+                }
+                Compliance::Partial(ratio) => {
+                    partial += 1;
+                    covered += ratio;
+                }
+                Compliance::Missed => missed += 1,
+                Compliance::Planned => upcoming += 1,
+            }
+        }
+        let due = done + partial + missed;
+        let summary = if due == 0 {
+            self.trf("{} planned, none due yet", &[&workouts.len()])
+        } else {
+            self.trf(
+                "{} of {} due done · {} partial · {} missed · {} upcoming | compliance {}%",
+                &[
+                    &done,
+                    &due,
+                    &partial,
+                    &missed,
+                    &upcoming,
+                    &format!("{:.0}", covered / due as f64 * 100.0),
+                ],
+            )
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+        let paragraph = Paragraph::new(summary)
+            .style(Style::default().fg(self.theme.warning))
+            .block(Block::new().borders(Borders::ALL).title(title));
+        f.render_widget(paragraph, chunks[0]);
+
+        // Whole weeks from the first weekday on or before the 1st
+        let start = self.locale.week_start(first);
+        let weeks = ((next - start).num_days() + 6) / 7;
+        let height = (chunks[1].height.saturating_sub(3) / weeks.max(1) as u16).clamp(2, 5);
+        let rows: Vec<Row> = (0..weeks)
+            .map(|week| {
+                let cells = (0..7).map(|weekday| {
+                    let day = start + chrono::Duration::days(week * 7 + weekday);
+                    let day_style = if day == today {
+                        Style::default().fg(self.theme.accent)
+                    } else if day < first || day >= next {
+                        Style::default().fg(self.theme.muted)
+                    } else {
+                        Style::default().fg(self.theme.text)
+                    };
+                    let mut lines = vec![Line::styled(day.day().to_string(), day_style)];
+                    for (workout, compliance) in workouts.iter().filter(|(w, _)| w.date == day) {
+                        let target = workout.target_label(self.units);
+                        let (text, color) = match compliance {
+                            Compliance::Done => (format!("✓ {}", target), self.theme.positive),
+                            Compliance::Partial(ratio) => (
+                                format!("◐ {} {:.0}%", target, ratio * 100.0),
+                                self.theme.warning,
+                            ),
+                            Compliance::Missed => (format!("✗ {}", target), self.theme.error),
+                            Compliance::Planned => (format!("· {}", target), self.theme.text),
+                        };
+                        lines.push(Line::styled(text, Style::default().fg(color)));
+                    }
+                    Cell::from(lines)
+                });
+                Row::new(cells).height(height)
+            })
+            .collect();
+
+        let table = Table::new(rows, [Constraint::Ratio(1, 7); 7])
+            .header(
+                Row::new(
+                    self.locale
+                        .weekdays()
+                        .map(|day| self.tr(i18n::weekday_name(day))),
+                )
+                .style(Style::default().fg(self.theme.accent)),
+            )
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.tr("✓ done · ◐ partly · ✗ missed · planned")),
+            );
+        f.render_widget(table, chunks[1]);
+    }
+}
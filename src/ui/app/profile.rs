@@ -0,0 +1,125 @@
+use super::{App, Effect, Screen};
+use crate::config::Units;
+use crate::ui::keymap::Action;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table};
+use ratatui::Frame;
+
+const POUNDS_PER_KG: f64 = 2.204_62;
+
+pub(super) struct Profile;
+
+impl Screen for Profile {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_profile(f, area);
+    }
+
+    fn handle_key(&self, _app: &mut App, _action: Action) -> Option<Effect> {
+        None
+    }
+}
+
+impl App {
+    fn render_profile(&self, f: &mut Frame, area: Rect) {
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(self.tr("Athlete Profile"));
+        let Some(profile) = &self.profile else {
+            let paragraph = Paragraph::new(self.tr("Loading profile..."))
+                .style(Style::default().fg(self.theme.text))
+                .block(block);
+            f.render_widget(paragraph, area);
+            return;
+        };
+        let athlete = &profile.athlete;
+
+        let mut name = format!("{} {}", athlete.firstname, athlete.lastname);
+        if let Some(username) = &athlete.username {
+            name.push_str(&format!(" (@{})", username));
+        }
+        // Strava often repeats the city as the state, e.g. Berlin, Berlin
+        let mut location: Vec<&str> = Vec::new();
+        for part in [&athlete.city, &profile.state, &athlete.country]
+            .into_iter()
+            .flatten()
+        {
+            if !part.is_empty() && location.last() != Some(&part.as_str()) {
+                location.push(part);
+            }
+        }
+        let dash = || "-".to_string();
+        let subscription = if profile.summit.or(profile.premium).unwrap_or(false) {
+            self.tr("Summit")
+        } else {
+            self.tr("Free")
+        };
+        let weight = profile.weight.filter(|w| *w > 0.0);
+        let weight_text = weight.map_or_else(dash, |kg| match self.units {
+            Units::Metric => format!("{:.1} kg", kg),
+            Units::Imperial => format!("{:.1} lb", kg * POUNDS_PER_KG),
+        });
+        let ftp_text = match (profile.ftp.filter(|ftp| *ftp > 0), weight) {
+            (Some(ftp), Some(kg)) => format!("{} W ({:.2} W/kg)", ftp, ftp as f64 / kg),
+            (Some(ftp), None) => format!("{} W", ftp),
+            (None, _) => dash(),
+        };
+        let count = |n: Option<u32>| n.map_or_else(dash, |n| n.to_string());
+        let preference = match profile.measurement_preference.as_deref() {
+            Some("meters") => self.tr("Metric").to_string(),
+            Some("feet") => self.tr("Imperial").to_string(),
+            _ => dash(),
+        };
+        let clubs = if profile.clubs.is_empty() {
+            dash()
+        } else {
+            profile
+                .clubs
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let fields = [
+            ("Name", name),
+            (
+                "Location",
+                if location.is_empty() {
+                    dash()
+                } else {
+                    location.join(", ")
+                },
+            ),
+            (
+                "Member since",
+                profile
+                    .created_at
+                    .map_or_else(dash, |at| self.locale.date(at.date_naive())),
+            ),
+            ("Subscription", subscription.to_string()),
+            ("Weight", weight_text),
+            ("FTP", ftp_text),
+            ("Followers", count(profile.follower_count)),
+            ("Following", count(profile.friend_count)),
+            ("Units on Strava", preference),
+            (
+                "Gear",
+                self.trf(
+                    "{} bikes, {} shoes",
+                    &[&athlete.bikes.len(), &athlete.shoes.len()],
+                ),
+            ),
+            ("Clubs", clubs),
+        ];
+        let rows: Vec<Row> = fields
+            .into_iter()
+            .map(|(label, value)| {
+                Row::new(vec![self.tr(label).to_string(), value])
+                    .style(Style::default().fg(self.theme.text))
+            })
+            .collect();
+        let table = Table::new(rows, [Constraint::Length(16), Constraint::Min(0)]).block(block);
+        f.render_widget(table, area);
+    }
+}
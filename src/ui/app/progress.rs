@@ -0,0 +1,176 @@
+use super::{App, Screen};
+use chrono::{Datelike, NaiveDate};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Borders, Cell, Row, Table};
+use ratatui::Frame;
+
+// One month of the progress comparison. `to_date` sums only the days up to
+// today's day of month, so partial and full months compare fairly.
+struct ProgressMonth {
+    label: String,
+    count: u32,
+    distance: f64,
+    moving_time: u32,
+    elevation: f64,
+    distance_to_date: f64,
+}
+
+pub(super) struct Progress;
+
+impl Screen for Progress {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_progress(f, area);
+    }
+}
+
+impl App {
+    fn render_progress(&self, f: &mut Frame, area: Rect) {
+        let today = chrono::Local::now().date_naive();
+        let months = self.compute_progress(today);
+        let title = self.trf(
+            "Progress - {} cached activities, to date = through day {}",
+            &[&self.history.len(), &today.day()],
+        );
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(11), Constraint::Min(0)])
+            .split(area);
+
+        let current = &months[0];
+        let change = |now: f64, then: f64| {
+            if then > 0.0 {
+                format!("{:+.0}%", (now - then) / then * 100.0)
+            } else {
+                "---".to_string()
+            }
+        };
+        let rows: Vec<Row> = months
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let style = if i == 0 {
+                    Style::default().fg(self.theme.warning)
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                let vs = if i == 0 {
+                    String::new()
+                } else {
+                    change(current.distance_to_date, m.distance_to_date)
+                };
+                Row::new(vec![
+                    Cell::from(m.label.clone()),
+                    Cell::from(m.count.to_string()),
+                    Cell::from(format!("{:.1}", self.units.distance(m.distance)))
+                        .style(style.fg(self.theme.accent)),
+                    Cell::from(format!(
+                        "{}:{:02}",
+                        m.moving_time / 3600,
+                        (m.moving_time % 3600) / 60
+                    ))
+                    .style(style.fg(self.theme.positive)),
+                    Cell::from(format!("{:.0}", self.units.elevation(m.elevation))),
+                    Cell::from(format!("{:.1}", self.units.distance(m.distance_to_date))),
+                    Cell::from(vs).style(style.fg(self.theme.special)),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(22),
+                Constraint::Length(6),
+                Constraint::Length(10),
+                Constraint::Length(8),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(12),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                self.tr("Month").to_string(),
+                self.tr("Count").to_string(),
+                format!("{} {}", self.tr("Distance"), self.units.distance_unit()),
+                self.tr("Time").to_string(),
+                format!("{} {}", self.tr("Elev"), self.units.elevation_unit()),
+                self.tr("To date").to_string(),
+                self.tr("This month").to_string(),
+            ])
+            .style(self.theme.header()),
+        )
+        .block(Block::new().borders(Borders::ALL).title(title));
+        f.render_widget(table, chunks[0]);
+
+        // Oldest month on the left, last year's month kept out of the timeline
+        let bars: Vec<Bar> = months[..6]
+            .iter()
+            .rev()
+            .map(|m| {
+                let distance = self.units.distance(m.distance);
+                Bar::default()
+                    .label(m.label.chars().take(3).collect::<String>().into())
+                    .value(distance.round() as u64)
+                    .text_value(format!("{:.0}", distance))
+            })
+            .collect();
+        let chart = BarChart::default()
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.trf("Monthly distance ({})", &[&self.units.distance_unit()])),
+            )
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(5)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(self.theme.accent))
+            .value_style(self.theme.bar_value());
+        f.render_widget(chart, chunks[1]);
+    }
+
+    // The current month, the five before it and the same month last year,
+    // computed from the cached history
+    fn compute_progress(&self, today: NaiveDate) -> Vec<ProgressMonth> {
+        let month_offset = |back: i32| {
+            let months = today.year() * 12 + today.month0() as i32 - back;
+            NaiveDate::from_ymd_opt(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1)
+                .unwrap_or(today)
+        };
+
+        [0, 1, 2, 3, 4, 5, 12]
+            .into_iter()
+            .map(|back| {
+                let start = month_offset(back);
+                let mut month = ProgressMonth {
+                    label: start.format("%b %Y").to_string(),
+                    count: 0,
+                    distance: 0.0,
+                    moving_time: 0,
+                    elevation: 0.0,
+                    distance_to_date: 0.0,
+                };
+                if back == 12 {
+                    month.label.push_str(self.tr(" (last year)"));
+                }
+                for activity in self.history.values() {
+                    let date = activity.start_date_local.date_naive();
+                    if date.year() != start.year() || date.month() != start.month() {
+                        continue;
+                    }
+                    month.count += 1;
+                    month.distance += activity.distance;
+                    month.moving_time += activity.moving_time;
+                    month.elevation += activity.total_elevation_gain;
+                    if date.day() <= today.day() {
+                        month.distance_to_date += activity.distance;
+                    }
+                }
+                month
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,231 @@
+use super::{App, Effect, Screen};
+use crate::analytics;
+use crate::api::types::Activity;
+use crate::ui::keymap::Action;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::symbols::Marker;
+use ratatui::widgets::{
+    Axis, Block, Borders, Cell, Chart, Dataset, GraphType, LegendPosition, Paragraph, Row, Table,
+    Wrap,
+};
+use ratatui::Frame;
+
+pub(super) struct Routes;
+
+impl Screen for Routes {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_routes(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Down => app.select_next_route(),
+            Action::Up => app.select_prev_route(),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl App {
+    fn render_routes(&mut self, f: &mut Frame, area: Rect) {
+        let routes = analytics::repeated_routes(self.history.values());
+        if routes.is_empty() {
+            let paragraph = Paragraph::new(self.tr(
+                "No route done twice yet - activities need a map and a similar distance, start and end",
+            ))
+            .style(Style::default().fg(self.theme.muted))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.tr("Repeated Routes")),
+            );
+            f.render_widget(paragraph, area);
+            return;
+        }
+        self.selected_route_index = self.selected_route_index.min(routes.len() - 1);
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(area);
+
+        let unit = self.units.distance_unit();
+        let rows: Vec<Row> = routes
+            .iter()
+            .enumerate()
+            .map(|(i, route)| {
+                let latest = route[route.len() - 1];
+                let best = route.iter().map(|a| a.moving_time).min().unwrap_or(0);
+                let style = if i == self.selected_route_index {
+                    self.theme.selection()
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                Row::new(vec![
+                    Cell::from(latest.name.chars().take(24).collect::<String>())
+                        .style(Style::default().fg(self.theme.sport(&latest.sport_type))),
+                    Cell::from(format!("{:.1}", self.units.distance(latest.distance))),
+                    Cell::from(route.len().to_string()),
+                    Cell::from(Self::format_hms(best as f64))
+                        .style(Style::default().fg(self.theme.positive)),
+                    Cell::from(self.locale.date(latest.start_date_local.date_naive())),
+                ])
+                .style(style)
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(12),
+                Constraint::Length(6),
+                Constraint::Length(5),
+                Constraint::Length(8),
+                Constraint::Length(10),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                self.tr("Route").to_string(),
+                unit.to_string(),
+                self.tr("Done").to_string(),
+                self.tr("Best").to_string(),
+                self.tr("Latest").to_string(),
+            ])
+            .style(self.theme.header()),
+        )
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.trf("Repeated Routes ({}) - j/k select", &[&routes.len()])),
+        );
+        f.render_widget(table, chunks[0]);
+
+        self.render_route_progression(f, chunks[1], &routes[self.selected_route_index]);
+    }
+
+    // Moving time on every repeat of one route, oldest on the left
+    fn render_route_progression(&self, f: &mut Frame, area: Rect, route: &[&Activity]) {
+        let panels = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+
+        let first = route[0].start_date;
+        let minutes: Vec<(f64, f64)> = route
+            .iter()
+            .map(|a| {
+                (
+                    (a.start_date - first).num_hours() as f64 / 24.0,
+                    a.moving_time as f64 / 60.0,
+                )
+            })
+            .collect();
+        let best = route.iter().map(|a| a.moving_time).min().unwrap_or(0);
+        let best_points: Vec<(f64, f64)> = minutes
+            .iter()
+            .zip(route)
+            .filter(|(_, a)| a.moving_time == best)
+            .map(|(&p, _)| p)
+            .collect();
+        let (low, high) = minutes
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &(_, m)| {
+                (lo.min(m), hi.max(m))
+            });
+        let (low, high) = ((low - 1.0).floor().max(0.0), (high + 1.0).ceil());
+        let span = minutes[minutes.len() - 1].0.max(1.0);
+
+        let datasets = vec![
+            Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(self.theme.accent))
+                .data(&minutes),
+            Dataset::default()
+                .name(self.trf("best {}", &[&Self::format_hms(best as f64)]))
+                .marker(Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(Style::default().fg(self.theme.positive))
+                .data(&best_points),
+        ];
+        let chart = Chart::new(datasets)
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.tr("Moving time (min) per repeat")),
+            )
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(self.theme.muted))
+                    .bounds([0.0, span])
+                    .labels([
+                        self.locale.date(route[0].start_date_local.date_naive()),
+                        self.locale
+                            .date(route[route.len() - 1].start_date_local.date_naive()),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(self.theme.muted))
+                    .bounds([low, high])
+                    .labels([format!("{:.0}", low), format!("{:.0}", high)]),
+            )
+            .legend_position(Some(LegendPosition::TopRight))
+            .hidden_legend_constraints((Constraint::Ratio(1, 1), Constraint::Ratio(1, 1)));
+        f.render_widget(chart, panels[0]);
+
+        let rows: Vec<Row> = route
+            .iter()
+            .rev()
+            .map(|a| {
+                let delta = a.moving_time as i64 - best as i64;
+                let (delta, color) = if delta == 0 {
+                    (self.tr("best").to_string(), self.theme.positive)
+                } else {
+                    (
+                        format!("+{}", Self::format_pace(delta as f64)),
+                        self.theme.warning,
+                    )
+                };
+                let pace =
+                    Self::format_pace(a.moving_time as f64 / self.units.distance(a.distance));
+                Row::new(vec![
+                    Cell::from(self.locale.date(a.start_date_local.date_naive())),
+                    Cell::from(Self::format_hms(a.moving_time as f64)),
+                    Cell::from(format!("{} /{}", pace, self.units.distance_unit()))
+                        .style(Style::default().fg(self.theme.warning)),
+                    Cell::from(
+                        a.average_heartrate
+                            .map(|hr| format!("{:.0}", hr))
+                            .unwrap_or_else(|| "---".to_string()),
+                    )
+                    .style(Style::default().fg(self.theme.error)),
+                    Cell::from(delta).style(Style::default().fg(color)),
+                ])
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Length(8),
+                Constraint::Length(10),
+                Constraint::Length(4),
+                Constraint::Min(6),
+            ],
+        )
+        .header(
+            Row::new(["Date", "Time", "Pace", "HR", "vs best"].map(|label| self.tr(label)))
+                .style(self.theme.header()),
+        )
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.trf("{} repeats, newest first", &[&route.len()])),
+        );
+        f.render_widget(table, panels[1]);
+    }
+}
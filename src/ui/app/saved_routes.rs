@@ -0,0 +1,271 @@
+use super::{App, Effect, Screen};
+use crate::analytics;
+use crate::api::types::{Route, StreamSet};
+use crate::ui::keymap::Action;
+use chrono::{Duration, Utc};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+
+// Recent enough to reflect current form, long enough to include some hills
+const ROUTE_ESTIMATE_DAYS: i64 = 90;
+// Rows of the elevation profile and time estimate below the list
+const ROUTE_PREVIEW_HEIGHT: u16 = 12;
+
+pub(super) struct SavedRoutes;
+
+impl Screen for SavedRoutes {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_saved_routes(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Down => app.select_next_saved_route(),
+            Action::Up => app.select_prev_saved_route(),
+            Action::DownloadGpx => {
+                let route = app.selected_saved_route()?;
+                let notice = app.trf("Downloading {}...", &[&route.name]);
+                let id = route.id;
+                app.show_notice(notice);
+                return Some(Effect::DownloadRoute(id));
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl App {
+    pub fn set_saved_routes(&mut self, routes: Vec<Route>) {
+        self.saved_routes = Some(routes);
+        self.selected_saved_route = 0;
+    }
+
+    pub fn has_saved_routes(&self) -> bool {
+        self.saved_routes.is_some()
+    }
+
+    pub fn saved_route(&self, route_id: u64) -> Option<&Route> {
+        self.saved_routes
+            .as_ref()?
+            .iter()
+            .find(|r| r.id == route_id)
+    }
+
+    fn selected_saved_route(&self) -> Option<&Route> {
+        self.saved_routes.as_ref()?.get(self.selected_saved_route)
+    }
+
+    pub fn selected_saved_route_id(&self) -> Option<u64> {
+        self.selected_saved_route().map(|r| r.id)
+    }
+
+    pub fn set_route_streams(&mut self, route_id: u64, streams: StreamSet) {
+        self.route_streams.insert(route_id, streams);
+    }
+
+    pub fn has_route_streams_for(&self, route_id: u64) -> bool {
+        self.route_streams.contains_key(&route_id)
+    }
+
+    pub fn select_next_saved_route(&mut self) {
+        let count = self.saved_routes.as_ref().map_or(0, Vec::len);
+        self.selected_saved_route = (self.selected_saved_route + 1).min(count.saturating_sub(1));
+    }
+
+    pub fn select_prev_saved_route(&mut self) {
+        self.selected_saved_route = self.selected_saved_route.saturating_sub(1);
+    }
+
+    fn render_saved_routes(&self, f: &mut Frame, area: Rect) {
+        let routes = match &self.saved_routes {
+            Some(routes) if !routes.is_empty() => routes,
+            loaded => {
+                let message = if loaded.is_none() {
+                    "Loading routes..."
+                } else {
+                    "No saved routes - create or star one on strava.com"
+                };
+                let paragraph = Paragraph::new(self.tr(message))
+                    .style(Style::default().fg(self.theme.text))
+                    .block(
+                        Block::new()
+                            .borders(Borders::ALL)
+                            .title(self.tr("Saved Routes")),
+                    );
+                f.render_widget(paragraph, area);
+                return;
+            }
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(ROUTE_PREVIEW_HEIGHT)])
+            .split(area);
+
+        let rows: Vec<Row> = routes
+            .iter()
+            .enumerate()
+            .map(|(i, route)| {
+                let row_style = if i == self.selected_saved_route {
+                    self.theme.selection()
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                let created = route
+                    .created_at
+                    .map(|at| self.locale.date(at.date_naive()))
+                    .unwrap_or_default();
+
+                Row::new(vec![
+                    Cell::from(if route.starred { "★" } else { "" }).style(row_style),
+                    Cell::from(route.name.chars().take(30).collect::<String>())
+                        .style(row_style.fg(self.theme.sport(route.sport()))),
+                    Cell::from(route.sport()).style(row_style),
+                    Cell::from(format!("{:.1}", self.units.distance(route.distance)))
+                        .style(row_style.fg(self.theme.accent)),
+                    Cell::from(format!("{:.0}", self.units.elevation(route.elevation_gain)))
+                        .style(row_style),
+                    Cell::from(created).style(row_style),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Length(30),
+                Constraint::Length(8),
+                Constraint::Length(9),
+                Constraint::Length(7),
+                Constraint::Length(11),
+            ],
+        )
+        .header(
+            Row::new(
+                ["", "Name", "Sport", "Distance", "Elev", "Created"].map(|label| self.tr(label)),
+            )
+            .style(self.theme.header()),
+        )
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.trf("Saved Routes ({}) - x downloads the GPX", &[&routes.len()])),
+        );
+        f.render_widget(table, chunks[0]);
+
+        if let Some(route) = routes.get(self.selected_saved_route) {
+            self.render_route_preview(f, chunks[1], route);
+        }
+    }
+
+    fn render_route_preview(&self, f: &mut Frame, area: Rect, route: &Route) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(20), Constraint::Length(34)])
+            .split(area);
+
+        let profile = self.route_streams.get(&route.id).map(|streams| {
+            (
+                streams.distance.as_ref().map(|s| s.data.as_slice()),
+                streams.altitude.as_ref().map(|s| s.data.as_slice()),
+            )
+        });
+        match profile {
+            Some((Some(distance), Some(altitude)))
+                if distance.len() == altitude.len() && distance.len() > 1 =>
+            {
+                self.render_elevation_profile(f, chunks[0], distance, altitude)
+            }
+            loaded => {
+                let message = if loaded.is_none() {
+                    "Loading elevation..."
+                } else {
+                    "No elevation data for this route"
+                };
+                let paragraph = Paragraph::new(self.tr(message))
+                    .style(Style::default().fg(self.theme.muted))
+                    .block(
+                        Block::new()
+                            .borders(Borders::ALL)
+                            .title(self.tr("Elevation")),
+                    );
+                f.render_widget(paragraph, chunks[0]);
+            }
+        }
+
+        let since = Utc::now() - Duration::days(ROUTE_ESTIMATE_DAYS);
+        let estimate = analytics::estimate_route_time(
+            self.history.values(),
+            route.sport(),
+            since,
+            route.distance,
+            route.elevation_gain,
+        );
+        let lines = match estimate {
+            Some(estimate) => {
+                let flat = if analytics::is_on_foot(route.sport()) {
+                    self.trf(
+                        "{} /{} on the flat",
+                        &[
+                            &Self::format_pace(1.0 / self.units.distance(estimate.flat_speed)),
+                            &self.units.distance_unit(),
+                        ],
+                    )
+                } else {
+                    self.trf(
+                        "{} {} on the flat",
+                        &[
+                            &format!("{:.1}", self.units.speed(estimate.flat_speed)),
+                            &self.units.speed_unit(),
+                        ],
+                    )
+                };
+                let mut lines = vec![
+                    Line::styled(
+                        Self::format_hms(estimate.moving_time),
+                        Style::default().fg(self.theme.accent),
+                    ),
+                    Line::from(flat),
+                ];
+                if let Some(rate) = estimate.climb_rate {
+                    lines.push(Line::from(self.trf(
+                        "plus {} {}/h climbing",
+                        &[
+                            &format!("{:.0}", self.units.elevation(rate)),
+                            &self.units.elevation_unit(),
+                        ],
+                    )));
+                }
+                lines.push(Line::styled(
+                    self.trf(
+                        "from {} activities in the last {} days",
+                        &[&estimate.activities, &ROUTE_ESTIMATE_DAYS],
+                    ),
+                    Style::default().fg(self.theme.muted),
+                ));
+                lines
+            }
+            None => vec![Line::styled(
+                self.trf(
+                    "Not enough {} activities in the last {} days to estimate",
+                    &[&route.sport(), &ROUTE_ESTIMATE_DAYS],
+                ),
+                Style::default().fg(self.theme.muted),
+            )],
+        };
+        let paragraph = Paragraph::new(lines)
+            .style(Style::default().fg(self.theme.text))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.tr("Estimated moving time")),
+            );
+        f.render_widget(paragraph, chunks[1]);
+    }
+}
@@ -0,0 +1,252 @@
+use super::{App, Effect, Screen};
+use crate::api::types::SummarySegment;
+use crate::ui::keymap::Action;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::Frame;
+
+pub(super) struct Segments;
+
+impl Screen for Segments {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_segments(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Down => app.select_next_segment(),
+            Action::Up => app.select_prev_segment(),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl App {
+    fn render_segments(&self, f: &mut Frame, area: Rect) {
+        let segments = match &self.starred_segments {
+            None => {
+                let paragraph = Paragraph::new(self.tr("Loading starred segments..."))
+                    .style(Style::default().fg(self.theme.warning))
+                    .block(
+                        Block::new()
+                            .borders(Borders::ALL)
+                            .title(self.tr("Starred Segments")),
+                    );
+                f.render_widget(paragraph, area);
+                return;
+            }
+            Some(segments) if segments.is_empty() => {
+                let paragraph =
+                    Paragraph::new(self.tr("No starred segments - star some on Strava"))
+                        .style(Style::default().fg(self.theme.text))
+                        .block(
+                            Block::new()
+                                .borders(Borders::ALL)
+                                .title(self.tr("Starred Segments")),
+                        );
+                f.render_widget(paragraph, area);
+                return;
+            }
+            Some(segments) => segments,
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(6)])
+            .split(area);
+
+        let athlete_id = self.athlete.as_ref().map(|a| a.id);
+        let rows: Vec<Row> = segments
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| {
+                let detail = self.segment_details.get(&segment.id);
+                // The detailed endpoint has fresher stats than the starred list
+                let stats = detail
+                    .and_then(|d| d.segment.my_stats())
+                    .or(segment.my_stats());
+                let pr = stats
+                    .and_then(|s| s.pr_elapsed_time)
+                    .map(|t| Self::format_pace(t as f64))
+                    .unwrap_or_else(|| "---".to_string());
+                let efforts = stats
+                    .and_then(|s| s.effort_count)
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "---".to_string());
+                let (legend, legend_style) = match detail {
+                    None => (
+                        self.tr("loading...").to_string(),
+                        Style::default().fg(self.theme.muted),
+                    ),
+                    Some(d) => match &d.local_legend {
+                        Some(ll) if ll.athlete_id.is_some() && ll.athlete_id == athlete_id => (
+                            self.tr("You!").to_string(),
+                            Style::default().fg(self.theme.positive),
+                        ),
+                        Some(ll) => (
+                            [ll.title.as_deref(), ll.effort_description.as_deref()]
+                                .into_iter()
+                                .flatten()
+                                .collect::<Vec<_>>()
+                                .join(" - "),
+                            Style::default().fg(self.theme.text),
+                        ),
+                        None => ("---".to_string(), Style::default().fg(self.theme.text)),
+                    },
+                };
+                let style = if i == self.selected_segment_index {
+                    self.theme.selection()
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                Row::new(vec![
+                    Cell::from(segment.name.chars().take(30).collect::<String>()),
+                    Cell::from(segment.activity_type.clone().unwrap_or_default()),
+                    Cell::from(format!("{:.2}", self.units.distance(segment.distance))),
+                    Cell::from(
+                        segment
+                            .average_grade
+                            .map(|g| format!("{:.1}%", g))
+                            .unwrap_or_else(|| "---".to_string()),
+                    ),
+                    Cell::from(pr).style(Style::default().fg(self.theme.accent)),
+                    Cell::from(efforts),
+                    Cell::from(legend).style(legend_style),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(30),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(7),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Min(12),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                self.tr("Segment").to_string(),
+                self.tr("Type").to_string(),
+                self.units.distance_unit().to_string(),
+                self.tr("Grade").to_string(),
+                self.tr("My PR").to_string(),
+                self.tr("Efforts").to_string(),
+                self.tr("Local Legend").to_string(),
+            ])
+            .style(self.theme.header()),
+        )
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(self.trf("Starred Segments ({})", &[&segments.len()])),
+        );
+        f.render_widget(table, chunks[0]);
+
+        if let Some(segment) = segments.get(self.selected_segment_index) {
+            self.render_segment_summary(f, chunks[1], segment);
+        }
+    }
+
+    // Local legend, own efforts and popularity of the selected segment
+    fn render_segment_summary(&self, f: &mut Frame, area: Rect, segment: &SummarySegment) {
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title(segment.name.clone());
+        let Some(detail) = self.segment_details.get(&segment.id) else {
+            let paragraph = Paragraph::new(self.tr("Loading segment details..."))
+                .style(Style::default().fg(self.theme.muted))
+                .block(block);
+            f.render_widget(paragraph, area);
+            return;
+        };
+
+        let athlete_id = self.athlete.as_ref().map(|a| a.id);
+        let legend = match &detail.local_legend {
+            Some(ll) if ll.athlete_id.is_some() && ll.athlete_id == athlete_id => Line::styled(
+                match ll.effort_count {
+                    Some(count) => self.trf(
+                        "You are the Local Legend with {} efforts in the last 90 days",
+                        &[&count],
+                    ),
+                    None => self.tr("You are the Local Legend").to_string(),
+                },
+                Style::default().fg(self.theme.positive),
+            ),
+            Some(ll) => {
+                let title = ll.title.as_deref().unwrap_or("?");
+                Line::from(match ll.effort_count {
+                    Some(count) => self.trf(
+                        "Local Legend: {} with {} efforts in the last 90 days",
+                        &[&title, &count],
+                    ),
+                    None => self.trf("Local Legend: {}", &[&title]),
+                })
+            }
+            None => Line::from(self.tr("No Local Legend yet")),
+        };
+
+        let stats = detail.segment.my_stats().or(segment.my_stats());
+        let efforts = stats.and_then(|s| s.effort_count).unwrap_or(0);
+        let mine = match stats.and_then(|s| s.pr_elapsed_time) {
+            Some(pr) => {
+                let date = stats
+                    .and_then(|s| s.pr_date.as_deref())
+                    .map(|d| d.chars().take(10).collect::<String>())
+                    .unwrap_or_else(|| "?".to_string());
+                self.trf(
+                    "My efforts: {}, PR {} on {}",
+                    &[&efforts, &Self::format_pace(pr as f64), &date],
+                )
+            }
+            None => self.trf("My efforts: {}", &[&efforts]),
+        };
+
+        let count = |n: Option<u32>| n.map_or_else(|| "?".to_string(), |n| n.to_string());
+        let popularity = self.trf(
+            "{} efforts by {} athletes, starred {} times",
+            &[
+                &count(detail.effort_count),
+                &count(detail.athlete_count),
+                &count(detail.star_count),
+            ],
+        );
+        let mut climb = match detail.total_elevation_gain {
+            Some(gain) => self.trf(
+                "Elevation gain {} {}",
+                &[
+                    &format!("{:.0}", self.units.elevation(gain)),
+                    &self.units.elevation_unit(),
+                ],
+            ),
+            None => String::new(),
+        };
+        if detail.hazardous {
+            if !climb.is_empty() {
+                climb.push_str(" · ");
+            }
+            climb.push_str(self.tr("flagged as hazardous"));
+        }
+
+        let mut lines = vec![
+            legend,
+            Line::styled(mine, Style::default().fg(self.theme.accent)),
+            Line::from(popularity),
+        ];
+        if !climb.is_empty() {
+            lines.push(Line::styled(climb, Style::default().fg(self.theme.muted)));
+        }
+        let paragraph = Paragraph::new(lines)
+            .style(Style::default().fg(self.theme.text))
+            .block(block);
+        f.render_widget(paragraph, area);
+    }
+}
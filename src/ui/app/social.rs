@@ -0,0 +1,212 @@
+use super::{App, Effect, Screen};
+use crate::api::types::{FeedActivity, Page};
+use crate::ui::keymap::Action;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::Frame;
+
+// Recent activities of the athletes the user follows, paged like the club feed
+#[derive(Default)]
+pub(super) struct FollowingFeed {
+    activities: Vec<FeedActivity>,
+    page: u32,
+    loading: bool,
+    exhausted: bool,
+    // Strava doesn't serve the feed to this app
+    unavailable: bool,
+    selected: usize,
+}
+
+pub(super) struct Social;
+
+impl Screen for Social {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_following(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::Down => app.select_next_following(),
+            Action::Up => app.select_prev_following(),
+            Action::NextWithoutKudos => app.select_next_without_kudos(),
+            // The API can't give kudos, so they are given on the activity's page
+            Action::Kudos => {
+                let id = app.following.activities.get(app.following.selected)?.id;
+                return Some(Effect::Browse(
+                    format!("https://www.strava.com/activities/{}", id),
+                    "Opened in the browser - give kudos there",
+                ));
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl App {
+    pub fn should_load_following(&self) -> bool {
+        let feed = &self.following;
+        !feed.loading
+            && !feed.exhausted
+            && !feed.unavailable
+            && feed.selected >= feed.activities.len().saturating_sub(5)
+    }
+
+    pub fn following_page(&self) -> u32 {
+        self.following.page
+    }
+
+    pub fn set_following_loading(&mut self, loading: bool) {
+        self.following.loading = loading;
+    }
+
+    pub fn add_following_activities(
+        &mut self,
+        page: u32,
+        activities: Page<FeedActivity>,
+        per_page: u32,
+    ) {
+        let feed = &mut self.following;
+        if page != feed.page + 1 {
+            return;
+        }
+        feed.exhausted = activities.is_last(per_page);
+        feed.activities.extend(activities.items);
+        feed.page = page;
+        feed.loading = false;
+    }
+
+    pub fn set_following_unavailable(&mut self) {
+        self.following.unavailable = true;
+        self.following.loading = false;
+    }
+
+    // Stops paging, so a failing feed isn't requested again on every tick
+    pub fn set_following_load_error(&mut self) {
+        self.following.loading = false;
+        self.following.exhausted = true;
+    }
+
+    pub fn select_next_following(&mut self) {
+        let count = self.following.activities.len();
+        self.following.selected = (self.following.selected + 1).min(count.saturating_sub(1));
+    }
+
+    pub fn select_prev_following(&mut self) {
+        self.following.selected = self.following.selected.saturating_sub(1);
+    }
+
+    pub fn select_next_without_kudos(&mut self) {
+        let feed = &mut self.following;
+        match feed
+            .activities
+            .iter()
+            .skip(feed.selected + 1)
+            .position(|a| !a.has_kudoed)
+        {
+            Some(offset) => feed.selected += offset + 1,
+            None => self.show_notice(self.tr("No more activities without your kudos").to_string()),
+        }
+    }
+
+    fn render_following(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let count = |n: Option<u32>| n.map_or_else(|| "-".to_string(), |n| n.to_string());
+        let profile = self.profile.as_ref();
+        let counts = self.trf(
+            "Followers: {}   Following: {}",
+            &[
+                &count(profile.and_then(|p| p.follower_count)),
+                &count(profile.and_then(|p| p.friend_count)),
+            ],
+        );
+        let counts = Paragraph::new(counts)
+            .style(Style::default().fg(self.theme.text))
+            .block(Block::new().borders(Borders::ALL).title(self.tr("Social")));
+        f.render_widget(counts, chunks[0]);
+
+        let feed = &self.following;
+        let title = self.tr("Athletes you follow (K kudos, n next without kudos)");
+        if feed.activities.is_empty() {
+            let message = if feed.unavailable {
+                "Strava doesn't share the feed of athletes you follow with this app"
+            } else if feed.exhausted {
+                "No recent activities from athletes you follow"
+            } else {
+                "Loading feed..."
+            };
+            let paragraph = Paragraph::new(self.tr(message))
+                .style(Style::default().fg(self.theme.text))
+                .block(Block::new().borders(Borders::ALL).title(title));
+            f.render_widget(paragraph, chunks[1]);
+            return;
+        }
+
+        let rows: Vec<Row> = feed
+            .activities
+            .iter()
+            .enumerate()
+            .map(|(i, activity)| {
+                let row_style = if i == feed.selected {
+                    self.theme.selection()
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                let athlete = format!(
+                    "{} {}",
+                    activity.athlete.firstname, activity.athlete.lastname
+                );
+                let duration = format!(
+                    "{}:{:02}:{:02}",
+                    activity.moving_time / 3600,
+                    (activity.moving_time % 3600) / 60,
+                    activity.moving_time % 60
+                );
+                // Marks the activities the user already gave kudos
+                let kudos = if activity.has_kudoed {
+                    format!("♥ {}", activity.kudos_count)
+                } else {
+                    activity.kudos_count.to_string()
+                };
+
+                Row::new(vec![
+                    Cell::from(self.locale.short_date(activity.start_date.date_naive()))
+                        .style(row_style),
+                    Cell::from(athlete.chars().take(20).collect::<String>()).style(row_style),
+                    Cell::from(activity.name.chars().take(25).collect::<String>())
+                        .style(row_style.fg(self.theme.sport(&activity.sport_type))),
+                    Cell::from(format!("{:.1}", self.units.distance(activity.distance)))
+                        .style(row_style.fg(self.theme.accent)),
+                    Cell::from(duration).style(row_style.fg(self.theme.positive)),
+                    Cell::from(kudos).style(row_style.fg(self.theme.warning)),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(6),
+                Constraint::Length(20),
+                Constraint::Length(25),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(6),
+            ],
+        )
+        .header(
+            Row::new(
+                ["Date", "Athlete", "Name", "Distance", "Duration", "Kudos"]
+                    .map(|label| self.tr(label)),
+            )
+            .style(self.theme.header()),
+        )
+        .block(Block::new().borders(Borders::ALL).title(title));
+        f.render_widget(table, chunks[1]);
+    }
+}
@@ -0,0 +1,143 @@
+use super::{App, Screen};
+use crate::analytics;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::Frame;
+
+pub(super) struct Sports;
+
+impl Screen for Sports {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_sports(f, area);
+    }
+}
+
+impl App {
+    fn render_sports(&self, f: &mut Frame, area: Rect) {
+        let sports = analytics::sport_breakdown(self.counted_history());
+        let total: u32 = sports.iter().map(|s| s.count).sum();
+        let title = self.trf(
+            "Sports ({} types in {} cached activities, by moving time)",
+            &[&sports.len(), &total],
+        );
+        if sports.is_empty() {
+            let paragraph = Paragraph::new(self.tr("No cached activities yet"))
+                .style(Style::default().fg(self.theme.muted))
+                .block(Block::new().borders(Borders::ALL).title(title));
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let unit = self.units.distance_unit();
+        let hours = |seconds: u64| format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60);
+        // Pace for sports on foot and in the water, speed for the rest
+        let average = |sport: &str, speed: Option<f64>| {
+            let Some(speed) = speed else {
+                return "—".to_string();
+            };
+            if sport.contains("Swim") {
+                format!("{} /100m", Self::format_pace(100.0 / speed))
+            } else if analytics::is_on_foot(sport) {
+                format!(
+                    "{} /{}",
+                    Self::format_pace(1.0 / self.units.distance(speed)),
+                    unit
+                )
+            } else {
+                format!("{:.1} {}", self.units.speed(speed), self.units.speed_unit())
+            }
+        };
+
+        let mut rows: Vec<Row> = sports
+            .iter()
+            .map(|s| {
+                Row::new(vec![
+                    Cell::from(s.sport.clone())
+                        .style(Style::default().fg(self.theme.sport(&s.sport))),
+                    Cell::from(s.count.to_string()),
+                    Cell::from(format!("{:.1}", self.units.distance(s.distance))),
+                    Cell::from(hours(s.moving_time)),
+                    Cell::from(format!("{:.0}", self.units.elevation(s.elevation))),
+                    Cell::from(average(&s.sport, s.average_speed())),
+                    Cell::from(format!("{:.1}", self.units.distance(s.longest))),
+                    Cell::from(
+                        self.locale
+                            .date(s.last.with_timezone(&chrono::Local).date_naive()),
+                    ),
+                ])
+                .style(Style::default().fg(self.theme.text))
+            })
+            .collect();
+        rows.push(
+            Row::new(vec![
+                Cell::from(self.tr("Total")),
+                Cell::from(total.to_string()),
+                Cell::from(format!(
+                    "{:.1}",
+                    self.units
+                        .distance(sports.iter().map(|s| s.distance).sum::<f64>())
+                )),
+                Cell::from(hours(sports.iter().map(|s| s.moving_time).sum())),
+                Cell::from(format!(
+                    "{:.0}",
+                    self.units
+                        .elevation(sports.iter().map(|s| s.elevation).sum::<f64>())
+                )),
+            ])
+            .style(Style::default().fg(self.theme.warning))
+            .top_margin(1),
+        );
+        // Trainer and virtual activities across all sports
+        let (indoor_count, indoor_distance, indoor_time) = self
+            .counted_history()
+            .filter(|a| analytics::is_indoor(a))
+            .fold((0, 0.0, 0), |(count, distance, time), a| {
+                (
+                    count + 1,
+                    distance + a.distance,
+                    time + a.moving_time as u64,
+                )
+            });
+        if indoor_count > 0 {
+            rows.push(
+                Row::new(vec![
+                    Cell::from(self.tr("of which indoor")),
+                    Cell::from(indoor_count.to_string()),
+                    Cell::from(format!("{:.1}", self.units.distance(indoor_distance))),
+                    Cell::from(hours(indoor_time)),
+                ])
+                .style(Style::default().fg(self.theme.muted)),
+            );
+        }
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(16),
+                Constraint::Length(6),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(14),
+                Constraint::Length(10),
+                Constraint::Length(11),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                self.tr("Sport").to_string(),
+                self.tr("Count").to_string(),
+                format!("{} {}", self.tr("Dist"), unit),
+                self.tr("Time").to_string(),
+                format!("{} {}", self.tr("Elev"), self.units.elevation_unit()),
+                self.tr("Avg pace/speed").to_string(),
+                format!("{} {}", self.tr("Longest"), unit),
+                self.tr("Last").to_string(),
+            ])
+            .style(self.theme.header()),
+        )
+        .block(Block::new().borders(Borders::ALL).title(title));
+        f.render_widget(table, area);
+    }
+}
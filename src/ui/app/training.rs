@@ -0,0 +1,182 @@
+use super::{App, Effect, Screen, TrainingPeriod};
+use crate::ui::keymap::Action;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Borders, Cell, Paragraph, Row, Table};
+use ratatui::Frame;
+use std::collections::BTreeMap;
+
+struct TrainingBucket {
+    key: String,
+    label: String,
+    count: u32,
+    distance: f64,
+    moving_time: u32,
+    elevation: f64,
+    per_sport: BTreeMap<String, u32>,
+}
+
+pub(super) struct Training;
+
+impl Screen for Training {
+    fn render(&self, app: &mut App, f: &mut Frame, area: Rect) {
+        app.render_training(f, area);
+    }
+
+    fn handle_key(&self, app: &mut App, action: Action) -> Option<Effect> {
+        match action {
+            Action::TogglePeriod => app.toggle_training_period(),
+            Action::Down => app.select_next_activity(),
+            Action::Up => app.select_prev_activity(),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl App {
+    fn render_training(&self, f: &mut Frame, area: Rect) {
+        let (period, toggle_hint) = match self.training_period {
+            TrainingPeriod::Week => ("Weekly Training", "w: monthly"),
+            TrainingPeriod::Month => ("Monthly Training", "w: weekly"),
+        };
+        let title = format!("{} - {}", self.tr(period), self.tr(toggle_hint));
+
+        let buckets = self.compute_training_buckets();
+        if buckets.is_empty() {
+            let paragraph = Paragraph::new(self.tr("No activities loaded"))
+                .style(Style::default().fg(self.theme.text))
+                .block(Block::new().borders(Borders::ALL).title(title));
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(40), Constraint::Min(0)])
+            .split(area);
+
+        // Oldest bucket on the left so the chart reads like a timeline
+        let bar_width = 5u16;
+        let max_bars = (chunks[0].width.saturating_sub(2) / (bar_width + 1)).max(1) as usize;
+        let bars: Vec<Bar> = buckets
+            .iter()
+            .take(max_bars)
+            .rev()
+            .map(|b| {
+                let distance = self.units.distance(b.distance);
+                Bar::default()
+                    .label(b.label.clone().into())
+                    .value(distance.round() as u64)
+                    .text_value(format!("{:.0}", distance))
+            })
+            .collect();
+
+        let chart = BarChart::default()
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(self.trf("{} - distance ({})", &[&title, &self.units.distance_unit()])),
+            )
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(self.theme.accent))
+            .value_style(self.theme.bar_value());
+        f.render_widget(chart, chunks[0]);
+
+        let rows: Vec<Row> = buckets
+            .iter()
+            .map(|b| {
+                let sports = b
+                    .per_sport
+                    .iter()
+                    .map(|(sport, count)| format!("{} {}", sport, count))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Row::new(vec![
+                    Cell::from(b.key.clone()),
+                    Cell::from(b.count.to_string()),
+                    Cell::from(format!("{:.1}", self.units.distance(b.distance)))
+                        .style(Style::default().fg(self.theme.accent)),
+                    Cell::from(format!(
+                        "{}:{:02}",
+                        b.moving_time / 3600,
+                        (b.moving_time % 3600) / 60
+                    ))
+                    .style(Style::default().fg(self.theme.positive)),
+                    Cell::from(format!("{:.0}", self.units.elevation(b.elevation))),
+                    Cell::from(sports).style(Style::default().fg(self.theme.special)),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Length(6),
+                Constraint::Length(9),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Min(10),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                self.tr("Period").to_string(),
+                self.tr("Count").to_string(),
+                format!("{} {}", self.tr("Dist"), self.units.distance_unit()),
+                self.tr("Time").to_string(),
+                format!("{} {}", self.tr("Elev"), self.units.elevation_unit()),
+                self.tr("Sports").to_string(),
+            ])
+            .style(self.theme.header()),
+        )
+        .block(Block::new().borders(Borders::ALL).title(self.tr("Totals")));
+        f.render_widget(table, chunks[1]);
+    }
+
+    fn compute_training_buckets(&self) -> Vec<TrainingBucket> {
+        let mut buckets: BTreeMap<String, TrainingBucket> = BTreeMap::new();
+
+        for activity in &self.activities {
+            let date = activity.start_date_local;
+            let (key, label) = match self.training_period {
+                TrainingPeriod::Week => {
+                    let week = self.locale.week(date.date_naive());
+                    (
+                        format!("{}-W{:02}", week.year(), week.week()),
+                        format!("W{:02}", week.week()),
+                    )
+                }
+                TrainingPeriod::Month => (
+                    date.format("%Y-%m").to_string(),
+                    date.format("%b").to_string(),
+                ),
+            };
+
+            let bucket = buckets
+                .entry(key.clone())
+                .or_insert_with(|| TrainingBucket {
+                    key,
+                    label,
+                    count: 0,
+                    distance: 0.0,
+                    moving_time: 0,
+                    elevation: 0.0,
+                    per_sport: BTreeMap::new(),
+                });
+            bucket.count += 1;
+            bucket.distance += activity.distance;
+            bucket.moving_time += activity.moving_time;
+            bucket.elevation += activity.total_elevation_gain;
+            *bucket
+                .per_sport
+                .entry(activity.sport_type.clone())
+                .or_insert(0) += 1;
+        }
+
+        buckets.into_values().rev().collect()
+    }
+}
@@ -0,0 +1,155 @@
+use crate::analytics::SportGroup;
+use serde::{Deserialize, Serialize};
+
+/// Columns of the activities table, configured with `activity_columns`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityColumn {
+    Date,
+    Name,
+    Sport,
+    Distance,
+    Elevation,
+    // Meters climbed per kilometer, or feet per mile
+    ClimbDensity,
+    Duration,
+    ElapsedTime,
+    Pace,
+    // Grade adjusted pace, known once the activity's streams were loaded
+    Gap,
+    Speed,
+    MaxSpeed,
+    Heartrate,
+    MaxHeartrate,
+    Calories,
+    RelPerf,
+    Power,
+    Kudos,
+    Comments,
+    Gear,
+    // A function of the user's script, written `{ script = "name" }`
+    Script(String),
+}
+
+impl ActivityColumn {
+    pub const DEFAULT: [ActivityColumn; 10] = [
+        ActivityColumn::Date,
+        ActivityColumn::Name,
+        ActivityColumn::Distance,
+        ActivityColumn::Elevation,
+        ActivityColumn::Duration,
+        ActivityColumn::Pace,
+        ActivityColumn::Heartrate,
+        ActivityColumn::Calories,
+        ActivityColumn::RelPerf,
+        ActivityColumn::Gear,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActivityColumn::Date => "Date",
+            ActivityColumn::Name => "Name",
+            ActivityColumn::Sport => "Sport",
+            ActivityColumn::Distance => "Distance",
+            ActivityColumn::Elevation => "Elev",
+            ActivityColumn::ClimbDensity => "Climb",
+            ActivityColumn::Duration => "Duration",
+            ActivityColumn::ElapsedTime => "Elapsed",
+            ActivityColumn::Pace => "Pace",
+            ActivityColumn::Gap => "GAP",
+            ActivityColumn::Speed => "Speed",
+            ActivityColumn::MaxSpeed => "Max",
+            ActivityColumn::Heartrate => "HR",
+            ActivityColumn::MaxHeartrate => "MaxHR",
+            ActivityColumn::Calories => "Cal",
+            ActivityColumn::RelPerf => "RelPerf",
+            ActivityColumn::Power => "Watts",
+            ActivityColumn::Kudos => "Kudos",
+            ActivityColumn::Comments => "Cmts",
+            ActivityColumn::Gear => "Gear",
+            ActivityColumn::Script(_) => "Script",
+        }
+    }
+
+    /// Upper bound for auto-sized columns holding free text.
+    pub fn max_width(&self) -> u16 {
+        match self {
+            ActivityColumn::Name => 25,
+            ActivityColumn::Gear => 16,
+            // Room for a 12-hour clock
+            ActivityColumn::Date => 15,
+            _ => 12,
+        }
+    }
+}
+
+/// Icons in front of activity names, configured with `sport_icons`. Emoji
+/// and Nerd Font glyphs need a terminal font that has them; `ascii` works
+/// everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SportIcons {
+    #[default]
+    None,
+    Emoji,
+    NerdFont,
+    Ascii,
+}
+
+impl SportIcons {
+    pub fn icon(self, sport_type: &str) -> Option<&'static str> {
+        let group = SportGroup::of(sport_type);
+        let icon = match self {
+            SportIcons::None => return None,
+            SportIcons::Emoji => match (group, sport_type) {
+                (_, "Walk") => "🚶",
+                (_, "Kayaking" | "Canoeing" | "StandUpPaddling") => "🛶",
+                (_, "Rowing" | "VirtualRow") => "🚣",
+                (_, "Sail") => "⛵",
+                (_, "Surfing" | "Kitesurf" | "Windsurf") => "🏄",
+                (_, "Snowboard") => "🏂",
+                (_, "Yoga" | "Pilates") => "🧘",
+                (_, "RockClimbing") => "🧗",
+                (_, "Soccer") => "⚽",
+                (
+                    _,
+                    "Tennis" | "TableTennis" | "Badminton" | "Squash" | "Racquetball"
+                    | "Pickleball",
+                ) => "🎾",
+                (_, "Golf") => "⛳",
+                (SportGroup::Run | SportGroup::VirtualRun, _) => "🏃",
+                (SportGroup::Ride | SportGroup::VirtualRide, _) => "🚴",
+                (SportGroup::EBike, _) => "🚲",
+                (SportGroup::Foot, _) => "🥾",
+                (SportGroup::Water, _) => "🏊",
+                (SportGroup::Winter, _) => "🎿",
+                (SportGroup::Other, _) => "💪",
+            },
+            SportIcons::NerdFont => match (group, sport_type) {
+                (_, "Walk") => "\u{f0583}",
+                (_, "Kayaking" | "Canoeing" | "StandUpPaddling") => "\u{f08af}",
+                (_, "Rowing" | "VirtualRow") => "\u{f0609}",
+                (SportGroup::Run | SportGroup::VirtualRun, _) => "\u{f070e}",
+                (SportGroup::Ride | SportGroup::VirtualRide, _) => "\u{f00a3}",
+                (SportGroup::EBike, _) => "\u{f15b4}",
+                (SportGroup::Foot, _) => "\u{f0d7f}",
+                (SportGroup::Water, _) => "\u{f0645}",
+                (SportGroup::Winter, _) => "\u{f1304}",
+                (SportGroup::Other, _) => "\u{f01e5}",
+            },
+            SportIcons::Ascii => match (group, sport_type) {
+                (_, "Walk") => "WLK",
+                (SportGroup::Run, _) => "RUN",
+                (SportGroup::VirtualRun, _) => "VRN",
+                (SportGroup::Ride, _) => "BIK",
+                (SportGroup::VirtualRide, _) => "VBK",
+                (SportGroup::EBike, _) => "EBK",
+                (SportGroup::Foot, _) => "HIK",
+                (SportGroup::Water, _) => "H2O",
+                (SportGroup::Winter, _) => "SKI",
+                (SportGroup::Other, _) => "---",
+            },
+        };
+        Some(icon)
+    }
+}
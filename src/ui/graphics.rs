@@ -0,0 +1,188 @@
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use std::fmt::Write;
+
+// Kitty limits each escape sequence to 4096 bytes of payload
+const KITTY_CHUNK: usize = 4096;
+// Used when the terminal doesn't report its size in pixels
+const DEFAULT_CELL_SIZE: (u32, u32) = (10, 20);
+
+/// Terminal image protocols used to show activity photos. Terminals without
+/// any of them get the list of photo URLs instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+}
+
+impl GraphicsProtocol {
+    /// Guesses the protocol from the environment. `SPORTFREI_GRAPHICS` can
+    /// force one (`kitty`, `iterm2`, `sixel`) or turn images off (`none`).
+    pub fn detect() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        match var("SPORTFREI_GRAPHICS").as_str() {
+            "kitty" => return Some(Self::Kitty),
+            "iterm2" => return Some(Self::Iterm2),
+            "sixel" => return Some(Self::Sixel),
+            "none" => return None,
+            _ => {}
+        }
+
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+        if term == "xterm-kitty" || term == "xterm-ghostty" || !var("KITTY_WINDOW_ID").is_empty() {
+            Some(Self::Kitty)
+        } else if matches!(program.as_str(), "iTerm.app" | "WezTerm") {
+            Some(Self::Iterm2)
+        } else if term.contains("sixel") || term == "foot" || term == "mlterm" {
+            Some(Self::Sixel)
+        } else {
+            None
+        }
+    }
+
+    /// Escape sequence drawing `image` (JPEG or PNG bytes) scaled to fit
+    /// `cols` x `rows` cells at the cursor position.
+    pub fn encode(&self, image: &[u8], cols: u16, rows: u16) -> Result<String> {
+        if *self == Self::Iterm2 {
+            // iTerm2 decodes and scales the image itself
+            return Ok(format!(
+                "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1;doNotMoveCursor=1:{}\x07",
+                image.len(),
+                cols,
+                rows,
+                STANDARD.encode(image)
+            ));
+        }
+
+        let (cell_width, cell_height) = cell_size();
+        let decoded = image::load_from_memory(image)?.resize(
+            cols as u32 * cell_width,
+            rows as u32 * cell_height,
+            FilterType::Triangle,
+        );
+        Ok(match self {
+            Self::Kitty => kitty(&decoded),
+            _ => sixel(&decoded),
+        })
+    }
+
+    /// Removes images that text drawn over them wouldn't hide.
+    pub fn clear(&self) -> &'static str {
+        match self {
+            Self::Kitty => "\x1b_Ga=d,q=2\x1b\\",
+            _ => "",
+        }
+    }
+}
+
+fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns) as u32,
+            (size.height / size.rows) as u32,
+        ),
+        _ => DEFAULT_CELL_SIZE,
+    }
+}
+
+fn kitty(image: &DynamicImage) -> String {
+    let rgba = image.to_rgba8();
+    let payload = STANDARD.encode(rgba.as_raw());
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        // Base64 is ASCII, so the chunks are valid UTF-8
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            let _ = write!(
+                out,
+                "\x1b_Ga=T,f=32,s={},v={},C=1,q=2,m={};{}\x1b\\",
+                rgba.width(),
+                rgba.height(),
+                more,
+                chunk
+            );
+        } else {
+            let _ = write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk);
+        }
+    }
+    out
+}
+
+// Six levels per channel, so every pixel maps to one of 216 palette entries
+fn sixel(image: &DynamicImage) -> String {
+    let rgb = image.to_rgb8();
+    let (width, height) = rgb.dimensions();
+    let level = |v: u8| (v as usize * 5 + 127) / 255;
+    let index: Vec<usize> = rgb
+        .pixels()
+        .map(|p| level(p[0]) * 36 + level(p[1]) * 6 + level(p[2]))
+        .collect();
+
+    let mut out = format!("\x1bPq\"1;1;{};{}", width, height);
+    for color in 0..216 {
+        let percent = |l: usize| l * 100 / 5;
+        let _ = write!(
+            out,
+            "#{};2;{};{};{}",
+            color,
+            percent(color / 36),
+            percent(color / 6 % 6),
+            percent(color % 6)
+        );
+    }
+
+    for band in (0..height as usize).step_by(6) {
+        let rows = band..(band + 6).min(height as usize);
+        let mut used = [false; 216];
+        for y in rows.clone() {
+            for x in 0..width as usize {
+                used[index[y * width as usize + x]] = true;
+            }
+        }
+
+        for color in (0..216).filter(|&c| used[c]) {
+            let _ = write!(out, "#{}", color);
+            let mut run: Option<(char, usize)> = None;
+            for x in 0..width as usize {
+                let bits = rows
+                    .clone()
+                    .enumerate()
+                    .filter(|(_, y)| index[y * width as usize + x] == color)
+                    .fold(0u8, |bits, (bit, _)| bits | 1 << bit);
+                let c = (b'?' + bits) as char;
+                run = match run {
+                    Some((prev, n)) if prev == c => Some((prev, n + 1)),
+                    Some((prev, n)) => {
+                        push_run(&mut out, prev, n);
+                        Some((c, 1))
+                    }
+                    None => Some((c, 1)),
+                };
+            }
+            if let Some((c, n)) = run {
+                push_run(&mut out, c, n);
+            }
+            // Back to the start of the band for the next color
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_run(out: &mut String, c: char, n: usize) {
+    if n > 3 {
+        let _ = write!(out, "!{}{}", n, c);
+    } else {
+        out.extend(std::iter::repeat_n(c, n));
+    }
+}
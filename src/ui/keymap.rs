@@ -0,0 +1,223 @@
+use crate::ui::app::View;
+use crossterm::event::KeyCode;
+
+/// What a key press does in normal mode. The event loop dispatches on these,
+/// so the help overlay and the actual bindings come from the same table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    ShowView(View),
+    NextView,
+    PrevView,
+    ToggleErrorLog,
+    ToggleHelp,
+    Down,
+    Up,
+    Left,
+    Right,
+    /// Opens the go-to-date prompt, where a second `g` jumps to the top
+    GoToDate,
+    Home,
+    Bottom,
+    PageDown,
+    PageUp,
+    Open,
+    Back,
+    DateRange,
+    Sort,
+    Edit,
+    SplitsMode,
+    ToggleLaps,
+    TogglePeriod,
+    GroupActivities,
+    Tag,
+    Note,
+    FilterTag,
+    HideCommutes,
+    HidePrivate,
+    ExcludeIndoor,
+    TimeBasis,
+    TimeFormat,
+    Mute,
+    Delete,
+    CopyLink,
+    CopySummary,
+    Kudos,
+    NextWithoutKudos,
+    DownloadGpx,
+    Refresh,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Global,
+    View(View),
+}
+
+pub struct Binding {
+    pub keys: &'static [KeyCode],
+    /// How the keys are shown in the help overlay
+    pub label: &'static str,
+    pub action: Action,
+    pub scope: Scope,
+    pub description: &'static str,
+}
+
+const fn global(
+    keys: &'static [KeyCode],
+    label: &'static str,
+    action: Action,
+    description: &'static str,
+) -> Binding {
+    Binding {
+        keys,
+        label,
+        action,
+        scope: Scope::Global,
+        description,
+    }
+}
+
+const fn in_view(
+    view: View,
+    keys: &'static [KeyCode],
+    label: &'static str,
+    action: Action,
+    description: &'static str,
+) -> Binding {
+    Binding {
+        keys,
+        label,
+        action,
+        scope: Scope::View(view),
+        description,
+    }
+}
+
+const DOWN: &[KeyCode] = &[KeyCode::Char('j'), KeyCode::Down];
+const UP: &[KeyCode] = &[KeyCode::Char('k'), KeyCode::Up];
+const LEFT: &[KeyCode] = &[KeyCode::Char('h'), KeyCode::Left];
+const RIGHT: &[KeyCode] = &[KeyCode::Char('l'), KeyCode::Right];
+
+#[rustfmt::skip]
+pub const BINDINGS: &[Binding] = &[
+    global(&[KeyCode::Tab], "Tab", Action::NextView, "Next view"),
+    global(&[KeyCode::BackTab], "⇧Tab", Action::PrevView, "Previous view"),
+    // The number keys follow the tab bar, see View::TABS
+    global(&[KeyCode::Char('d'), KeyCode::Char('1')], "d/1", Action::ShowView(View::Dashboard), "Dashboard"),
+    global(&[KeyCode::Char('a'), KeyCode::Char('2')], "a/2", Action::ShowView(View::Activities), "Activities"),
+    global(&[KeyCode::Char('S'), KeyCode::Char('3')], "S/3", Action::ShowView(View::Segments), "Starred segments"),
+    global(&[KeyCode::Char('b'), KeyCode::Char('4')], "b/4", Action::ShowView(View::Gear), "Bikes & shoes"),
+    global(&[KeyCode::Char('c'), KeyCode::Char('5')], "c/5", Action::ShowView(View::Clubs), "Clubs"),
+    global(&[KeyCode::Char('t'), KeyCode::Char('6')], "t/6", Action::ShowView(View::Training), "Training"),
+    global(&[KeyCode::Char('o'), KeyCode::Char('7')], "o/7", Action::ShowView(View::Progress), "Progress"),
+    global(&[KeyCode::Char('y'), KeyCode::Char('8')], "y/8", Action::ShowView(View::Heatmap), "Year heatmap"),
+    global(&[KeyCode::Char('F'), KeyCode::Char('9')], "F/9", Action::ShowView(View::Fitness), "Fitness & freshness"),
+    global(&[KeyCode::Char('R')], "R", Action::ShowView(View::Routes), "Repeated routes"),
+    global(&[KeyCode::Char('W')], "W", Action::ShowView(View::Plan), "Training plan"),
+    global(&[KeyCode::Char('B')], "B", Action::ShowView(View::Sports), "Breakdown by sport type"),
+    global(&[KeyCode::Char('p')], "p", Action::ShowView(View::Achievements), "PRs & achievements"),
+    global(&[KeyCode::Char('i')], "i", Action::ShowView(View::Profile), "Athlete profile"),
+    global(&[KeyCode::Char('u')], "u", Action::ShowView(View::Social), "Activities of athletes you follow"),
+    global(&[KeyCode::Char('v')], "v", Action::ShowView(View::SavedRoutes), "Saved routes"),
+    global(&[KeyCode::Char('C')], "C", Action::HideCommutes, "Hide / show commutes"),
+    global(&[KeyCode::Char('P')], "P", Action::HidePrivate, "Hide / show private activities"),
+    global(&[KeyCode::Char('I')], "I", Action::ExcludeIndoor, "Count / leave out indoor activities"),
+    global(&[KeyCode::Char('E')], "E", Action::TimeBasis, "Moving / elapsed time"),
+    global(&[KeyCode::Char('H')], "H", Action::TimeFormat, "12 / 24-hour clock"),
+    global(&[KeyCode::Char('r')], "r", Action::Refresh, "Refresh, or retry a failed page"),
+    global(&[KeyCode::Esc], "Esc", Action::Back, "Back to the previous view"),
+    global(&[KeyCode::Char('L')], "L", Action::ToggleErrorLog, "Recent errors"),
+    global(&[KeyCode::Char('?')], "?", Action::ToggleHelp, "This help"),
+    global(&[KeyCode::Char('q')], "q", Action::Quit, "Quit"),
+    global(DOWN, "j/↓", Action::Down, "Next activity"),
+    global(UP, "k/↑", Action::Up, "Previous activity"),
+    in_view(View::Dashboard, LEFT, "h/←", Action::Left, "Shorter time range"),
+    in_view(View::Dashboard, RIGHT, "l/→", Action::Right, "Longer time range"),
+    in_view(View::Activities, &[KeyCode::Enter], "Enter", Action::Open, "Open activity"),
+    in_view(View::Activities, &[KeyCode::Char('g')], "g", Action::GoToDate, "Go to date (gg: first activity)"),
+    in_view(View::Activities, &[KeyCode::Home], "Home", Action::Home, "First activity"),
+    in_view(View::Activities, &[KeyCode::Char('G'), KeyCode::End], "G/End", Action::Bottom, "Last activity"),
+    in_view(View::Activities, &[KeyCode::PageDown], "PgDn", Action::PageDown, "Page down"),
+    in_view(View::Activities, &[KeyCode::PageUp], "PgUp", Action::PageUp, "Page up"),
+    in_view(View::Activities, LEFT, "h/←", Action::Left, "Scroll columns left"),
+    in_view(View::Activities, RIGHT, "l/→", Action::Right, "Scroll columns right"),
+    in_view(View::Activities, &[KeyCode::Char('f')], "f", Action::DateRange, "Filter by date range"),
+    in_view(View::Activities, &[KeyCode::Char('s')], "s", Action::Sort, "Sort"),
+    in_view(View::Activities, &[KeyCode::Char('w')], "w", Action::GroupActivities, "Group by week / month / off"),
+    in_view(View::Activities, &[KeyCode::Char('T')], "T", Action::Tag, "Tag activity"),
+    in_view(View::Activities, &[KeyCode::Char('#')], "#", Action::FilterTag, "Filter by tag (cycles)"),
+    in_view(View::ActivityDetail, LEFT, "h/←", Action::Left, "Previous tab"),
+    in_view(View::ActivityDetail, RIGHT, "l/→", Action::Right, "Next tab"),
+    in_view(View::ActivityDetail, &[KeyCode::Char('e')], "e", Action::Edit, "Edit activity"),
+    in_view(View::ActivityDetail, &[KeyCode::Char('m')], "m", Action::SplitsMode, "Cycle splits"),
+    in_view(View::ActivityDetail, &[KeyCode::Char('z')], "z", Action::ToggleLaps, "Splits / laps"),
+    in_view(View::ActivityDetail, &[KeyCode::Char('T')], "T", Action::Tag, "Tag activity"),
+    in_view(View::ActivityDetail, &[KeyCode::Char('n')], "n", Action::Note, "Write notes"),
+    in_view(View::Activities, &[KeyCode::Char('M')], "M", Action::Mute, "Mute activity"),
+    in_view(View::Activities, &[KeyCode::Char('X')], "X", Action::Delete, "Delete activity"),
+    in_view(View::Activities, &[KeyCode::Char('y')], "y", Action::CopyLink, "Copy Strava link"),
+    in_view(View::Activities, &[KeyCode::Char('Y')], "Y", Action::CopySummary, "Copy summary"),
+    in_view(View::ActivityDetail, &[KeyCode::Char('M')], "M", Action::Mute, "Mute activity"),
+    in_view(View::ActivityDetail, &[KeyCode::Char('X')], "X", Action::Delete, "Delete activity"),
+    in_view(View::ActivityDetail, &[KeyCode::Char('y')], "y", Action::CopyLink, "Copy Strava link"),
+    in_view(View::ActivityDetail, &[KeyCode::Char('Y')], "Y", Action::CopySummary, "Copy summary"),
+    in_view(View::Training, &[KeyCode::Char('w')], "w", Action::TogglePeriod, "Weeks / months"),
+    in_view(View::Segments, DOWN, "j/↓", Action::Down, "Next segment"),
+    in_view(View::Segments, UP, "k/↑", Action::Up, "Previous segment"),
+    in_view(View::Routes, DOWN, "j/↓", Action::Down, "Next route"),
+    in_view(View::Routes, UP, "k/↑", Action::Up, "Previous route"),
+    in_view(View::Plan, LEFT, "h/←", Action::Left, "Previous month"),
+    in_view(View::Plan, RIGHT, "l/→", Action::Right, "Next month"),
+    in_view(View::Clubs, DOWN, "j/↓", Action::Down, "Next club activity"),
+    in_view(View::Clubs, UP, "k/↑", Action::Up, "Previous club activity"),
+    in_view(View::Clubs, LEFT, "h/←", Action::Left, "Previous club"),
+    in_view(View::Clubs, RIGHT, "l/→", Action::Right, "Next club"),
+    in_view(View::SavedRoutes, DOWN, "j/↓", Action::Down, "Next route"),
+    in_view(View::SavedRoutes, UP, "k/↑", Action::Up, "Previous route"),
+    in_view(View::SavedRoutes, &[KeyCode::Char('x')], "x", Action::DownloadGpx, "Download the route as GPX"),
+    in_view(View::Social, DOWN, "j/↓", Action::Down, "Next activity"),
+    in_view(View::Social, UP, "k/↑", Action::Up, "Previous activity"),
+    in_view(View::Social, &[KeyCode::Char('K')], "K", Action::Kudos, "Give kudos on strava.com"),
+    in_view(View::Social, &[KeyCode::Char('n')], "n", Action::NextWithoutKudos, "Next activity without your kudos"),
+];
+
+/// Looks up the action for `code` in `view`. Bindings of the view win over
+/// global ones bound to the same key.
+pub fn action(view: View, code: KeyCode) -> Option<Action> {
+    let find = |scope: Scope| {
+        BINDINGS
+            .iter()
+            .find(|b| b.scope == scope && b.keys.contains(&code))
+            .map(|b| b.action)
+    };
+    find(Scope::View(view)).or_else(|| find(Scope::Global))
+}
+
+/// Keys and descriptions shown in the help overlay for `view`: its own
+/// bindings first, then the global ones without the keys it shadows.
+pub fn help(view: View) -> Vec<(String, &'static str)> {
+    let own: Vec<&Binding> = BINDINGS
+        .iter()
+        .filter(|b| b.scope == Scope::View(view))
+        .collect();
+    let shadowed = |code: &KeyCode| own.iter().any(|o| o.keys.contains(code));
+    let globals = BINDINGS
+        .iter()
+        .filter(|b| b.scope == Scope::Global)
+        .filter_map(|b| {
+            // The label names the keys in order, separated by slashes
+            let keys: Vec<&str> = b
+                .label
+                .split('/')
+                .zip(b.keys)
+                .filter(|(_, code)| !shadowed(code))
+                .map(|(label, _)| label)
+                .collect();
+            (!keys.is_empty()).then(|| (keys.join("/"), b.description))
+        });
+    own.iter()
+        .map(|b| (b.label.to_string(), b.description))
+        .chain(globals)
+        .collect()
+}
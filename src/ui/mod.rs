@@ -1 +1,5 @@
 pub mod app;
+pub mod columns;
+pub mod graphics;
+pub mod keymap;
+pub mod theme;
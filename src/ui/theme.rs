@@ -0,0 +1,283 @@
+use crate::analytics::{self, SportGroup};
+use anyhow::{anyhow, Result};
+use ratatui::style::{Color, Style};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Colors used by the UI, chosen with `theme` in the config. Custom themes go
+/// in `[themes.<name>]` tables that start from `base` (a built-in, `dark` by
+/// default) and override individual slots, e.g. `accent = "#268bd2"`, or the
+/// color of a single sport type, e.g. `GravelRide = "brown"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub text: Color,
+    pub muted: Color,
+    pub header_bg: Color,
+    pub selection_bg: Color,
+    pub selection_fg: Color,
+    pub accent: Color,
+    pub positive: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub special: Color,
+    pub info: Color,
+    pub run: Color,
+    pub ride: Color,
+    /// Also paddling, rowing and sailing
+    pub swim: Color,
+    pub hike: Color,
+    pub ebike: Color,
+    /// Skiing, snowboarding, snowshoeing and skating
+    pub winter: Color,
+    /// Zwift and other virtual rides and runs, set apart from the outdoor ones
+    pub virtual_ride: Color,
+    pub virtual_run: Color,
+    pub other_sport: Color,
+    /// Colors for single sport types, e.g. `GravelRide`, ahead of their group's
+    pub sports: BTreeMap<String, Color>,
+    /// Calendar heatmap, from no activity to the busiest days
+    pub heatmap: [Color; 5],
+}
+
+impl Theme {
+    pub const BUILT_IN: [&'static str; 4] = ["dark", "light", "solarized", "gruvbox"];
+
+    pub fn dark() -> Self {
+        Self {
+            text: Color::White,
+            muted: Color::DarkGray,
+            header_bg: Color::Black,
+            selection_bg: Color::DarkGray,
+            selection_fg: Color::White,
+            accent: Color::Cyan,
+            positive: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            special: Color::Magenta,
+            info: Color::Blue,
+            run: Color::Green,
+            ride: Color::Blue,
+            swim: Color::Cyan,
+            hike: Color::Yellow,
+            virtual_ride: Color::LightBlue,
+            virtual_run: Color::LightGreen,
+            ebike: Color::LightCyan,
+            winter: Color::White,
+            other_sport: Color::Magenta,
+            sports: BTreeMap::new(),
+            heatmap: [
+                Color::DarkGray,
+                Color::Indexed(22),
+                Color::Indexed(28),
+                Color::Indexed(34),
+                Color::Indexed(46),
+            ],
+        }
+    }
+
+    // 256-color palette so it also works in terminals without true color
+    pub fn light() -> Self {
+        Self {
+            text: Color::Black,
+            muted: Color::Indexed(245),
+            header_bg: Color::Indexed(252),
+            selection_bg: Color::Indexed(153),
+            selection_fg: Color::Black,
+            accent: Color::Indexed(25),
+            positive: Color::Indexed(28),
+            warning: Color::Indexed(130),
+            error: Color::Indexed(160),
+            special: Color::Indexed(90),
+            info: Color::Indexed(19),
+            run: Color::Indexed(28),
+            ride: Color::Indexed(19),
+            swim: Color::Indexed(30),
+            hike: Color::Indexed(130),
+            virtual_ride: Color::Indexed(61),
+            virtual_run: Color::Indexed(65),
+            ebike: Color::Indexed(31),
+            winter: Color::Indexed(67),
+            other_sport: Color::Indexed(90),
+            sports: BTreeMap::new(),
+            heatmap: [
+                Color::Indexed(254),
+                Color::Indexed(151),
+                Color::Indexed(114),
+                Color::Indexed(71),
+                Color::Indexed(28),
+            ],
+        }
+    }
+
+    pub fn solarized() -> Self {
+        Self {
+            text: Color::Rgb(0x93, 0xa1, 0xa1),
+            muted: Color::Rgb(0x58, 0x6e, 0x75),
+            header_bg: Color::Rgb(0x07, 0x36, 0x42),
+            selection_bg: Color::Rgb(0x07, 0x36, 0x42),
+            selection_fg: Color::Rgb(0xee, 0xe8, 0xd5),
+            accent: Color::Rgb(0x2a, 0xa1, 0x98),
+            positive: Color::Rgb(0x85, 0x99, 0x00),
+            warning: Color::Rgb(0xb5, 0x89, 0x00),
+            error: Color::Rgb(0xdc, 0x32, 0x2f),
+            special: Color::Rgb(0xd3, 0x36, 0x82),
+            info: Color::Rgb(0x26, 0x8b, 0xd2),
+            run: Color::Rgb(0x85, 0x99, 0x00),
+            ride: Color::Rgb(0x26, 0x8b, 0xd2),
+            swim: Color::Rgb(0x2a, 0xa1, 0x98),
+            hike: Color::Rgb(0xcb, 0x4b, 0x16),
+            virtual_ride: Color::Rgb(0x6c, 0xa6, 0xd9),
+            virtual_run: Color::Rgb(0xa4, 0xb2, 0x4c),
+            ebike: Color::Rgb(0x6c, 0xc4, 0xb8),
+            winter: Color::Rgb(0xee, 0xe8, 0xd5),
+            other_sport: Color::Rgb(0x6c, 0x71, 0xc4),
+            sports: BTreeMap::new(),
+            heatmap: [
+                Color::Rgb(0x07, 0x36, 0x42),
+                Color::Rgb(0x3a, 0x52, 0x1c),
+                Color::Rgb(0x5a, 0x6e, 0x0e),
+                Color::Rgb(0x72, 0x86, 0x05),
+                Color::Rgb(0x85, 0x99, 0x00),
+            ],
+        }
+    }
+
+    pub fn gruvbox() -> Self {
+        Self {
+            text: Color::Rgb(0xeb, 0xdb, 0xb2),
+            muted: Color::Rgb(0x92, 0x83, 0x74),
+            header_bg: Color::Rgb(0x3c, 0x38, 0x36),
+            selection_bg: Color::Rgb(0x50, 0x49, 0x45),
+            selection_fg: Color::Rgb(0xfb, 0xf1, 0xc7),
+            accent: Color::Rgb(0x8e, 0xc0, 0x7c),
+            positive: Color::Rgb(0xb8, 0xbb, 0x26),
+            warning: Color::Rgb(0xfa, 0xbd, 0x2f),
+            error: Color::Rgb(0xfb, 0x49, 0x34),
+            special: Color::Rgb(0xd3, 0x86, 0x9b),
+            info: Color::Rgb(0x83, 0xa5, 0x98),
+            run: Color::Rgb(0xb8, 0xbb, 0x26),
+            ride: Color::Rgb(0x83, 0xa5, 0x98),
+            swim: Color::Rgb(0x8e, 0xc0, 0x7c),
+            hike: Color::Rgb(0xfe, 0x80, 0x19),
+            virtual_ride: Color::Rgb(0x45, 0x85, 0x88),
+            virtual_run: Color::Rgb(0x98, 0x97, 0x1a),
+            ebike: Color::Rgb(0x68, 0x9d, 0x6a),
+            winter: Color::Rgb(0xd5, 0xc4, 0xa1),
+            other_sport: Color::Rgb(0xd3, 0x86, 0x9b),
+            sports: BTreeMap::new(),
+            heatmap: [
+                Color::Rgb(0x3c, 0x38, 0x36),
+                Color::Rgb(0x5a, 0x63, 0x1e),
+                Color::Rgb(0x79, 0x74, 0x0e),
+                Color::Rgb(0x98, 0x97, 0x1a),
+                Color::Rgb(0xb8, 0xbb, 0x26),
+            ],
+        }
+    }
+
+    pub fn built_in(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            "gruvbox" => Some(Self::gruvbox()),
+            _ => None,
+        }
+    }
+
+    /// Looks `name` up in the custom themes first, then the built-ins.
+    pub fn resolve(
+        name: Option<&str>,
+        custom: &BTreeMap<String, BTreeMap<String, String>>,
+    ) -> Result<Self> {
+        let Some(name) = name else {
+            return Ok(Self::dark());
+        };
+        let Some(overrides) = custom.get(name) else {
+            return Self::built_in(name).ok_or_else(|| {
+                anyhow!(
+                    "unknown theme '{}' (built-in themes: {})",
+                    name,
+                    Self::BUILT_IN.join(", ")
+                )
+            });
+        };
+
+        let base = overrides.get("base").map_or("dark", String::as_str);
+        let mut theme = Self::built_in(base)
+            .ok_or_else(|| anyhow!("theme '{}' has unknown base '{}'", name, base))?;
+        for (slot, value) in overrides.iter().filter(|(slot, _)| *slot != "base") {
+            let color = Color::from_str(value)
+                .map_err(|_| anyhow!("theme '{}': invalid color '{}' for {}", name, value, slot))?;
+            theme.set(slot, color)?;
+        }
+        Ok(theme)
+    }
+
+    fn set(&mut self, slot: &str, color: Color) -> Result<()> {
+        let target = match slot {
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "header_bg" => &mut self.header_bg,
+            "selection_bg" => &mut self.selection_bg,
+            "selection_fg" => &mut self.selection_fg,
+            "accent" => &mut self.accent,
+            "positive" => &mut self.positive,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "special" => &mut self.special,
+            "info" => &mut self.info,
+            "run" => &mut self.run,
+            "ride" => &mut self.ride,
+            "swim" => &mut self.swim,
+            "hike" => &mut self.hike,
+            "ebike" => &mut self.ebike,
+            "winter" => &mut self.winter,
+            "virtual_ride" => &mut self.virtual_ride,
+            "virtual_run" => &mut self.virtual_run,
+            "other_sport" => &mut self.other_sport,
+            sport if analytics::SPORT_TYPES.contains(&sport) => {
+                self.sports.insert(sport.to_string(), color);
+                return Ok(());
+            }
+            _ => return Err(anyhow!("unknown theme color '{}'", slot)),
+        };
+        *target = color;
+        Ok(())
+    }
+
+    pub fn sport(&self, sport_type: &str) -> Color {
+        if let Some(color) = self.sports.get(sport_type) {
+            return *color;
+        }
+        match SportGroup::of(sport_type) {
+            SportGroup::Run => self.run,
+            SportGroup::VirtualRun => self.virtual_run,
+            SportGroup::Ride => self.ride,
+            SportGroup::VirtualRide => self.virtual_ride,
+            SportGroup::EBike => self.ebike,
+            SportGroup::Foot => self.hike,
+            SportGroup::Water => self.swim,
+            SportGroup::Winter => self.winter,
+            SportGroup::Other => self.other_sport,
+        }
+    }
+
+    pub fn selection(&self) -> Style {
+        Style::default().bg(self.selection_bg).fg(self.selection_fg)
+    }
+
+    pub fn header(&self) -> Style {
+        Style::default().fg(self.text).bg(self.header_bg)
+    }
+
+    pub fn bar_value(&self) -> Style {
+        Style::default().fg(self.header_bg).bg(self.accent)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
@@ -0,0 +1,106 @@
+use crate::cache::UploadLog;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How often the watched folder is listed. Polling rather than file system
+/// events, as those don't survive the folder being unmounted and mounted
+/// again when a watch is plugged in.
+pub const SCAN_INTERVAL: Duration = Duration::from_secs(5);
+/// How often the UI asks whether Strava has processed an upload, and how
+/// many times before giving up; a file usually takes a few seconds
+pub const UPLOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+pub const UPLOAD_POLL_ATTEMPTS: u32 = 60;
+
+/// A folder, e.g. where a watch mounts, whose new FIT files get uploaded to
+/// Strava while the UI is open.
+#[derive(Debug)]
+pub struct FolderWatch {
+    dir: PathBuf,
+    log: UploadLog,
+    // Size and modification time at the last scan, by file name
+    seen: HashMap<String, (u64, SystemTime)>,
+    // Handed out for upload this session
+    started: HashSet<String>,
+}
+
+impl FolderWatch {
+    /// Starts watching `dir`. The first time a folder is watched only files
+    /// written from then on count as new, so a watch full of old activities
+    /// isn't uploaded again.
+    pub fn new(dir: PathBuf, mut log: UploadLog, now: DateTime<Utc>) -> Self {
+        log.since.get_or_insert(now);
+        Self {
+            dir,
+            log,
+            seen: HashMap::new(),
+            started: HashSet::new(),
+        }
+    }
+
+    pub fn log(&self) -> &UploadLog {
+        &self.log
+    }
+
+    /// FIT files to upload: written since watching began, not uploaded yet
+    /// and the same size as at the previous scan, so a file still being
+    /// copied isn't sent half-written. A missing folder, such as an unplugged
+    /// watch, has none.
+    pub fn scan(&mut self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            self.seen.clear();
+            return Vec::new();
+        };
+        let since = self.log.since.map(SystemTime::from);
+        let mut seen = HashMap::new();
+        let mut ready = Vec::new();
+        for entry in entries.flatten() {
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if !is_fit(&name) || self.started.contains(&name) || self.log.contains(&name) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let Ok(modified) = metadata.modified() else {
+                continue;
+            };
+            if !metadata.is_file() || since.is_some_and(|since| modified < since) {
+                continue;
+            }
+            let state = (metadata.len(), modified);
+            if self.seen.get(&name) == Some(&state) && metadata.len() > 0 {
+                self.started.insert(name.clone());
+                ready.push(entry.path());
+            } else {
+                seen.insert(name, state);
+            }
+        }
+        ready.sort();
+        self.seen = seen;
+        ready
+    }
+
+    /// Remembers an upload that Strava turned into an activity or rejected,
+    /// so the file isn't sent again. Files that failed otherwise, e.g. while
+    /// offline, are tried again the next time SportFrei starts.
+    pub fn finish(&mut self, path: &Path, activity_id: Option<u64>) {
+        self.log.record(file_name(path), activity_id);
+    }
+}
+
+pub fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// Garmin writes upper case names, other devices lower case
+fn is_fit(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.ends_with(".fit") || name.ends_with(".fit.gz")
+}
@@ -0,0 +1,447 @@
+use crate::api::types::{
+    Activity, ActivityZone, Athlete, AthleteStats, ClubActivity, Comment, DateRange,
+    DetailedActivity, DetailedAthlete, DetailedSegment, FeedActivity, Gear, Page, Photo, Route,
+    StreamKey, StreamSet, SummaryAthlete, SummaryClub, SummarySegment, UpdatableActivity, Upload,
+    UploadDataType,
+};
+use crate::api::StravaApi;
+use crate::demo::Demo;
+use crate::error::{self, RateLimit};
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+use tokio::sync::mpsc as async_mpsc;
+
+const DETAIL_STREAMS: [StreamKey; 6] = [
+    StreamKey::Time,
+    StreamKey::Distance,
+    StreamKey::Heartrate,
+    StreamKey::Altitude,
+    StreamKey::VelocitySmooth,
+    StreamKey::Latlng,
+];
+
+// More than anyone uploads between two auto-refresh checks
+const NEW_ACTIVITIES_PER_PAGE: u32 = 50;
+
+// Only the first page of starred segments is shown
+const STARRED_PER_PAGE: u32 = 100;
+
+// Strava's maximum; more saved routes than that are rare enough to leave out
+const ROUTES_PER_PAGE: u32 = 200;
+
+#[derive(Debug)]
+pub enum Request {
+    Page {
+        page: u32,
+        per_page: u32,
+        range: DateRange,
+    },
+    Athlete,
+    Stats {
+        athlete_id: u64,
+    },
+    /// Athlete, stats and the first page again, to pick up new uploads
+    Refresh {
+        per_page: u32,
+        range: DateRange,
+    },
+    /// Activities started after `after` (epoch seconds), for auto-refresh
+    NewActivities {
+        after: i64,
+    },
+    Streams {
+        activity_id: u64,
+    },
+    Detail {
+        activity_id: u64,
+    },
+    Social {
+        activity_id: u64,
+    },
+    /// `download` also fetches the first photo for terminals that can show it
+    Photos {
+        activity_id: u64,
+        download: bool,
+    },
+    Zones {
+        activity_id: u64,
+    },
+    Update {
+        activity_id: u64,
+        update: UpdatableActivity,
+    },
+    Delete {
+        activity_id: u64,
+    },
+    Gear {
+        gear_id: String,
+    },
+    StarredSegments,
+    Segment {
+        segment_id: u64,
+    },
+    Clubs,
+    ClubActivities {
+        club_id: u64,
+        page: u32,
+        per_page: u32,
+    },
+    Profile,
+    Following {
+        page: u32,
+        per_page: u32,
+    },
+    SavedRoutes {
+        athlete_id: u64,
+    },
+    RouteGpx {
+        route_id: u64,
+    },
+    RouteStreams {
+        route_id: u64,
+    },
+    /// A file from the watched folder, answered before Strava processed it
+    Upload {
+        path: PathBuf,
+        data_type: UploadDataType,
+    },
+    UploadStatus {
+        path: PathBuf,
+        upload_id: u64,
+    },
+    /// The activity an upload turned into
+    UploadedActivity {
+        path: PathBuf,
+        activity_id: u64,
+    },
+}
+
+pub enum Response {
+    Page {
+        page: u32,
+        range: DateRange,
+        result: error::Result<Page<Activity>>,
+    },
+    Athlete {
+        result: error::Result<Box<Athlete>>,
+    },
+    Stats {
+        result: error::Result<Box<AthleteStats>>,
+    },
+    Refreshed {
+        result: error::Result<(Athlete, Box<AthleteStats>, Vec<Activity>)>,
+    },
+    NewActivities {
+        result: error::Result<Vec<Activity>>,
+    },
+    Streams {
+        activity_id: u64,
+        result: error::Result<Box<StreamSet>>,
+    },
+    Detail {
+        activity_id: u64,
+        result: error::Result<Box<DetailedActivity>>,
+    },
+    Social {
+        activity_id: u64,
+        result: error::Result<(Vec<SummaryAthlete>, Vec<Comment>)>,
+    },
+    Photos {
+        activity_id: u64,
+        result: error::Result<(Vec<Photo>, Option<Vec<u8>>)>,
+    },
+    Zones {
+        activity_id: u64,
+        result: error::Result<Vec<ActivityZone>>,
+    },
+    Updated {
+        result: error::Result<Box<DetailedActivity>>,
+    },
+    Deleted {
+        activity_id: u64,
+        result: error::Result<()>,
+    },
+    Gear {
+        gear_id: String,
+        result: error::Result<Gear>,
+    },
+    StarredSegments {
+        result: error::Result<Vec<SummarySegment>>,
+    },
+    Segment {
+        segment_id: u64,
+        result: error::Result<Box<DetailedSegment>>,
+    },
+    Clubs {
+        result: error::Result<Vec<SummaryClub>>,
+    },
+    ClubActivities {
+        club_id: u64,
+        page: u32,
+        result: error::Result<Page<ClubActivity>>,
+    },
+    Profile {
+        result: error::Result<Box<DetailedAthlete>>,
+    },
+    Following {
+        page: u32,
+        result: error::Result<Page<FeedActivity>>,
+    },
+    SavedRoutes {
+        result: error::Result<Vec<Route>>,
+    },
+    RouteGpx {
+        route_id: u64,
+        result: error::Result<Vec<u8>>,
+    },
+    RouteStreams {
+        route_id: u64,
+        result: error::Result<Box<StreamSet>>,
+    },
+    /// Answers both `Upload` and `UploadStatus`
+    Upload {
+        path: PathBuf,
+        result: error::Result<Upload>,
+    },
+    UploadedActivity {
+        path: PathBuf,
+        activity_id: u64,
+        result: error::Result<Box<DetailedActivity>>,
+    },
+    /// Follows a response whenever the API reported its rate limit
+    RateLimit(RateLimit),
+}
+
+/// Runs API calls on a dedicated thread so the UI loop never blocks on the
+/// network. Requests are handled concurrently; responses arrive in completion
+/// order. Dropping the worker cancels whatever is still in flight.
+pub struct Worker {
+    requests: async_mpsc::UnboundedSender<Request>,
+    responses: mpsc::Receiver<Response>,
+}
+
+impl Worker {
+    pub fn start(client: impl StravaApi) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let (request_tx, mut request_rx) = async_mpsc::unbounded_channel();
+        let (response_tx, response_rx) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("sportfrei-worker".to_string())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    while let Some(request) = request_rx.recv().await {
+                        let client = client.clone();
+                        let response_tx = response_tx.clone();
+                        tokio::spawn(async move {
+                            let response = handle(&client, request).await;
+                            // The receiver is gone once the UI loop has exited
+                            let _ = response_tx.send(response);
+                            if let Some(limit) = client.rate_limit() {
+                                let _ = response_tx.send(Response::RateLimit(limit));
+                            }
+                        });
+                    }
+                });
+            })?;
+
+        Ok(Self {
+            requests: request_tx,
+            responses: response_rx,
+        })
+    }
+
+    /// Answers from the bundled demo data instead of Strava, one request
+    /// after the other.
+    pub fn demo(mut demo: Demo) -> Result<Self> {
+        let (request_tx, mut request_rx) = async_mpsc::unbounded_channel();
+        let (response_tx, response_rx) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("sportfrei-demo".to_string())
+            .spawn(move || {
+                while let Some(request) = request_rx.blocking_recv() {
+                    if response_tx.send(demo.respond(request)).is_err() {
+                        break;
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            requests: request_tx,
+            responses: response_rx,
+        })
+    }
+
+    pub fn send(&self, request: Request) {
+        // Only fails if the worker thread died, which surfaces as missing responses
+        let _ = self.requests.send(request);
+    }
+
+    pub fn try_recv(&self) -> Option<Response> {
+        self.responses.try_recv().ok()
+    }
+}
+
+async fn refresh(
+    client: &impl StravaApi,
+    per_page: u32,
+    range: DateRange,
+) -> error::Result<(Athlete, Box<AthleteStats>, Vec<Activity>)> {
+    let athlete = client.get_athlete().await?;
+    let (stats, activities) = tokio::try_join!(
+        client.get_athlete_stats(athlete.id),
+        client.get_activities(1, per_page, range)
+    )?;
+    Ok((athlete, Box::new(stats), activities.items))
+}
+
+async fn handle(client: &impl StravaApi, request: Request) -> Response {
+    match request {
+        Request::Page {
+            page,
+            per_page,
+            range,
+        } => Response::Page {
+            page,
+            range,
+            result: client.get_activities(page, per_page, range).await,
+        },
+        Request::Athlete => Response::Athlete {
+            result: client.get_athlete().await.map(Box::new),
+        },
+        Request::Stats { athlete_id } => Response::Stats {
+            result: client.get_athlete_stats(athlete_id).await.map(Box::new),
+        },
+        Request::Refresh { per_page, range } => Response::Refreshed {
+            result: refresh(client, per_page, range).await,
+        },
+        Request::NewActivities { after } => Response::NewActivities {
+            result: client
+                .get_activities(
+                    1,
+                    NEW_ACTIVITIES_PER_PAGE,
+                    DateRange {
+                        after: Some(after),
+                        before: None,
+                    },
+                )
+                .await
+                .map(|page| page.items),
+        },
+        Request::Streams { activity_id } => Response::Streams {
+            activity_id,
+            result: client
+                .get_activity_streams(activity_id, &DETAIL_STREAMS)
+                .await
+                .map(Box::new),
+        },
+        Request::Detail { activity_id } => Response::Detail {
+            activity_id,
+            result: client.get_activity(activity_id).await.map(Box::new),
+        },
+        Request::Social { activity_id } => Response::Social {
+            activity_id,
+            result: tokio::try_join!(
+                client.get_activity_kudoers(activity_id),
+                client.get_activity_comments(activity_id)
+            ),
+        },
+        Request::Photos {
+            activity_id,
+            download,
+        } => {
+            let result = match client.get_activity_photos(activity_id).await {
+                Ok(photos) => {
+                    let url = photos.first().and_then(|p| p.url()).filter(|_| download);
+                    // A failed download still leaves the URL list to show
+                    let image = match url {
+                        Some(url) => client.get_photo(url).await.ok(),
+                        None => None,
+                    };
+                    Ok((photos, image))
+                }
+                Err(e) => Err(e),
+            };
+            Response::Photos {
+                activity_id,
+                result,
+            }
+        }
+        Request::Zones { activity_id } => Response::Zones {
+            activity_id,
+            result: client.get_activity_zones(activity_id).await,
+        },
+        Request::Update {
+            activity_id,
+            update,
+        } => Response::Updated {
+            result: client
+                .update_activity(activity_id, &update)
+                .await
+                .map(Box::new),
+        },
+        Request::Delete { activity_id } => Response::Deleted {
+            activity_id,
+            result: client.delete_activity(activity_id).await,
+        },
+        Request::Gear { gear_id } => {
+            let result = client.get_gear(&gear_id).await;
+            Response::Gear { gear_id, result }
+        }
+        Request::StarredSegments => Response::StarredSegments {
+            result: client.get_starred_segments(1, STARRED_PER_PAGE).await,
+        },
+        Request::Segment { segment_id } => Response::Segment {
+            segment_id,
+            result: client.get_segment(segment_id).await.map(Box::new),
+        },
+        Request::Clubs => Response::Clubs {
+            result: client.get_clubs().await,
+        },
+        Request::Profile => Response::Profile {
+            result: client.get_detailed_athlete().await.map(Box::new),
+        },
+        Request::ClubActivities {
+            club_id,
+            page,
+            per_page,
+        } => Response::ClubActivities {
+            club_id,
+            page,
+            result: client.get_club_activities(club_id, page, per_page).await,
+        },
+        Request::Following { page, per_page } => Response::Following {
+            page,
+            result: client.get_following_activities(page, per_page).await,
+        },
+        Request::SavedRoutes { athlete_id } => Response::SavedRoutes {
+            result: client.get_routes(athlete_id, 1, ROUTES_PER_PAGE).await,
+        },
+        Request::RouteGpx { route_id } => Response::RouteGpx {
+            route_id,
+            result: client.export_route_gpx(route_id).await,
+        },
+        Request::RouteStreams { route_id } => Response::RouteStreams {
+            route_id,
+            result: client.get_route_streams(route_id).await.map(Box::new),
+        },
+        Request::Upload { path, data_type } => {
+            let result = client.create_upload(&path, data_type, None).await;
+            Response::Upload { path, result }
+        }
+        Request::UploadStatus { path, upload_id } => Response::Upload {
+            path,
+            result: client.get_upload(upload_id).await,
+        },
+        Request::UploadedActivity { path, activity_id } => Response::UploadedActivity {
+            path,
+            activity_id,
+            result: client.get_activity(activity_id).await.map(Box::new),
+        },
+    }
+}
@@ -0,0 +1,453 @@
+mod common;
+
+use chrono::{DateTime, Utc};
+use sportfrei::analytics;
+use sportfrei::api::types::{Activity, BestEffort, Reference};
+
+fn effort(name: &str, distance: f64, elapsed_time: u32, start: &str) -> BestEffort {
+    let start: DateTime<Utc> = start.parse().unwrap();
+    BestEffort {
+        id: 1,
+        name: name.to_string(),
+        activity: Reference {
+            id: 1,
+            resource_state: 1,
+        },
+        athlete: Reference {
+            id: 12345,
+            resource_state: 1,
+        },
+        elapsed_time,
+        moving_time: elapsed_time,
+        start_date: start,
+        start_date_local: start,
+        distance,
+        pr_rank: None,
+    }
+}
+
+fn activity(id: u64, sport: &str, start: &str, distance: f64, speed: f64, climb: f64) -> Activity {
+    common::activity_with(
+        id,
+        sport,
+        start,
+        distance,
+        (distance / speed) as u32,
+        serde_json::json!({"total_elevation_gain": climb, "average_speed": speed}),
+    )
+}
+
+#[test]
+fn test_vdot_matches_daniels_tables() {
+    // Daniels lists 19:57 for 5k at VDOT 50 and 3:10:49 for the marathon
+    let five_k = analytics::vdot(5000.0, 1197.0).unwrap();
+    assert!((five_k - 50.0).abs() < 0.5, "VDOT was {}", five_k);
+    let marathon = analytics::vdot(42195.0, 11449.0).unwrap();
+    assert!((marathon - 50.0).abs() < 0.5, "VDOT was {}", marathon);
+    assert_eq!(analytics::vdot(5000.0, 0.0), None);
+}
+
+#[test]
+fn test_riegel_prediction() {
+    let ten_k = analytics::riegel(5000.0, 1200.0, 10000.0);
+    assert_eq!(ten_k.round(), 2502.0);
+}
+
+#[test]
+fn test_estimate_races_uses_best_recent_effort() {
+    let since: DateTime<Utc> = "2024-03-01T00:00:00Z".parse().unwrap();
+    let efforts = [
+        // Faster, but before the window
+        effort("5k", 5000.0, 1100, "2024-01-10T08:00:00Z"),
+        // Too short to say much about endurance
+        effort("400m", 400.0, 60, "2024-03-05T08:00:00Z"),
+        effort("5k", 5000.0, 1260, "2024-03-05T08:00:00Z"),
+        effort("10k", 10000.0, 2520, "2024-03-12T08:00:00Z"),
+    ];
+
+    let estimate = analytics::estimate_races(&efforts, since).unwrap();
+    assert_eq!(estimate.effort.name, "10k");
+    assert!(
+        (estimate.vdot - 49.0).abs() < 1.0,
+        "VDOT was {}",
+        estimate.vdot
+    );
+    let names: Vec<&str> = estimate.predictions.iter().map(|(n, _)| *n).collect();
+    assert_eq!(names, ["5k", "10k", "Half", "Marathon"]);
+    assert_eq!(estimate.predictions[1].1, 2520.0);
+    assert!(estimate.predictions[2].1 > 2.0 * 2520.0);
+
+    assert!(analytics::estimate_races(&efforts[..2], since).is_none());
+}
+
+#[test]
+fn test_personal_records_across_history() {
+    let activities = [
+        activity(1, "Run", "2024-03-01T08:00:00Z", 5000.0, 3.5, 20.0),
+        activity(2, "TrailRun", "2024-03-03T08:00:00Z", 12000.0, 2.8, 450.0),
+        activity(3, "Run", "2024-03-05T08:00:00Z", 4900.0, 4.0, 10.0),
+        // Ties the first run's speed, so the older one keeps the record
+        activity(4, "Run", "2024-03-07T08:00:00Z", 5000.0, 3.5, 0.0),
+        activity(5, "Ride", "2024-03-02T08:00:00Z", 60000.0, 8.0, 300.0),
+    ];
+    let records = analytics::personal_records(&activities);
+    let holder = |name: &str| {
+        records
+            .iter()
+            .find(|(kind, _)| kind.name == name)
+            .map(|(_, activity)| activity.id)
+    };
+
+    assert_eq!(holder("Longest run"), Some(2));
+    assert_eq!(holder("Biggest climb (run)"), Some(2));
+    // The faster 4.9k run is too short to count
+    assert_eq!(holder("Fastest run over 5k"), Some(1));
+    assert_eq!(holder("Fastest run over 10k"), Some(2));
+    assert_eq!(holder("Fastest run over 21k"), None);
+    assert_eq!(holder("Longest ride"), Some(5));
+    assert_eq!(holder("Fastest ride over 50k"), Some(5));
+    assert_eq!(holder("Fastest ride over 100k"), None);
+}
+
+// Google's polyline encoding, the inverse of api::polyline::decode
+fn encode(points: &[(f64, f64)]) -> String {
+    let mut out = String::new();
+    let (mut last_lat, mut last_lng) = (0i64, 0i64);
+    for &(lat, lng) in points {
+        let (lat, lng) = ((lat * 1e5).round() as i64, (lng * 1e5).round() as i64);
+        for delta in [lat - last_lat, lng - last_lng] {
+            let mut value = if delta < 0 { !(delta << 1) } else { delta << 1 };
+            while value >= 0x20 {
+                out.push((((value & 0x1f) | 0x20) as u8 + 63) as char);
+                value >>= 5;
+            }
+            out.push((value as u8 + 63) as char);
+        }
+        (last_lat, last_lng) = (lat, lng);
+    }
+    out
+}
+
+fn mapped(id: u64, start: &str, distance: f64, points: &[(f64, f64)]) -> Activity {
+    let mut activity = activity(id, "Run", start, distance, 3.0, 0.0);
+    activity.map = serde_json::from_value(serde_json::json!({
+        "id": format!("a{}", id),
+        "summary_polyline": encode(points)
+    }))
+    .unwrap();
+    activity
+}
+
+#[test]
+fn test_repeated_routes_groups_similar_tracks() {
+    // A loop of roughly 4 km around a park, and an out-and-back from the same start
+    let park = [
+        (52.5000, 13.4000),
+        (52.5090, 13.4000),
+        (52.5090, 13.4150),
+        (52.5000, 13.4150),
+        (52.5000, 13.4000),
+    ];
+    let nudged: Vec<(f64, f64)> = park.iter().map(|&(lat, lng)| (lat + 0.0004, lng)).collect();
+    let out_and_back = [(52.5000, 13.4000), (52.5180, 13.4000), (52.5000, 13.4000)];
+
+    let activities = [
+        mapped(1, "2024-03-01T08:00:00Z", 4020.0, &park),
+        mapped(2, "2024-03-08T08:00:00Z", 3990.0, &nudged),
+        mapped(3, "2024-03-09T08:00:00Z", 4000.0, &out_and_back),
+        mapped(4, "2024-03-15T08:00:00Z", 4050.0, &park),
+        // Same track, but much longer, e.g. a few laps
+        mapped(5, "2024-03-16T08:00:00Z", 12000.0, &park),
+        activity(6, "Run", "2024-03-17T08:00:00Z", 4000.0, 3.0, 0.0),
+    ];
+
+    let routes = analytics::repeated_routes(&activities);
+    assert_eq!(routes.len(), 1);
+    let ids: Vec<u64> = routes[0].iter().map(|a| a.id).collect();
+    assert_eq!(ids, [1, 2, 4]);
+}
+
+#[test]
+fn test_estimate_route_time() {
+    let since: DateTime<Utc> = "2024-03-01T00:00:00Z".parse().unwrap();
+    // 3 m/s on the flat and 600 m/h climbing, i.e. 6 s per meter climbed
+    let hilly = |id: u64, sport: &str, start: &str, distance: f64, climb: f64| {
+        let mut activity = activity(id, sport, start, distance, 3.0, climb);
+        activity.moving_time = (distance / 3.0 + climb * 6.0) as u32;
+        activity
+    };
+    let activities = [
+        hilly(1, "Run", "2024-03-02T08:00:00Z", 10000.0, 50.0),
+        hilly(2, "TrailRun", "2024-03-04T08:00:00Z", 15000.0, 600.0),
+        hilly(3, "Run", "2024-03-06T08:00:00Z", 6000.0, 10.0),
+        hilly(4, "Run", "2024-03-08T08:00:00Z", 21000.0, 250.0),
+        // Too old, and not a run
+        hilly(5, "Run", "2024-01-02T08:00:00Z", 42000.0, 0.0),
+        hilly(6, "Ride", "2024-03-03T08:00:00Z", 50000.0, 500.0),
+    ];
+
+    let estimate =
+        analytics::estimate_route_time(&activities, "Run", since, 12000.0, 300.0).unwrap();
+    assert_eq!(estimate.activities, 4);
+    assert!((estimate.flat_speed - 3.0).abs() < 0.01);
+    assert!((estimate.climb_rate.unwrap() - 600.0).abs() < 5.0);
+    assert!((estimate.moving_time - (4000.0 + 1800.0)).abs() < 5.0);
+
+    // Flat activities say nothing about climbing, so the average speed is used
+    let flat = [
+        activity(7, "Ride", "2024-03-02T08:00:00Z", 40000.0, 8.0, 0.0),
+        activity(8, "Ride", "2024-03-04T08:00:00Z", 60000.0, 8.0, 0.0),
+        activity(9, "Ride", "2024-03-06T08:00:00Z", 20000.0, 8.0, 0.0),
+    ];
+    let estimate = analytics::estimate_route_time(&flat, "Ride", since, 80000.0, 900.0).unwrap();
+    assert_eq!(estimate.climb_rate, None);
+    assert!((estimate.flat_speed - 8.0).abs() < 0.01);
+    assert!((estimate.moving_time - 10000.0).abs() < 1.0);
+
+    assert_eq!(
+        analytics::estimate_route_time(&flat[..2], "Ride", since, 80000.0, 900.0),
+        None
+    );
+}
+
+#[test]
+fn test_sport_breakdown() {
+    let activities = [
+        activity(1, "Run", "2024-03-01T08:00:00Z", 10000.0, 3.0, 50.0),
+        activity(2, "Run", "2024-03-05T08:00:00Z", 5000.0, 2.5, 20.0),
+        activity(3, "Ride", "2024-03-03T08:00:00Z", 40000.0, 8.0, 300.0),
+        activity(4, "WeightTraining", "2024-03-04T08:00:00Z", 0.0, 1.0, 0.0),
+    ];
+    let sports = analytics::sport_breakdown(&activities);
+    let names: Vec<&str> = sports.iter().map(|s| s.sport.as_str()).collect();
+    // Runs: 3333 + 2000 seconds, ride: 5000 seconds
+    assert_eq!(names, ["Run", "Ride", "WeightTraining"]);
+
+    let run = &sports[0];
+    assert_eq!(run.count, 2);
+    assert_eq!(run.distance, 15000.0);
+    assert_eq!(run.moving_time, 5333);
+    assert_eq!(run.elevation, 70.0);
+    assert_eq!(run.longest, 10000.0);
+    assert_eq!(
+        run.last,
+        "2024-03-05T08:00:00Z".parse::<DateTime<Utc>>().unwrap()
+    );
+    // Weighted by time: 15 km in 5333 s, not the mean of 3.0 and 2.5
+    assert!((run.average_speed().unwrap() - 2.8127).abs() < 0.001);
+    assert_eq!(sports[2].average_speed(), None);
+}
+
+#[test]
+fn test_climb_density() {
+    assert_eq!(analytics::climb_density(150.0, 10000.0), Some(0.015));
+    assert_eq!(analytics::climb_density(20.0, 800.0), None);
+
+    let activities = [
+        activity(1, "Ride", "2024-03-01T08:00:00Z", 40000.0, 8.0, 600.0),
+        activity(2, "Run", "2024-03-02T08:00:00Z", 10000.0, 3.0, 150.0),
+    ];
+    let totals = analytics::climb_totals(&activities);
+    assert_eq!(totals.count, 2);
+    assert_eq!(totals.elevation, 750.0);
+    assert_eq!(totals.distance, 50000.0);
+    assert_eq!(totals.density(), Some(0.015));
+}
+
+#[test]
+fn test_sport_groups() {
+    use sportfrei::analytics::SportGroup;
+
+    assert_eq!(SportGroup::of("MountainBikeRide"), SportGroup::Ride);
+    assert_eq!(SportGroup::of("EBikeRide"), SportGroup::EBike);
+    assert_eq!(SportGroup::of("BackcountrySki"), SportGroup::Winter);
+    assert_eq!(SportGroup::of("StandUpPaddling"), SportGroup::Water);
+    assert_eq!(SportGroup::of("Yoga"), SportGroup::Other);
+    // Every known type is spelled like Strava's
+    assert!(analytics::SPORT_TYPES
+        .iter()
+        .all(|sport| sport.chars().all(|c| c.is_ascii_alphabetic())));
+
+    assert!(analytics::is_on_foot("TrailRun"));
+    assert!(analytics::is_on_foot("VirtualRun"));
+    assert!(!analytics::is_on_foot("NordicSki"));
+}
+
+#[test]
+fn test_indoor_activities() {
+    let zwift = activity(
+        1,
+        "VirtualRide",
+        "2024-03-01T08:00:00Z",
+        30000.0,
+        9.0,
+        200.0,
+    );
+    let mut treadmill = activity(2, "Run", "2024-03-02T08:00:00Z", 8000.0, 3.0, 0.0);
+    let outside = activity(3, "Run", "2024-03-03T08:00:00Z", 8000.0, 3.0, 0.0);
+    treadmill.trainer = Some(true);
+
+    assert!(analytics::is_indoor(&zwift));
+    assert!(analytics::is_indoor(&treadmill));
+    assert!(!analytics::is_indoor(&outside));
+
+    // Virtual sports keep their own row instead of adding to the outdoor one
+    let sports = analytics::sport_breakdown([&zwift, &treadmill, &outside]);
+    let names: Vec<&str> = sports.iter().map(|s| s.sport.as_str()).collect();
+    assert_eq!(names, ["Run", "VirtualRide"]);
+}
+
+#[test]
+fn test_efficiency_metrics() {
+    use sportfrei::analytics::EfficiencyMetric;
+    use sportfrei::config::Units;
+
+    let mut run = activity(1, "Run", "2024-03-01T08:00:00Z", 10000.0, 3.125, 0.0);
+    run.average_heartrate = Some(160.0);
+    let value = |metric: EfficiencyMetric, activity: &Activity| {
+        metric.value(activity, None, Units::Metric).unwrap()
+    };
+    // 3200 s moving at 160 bpm
+    assert_eq!(value(EfficiencyMetric::TimePerBeat, &run), 20.0);
+    // 5:20 /km is 320 s
+    assert_eq!(value(EfficiencyMetric::PaceHr, &run), 2.0);
+    // 187.5 m/min
+    assert!((value(EfficiencyMetric::EfficiencyFactor, &run) - 1.1719).abs() < 0.001);
+
+    let mut ride = activity(2, "Ride", "2024-03-01T08:00:00Z", 40000.0, 8.0, 0.0);
+    ride.average_heartrate = Some(140.0);
+    ride.average_watts = Some(180.0);
+    ride.weighted_average_watts = Some(210.0);
+    assert_eq!(value(EfficiencyMetric::EfficiencyFactor, &ride), 1.5);
+
+    run.average_heartrate = None;
+    assert_eq!(
+        EfficiencyMetric::PaceHr.value(&run, None, Units::Metric),
+        None
+    );
+    assert_eq!(
+        EfficiencyMetric::AerobicDecoupling.value(&ride, None, Units::Metric),
+        None
+    );
+}
+
+#[test]
+fn test_aerobic_decoupling() {
+    use sportfrei::api::types::{Stream, StreamSet};
+
+    let stream = |data: Vec<f64>| Stream {
+        data,
+        series_type: Some("time".to_string()),
+        original_size: None,
+        resolution: None,
+    };
+    let streams = |speed: Vec<f64>, heartrate: Vec<f64>| StreamSet {
+        time: Some(Stream {
+            data: vec![0, 600, 1200, 1800],
+            series_type: Some("time".to_string()),
+            original_size: None,
+            resolution: None,
+        }),
+        velocity_smooth: Some(stream(speed)),
+        heartrate: Some(stream(heartrate)),
+        ..Default::default()
+    };
+
+    // Same speed, heart rate drifting up by 10%
+    let drifting = streams(vec![3.0; 4], vec![150.0, 150.0, 165.0, 165.0]);
+    let decoupling = analytics::aerobic_decoupling(&drifting).unwrap();
+    assert!((decoupling - 9.09).abs() < 0.01, "was {}", decoupling);
+
+    let steady = streams(vec![3.0; 4], vec![150.0; 4]);
+    assert_eq!(analytics::aerobic_decoupling(&steady), Some(0.0));
+
+    let no_heartrate = StreamSet {
+        heartrate: None,
+        ..steady
+    };
+    assert_eq!(analytics::aerobic_decoupling(&no_heartrate), None);
+
+    assert!(analytics::is_steady(&drifting));
+    assert_eq!(analytics::decoupling_label(9.09), "some drift");
+    // Intervals: fast repeats with slow recoveries
+    let intervals = streams(vec![5.0, 2.0, 5.0, 2.0], vec![150.0; 4]);
+    assert!(!analytics::is_steady(&intervals));
+}
+
+#[test]
+fn test_heart_rate_zones() {
+    use sportfrei::api::types::{Stream, StreamSet};
+
+    let streams = StreamSet {
+        // The 300 s gap is a pause and counts for no zone
+        time: Some(Stream {
+            data: vec![0, 10, 20, 30, 40, 340, 350],
+            series_type: Some("time".to_string()),
+            original_size: None,
+            resolution: None,
+        }),
+        heartrate: Some(Stream {
+            data: vec![100.0, 110.0, 125.0, 145.0, 185.0, 150.0, 0.0],
+            series_type: Some("time".to_string()),
+            original_size: None,
+            resolution: None,
+        }),
+        ..Default::default()
+    };
+    let zones = analytics::heart_rate_zones(&streams, 200.0).unwrap();
+    assert_eq!(zones.zone_type, "heartrate");
+    let times: Vec<f64> = zones.distribution_buckets.iter().map(|b| b.time).collect();
+    assert_eq!(times, vec![10.0, 10.0, 10.0, 0.0, 10.0]);
+    assert_eq!(zones.distribution_buckets[1].min, 120.0);
+    assert_eq!(zones.distribution_buckets[1].max, 140.0);
+    assert_eq!(zones.distribution_buckets[4].max, -1.0);
+    assert_eq!(zones.total_time(), 40.0);
+
+    let no_heartrate = StreamSet {
+        heartrate: None,
+        ..streams
+    };
+    assert!(analytics::heart_rate_zones(&no_heartrate, 200.0).is_none());
+}
+
+#[test]
+fn test_grade_adjusted_speed() {
+    use sportfrei::api::types::{Stream, StreamSet};
+
+    assert_eq!(analytics::grade_cost(0.0), 1.0);
+    assert!(analytics::grade_cost(0.1) > 1.5);
+    assert!(analytics::grade_cost(-0.05) < 1.0);
+    // Steep descents get expensive again
+    assert!(analytics::grade_cost(-0.4) > analytics::grade_cost(-0.15));
+
+    let stream = |data: Vec<f64>| Stream {
+        data,
+        series_type: Some("distance".to_string()),
+        original_size: None,
+        resolution: None,
+    };
+    // 300 m in 100 s, climbing 10%
+    let hill = |climb: f64| StreamSet {
+        time: Some(Stream {
+            data: (0..=10).map(|i| i * 10).collect(),
+            series_type: Some("distance".to_string()),
+            original_size: None,
+            resolution: None,
+        }),
+        distance: Some(stream((0..=10).map(|i| i as f64 * 30.0).collect())),
+        altitude: Some(stream((0..=10).map(|i| i as f64 * climb).collect())),
+        ..Default::default()
+    };
+
+    let flat = analytics::grade_adjusted_speed(&hill(0.0), 0.0..f64::INFINITY).unwrap();
+    assert!((flat - 3.0).abs() < 1e-9);
+    let uphill = analytics::grade_adjusted_speed(&hill(3.0), 0.0..f64::INFINITY).unwrap();
+    assert!((uphill - 4.97).abs() < 0.01, "was {}", uphill);
+    // The first half only
+    let half = analytics::grade_adjusted_speed(&hill(3.0), 0.0..150.0).unwrap();
+    assert!((half - uphill).abs() < 1e-9);
+    assert_eq!(
+        analytics::grade_adjusted_speed(&hill(3.0), 1000.0..2000.0),
+        None
+    );
+}
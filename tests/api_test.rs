@@ -1,53 +1,280 @@
 // Integration tests using mockito
-// These tests validate the API client logic using a mock HTTP server
-
-use mockito::Server;
+// These tests point the real API client at a mock HTTP server
+
+use chrono::{TimeZone, Utc};
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use reqwest::header::{HeaderMap, HeaderValue};
+use sportfrei::api::client::StravaClient;
+use sportfrei::api::types::DateRange;
+use sportfrei::config::{Config, RetryConfig, Units};
+use sportfrei::error::{rate_limit_reset, Error, RateLimit};
+use std::path::{Path, PathBuf};
+
+const ACTIVITY_1: &str = r#"{"id": 1, "name": "Activity 1", "type": "Run", "sport_type": "Run",
+    "start_date": "2024-01-15T08:30:00Z", "start_date_local": "2024-01-15T09:30:00+01:00",
+    "timezone": "Europe/Berlin", "distance": 5000.0, "moving_time": 1800,
+    "elapsed_time": 2000, "total_elevation_gain": 50.0}"#;
+const ACTIVITY_2: &str = r#"{"id": 2, "name": "Activity 2", "type": "Run", "sport_type": "Run",
+    "start_date": "2024-01-14T08:30:00Z", "start_date_local": "2024-01-14T09:30:00+01:00",
+    "timezone": "Europe/Berlin", "distance": 6000.0, "moving_time": 2000,
+    "elapsed_time": 2200, "total_elevation_gain": 60.0}"#;
+
+fn data_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sportfrei-api-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
 
-#[test]
-fn test_oauth_token_refresh() {
-    let mut server = Server::new();
+// A client for the mock server that keeps its token and HTTP cache in `dir`
+fn client(server: &ServerGuard, dir: &Path) -> StravaClient {
+    let config = Config {
+        client_id: Some("test_id".to_string()),
+        refresh_token: Some("test_refresh".to_string()),
+        // Failures surface right away instead of after backoff
+        retry: RetryConfig {
+            max_retries: 0,
+            ..RetryConfig::default()
+        },
+        ..Config::default()
+    };
+    StravaClient::from_config_in(&config, dir.join("config.toml"), dir)
+        .unwrap()
+        .with_api_url(server.url() + "/api/v3")
+        .with_token_url(server.url() + "/oauth/token")
+}
 
-    let mock = server
+// Returns the same refresh token, so the client has no reason to rewrite the config
+async fn token_mock(server: &mut ServerGuard) -> Mock {
+    server
         .mock("POST", "/oauth/token")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("client_id".into(), "test_id".into()),
+            Matcher::UrlEncoded("refresh_token".into(), "test_refresh".into()),
+            Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+        ]))
         .with_status(200)
         .with_body(
             r#"{
             "access_token": "test_access_token",
-            "refresh_token": "test_refresh_token",
+            "refresh_token": "test_refresh",
             "expires_at": 9999999999,
             "token_type": "Bearer"
         }"#,
         )
-        .create();
-
-    let client = reqwest::blocking::Client::new();
-
-    let response = client
-        .post(server.url() + "/oauth/token")
-        .form(&[
-            ("client_id", "test_id"),
-            ("client_secret", "test_secret"),
-            ("refresh_token", "test_refresh"),
-            ("grant_type", "refresh_token"),
-        ])
-        .send()
-        .unwrap();
+        .create_async()
+        .await
+}
 
-    assert_eq!(response.status(), 200);
+#[tokio::test]
+async fn test_oauth_token_refresh() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("token");
+    let token = token_mock(&mut server).await;
+    let athlete = server
+        .mock("GET", "/api/v3/athlete")
+        .match_header("authorization", "Bearer test_access_token")
+        .with_status(200)
+        .with_body(r#"{"id": 12345, "firstname": "Test", "lastname": "User"}"#)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = client(&server, &dir);
+    assert_eq!(client.get_athlete().await.unwrap().id, 12345);
+    // The second request reuses the token, which is also saved for the next start
+    client.get_athlete().await.unwrap();
+    assert!(std::fs::read_to_string(dir.join("token.toml"))
+        .unwrap()
+        .contains("test_access_token"));
+
+    token.assert_async().await;
+    athlete.assert_async().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_get_detailed_athlete() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("profile");
+    let _token = token_mock(&mut server).await;
+    let _athlete = server
+        .mock("GET", "/api/v3/athlete")
+        .with_status(200)
+        .with_body(
+            r#"{"id": 12345, "firstname": "Test", "lastname": "User", "city": "Hamburg",
+                "state": null, "summit": true, "follower_count": 10, "friend_count": 7,
+                "measurement_preference": "meters", "ftp": 250, "weight": 70.0,
+                "created_at": "2015-04-01T10:00:00Z",
+                "clubs": [{"id": 1, "name": "Alster Runners"}],
+                "bikes": [{"id": "b1", "name": "Road", "distance": 1000.0}]}"#,
+        )
+        .create_async()
+        .await;
+
+    let profile = client(&server, &dir).get_detailed_athlete().await.unwrap();
+    assert_eq!(profile.athlete.id, 12345);
+    assert_eq!(profile.athlete.city.as_deref(), Some("Hamburg"));
+    assert_eq!(profile.athlete.bikes.len(), 1);
+    assert_eq!(profile.state, None);
+    assert_eq!(profile.summit, Some(true));
+    assert_eq!(profile.follower_count, Some(10));
+    assert_eq!(profile.ftp, Some(250));
+    assert_eq!(profile.weight, Some(70.0));
+    assert_eq!(profile.clubs[0].name, "Alster Runners");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
 
-    let token: serde_json::Value = response.json().unwrap();
-    assert_eq!(token["access_token"], "test_access_token");
-    assert_eq!(token["refresh_token"], "test_refresh_token");
+#[tokio::test]
+async fn test_following_feed() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("following");
+    let _token = token_mock(&mut server).await;
+    let feed = server
+        .mock("GET", "/api/v3/activities/following")
+        .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
+        .with_status(200)
+        .with_body(
+            r#"[{"id": 7, "athlete": {"id": 3, "firstname": "Sam", "lastname": "B."},
+                 "name": "Long run", "sport_type": "Run", "start_date": "2024-06-30T07:00:00Z",
+                 "distance": 21000.0, "moving_time": 7000, "kudos_count": 4, "has_kudoed": true}]"#,
+        )
+        .create_async()
+        .await;
+    let _hidden = server
+        .mock("GET", "/api/v3/activities/following")
+        .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+        .with_status(404)
+        .with_body(r#"{"message": "Record Not Found", "errors": []}"#)
+        .create_async()
+        .await;
+
+    let client = client(&server, &dir);
+    let activities = client.get_following_activities(1, 30).await.unwrap().items;
+    assert_eq!(activities[0].id, 7);
+    assert_eq!(activities[0].athlete.firstname, "Sam");
+    assert_eq!(activities[0].kudos_count, 4);
+    assert!(activities[0].has_kudoed);
+    // Apps without access to the feed get a 404, which the social view reports as such
+    assert!(matches!(
+        client.get_following_activities(2, 30).await,
+        Err(Error::NotFound(_))
+    ));
+
+    feed.assert_async().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+}
 
-    mock.assert();
+#[tokio::test]
+async fn test_get_activity_zones() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("zones");
+    let _token = token_mock(&mut server).await;
+    let zones = server
+        .mock("GET", "/api/v3/activities/1/zones")
+        .with_status(200)
+        .with_body(
+            r#"[{"score": 54, "type": "heartrate", "sensor_based": true, "custom_zones": false,
+                 "distribution_buckets": [{"min": 0, "max": 123, "time": 64},
+                                          {"min": 123, "max": 153, "time": 1708},
+                                          {"min": 153, "max": -1, "time": 28}]},
+                {"type": "power", "sensor_based": null,
+                 "distribution_buckets": [{"min": 0, "max": 135, "time": 900}]}]"#,
+        )
+        .create_async()
+        .await;
+    let _locked = server
+        .mock("GET", "/api/v3/activities/2/zones")
+        .with_status(402)
+        .with_body(r#"{"message": "Payment Required", "errors": []}"#)
+        .create_async()
+        .await;
+
+    let client = client(&server, &dir);
+    let result = client.get_activity_zones(1).await.unwrap();
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[0].zone_type, "heartrate");
+    assert_eq!(result[0].score, Some(54.0));
+    assert_eq!(result[0].distribution_buckets[2].max, -1.0);
+    assert_eq!(result[0].total_time(), 1800.0);
+    assert_eq!(result[1].zone_type, "power");
+    assert!(!result[1].sensor_based);
+    // Zones are a subscriber feature; the detail view shows none for others
+    assert!(matches!(
+        client.get_activity_zones(2).await,
+        Err(Error::Api { status: 402, .. })
+    ));
+
+    zones.assert_async().await;
+    std::fs::remove_dir_all(&dir).unwrap();
 }
 
-#[test]
-fn test_api_error_handling() {
-    let mut server = Server::new();
+#[tokio::test]
+async fn test_saved_routes_and_gpx_export() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("routes");
+    let _token = token_mock(&mut server).await;
+    let routes = server
+        .mock("GET", "/api/v3/athletes/12345/routes")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("page".into(), "1".into()),
+            Matcher::UrlEncoded("per_page".into(), "200".into()),
+        ]))
+        .with_status(200)
+        .with_body(
+            r#"[{"id": 42, "name": "Havel loop", "distance": 68400.0,
+                 "elevation_gain": 412.0, "type": 1, "starred": false}]"#,
+        )
+        .create_async()
+        .await;
+    let gpx = server
+        .mock("GET", "/api/v3/routes/42/export_gpx")
+        .with_status(200)
+        .with_header("content-type", "application/gpx+xml")
+        .with_body("<gpx><trk><name>Havel loop</name></trk></gpx>")
+        .create_async()
+        .await;
+
+    let client = client(&server, &dir);
+    let saved = client.get_routes(12345, 1, 200).await.unwrap();
+    assert_eq!(saved[0].id, 42);
+    assert_eq!(saved[0].sport(), "Ride");
+    assert_eq!(saved[0].elevation_gain, 412.0);
+    assert_eq!(
+        client.export_route_gpx(42).await.unwrap(),
+        b"<gpx><trk><name>Havel loop</name></trk></gpx>"
+    );
+
+    // Route streams come as a list rather than keyed by type
+    let _streams = server
+        .mock("GET", "/api/v3/routes/42/streams")
+        .with_status(200)
+        .with_body(
+            r#"[{"type": "latlng", "data": [[52.5, 13.4], [52.6, 13.5]]},
+                {"type": "distance", "data": [0.0, 68400.0], "series_type": "distance"},
+                {"type": "altitude", "data": [34.0, 61.5], "series_type": "distance"}]"#,
+        )
+        .create_async()
+        .await;
+    let streams = client.get_route_streams(42).await.unwrap();
+    assert_eq!(streams.distance.unwrap().data, vec![0.0, 68400.0]);
+    assert_eq!(streams.altitude.unwrap().data, vec![34.0, 61.5]);
+    assert_eq!(streams.latlng.unwrap().data[1], [52.6, 13.5]);
+    assert!(streams.time.is_none());
+
+    routes.assert_async().await;
+    gpx.assert_async().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+}
 
-    let mock = server
+#[tokio::test]
+async fn test_api_error_handling() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("error");
+    // Refreshed once more after the 401, in case the token had just expired
+    let token = token_mock(&mut server).await.expect(2);
+    let _activities = server
         .mock("GET", "/api/v3/athlete/activities")
+        .match_query(Matcher::Any)
         .with_status(401)
         .with_body(
             r#"{
@@ -59,202 +286,393 @@ fn test_api_error_handling() {
             }]
         }"#,
         )
-        .create();
-
-    let client = reqwest::blocking::Client::new();
-
-    let response = client
-        .get(server.url() + "/api/v3/athlete/activities")
-        .header("Authorization", "Bearer invalid_token")
-        .send()
-        .unwrap();
-
-    assert_eq!(response.status(), 401);
-
-    let body: serde_json::Value = response.json().unwrap();
-    assert_eq!(body["message"], "Authorization Error");
-    assert_eq!(body["errors"][0]["code"], "missing");
-
-    mock.assert();
+        .create_async()
+        .await;
+
+    let error = client(&server, &dir)
+        .get_activities(1, 30, DateRange::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(&error, Error::MissingScope(scope) if scope == "activity:read"));
+    assert!(error.needs_reauth());
+
+    token.assert_async().await;
+    std::fs::remove_dir_all(&dir).unwrap();
 }
 
-#[test]
-fn test_pagination() {
-    let mut server = Server::new();
-
-    let mock_p1 = server
+#[tokio::test]
+async fn test_pagination() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("pagination");
+    let _token = token_mock(&mut server).await;
+    let page = server
         .mock("GET", "/api/v3/athlete/activities")
-        .match_query(mockito::Matcher::Any)
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("page".into(), "1".into()),
+            Matcher::UrlEncoded("per_page".into(), "2".into()),
+            Matcher::UrlEncoded("after".into(), "1700000000".into()),
+        ]))
         .with_status(200)
-        .with_body(
-            r#"[
-            {"id": 1, "name": "Activity 1", "type": "Run", "sport_type": "Run", 
-             "start_date": "2024-01-15T08:30:00Z", "start_date_local": "2024-01-15T09:30:00+01:00",
-             "timezone": "Europe/Berlin", "distance": 5000.0, "moving_time": 1800, 
-             "elapsed_time": 2000, "total_elevation_gain": 50.0},
-            {"id": 2, "name": "Activity 2", "type": "Run", "sport_type": "Run",
-             "start_date": "2024-01-14T08:30:00Z", "start_date_local": "2024-01-14T09:30:00+01:00", 
-             "timezone": "Europe/Berlin", "distance": 6000.0, "moving_time": 2000,
-             "elapsed_time": 2200, "total_elevation_gain": 60.0}
-        ]"#,
-        )
-        .create();
+        .with_body(format!("[{}, {}]", ACTIVITY_1, ACTIVITY_2))
+        .create_async()
+        .await;
+
+    let range = DateRange {
+        after: Some(1_700_000_000),
+        before: None,
+    };
+    let activities = client(&server, &dir)
+        .get_activities(1, 2, range)
+        .await
+        .unwrap()
+        .items;
+    assert_eq!(activities.len(), 2);
+    assert_eq!(activities[1].name, "Activity 2");
 
-    let client = reqwest::blocking::Client::new();
+    page.assert_async().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+}
 
-    let resp_p1 = client
-        .get(server.url() + "/api/v3/athlete/activities")
-        .header("Authorization", "Bearer token")
-        .query(&[("page", "1"), ("per_page", "2")])
-        .send()
+#[tokio::test]
+async fn test_malformed_activity_is_skipped() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("malformed");
+    let _token = token_mock(&mut server).await;
+    let _page = server
+        .mock("GET", "/api/v3/athlete/activities")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(format!(
+            r#"[{}, {{"id": "broken"}}, {}]"#,
+            ACTIVITY_1, ACTIVITY_2
+        ))
+        .create_async()
+        .await;
+
+    let page = client(&server, &dir)
+        .get_activities(1, 3, DateRange::default())
+        .await
         .unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[1].id, 2);
+    // Still a full page, so the list goes on
+    assert_eq!(page.skipped(), 1);
+    assert!(!page.is_last(3));
 
-    let activities: Vec<serde_json::Value> = resp_p1.json().unwrap();
-    assert_eq!(activities.len(), 2);
-
-    mock_p1.assert();
+    std::fs::remove_dir_all(&dir).unwrap();
 }
 
-#[test]
-fn test_infinite_scroll_pagination() {
+#[tokio::test]
+async fn test_infinite_scroll_pagination() {
     // Test loading multiple pages of activities (simulating infinite scroll)
-    let mut server = Server::new();
+    let mut server = Server::new_async().await;
+    let dir = data_dir("scroll");
+    let _token = token_mock(&mut server).await;
+    let pages = [
+        ("1", format!("[{}]", ACTIVITY_1)),
+        ("2", format!("[{}]", ACTIVITY_2)),
+        // End of list
+        ("3", "[]".to_string()),
+    ];
+    let mut mocks = Vec::new();
+    for (page, body) in pages {
+        mocks.push(
+            server
+                .mock("GET", "/api/v3/athlete/activities")
+                .match_query(Matcher::AllOf(vec![
+                    Matcher::UrlEncoded("page".into(), page.into()),
+                    Matcher::UrlEncoded("per_page".into(), "30".into()),
+                ]))
+                .with_status(200)
+                .with_body(body)
+                .create_async()
+                .await,
+        );
+    }
+
+    let client = client(&server, &dir);
+    let mut ids = Vec::new();
+    for page in 1.. {
+        let activities = client
+            .get_activities(page, 30, DateRange::default())
+            .await
+            .unwrap()
+            .items;
+        if activities.is_empty() {
+            break;
+        }
+        ids.extend(activities.iter().map(|a| a.id));
+    }
+    assert_eq!(ids, vec![1, 2]);
+
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
 
-    // Mock page 1
-    let _mock_page1 = server
+#[tokio::test]
+async fn test_pagination_auth_error() {
+    // Test error handling when access is revoked during pagination
+    let mut server = Server::new_async().await;
+    let dir = data_dir("revoked");
+    let _token = token_mock(&mut server).await;
+    let _first = server
         .mock("GET", "/api/v3/athlete/activities")
-        .match_query(mockito::Matcher::AllOf(vec![
-            mockito::Matcher::UrlEncoded("page".into(), "1".into()),
-            mockito::Matcher::UrlEncoded("per_page".into(), "30".into()),
-        ]))
+        .match_query(Matcher::UrlEncoded("page".into(), "1".into()))
         .with_status(200)
-        .with_body(
-            r#"[
-            {"id": 1, "name": "Activity 1", "type": "Run", "sport_type": "Run", 
-             "start_date": "2024-01-15T08:30:00Z", "start_date_local": "2024-01-15T09:30:00+01:00",
-             "timezone": "Europe/Berlin", "distance": 5000.0, "moving_time": 1800, 
-             "elapsed_time": 2000, "total_elevation_gain": 50.0}
-        ]"#,
-        )
-        .create();
-
-    // Mock page 2
-    let _mock_page2 = server
+        .with_body(format!("[{}]", ACTIVITY_1))
+        .create_async()
+        .await;
+    let _second = server
         .mock("GET", "/api/v3/athlete/activities")
-        .match_query(mockito::Matcher::AllOf(vec![
-            mockito::Matcher::UrlEncoded("page".into(), "2".into()),
-            mockito::Matcher::UrlEncoded("per_page".into(), "30".into()),
-        ]))
+        .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+        .with_status(401)
+        .with_body(r#"{"message": "Authorization Error", "errors": []}"#)
+        .create_async()
+        .await;
+
+    let client = client(&server, &dir);
+    assert_eq!(
+        client
+            .get_activities(1, 30, DateRange::default())
+            .await
+            .unwrap()
+            .items
+            .len(),
+        1
+    );
+    let error = client
+        .get_activities(2, 30, DateRange::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(&error, Error::Auth(message) if message == "Authorization Error"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_etag_revalidation() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("etag");
+    let _token = token_mock(&mut server).await;
+
+    let full = server
+        .mock("GET", "/api/v3/athlete")
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"abc123\"")
+        .with_body(r#"{"id": 12345, "firstname": "Test", "lastname": "User"}"#)
+        .create_async()
+        .await;
+    let not_modified = server
+        .mock("GET", "/api/v3/athlete")
+        .match_header("if-none-match", "\"abc123\"")
+        .with_status(304)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let first = client(&server, &dir);
+    assert_eq!(first.get_athlete().await.unwrap().id, 12345);
+    assert_eq!(first.get_athlete().await.unwrap().id, 12345);
+    // The cache survives a restart
+    let restarted = client(&server, &dir);
+    assert_eq!(restarted.get_athlete().await.unwrap().firstname, "Test");
+
+    full.assert_async().await;
+    not_modified.assert_async().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_error_classes() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("classes");
+    let _token = token_mock(&mut server).await;
+    let _missing = server
+        .mock("GET", "/api/v3/activities/1")
+        .with_status(404)
+        .with_body(r#"{"message": "Record Not Found", "errors": []}"#)
+        .create_async()
+        .await;
+    let _limited = server
+        .mock("GET", "/api/v3/activities/2")
+        .with_status(429)
+        .with_header("x-ratelimit-limit", "100,1000")
+        .with_header("x-ratelimit-usage", "100,640")
+        .create_async()
+        .await;
+    let _forbidden = server
+        .mock("DELETE", "/api/v3/activities/3")
+        .with_status(403)
+        .create_async()
+        .await;
+
+    let client = client(&server, &dir);
+    let error = client.get_activity(1).await.unwrap_err();
+    assert!(matches!(error, Error::NotFound(_)));
+    assert!(!error.is_transient());
+
+    let error = client.get_activity(2).await.unwrap_err();
+    assert!(matches!(error, Error::RateLimited { reset: Some(_) }));
+    assert!(error.is_transient());
+
+    let error = client.delete_activity(3).await.unwrap_err();
+    assert!(matches!(&error, Error::MissingScope(scope) if scope == "activity:write"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_rotated_refresh_token_is_saved() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("rotated-token");
+    let _token = server
+        .mock("POST", "/oauth/token")
         .with_status(200)
         .with_body(
-            r#"[
-            {"id": 31, "name": "Activity 31", "type": "Run", "sport_type": "Run", 
-             "start_date": "2024-01-14T08:30:00Z", "start_date_local": "2024-01-14T09:30:00+01:00",
-             "timezone": "Europe/Berlin", "distance": 6000.0, "moving_time": 2000, 
-             "elapsed_time": 2200, "total_elevation_gain": 60.0}
-        ]"#,
+            r#"{
+            "access_token": "test_access_token",
+            "refresh_token": "rotated_refresh",
+            "expires_at": 9999999999,
+            "token_type": "Bearer"
+        }"#,
         )
-        .create();
-
-    // Mock page 3 - returns empty (end of list)
-    let _mock_page3 = server
-        .mock("GET", "/api/v3/athlete/activities")
-        .match_query(mockito::Matcher::AllOf(vec![
-            mockito::Matcher::UrlEncoded("page".into(), "3".into()),
-            mockito::Matcher::UrlEncoded("per_page".into(), "30".into()),
-        ]))
+        .create_async()
+        .await;
+    let _athlete = server
+        .mock("GET", "/api/v3/athlete")
         .with_status(200)
-        .with_body("[]")
-        .create();
-
-    let client = reqwest::blocking::Client::new();
+        .with_body(r#"{"id": 12345, "firstname": "Test", "lastname": "User"}"#)
+        .create_async()
+        .await;
+    // Written to the client's config file, not the one in the user's config directory
+    let config_path = dir.join("config.toml");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        &config_path,
+        "plaintext_secrets = true\nunits = \"imperial\"\n",
+    )
+    .unwrap();
+
+    client(&server, &dir).get_athlete().await.unwrap();
+    let config = Config::load_at(&config_path).unwrap();
+    assert_eq!(config.refresh_token.as_deref(), Some("rotated_refresh"));
+    // Other settings in the file are kept
+    assert_eq!(config.units, Units::Imperial);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
 
-    // Load page 1
-    let resp1 = client
-        .get(server.url() + "/api/v3/athlete/activities")
-        .header("Authorization", "Bearer token")
-        .query(&[("page", "1"), ("per_page", "30")])
-        .send()
-        .unwrap();
-    let activities1: Vec<serde_json::Value> = resp1.json().unwrap();
-    assert_eq!(activities1.len(), 1);
-    assert_eq!(activities1[0]["id"], 1);
-
-    // Load page 2 (simulating infinite scroll trigger)
-    let resp2 = client
-        .get(server.url() + "/api/v3/athlete/activities")
-        .header("Authorization", "Bearer token")
-        .query(&[("page", "2"), ("per_page", "30")])
-        .send()
-        .unwrap();
-    let activities2: Vec<serde_json::Value> = resp2.json().unwrap();
-    assert_eq!(activities2.len(), 1);
-    assert_eq!(activities2[0]["id"], 31);
-
-    // Load page 3 (empty - end of list)
-    let resp3 = client
-        .get(server.url() + "/api/v3/athlete/activities")
-        .header("Authorization", "Bearer token")
-        .query(&[("page", "3"), ("per_page", "30")])
-        .send()
-        .unwrap();
-    let activities3: Vec<serde_json::Value> = resp3.json().unwrap();
-    assert_eq!(activities3.len(), 0);
+#[tokio::test]
+async fn test_revoked_refresh_token() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("revoked-token");
+    let _token = server
+        .mock("POST", "/oauth/token")
+        .with_status(400)
+        .with_body(r#"{"message": "Bad Request", "errors": [{"resource": "RefreshToken", "field": "refresh_token", "code": "invalid"}]}"#)
+        .create_async()
+        .await;
+
+    let error = client(&server, &dir).get_athlete().await.unwrap_err();
+    assert!(matches!(error, Error::Auth(_)));
+    assert!(error.needs_reauth());
 }
 
 #[test]
-fn test_pagination_auth_error() {
-    // Test error handling when token expires during pagination
-    let mut server = Server::new();
+fn test_rate_limit_reset() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 18, 14, 7, 30).unwrap();
+    let headers = |limit: &str, usage: &str| {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from_str(limit).unwrap());
+        headers.insert("x-ratelimit-usage", HeaderValue::from_str(usage).unwrap());
+        headers
+    };
+
+    // The 15 minute window resets at the next quarter hour, the daily one at midnight UTC
+    assert_eq!(
+        rate_limit_reset(&headers("100,1000", "100,500"), now),
+        Some(Utc.with_ymd_and_hms(2024, 3, 18, 14, 15, 0).unwrap())
+    );
+    assert_eq!(
+        rate_limit_reset(&headers("100,1000", "20,1000"), now),
+        Some(Utc.with_ymd_and_hms(2024, 3, 19, 0, 0, 0).unwrap())
+    );
+    assert_eq!(rate_limit_reset(&headers("100,1000", "20,500"), now), None);
+    assert_eq!(rate_limit_reset(&HeaderMap::new(), now), None);
+}
 
-    // First request succeeds
-    let _mock_success = server
+#[tokio::test]
+async fn test_rate_limit_tracking() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("rate-limit");
+    let _token = token_mock(&mut server).await;
+    let _page = server
         .mock("GET", "/api/v3/athlete/activities")
-        .match_query(mockito::Matcher::AllOf(vec![mockito::Matcher::UrlEncoded(
-            "page".into(),
-            "1".into(),
-        )]))
+        .match_query(Matcher::Any)
         .with_status(200)
-        .with_body(
-            r#"[{"id": 1, "name": "Activity 1", "type": "Run", "sport_type": "Run", 
-             "start_date": "2024-01-15T08:30:00Z", "start_date_local": "2024-01-15T09:30:00+01:00",
-             "timezone": "Europe/Berlin", "distance": 5000.0, "moving_time": 1800, 
-             "elapsed_time": 2000, "total_elevation_gain": 50.0}]"#,
-        )
-        .create();
-
-    // Second request fails with 401 (expired token)
-    let _mock_expired = server.mock("GET", "/api/v3/athlete/activities")
-        .match_query(mockito::Matcher::AllOf(vec![
-            mockito::Matcher::UrlEncoded("page".into(), "2".into()),
-        ]))
-        .with_status(401)
-        .with_body(r#"{
-            "message": "Authorization Error",
-            "errors": [{"resource": "AccessToken", "field": "activity:read_permission", "code": "missing"}]
-        }"#)
-        .create();
-
-    let client = reqwest::blocking::Client::new();
-
-    // First page works
-    let resp1 = client
-        .get(server.url() + "/api/v3/athlete/activities")
-        .header("Authorization", "Bearer valid_token")
-        .query(&[("page", "1")])
-        .send()
+        .with_header("x-ratelimit-limit", "100,1000")
+        .with_header("x-ratelimit-usage", "12,340")
+        .with_body(format!("[{}]", ACTIVITY_1))
+        .create_async()
+        .await;
+
+    let client = client(&server, &dir);
+    assert_eq!(client.rate_limit(), None);
+    client
+        .get_activities(1, 30, DateRange::default())
+        .await
         .unwrap();
-    assert_eq!(resp1.status(), 200);
-
-    // Second page fails with auth error
-    let resp2 = client
-        .get(server.url() + "/api/v3/athlete/activities")
-        .header("Authorization", "Bearer expired_token")
-        .query(&[("page", "2")])
-        .send()
-        .unwrap();
-    assert_eq!(resp2.status(), 401);
+    assert_eq!(
+        client.rate_limit(),
+        Some(RateLimit {
+            short_remaining: 88,
+            daily_remaining: 660,
+        })
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
 
-    let body: serde_json::Value = resp2.json().unwrap();
-    assert_eq!(body["message"], "Authorization Error");
+#[tokio::test]
+async fn test_server_errors_retry_only_gets() {
+    let mut server = Server::new_async().await;
+    let dir = data_dir("retry");
+    let _token = token_mock(&mut server).await;
+    let athlete = server
+        .mock("GET", "/api/v3/athlete")
+        .with_status(503)
+        .expect(3)
+        .create_async()
+        .await;
+    let delete = server
+        .mock("DELETE", "/api/v3/activities/1")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let config = Config {
+        client_id: Some("test_id".to_string()),
+        refresh_token: Some("test_refresh".to_string()),
+        retry: RetryConfig {
+            max_retries: 2,
+            base_delay_ms: 1,
+            max_delay_ms: 1,
+        },
+        ..Config::default()
+    };
+    let client = StravaClient::from_config_in(&config, dir.join("config.toml"), &dir)
+        .unwrap()
+        .with_api_url(server.url() + "/api/v3")
+        .with_token_url(server.url() + "/oauth/token");
+    assert!(client.get_athlete().await.is_err());
+    // The delete may have gone through, so it isn't sent again
+    assert!(client.delete_activity(1).await.is_err());
+
+    athlete.assert_async().await;
+    delete.assert_async().await;
+    std::fs::remove_dir_all(&dir).unwrap();
 }
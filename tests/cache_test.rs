@@ -0,0 +1,242 @@
+mod common;
+
+use sportfrei::api::types::{Activity, DetailedActivity, Stream, StreamSet};
+use sportfrei::cache::{
+    ActivityCache, BestEffortCache, DetailCache, NoteStore, SyncChanges, SyncState, TagStore,
+};
+use std::path::PathBuf;
+
+fn temp_cache_path(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("sportfrei-cache-{}-{}", name, std::process::id()))
+        .join("activities.json")
+}
+
+fn activity(id: u64, name: &str, start: &str) -> Activity {
+    common::activity_with(
+        id,
+        "Run",
+        start,
+        5000.0,
+        1500,
+        serde_json::json!({"name": name, "elapsed_time": 1600, "total_elevation_gain": 12.0}),
+    )
+}
+
+#[test]
+fn test_cache_merge_replaces_by_id() {
+    let mut cache = ActivityCache::default();
+    assert!(cache.is_empty());
+
+    let added = cache.merge([
+        activity(1, "Old", "2024-01-01T08:00:00Z"),
+        activity(2, "Newer", "2024-02-01T08:00:00Z"),
+    ]);
+    assert_eq!(added, 2);
+
+    let added = cache.merge([activity(1, "Renamed", "2024-01-01T08:00:00Z")]);
+    assert_eq!(added, 0);
+    assert_eq!(cache.len(), 2);
+
+    let activities = cache.activities();
+    assert_eq!(activities[0].name, "Newer");
+    assert_eq!(activities[1].name, "Renamed");
+}
+
+#[test]
+fn test_cache_round_trip() {
+    let path = temp_cache_path("roundtrip");
+    assert!(ActivityCache::load_from(&path).unwrap().is_empty());
+
+    let mut cache = ActivityCache::default();
+    cache.merge([activity(7, "Tempo", "2024-03-01T08:00:00Z")]);
+    cache.save_to(&path).unwrap();
+
+    let loaded = ActivityCache::load_from(&path).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded.activities()[0].name, "Tempo");
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_best_effort_cache_round_trip() {
+    let path = temp_cache_path("efforts").with_file_name("best_efforts.json");
+    let effort: sportfrei::api::types::BestEffort = serde_json::from_value(serde_json::json!({
+        "id": 9, "name": "5k",
+        "activity": {"id": 1, "resource_state": 1},
+        "athlete": {"id": 42, "resource_state": 1},
+        "elapsed_time": 1290, "moving_time": 1290,
+        "start_date": "2024-01-01T08:00:00Z", "start_date_local": "2024-01-01T09:00:00Z",
+        "distance": 5000.0, "pr_rank": 1
+    }))
+    .unwrap();
+
+    let mut cache = BestEffortCache::default();
+    cache.insert(1, vec![effort]);
+    // A ride has no best efforts but is still recorded as fetched
+    cache.insert(2, Vec::new());
+    cache.save_to(&path).unwrap();
+
+    let reloaded = BestEffortCache::load_from(&path).unwrap();
+    assert!(reloaded.contains(1));
+    assert!(reloaded.contains(2));
+    assert!(!reloaded.contains(3));
+    let efforts: Vec<_> = reloaded.iter().collect();
+    assert_eq!(efforts[0].1[0].elapsed_time, 1290);
+    assert!(efforts[1].1.is_empty());
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_tag_store_round_trip() {
+    let path = temp_cache_path("tags").with_file_name("tags.json");
+    let tags = TagStore::parse("#Race, easy  workout,");
+    assert_eq!(
+        tags.iter().map(String::as_str).collect::<Vec<_>>(),
+        ["easy", "race", "workout"]
+    );
+
+    let mut store = TagStore::default();
+    store.set(1, tags);
+    store.set(2, TagStore::parse("long"));
+    // Clearing the tags forgets the activity
+    store.set(2, TagStore::parse(" "));
+    store.save_to(&path).unwrap();
+
+    let reloaded = TagStore::load_from(&path).unwrap();
+    assert_eq!(reloaded.get(1).unwrap().len(), 3);
+    assert!(reloaded.get(2).is_none());
+    assert_eq!(reloaded.iter().count(), 1);
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_note_store_round_trip() {
+    let path = temp_cache_path("notes").with_file_name("notes.json");
+    let mut store = NoteStore::default();
+    store.set(
+        1,
+        "Legs heavy after yesterday.\nStill held 5:00/km.".to_string(),
+    );
+    store.set(2, "Easy".to_string());
+    store.set(2, "  \n".to_string());
+    store.save_to(&path).unwrap();
+
+    let reloaded = NoteStore::load_from(&path).unwrap();
+    assert_eq!(
+        reloaded.get(1),
+        Some("Legs heavy after yesterday.\nStill held 5:00/km.")
+    );
+    assert_eq!(reloaded.get(2), None);
+    assert_eq!(reloaded.iter().count(), 1);
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_sync_state_resumes_unfinished_sync() {
+    let path = temp_cache_path("sync").with_file_name("sync.json");
+    assert_eq!(SyncState::load_from(&path).unwrap(), None);
+
+    let mut state = SyncState::new(200);
+    state.next_page = 7;
+    state.save_to(&path).unwrap();
+    let saved = SyncState::load_from(&path).unwrap();
+    assert_eq!(saved, Some(state.clone()));
+    assert_eq!(SyncState::resume(saved.clone(), 200).next_page, 7);
+
+    // A different page size shifts every page, and a finished sync starts over
+    assert_eq!(SyncState::resume(saved, 100).next_page, 1);
+    state.complete = true;
+    assert_eq!(SyncState::resume(Some(state), 200), SyncState::new(200));
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_update_detects_new_edited_and_deleted_activities() {
+    let mut cache = ActivityCache::default();
+    cache.merge(vec![
+        activity(1, "Old Run", "2024-01-05T08:00:00Z"),
+        activity(2, "Recent Run", "2024-03-01T08:00:00Z"),
+        activity(3, "Double Upload", "2024-03-02T08:00:00Z"),
+    ]);
+
+    // Strava's list since March: 2 was renamed, 3 deleted, 4 is new
+    let fetched = vec![
+        activity(2, "Tempo Run", "2024-03-01T08:00:00Z"),
+        activity(4, "Long Run", "2024-03-03T08:00:00Z"),
+    ];
+    let seen = fetched.iter().map(|a| a.id).collect();
+    let mut changes = cache.update(fetched);
+    let since = "2024-02-15T00:00:00Z".parse().unwrap();
+    changes.removed = cache.remove_missing(&seen, Some(since));
+    assert_eq!(
+        changes,
+        SyncChanges {
+            added: 1,
+            updated: 1,
+            removed: 1
+        }
+    );
+
+    // Older activities weren't checked, so they stay
+    let names: Vec<String> = cache.activities().into_iter().map(|a| a.name).collect();
+    assert_eq!(names, ["Long Run", "Tempo Run", "Old Run"]);
+
+    // Unchanged activities don't count as updates
+    let again = cache.update(vec![activity(4, "Long Run", "2024-03-03T08:00:00Z")]);
+    assert_eq!(again, SyncChanges::default());
+}
+
+#[test]
+fn test_detail_cache_misses_after_the_activity_changed() {
+    let dir = temp_cache_path("details").with_file_name("details");
+    let details = DetailCache::in_dir(dir.clone());
+    let run = activity(1, "Morning Run", "2024-03-01T08:00:00Z");
+    assert!(details.detail(&run).is_none());
+
+    let detail = DetailedActivity {
+        activity: run.clone(),
+        segment_efforts: None,
+        splits_metric: Some(Vec::new()),
+        splits_standard: None,
+        laps: None,
+        best_efforts: None,
+        average_temp: None,
+        device_name: None,
+        embed_token: None,
+    };
+    let streams = StreamSet {
+        heartrate: Some(Stream {
+            data: vec![120.0, 140.0],
+            series_type: Some("distance".to_string()),
+            original_size: None,
+            resolution: None,
+        }),
+        ..Default::default()
+    };
+    details.save_detail(&detail).unwrap();
+    details.save_streams(&run, &streams).unwrap();
+    assert_eq!(details.detail(&run).unwrap().activity.name, "Morning Run");
+    assert_eq!(
+        details.streams(&run).unwrap().heartrate.unwrap().data,
+        [120.0, 140.0]
+    );
+
+    // Renamed on Strava since: fetch again
+    let renamed = Activity {
+        name: "Tempo Run".to_string(),
+        ..run.clone()
+    };
+    assert!(details.detail(&renamed).is_none());
+    assert!(details.streams(&renamed).is_none());
+
+    details.remove(1);
+    assert!(details.detail(&run).is_none());
+
+    std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,278 @@
+use clap::Parser;
+use sportfrei::api::types::{Activity, UploadDataType};
+use sportfrei::cli::{format_activity, ArchiveFormat, Cli, Command, OutputFormat};
+use sportfrei::config::Units;
+use sportfrei::export::Column;
+
+#[test]
+fn test_no_arguments_starts_tui() {
+    let cli = Cli::try_parse_from(["sportfrei"]).unwrap();
+    assert!(cli.command.is_none());
+}
+
+#[test]
+fn test_parse_subcommands() {
+    let cli = Cli::try_parse_from(["sportfrei", "activities", "--last", "25", "--json"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Activities {
+            last: 25,
+            json: true,
+            ..
+        })
+    ));
+
+    let cli = Cli::try_parse_from(["sportfrei", "activities"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Activities {
+            last: 10,
+            json: false,
+            format: OutputFormat::Table,
+            ..
+        })
+    ));
+
+    let cli = Cli::try_parse_from([
+        "sportfrei",
+        "activities",
+        "--format",
+        "csv",
+        "--columns",
+        "date,name,distance",
+        "-o",
+        "out.csv",
+    ])
+    .unwrap();
+    match cli.command {
+        Some(Command::Activities {
+            format,
+            columns,
+            output,
+            ..
+        }) => {
+            assert_eq!(format, OutputFormat::Csv);
+            assert_eq!(columns, vec![Column::Date, Column::Name, Column::Distance]);
+            assert_eq!(output.unwrap().to_str(), Some("out.csv"));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+
+    assert!(Cli::try_parse_from(["sportfrei", "activities", "--columns", "bogus"]).is_err());
+    assert!(Cli::try_parse_from(["sportfrei", "activities", "--json", "--format", "csv"]).is_err());
+
+    let cli = Cli::try_parse_from(["sportfrei", "export", "123456789"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Export { id: 123456789 })
+    ));
+
+    assert!(Cli::try_parse_from(["sportfrei", "export", "not-an-id"]).is_err());
+
+    let cli = Cli::try_parse_from([
+        "sportfrei",
+        "upload",
+        "track.xml",
+        "--data-type",
+        "GPX",
+        "--name",
+        "Morning Run",
+    ])
+    .unwrap();
+    match cli.command {
+        Some(Command::Upload {
+            file,
+            name,
+            data_type,
+        }) => {
+            assert_eq!(file.to_str(), Some("track.xml"));
+            assert_eq!(name.as_deref(), Some("Morning Run"));
+            assert_eq!(data_type, Some(UploadDataType::Gpx));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["sportfrei", "upload", "a.fit", "--data-type", "kml"]).is_err());
+
+    let cli = Cli::try_parse_from([
+        "sportfrei",
+        "calendar",
+        "-o",
+        "training.ics",
+        "--event-name",
+        "{sport} {distance}",
+        "--no-plan",
+    ])
+    .unwrap();
+    match cli.command {
+        Some(Command::Calendar {
+            output,
+            event_name,
+            no_plan,
+        }) => {
+            assert_eq!(output.unwrap().to_str(), Some("training.ics"));
+            assert_eq!(event_name.as_deref(), Some("{sport} {distance}"));
+            assert!(no_plan);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_sync() {
+    let cli = Cli::try_parse_from(["sportfrei", "sync"]).unwrap();
+    match cli.command {
+        Some(Command::Sync {
+            concurrency,
+            restart,
+        }) => {
+            assert_eq!(concurrency, 4);
+            assert!(!restart);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+
+    let cli =
+        Cli::try_parse_from(["sportfrei", "sync", "--concurrency", "2", "--restart"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Sync {
+            concurrency: 2,
+            restart: true
+        })
+    ));
+}
+
+#[test]
+fn test_parse_export_all() {
+    let cli = Cli::try_parse_from(["sportfrei", "export-all", "-o", "archive.json"]).unwrap();
+    match cli.command {
+        Some(Command::ExportAll {
+            format,
+            output,
+            no_sync,
+        }) => {
+            assert_eq!(format, ArchiveFormat::Json);
+            assert_eq!(output.to_str(), Some("archive.json"));
+            assert!(!no_sync);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+
+    let cli = Cli::try_parse_from([
+        "sportfrei",
+        "export-all",
+        "--format",
+        "gpx-dir",
+        "--output",
+        "tracks",
+        "--no-sync",
+    ])
+    .unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::ExportAll {
+            format: ArchiveFormat::GpxDir,
+            no_sync: true,
+            ..
+        })
+    ));
+    // The archive needs somewhere to go
+    assert!(Cli::try_parse_from(["sportfrei", "export-all"]).is_err());
+}
+
+#[test]
+fn test_parse_import() {
+    let cli = Cli::try_parse_from(["sportfrei", "import", "export_12345.zip"]).unwrap();
+    match cli.command {
+        Some(Command::Import { archive }) => {
+            assert_eq!(archive.to_str(), Some("export_12345.zip"))
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["sportfrei", "import"]).is_err());
+}
+
+#[test]
+fn test_parse_attach() {
+    let cli = Cli::try_parse_from(["sportfrei", "attach", "123", "ride.fit.gz"]).unwrap();
+    match cli.command {
+        Some(Command::Attach { activity_id, file }) => {
+            assert_eq!(activity_id, 123);
+            assert_eq!(file.to_str(), Some("ride.fit.gz"));
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["sportfrei", "attach", "ride.fit", "123"]).is_err());
+}
+
+#[test]
+fn test_parse_metrics() {
+    let cli = Cli::try_parse_from(["sportfrei", "metrics", "--listen", "127.0.0.1:9464"]).unwrap();
+    match cli.command {
+        Some(Command::Metrics {
+            listen,
+            csv,
+            interval,
+            sync,
+        }) => {
+            assert_eq!(listen.as_deref(), Some("127.0.0.1:9464"));
+            assert!(csv.is_none() && interval.is_none() && !sync);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+
+    let cli = Cli::try_parse_from([
+        "sportfrei",
+        "metrics",
+        "--csv",
+        "training.csv",
+        "--interval",
+        "60",
+        "--sync",
+    ])
+    .unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Metrics {
+            interval: Some(60),
+            sync: true,
+            ..
+        })
+    ));
+    // Exactly one place for the metrics to go
+    assert!(Cli::try_parse_from(["sportfrei", "metrics"]).is_err());
+    assert!(Cli::try_parse_from([
+        "sportfrei",
+        "metrics",
+        "--listen",
+        ":9464",
+        "--csv",
+        "training.csv"
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_format_activity_line() {
+    let json = r#"{
+        "id": 1,
+        "name": "Morning Run",
+        "type": "Run",
+        "sport_type": "Run",
+        "start_date": "2024-01-15T08:30:00Z",
+        "start_date_local": "2024-01-15T08:30:00Z",
+        "timezone": "Europe/Berlin",
+        "distance": 10000.0,
+        "moving_time": 3000,
+        "elapsed_time": 3100,
+        "total_elevation_gain": 50.0
+    }"#;
+    let activity: Activity = serde_json::from_str(json).unwrap();
+
+    let line = format_activity(&activity, Units::Metric);
+    assert!(line.starts_with("2024-01-15"));
+    assert!(line.contains("Morning Run"));
+    assert!(line.contains("10.00 km"));
+    assert!(line.contains("0:50:00"));
+    assert!(line.contains("5:00 /km"));
+}
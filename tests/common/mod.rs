@@ -0,0 +1,46 @@
+// Fixtures shared by the integration tests. Each test file compiles this
+// module on its own and uses only some of it.
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+use serde_json::Value;
+use sportfrei::api::types::Activity;
+
+/// An activity as Strava's list endpoint returns it, with no elevation and
+/// no stopped time.
+pub fn activity(id: u64, sport: &str, start: &str, distance: f64, moving_time: u32) -> Activity {
+    activity_with(id, sport, start, distance, moving_time, Value::Null)
+}
+
+/// Like [`activity`], with `fields` (a JSON object of Strava's field names)
+/// added or replacing the defaults.
+pub fn activity_with(
+    id: u64,
+    sport: &str,
+    start: &str,
+    distance: f64,
+    moving_time: u32,
+    fields: Value,
+) -> Activity {
+    let mut json = serde_json::json!({
+        "id": id,
+        "name": format!("Activity {}", id),
+        "type": sport,
+        "sport_type": sport,
+        "start_date": start,
+        "start_date_local": start,
+        "timezone": "Europe/Berlin",
+        "distance": distance,
+        "moving_time": moving_time,
+        "elapsed_time": moving_time,
+        "total_elevation_gain": 0.0
+    });
+    if let (Value::Object(json), Value::Object(fields)) = (&mut json, fields) {
+        json.extend(fields);
+    }
+    serde_json::from_value(json).unwrap()
+}
+
+pub fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
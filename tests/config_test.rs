@@ -0,0 +1,192 @@
+use sportfrei::analytics::EfficiencyMetric;
+use sportfrei::config::{Config, DefaultView, NotificationConfig, RetryConfig, TimeBasis, Units};
+use sportfrei::ui::columns::{ActivityColumn, SportIcons};
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn temp_config_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sportfrei-{}-{}", name, std::process::id()));
+    dir.join("config.toml")
+}
+
+#[test]
+fn test_config_defaults_when_missing() {
+    let config = Config::load_from(&temp_config_path("missing")).unwrap();
+
+    assert_eq!(config.client_id, None);
+    assert_eq!(config.units, Units::Metric);
+    assert_eq!(config.default_view, DefaultView::Dashboard);
+    assert_eq!(config.per_page, None);
+    assert_eq!(config.auto_refresh_minutes, None);
+    assert!(!config.plaintext_secrets);
+    assert!(!config.notifications.any());
+    assert!(!config.has_credentials());
+}
+
+#[test]
+fn test_config_parses_settings() {
+    let config: Config = toml::from_str(
+        r#"
+        client_id = "12345"
+        refresh_token = "abc"
+        units = "imperial"
+        theme = "gruvbox"
+        per_page = 50
+        prefetch_rows = 10
+        max_pages = 20
+        default_view = "activities"
+        auto_refresh_minutes = 5
+        max_heartrate = 188
+        hide_commutes = true
+        exclude_indoor = true
+        efficiency_metric = "pace_hr"
+        time_basis = "by_sport"
+        sport_icons = "nerd_font"
+        activity_columns = ["date", "name", "kudos", "power", { script = "efficiency" }]
+        download_dir = "/tmp/routes"
+        script = "/tmp/sportfrei.rhai"
+        watch_dir = "/media/watch/Activity"
+
+        [notifications]
+        new_activities = true
+        kudos = true
+        "#,
+    )
+    .unwrap();
+
+    assert!(config.has_credentials());
+    assert_eq!(config.client_secret, None);
+    assert_eq!(config.units, Units::Imperial);
+    assert_eq!(config.theme.as_deref(), Some("gruvbox"));
+    assert_eq!(config.per_page, Some(50));
+    assert_eq!(config.prefetch_rows, Some(10));
+    assert_eq!(config.max_pages, Some(20));
+    assert_eq!(config.default_view, DefaultView::Activities);
+    assert_eq!(config.auto_refresh_minutes, Some(5));
+    assert_eq!(config.max_heartrate, Some(188));
+    assert_eq!(config.resting_heartrate, None);
+    assert!(config.hide_commutes);
+    assert!(!config.hide_private);
+    assert!(config.exclude_indoor);
+    assert_eq!(config.efficiency_metric, EfficiencyMetric::PaceHr);
+    assert_eq!(config.time_basis, TimeBasis::BySport);
+    assert_eq!(config.sport_icons, SportIcons::NerdFont);
+    assert_eq!(
+        config.notifications,
+        NotificationConfig {
+            new_activities: true,
+            kudos: true,
+            records: false,
+        }
+    );
+    assert!(config.time_basis.uses_elapsed("GravelRide"));
+    assert!(!config.time_basis.uses_elapsed("Run"));
+    assert_eq!(
+        config.download_dir(),
+        std::path::PathBuf::from("/tmp/routes")
+    );
+    assert_eq!(
+        config.activity_columns,
+        vec![
+            ActivityColumn::Date,
+            ActivityColumn::Name,
+            ActivityColumn::Kudos,
+            ActivityColumn::Power,
+            ActivityColumn::Script("efficiency".to_string())
+        ]
+    );
+    assert_eq!(
+        config.watch_dir,
+        Some(std::path::PathBuf::from("/media/watch/Activity"))
+    );
+    assert_eq!(
+        config.script_path(),
+        Some(std::path::PathBuf::from("/tmp/sportfrei.rhai"))
+    );
+}
+
+#[test]
+fn test_relative_script_path_is_next_to_config() {
+    let config: Config = toml::from_str(r#"script = "columns.rhai""#).unwrap();
+    let path = config.script_path().unwrap();
+    assert_eq!(path.file_name().unwrap(), "columns.rhai");
+    assert_eq!(path.parent(), Config::path().unwrap().parent());
+    assert_eq!(Config::default().script_path(), None);
+}
+
+#[test]
+fn test_config_round_trips_unknown_keys() {
+    let path = temp_config_path("roundtrip");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &path,
+        r#"client_id = "12345"
+refresh_token = "old"
+my_custom_key = "keep me"
+
+[custom_section]
+value = 42
+"#,
+    )
+    .unwrap();
+
+    let mut config = Config::load_from(&path).unwrap();
+    config.refresh_token = Some("new".to_string());
+    config.save_to(&path).unwrap();
+
+    let reloaded = Config::load_from(&path).unwrap();
+    assert_eq!(reloaded.refresh_token.as_deref(), Some("new"));
+    assert_eq!(reloaded.extra["my_custom_key"].as_str(), Some("keep me"));
+    assert_eq!(
+        reloaded.extra["custom_section"]["value"].as_integer(),
+        Some(42)
+    );
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_units_conversion() {
+    assert!((Units::Metric.distance(5000.0) - 5.0).abs() < 1e-9);
+    assert!((Units::Imperial.distance(1609.344) - 1.0).abs() < 1e-9);
+    assert!((Units::Imperial.elevation(100.0) - 328.084).abs() < 1e-3);
+    assert_eq!(Units::Imperial.distance_unit(), "mi");
+}
+
+#[test]
+fn test_retry_config() {
+    let config: Config = toml::from_str(
+        r#"
+        [retry]
+        max_retries = 5
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.retry.max_retries, 5);
+    assert_eq!(config.retry.base_delay_ms, 500);
+
+    let retry = RetryConfig::default();
+    assert_eq!(retry.delay(0), Duration::from_millis(500));
+    assert_eq!(retry.delay(2), Duration::from_millis(2000));
+    assert_eq!(retry.delay(10), Duration::from_millis(8000));
+    assert_eq!(retry.delay(100), Duration::from_millis(8000));
+
+    // Defaults stay out of the saved file
+    assert!(!toml::to_string(&Config::default())
+        .unwrap()
+        .contains("retry"));
+}
+
+#[test]
+fn test_keyring_opt_out() {
+    let config: Config = toml::from_str("plaintext_secrets = true").unwrap();
+    assert!(config.plaintext_secrets);
+    assert!(toml::to_string(&config)
+        .unwrap()
+        .contains("plaintext_secrets = true"));
+
+    // The default is left out of the file
+    assert!(!toml::to_string(&Config::default())
+        .unwrap()
+        .contains("plaintext_secrets"));
+}
@@ -0,0 +1,98 @@
+use chrono::NaiveDate;
+use sportfrei::dates::parse_fuzzy;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn test_parse_fuzzy_numeric_dates() {
+    let today = date(2024, 7, 10);
+    assert_eq!(
+        parse_fuzzy("2023", today),
+        Ok((date(2023, 1, 1), date(2024, 1, 1)))
+    );
+    assert_eq!(
+        parse_fuzzy(" 2023-06 ", today),
+        Ok((date(2023, 6, 1), date(2023, 7, 1)))
+    );
+    assert_eq!(
+        parse_fuzzy("2023-06-15", today),
+        Ok((date(2023, 6, 15), date(2023, 6, 16)))
+    );
+    assert!(parse_fuzzy("2023-13", today).is_err());
+    assert!(parse_fuzzy("", today).is_err());
+}
+
+#[test]
+fn test_parse_fuzzy_relative_dates() {
+    // A Wednesday
+    let today = date(2024, 7, 10);
+    assert_eq!(
+        parse_fuzzy("yesterday", today),
+        Ok((date(2024, 7, 9), date(2024, 7, 10)))
+    );
+    assert_eq!(
+        parse_fuzzy("6 months ago", today),
+        Ok((date(2024, 1, 10), date(2024, 1, 11)))
+    );
+    assert_eq!(
+        parse_fuzzy("a week ago", today),
+        Ok((date(2024, 7, 3), date(2024, 7, 4)))
+    );
+    assert_eq!(
+        parse_fuzzy("Last Week", today),
+        Ok((date(2024, 7, 1), date(2024, 7, 8)))
+    );
+    assert_eq!(
+        parse_fuzzy("last month", today),
+        Ok((date(2024, 6, 1), date(2024, 7, 1)))
+    );
+    assert_eq!(
+        parse_fuzzy("this year", today),
+        Ok((date(2024, 1, 1), date(2025, 1, 1)))
+    );
+    assert!(parse_fuzzy("6 fortnights ago", today).is_err());
+}
+
+#[test]
+fn test_parse_fuzzy_seasons_and_months() {
+    // Summer is still going on, so last summer is a year back
+    let july = date(2024, 7, 10);
+    assert_eq!(
+        parse_fuzzy("this summer", july),
+        Ok((date(2024, 6, 1), date(2024, 9, 1)))
+    );
+    assert_eq!(
+        parse_fuzzy("last summer", july),
+        Ok((date(2023, 6, 1), date(2023, 9, 1)))
+    );
+    let october = date(2024, 10, 2);
+    assert_eq!(
+        parse_fuzzy("last summer", october),
+        Ok((date(2024, 6, 1), date(2024, 9, 1)))
+    );
+    // Winter spans the turn of the year
+    assert_eq!(
+        parse_fuzzy("last winter", date(2024, 1, 15)),
+        Ok((date(2022, 12, 1), date(2023, 3, 1)))
+    );
+    assert_eq!(
+        parse_fuzzy("winter 2023", july),
+        Ok((date(2023, 12, 1), date(2024, 3, 1)))
+    );
+
+    // Month names without a year never point into the future
+    assert_eq!(
+        parse_fuzzy("june", july),
+        Ok((date(2024, 6, 1), date(2024, 7, 1)))
+    );
+    assert_eq!(
+        parse_fuzzy("sep", july),
+        Ok((date(2023, 9, 1), date(2023, 10, 1)))
+    );
+    assert_eq!(
+        parse_fuzzy("Dec 2021", july),
+        Ok((date(2021, 12, 1), date(2022, 1, 1)))
+    );
+}
@@ -0,0 +1,153 @@
+use chrono::{Duration, TimeZone, Utc};
+use clap::Parser;
+use sportfrei::api::types::DateRange;
+use sportfrei::cli::Cli;
+use sportfrei::demo::Demo;
+use sportfrei::worker::{Request, Response};
+
+fn demo() -> Demo {
+    Demo::load(Utc.with_ymd_and_hms(2025, 3, 12, 18, 0, 0).unwrap()).unwrap()
+}
+
+#[test]
+fn test_parse_demo_flag() {
+    let cli = Cli::try_parse_from(["sportfrei", "--demo"]).unwrap();
+    assert!(cli.demo);
+    assert!(cli.command.is_none());
+    assert!(Cli::try_parse_from(["sportfrei", "--demo", "stats"]).is_err());
+}
+
+#[test]
+fn test_demo_dates_end_this_week() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 12, 18, 0, 0).unwrap();
+    let demo = demo();
+    let activities = demo.activities();
+    assert!(activities.len() > 100);
+    assert!(activities.iter().all(|a| a.start_date <= now));
+    assert!(activities[0].start_date > now - Duration::weeks(1));
+    assert!(!demo.goals.is_empty());
+    assert!(demo
+        .plan
+        .iter()
+        .any(|w| w.date > now.date_naive() - Duration::weeks(1)));
+}
+
+#[test]
+fn test_demo_pages() {
+    let mut demo = demo();
+    let newest = demo.activities()[0].id;
+    match demo.respond(Request::Page {
+        page: 1,
+        per_page: 10,
+        range: DateRange::default(),
+    }) {
+        Response::Page { result, .. } => {
+            let page = result.unwrap().items;
+            assert_eq!(page.len(), 10);
+            assert_eq!(page[0].id, newest);
+        }
+        _ => panic!("expected a page"),
+    }
+    match demo.respond(Request::Page {
+        page: 100,
+        per_page: 10,
+        range: DateRange::default(),
+    }) {
+        Response::Page { result, .. } => assert!(result.unwrap().items.is_empty()),
+        _ => panic!("expected a page"),
+    }
+}
+
+#[test]
+fn test_demo_detail_and_streams() {
+    let mut demo = demo();
+    let run = demo
+        .activities()
+        .iter()
+        .find(|a| a.sport_type == "Run" && a.distance > 5000.0)
+        .unwrap()
+        .clone();
+
+    match demo.respond(Request::Detail {
+        activity_id: run.id,
+    }) {
+        Response::Detail { result, .. } => {
+            let detail = result.unwrap();
+            let splits = detail.splits_metric.unwrap();
+            assert_eq!(splits.len(), (run.distance / 1000.0).ceil() as usize);
+            let total: f64 = splits.iter().map(|s| s.distance).sum();
+            assert!((total - run.distance).abs() < 1.0);
+            assert!(detail.best_efforts.unwrap().iter().any(|e| e.name == "5k"));
+        }
+        _ => panic!("expected a detail"),
+    }
+
+    match demo.respond(Request::Streams {
+        activity_id: run.id,
+    }) {
+        Response::Streams { result, .. } => {
+            let streams = result.unwrap();
+            let samples = streams.time.unwrap().data.len();
+            assert_eq!(streams.distance.unwrap().data.len(), samples);
+            assert_eq!(streams.latlng.unwrap().data.len(), samples);
+        }
+        _ => panic!("expected streams"),
+    }
+}
+
+#[test]
+fn test_demo_power_zones() {
+    let mut demo = demo();
+    let ride = demo
+        .activities()
+        .iter()
+        .find(|a| a.average_watts.is_some())
+        .unwrap()
+        .clone();
+
+    match demo.respond(Request::Zones {
+        activity_id: ride.id,
+    }) {
+        Response::Zones { result, .. } => {
+            let zones = result.unwrap();
+            assert_eq!(zones[0].zone_type, "power");
+            assert_eq!(zones[0].distribution_buckets.len(), 7);
+            assert!((zones[0].total_time() - ride.moving_time as f64).abs() <= 7.0);
+        }
+        _ => panic!("expected zones"),
+    }
+}
+
+#[test]
+fn test_demo_delete() {
+    let mut demo = demo();
+    let id = demo.activities()[0].id;
+    match demo.respond(Request::Delete { activity_id: id }) {
+        Response::Deleted { result, .. } => assert!(result.is_ok()),
+        _ => panic!("expected a deletion"),
+    }
+    assert!(demo.activities().iter().all(|a| a.id != id));
+    match demo.respond(Request::Delete { activity_id: id }) {
+        Response::Deleted { result, .. } => assert!(result.is_err()),
+        _ => panic!("expected a deletion"),
+    }
+}
+
+#[test]
+fn test_demo_saved_routes() {
+    let mut demo = demo();
+    let route = match demo.respond(Request::SavedRoutes { athlete_id: 1 }) {
+        Response::SavedRoutes { result } => result.unwrap().remove(0),
+        _ => panic!("expected saved routes"),
+    };
+    assert_eq!(route.gpx_file_name(), "tiergarten-loop.gpx");
+    match demo.respond(Request::RouteGpx { route_id: route.id }) {
+        Response::RouteGpx { route_id, result } => {
+            assert_eq!(route_id, route.id);
+            let gpx = String::from_utf8(result.unwrap()).unwrap();
+            assert!(gpx.contains("<name>Tiergarten Loop</name>"));
+            assert!(gpx.contains("<trkpt lat="));
+        }
+        _ => panic!("expected a GPX file"),
+    }
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use sportfrei::api::types::Activity;
+use sportfrei::config::Units;
+use sportfrei::export::{self, Column};
+use sportfrei::plan::Plan;
+
+fn activity(name: &str, distance: f64, heartrate: Option<f64>) -> Activity {
+    common::activity_with(
+        42,
+        "Run",
+        "2024-01-15T08:30:00Z",
+        distance,
+        1500,
+        serde_json::json!({
+            "name": name,
+            "elapsed_time": 1600,
+            "total_elevation_gain": 12.0,
+            "average_heartrate": heartrate
+        }),
+    )
+}
+
+#[test]
+fn test_activity_card() {
+    let mut run = activity("Morning Run", 5000.0, Some(148.0));
+    run.map = serde_json::from_value(serde_json::json!({
+        "summary_polyline": "_p~iF~ps|U_ulLnnqC_mqNvxq`@"
+    }))
+    .unwrap();
+
+    let card = export::to_card(&run, Units::Metric, false);
+    let lines: Vec<&str> = card.lines().collect();
+    assert!(lines.iter().all(|l| l.chars().count() == 44));
+    assert_eq!(lines[0], format!("+{}+", "-".repeat(42)));
+    assert!(card.contains("| Morning Run "));
+    assert!(card.contains("| Run - 2024-01-15 08:30 "));
+    assert!(card.contains("| Distance   5.00 km "));
+    assert!(card.contains("| Time       0:25:00 "));
+    assert!(card.contains("| Pace       5:00 /km "));
+    assert!(card.contains("| Elevation  12 m "));
+    assert!(card.contains("| Heart rate 148 bpm "));
+    assert!(card.contains("| strava.com/activities/42 "));
+    // The thumbnail runs from start to finish
+    assert_eq!(card.matches('S').count(), 1);
+    assert_eq!(card.matches('F').count(), 1);
+    assert!(card.matches('*').count() > 10);
+    assert!(!card.contains('\x1b'));
+
+    // Without a track there is no thumbnail; colors are opt-in
+    let card = export::to_card(&activity("Treadmill", 3000.0, None), Units::Metric, true);
+    assert!(!card.contains('*'));
+    assert!(!card.contains("Heart rate"));
+    assert!(card.contains("\x1b[1mTreadmill\x1b[0m"));
+}
+
+#[test]
+fn test_parse_columns() {
+    assert_eq!("distance".parse::<Column>().unwrap(), Column::Distance);
+    assert_eq!(" HeartRate ".parse::<Column>().unwrap(), Column::HeartRate);
+    assert!("bogus".parse::<Column>().is_err());
+}
+
+#[test]
+fn test_csv_export() {
+    let activities = vec![
+        activity("Easy run", 5000.0, Some(142.0)),
+        activity("Run, with \"quotes\"", 0.0, None),
+    ];
+    let columns = [
+        Column::Id,
+        Column::Name,
+        Column::Distance,
+        Column::Pace,
+        Column::HeartRate,
+    ];
+
+    let csv = export::to_csv(&activities, &columns, Units::Metric);
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines[0], "id,name,distance_km,pace_per_km,heartrate");
+    assert_eq!(lines[1], "42,Easy run,5.0,5:00,142.0");
+    assert_eq!(lines[2], "42,\"Run, with \"\"quotes\"\"\",0.0,,");
+
+    let csv = export::to_csv(&activities[..1], &[Column::Distance], Units::Imperial);
+    assert_eq!(csv, "distance_mi\n3.107\n");
+}
+
+#[test]
+fn test_json_export_selects_columns() {
+    let activities = vec![activity("Easy run", 5000.0, None)];
+
+    let json = export::to_json(
+        &activities,
+        &[Column::Name, Column::MovingTime],
+        Units::Metric,
+    )
+    .unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(
+        value,
+        serde_json::json!([{ "name": "Easy run", "moving_time_s": 1500 }])
+    );
+}
+
+#[test]
+fn test_ics_export() {
+    let activities = vec![activity("Hills; then coffee, finally", 5000.0, None)];
+    let plan: Plan = toml::from_str(
+        "[[workout]]\ndate = 2024-01-17\nsport = \"Run\"\nname = \"Intervals\"\ndistance = 8",
+    )
+    .unwrap();
+    let stamp = chrono::DateTime::parse_from_rfc3339("2024-01-20T12:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+
+    let ics = export::to_ics(
+        &activities,
+        &plan.workouts,
+        export::DEFAULT_EVENT_NAME,
+        Units::Metric,
+        stamp,
+    );
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert!(ics.contains("UID:activity-42@sportfrei\r\n"));
+    assert!(ics.contains("DTSTAMP:20240120T120000Z\r\n"));
+    assert!(ics.contains("DTSTART:20240115T083000Z\r\nDTEND:20240115T085640Z\r\n"));
+    assert!(ics.contains("SUMMARY:Run: Hills\\; then coffee\\, finally\r\n"));
+    assert!(ics.contains("DTSTART;VALUE=DATE:20240117\r\n"));
+    assert!(ics.contains("SUMMARY:Planned: Intervals (Run 8 km)\r\n"));
+    assert!(ics.lines().all(|line| line.len() <= 75));
+
+    let ics = export::to_ics(
+        &activities,
+        &[],
+        "{distance} {sport} in {duration}",
+        Units::Metric,
+        stamp,
+    );
+    assert!(ics.contains("SUMMARY:5.0 km Run in 0:25:00\r\n"));
+    assert!(!ics.contains("Planned"));
+}
+
+#[test]
+fn test_gpx_export() {
+    use sportfrei::api::types::{Stream, StreamSet};
+
+    fn stream<T>(data: Vec<T>) -> Stream<T> {
+        Stream {
+            data,
+            series_type: None,
+            original_size: None,
+            resolution: None,
+        }
+    }
+    let mut run = activity("Run & <Fun>", 5000.0, None);
+    let streams = StreamSet {
+        time: Some(stream(vec![0, 60])),
+        altitude: Some(stream(vec![34.0, 36.5])),
+        latlng: Some(stream(vec![[52.5, 13.4], [52.501, 13.401]])),
+        ..Default::default()
+    };
+    let gpx = export::to_gpx(&run, Some(&streams)).unwrap();
+    assert!(gpx.contains("<name>Run &amp; &lt;Fun&gt;</name><type>Run</type>"));
+    assert!(gpx.contains(
+        "<trkpt lat=\"52.501000\" lon=\"13.401000\"><ele>36.5</ele><time>2024-01-15T08:31:00Z</time></trkpt>"
+    ));
+    assert_eq!(export::gpx_file_name(&run), "2024-01-15-42.gpx");
+
+    // Without streams the map's line stands in, and manual activities have none
+    assert_eq!(export::to_gpx(&run, None), None);
+    run.map = serde_json::from_value(serde_json::json!({
+        "summary_polyline": "_p~iF~ps|U_ulLnnqC_mqNvxq`@"
+    }))
+    .unwrap();
+    let gpx = export::to_gpx(&run, None).unwrap();
+    assert_eq!(gpx.matches("<trkpt").count(), 3);
+    assert!(gpx.contains("<trkpt lat=\"38.50000\" lon=\"-120.20000\"/>"));
+}
+
+#[test]
+fn test_archive_export() {
+    use sportfrei::export::ArchivedActivity;
+
+    let mut run = activity("Morning Run", 5000.0, Some(148.0));
+    run.description = Some("Easy, with strides".to_string());
+    let plain = activity("Evening Run", 8000.0, None);
+    let archive = [
+        ArchivedActivity {
+            activity: &run,
+            tags: vec!["easy", "strides"],
+            note: Some("Left calf tight"),
+        },
+        ArchivedActivity {
+            activity: &plain,
+            tags: Vec::new(),
+            note: None,
+        },
+    ];
+
+    let json: serde_json::Value =
+        serde_json::from_str(&export::to_archive_json(&archive).unwrap()).unwrap();
+    assert_eq!(json[0]["name"], "Morning Run");
+    assert_eq!(json[0]["description"], "Easy, with strides");
+    assert_eq!(json[0]["tags"], serde_json::json!(["easy", "strides"]));
+    assert_eq!(json[0]["note"], "Left calf tight");
+    assert!(json[1].get("tags").is_none());
+    // The archived objects read back as activities
+    let restored: Vec<Activity> = serde_json::from_value(json).unwrap();
+    assert_eq!(restored[1].distance, 8000.0);
+
+    let csv = export::to_archive_csv(&archive, Units::Metric);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("id,date,name,sport,distance_km"));
+    assert!(lines[0].ends_with(",gear,description,tags,note"));
+    assert!(lines[1].ends_with(",\"Easy, with strides\",easy strides,Left calf tight"));
+    assert!(lines[2].ends_with(",,,"));
+}
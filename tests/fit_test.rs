@@ -0,0 +1,137 @@
+use sportfrei::fit::{decode, to_streams, FitRecord};
+
+const FIT_EPOCH: i64 = 631_065_600;
+// 2024-01-15 08:30:00 UTC
+const START: u32 = (1_705_307_400 - FIT_EPOCH) as u32;
+
+fn semicircles(degrees: f64) -> i32 {
+    (degrees * 2_147_483_648.0 / 180.0).round() as i32
+}
+
+// A FIT file with the given messages behind a 14 byte header; the CRCs are
+// left at zero, which the decoder doesn't check
+fn fit_file(messages: &[u8]) -> Vec<u8> {
+    let mut file = vec![14, 0x20, 0x08, 0x08];
+    file.extend((messages.len() as u32).to_le_bytes());
+    file.extend(b".FIT");
+    file.extend([0, 0]);
+    file.extend(messages);
+    file.extend([0, 0]);
+    file
+}
+
+// Local message 0: timestamp, position, enhanced altitude, distance, heart
+// rate and power of a `record`
+fn record_definition() -> Vec<u8> {
+    let mut message = vec![0x40, 0, 0];
+    message.extend(20u16.to_le_bytes());
+    message.push(7);
+    for field in [
+        [253, 4, 0x86],
+        [0, 4, 0x85],
+        [1, 4, 0x85],
+        [78, 4, 0x86],
+        [5, 4, 0x86],
+        [3, 1, 0x02],
+        [7, 2, 0x84],
+    ] {
+        message.extend(field);
+    }
+    message
+}
+
+fn record(
+    time: u32,
+    lat: f64,
+    lng: f64,
+    altitude: f64,
+    distance: f64,
+    hr: u8,
+    watts: u16,
+) -> Vec<u8> {
+    let mut message = vec![0x00];
+    message.extend(time.to_le_bytes());
+    message.extend(semicircles(lat).to_le_bytes());
+    message.extend(semicircles(lng).to_le_bytes());
+    message.extend((((altitude + 500.0) * 5.0) as u32).to_le_bytes());
+    message.extend(((distance * 100.0) as u32).to_le_bytes());
+    message.push(hr);
+    message.extend(watts.to_le_bytes());
+    message
+}
+
+fn ride() -> Vec<u8> {
+    // A file_id message first, with a developer field the decoder skips
+    let mut messages = vec![0x60, 0, 0, 0, 0, 1, 1, 1, 0x02, 1, 0, 2, 0];
+    messages.extend([0x00, 1, 0xaa, 0xbb]);
+    messages.extend(record_definition());
+    messages.extend(record(START, 52.52, 13.405, 34.0, 0.0, 120, 180));
+    messages.extend(record(START + 1, 52.5201, 13.4051, 34.2, 8.5, 0xff, 200));
+    // Local message 1 with only heart rate and cadence, timed by a
+    // compressed header 3 seconds on
+    messages.extend([0x41, 0, 0, 20, 0, 2, 3, 1, 0x02, 4, 1, 0x02]);
+    let offset = ((START + 4) & 0x1f) as u8;
+    messages.extend([0x80 | 0x20 | offset, 130, 85]);
+    fit_file(&messages)
+}
+
+#[test]
+fn test_decode_records() {
+    let records = decode(&ride()).unwrap();
+    assert_eq!(records.len(), 3);
+
+    let first = &records[0];
+    assert_eq!(
+        first.timestamp.unwrap().to_rfc3339(),
+        "2024-01-15T08:30:00+00:00"
+    );
+    let [lat, lng] = first.position.unwrap();
+    assert!((lat - 52.52).abs() < 1e-6 && (lng - 13.405).abs() < 1e-6);
+    assert_eq!(first.altitude, Some(34.0));
+    assert_eq!(first.distance, Some(0.0));
+    assert_eq!(first.heartrate, Some(120.0));
+    assert_eq!(first.power, Some(180.0));
+    assert_eq!(first.cadence, None);
+
+    // 0xff is FIT's marker for a missing value
+    assert_eq!(records[1].heartrate, None);
+    assert_eq!(records[1].power, Some(200.0));
+
+    let last = &records[2];
+    assert_eq!(
+        last.timestamp.unwrap().to_rfc3339(),
+        "2024-01-15T08:30:04+00:00"
+    );
+    assert_eq!(last.heartrate, Some(130.0));
+    assert_eq!(last.cadence, Some(85.0));
+    assert_eq!(last.position, None);
+}
+
+#[test]
+fn test_decode_damaged_files() {
+    assert!(decode(b"not a fit file").is_err());
+    assert!(decode(&[]).is_err());
+
+    // The samples before the cut survive
+    let mut file = ride();
+    file.truncate(file.len() - 6);
+    assert_eq!(decode(&file).unwrap().len(), 2);
+
+    assert!(decode(&fit_file(&[])).unwrap().is_empty());
+}
+
+#[test]
+fn test_records_to_streams() {
+    let streams = to_streams(&decode(&ride()).unwrap());
+    assert_eq!(streams.time.unwrap().data, vec![0, 1, 4]);
+    // Gaps take the previous value, leading ones the first
+    assert_eq!(streams.heartrate.unwrap().data, vec![120.0, 120.0, 130.0]);
+    assert_eq!(streams.cadence.unwrap().data, vec![85.0, 85.0, 85.0]);
+    assert_eq!(streams.watts.unwrap().data, vec![180.0, 200.0, 200.0]);
+    assert_eq!(streams.distance.unwrap().data, vec![0.0, 8.5, 8.5]);
+    assert_eq!(streams.latlng.unwrap().data.len(), 3);
+    assert!(streams.velocity_smooth.is_none());
+
+    let empty = to_streams(&[FitRecord::default()]);
+    assert!(empty.time.is_none() && empty.heartrate.is_none());
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::date;
+use sportfrei::api::types::Activity;
+use sportfrei::fitness::{self, HeartRateRange};
+
+fn activity(
+    start: &str,
+    moving_time: u32,
+    heartrate: Option<f64>,
+    effort: Option<f64>,
+) -> Activity {
+    common::activity_with(
+        1,
+        "Run",
+        start,
+        10000.0,
+        moving_time,
+        serde_json::json!({
+            "name": "Run",
+            "average_heartrate": heartrate,
+            "max_heartrate": heartrate.map(|hr| hr + 20.0),
+            "suffer_score": effort
+        }),
+    )
+}
+
+#[test]
+fn test_heart_rate_range_falls_back_to_highest_recorded() {
+    let activities = [
+        activity("2024-03-01T08:00:00Z", 3600, Some(150.0), None),
+        activity("2024-03-02T08:00:00Z", 3600, Some(165.0), None),
+    ];
+    let range = HeartRateRange::resolve(None, None, &activities).unwrap();
+    assert_eq!(range.max, 185.0);
+    assert_eq!(range.resting, 60.0);
+
+    let range = HeartRateRange::resolve(Some(50), Some(200), &activities).unwrap();
+    assert_eq!((range.resting, range.max), (50.0, 200.0));
+
+    // Nothing to go on without heart rate data
+    let no_hr = [activity("2024-03-01T08:00:00Z", 3600, None, None)];
+    assert!(HeartRateRange::resolve(None, None, &no_hr).is_none());
+}
+
+#[test]
+fn test_training_load_prefers_relative_effort() {
+    let range = HeartRateRange {
+        resting: 60.0,
+        max: 190.0,
+    };
+    let scored = activity("2024-03-01T08:00:00Z", 3600, Some(150.0), Some(87.0));
+    assert_eq!(fitness::training_load(&scored, Some(range)), Some(87.0));
+
+    // One hour at ~69% of heart rate reserve
+    let unscored = activity("2024-03-01T08:00:00Z", 3600, Some(150.0), None);
+    let trimp = fitness::training_load(&unscored, Some(range)).unwrap();
+    assert!((trimp - 100.4).abs() < 0.5, "TRIMP was {}", trimp);
+    assert_eq!(fitness::training_load(&unscored, None), None);
+}
+
+#[test]
+fn test_fitness_history_builds_up_and_decays() {
+    let activities: Vec<Activity> = (1..=14)
+        .map(|d| {
+            activity(
+                &format!("2024-03-{:02}T08:00:00Z", d),
+                3600,
+                None,
+                Some(100.0),
+            )
+        })
+        .collect();
+    let history = fitness::fitness_history(&activities, None, date(2024, 3, 28));
+    assert_eq!(history.len(), 28);
+    assert_eq!(history[0].date, date(2024, 3, 1));
+
+    // Two weeks of daily training: fatigue rises faster than fitness
+    let trained = &history[13];
+    assert_eq!(trained.load, 100.0);
+    assert!(trained.fatigue > trained.fitness);
+    assert!(trained.form < 0.0);
+
+    // Two rest weeks: fatigue drops off and form turns positive
+    let rested = history.last().unwrap();
+    assert_eq!(rested.load, 0.0);
+    assert!(rested.fatigue < rested.fitness);
+    assert!(rested.form > 0.0);
+    assert!(rested.fitness < trained.fitness);
+}
+
+#[test]
+fn test_fitness_history_empty_without_loads() {
+    let activities = [activity("2024-03-01T08:00:00Z", 3600, None, None)];
+    assert!(fitness::fitness_history(&activities, None, date(2024, 3, 28)).is_empty());
+}
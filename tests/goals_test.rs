@@ -0,0 +1,97 @@
+mod common;
+
+use common::{activity, date};
+use sportfrei::config::{Config, Units};
+use sportfrei::goals::{Goal, GoalMetric, GoalPeriod};
+
+#[test]
+fn test_goal_period_bounds() {
+    // A Wednesday
+    let today = date(2024, 2, 14);
+    assert_eq!(
+        GoalPeriod::Week.bounds(today),
+        (date(2024, 2, 12), date(2024, 2, 18))
+    );
+    assert_eq!(
+        GoalPeriod::Month.bounds(today),
+        (date(2024, 2, 1), date(2024, 2, 29))
+    );
+    assert_eq!(
+        GoalPeriod::Year.bounds(today),
+        (date(2024, 1, 1), date(2024, 12, 31))
+    );
+}
+
+#[test]
+fn test_goal_progress_and_projection() {
+    let activities = [
+        activity(1, "Run", "2024-04-02T08:00:00Z", 10000.0, 3000),
+        activity(2, "Run", "2024-04-09T08:00:00Z", 20000.0, 6000),
+        activity(3, "Ride", "2024-04-05T08:00:00Z", 50000.0, 7200),
+        // Last month and after today are ignored
+        activity(4, "Run", "2024-03-31T08:00:00Z", 10000.0, 3000),
+        activity(5, "Run", "2024-04-20T08:00:00Z", 10000.0, 3000),
+    ];
+    let today = date(2024, 4, 10);
+
+    let runs = Goal {
+        period: GoalPeriod::Month,
+        metric: GoalMetric::Distance,
+        target: 100.0,
+        sport: Some("run".to_string()),
+    };
+    let progress = runs.progress(&activities, today, Units::Metric);
+    assert_eq!(progress.done, 30.0);
+    // 30 km in 10 of 30 days
+    assert!((progress.projected - 90.0).abs() < 1e-9);
+    assert!((progress.ratio(&runs) - 0.3).abs() < 1e-9);
+    assert_eq!(runs.label(Units::Metric), "Monthly run 100km");
+
+    let time = Goal {
+        period: GoalPeriod::Month,
+        metric: GoalMetric::Time,
+        target: 2.0,
+        sport: None,
+    };
+    let progress = time.progress(&activities, today, Units::Metric);
+    assert!((progress.done - 4.5).abs() < 1e-9);
+    assert_eq!(progress.ratio(&time), 1.0);
+}
+
+#[test]
+fn test_goals_round_trip_through_config() {
+    let path = std::env::temp_dir()
+        .join(format!("sportfrei-goals-{}", std::process::id()))
+        .join("config.toml");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &path,
+        r#"client_id = "12345"
+my_custom_key = "keep me"
+
+[[goals]]
+period = "week"
+metric = "distance"
+target = 30.0
+sport = "Run"
+"#,
+    )
+    .unwrap();
+
+    let mut config = Config::load_from(&path).unwrap();
+    assert_eq!(config.goals.len(), 1);
+    assert_eq!(config.goals[0].period, GoalPeriod::Week);
+    config.goals.push(Goal {
+        period: GoalPeriod::Year,
+        metric: GoalMetric::Time,
+        target: 200.0,
+        sport: None,
+    });
+    config.save_to(&path).unwrap();
+
+    let reloaded = Config::load_from(&path).unwrap();
+    assert_eq!(reloaded.goals, config.goals);
+    assert_eq!(reloaded.extra["my_custom_key"].as_str(), Some("keep me"));
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
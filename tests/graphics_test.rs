@@ -0,0 +1,36 @@
+use image::{ImageFormat, Rgb, RgbImage};
+use sportfrei::ui::graphics::GraphicsProtocol;
+use std::io::Cursor;
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let image = RgbImage::from_pixel(width, height, Rgb([255, 0, 0]));
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn test_encode_image_protocols() {
+    let image = png(40, 30);
+
+    let iterm = GraphicsProtocol::Iterm2.encode(&image, 4, 2).unwrap();
+    assert!(iterm.starts_with("\x1b]1337;File=inline=1;"));
+    assert!(iterm.contains("width=4;height=2"));
+    assert!(iterm.ends_with('\x07'));
+
+    let kitty = GraphicsProtocol::Kitty.encode(&image, 4, 2).unwrap();
+    assert!(kitty.starts_with("\x1b_Ga=T,f=32,"));
+    assert!(kitty.ends_with("\x1b\\"));
+
+    let sixel = GraphicsProtocol::Sixel.encode(&image, 4, 2).unwrap();
+    assert!(sixel.starts_with("\x1bPq"));
+    // Pure red is palette entry 5 * 36
+    assert!(sixel.contains("#180!"));
+    assert!(sixel.ends_with("-\x1b\\"));
+
+    assert!(GraphicsProtocol::Sixel
+        .encode(b"not an image", 4, 2)
+        .is_err());
+}
@@ -0,0 +1,103 @@
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use sportfrei::config::Config;
+use sportfrei::i18n::{self, Language, Locale, TimeFormat};
+
+#[test]
+fn test_language_from_locale() {
+    assert_eq!(Language::from_locale("de_DE.UTF-8"), Some(Language::German));
+    assert_eq!(Language::from_locale("de-AT"), Some(Language::German));
+    assert_eq!(Language::from_locale("en_US"), Some(Language::English));
+    assert_eq!(Language::from_locale("fr_FR.UTF-8"), None);
+    assert_eq!(Language::from_locale("C"), None);
+}
+
+#[test]
+fn test_translation_falls_back_to_english() {
+    assert_eq!(Language::German.tr("Activities"), "Aktivitäten");
+    assert_eq!(Language::English.tr("Activities"), "Activities");
+    assert_eq!(
+        Language::German.tr("Not in any catalog"),
+        "Not in any catalog"
+    );
+}
+
+#[test]
+fn test_translation_fills_placeholders() {
+    assert_eq!(
+        Language::German.trf("Welcome, {}!", &[&"Dennis"]),
+        "Willkommen, Dennis!"
+    );
+    assert_eq!(i18n::fill("{} of {}", &[&1, &2]), "1 of 2");
+    // Missing arguments leave the gap empty rather than panicking
+    assert_eq!(i18n::fill("{} of {}", &[&1]), "1 of ");
+}
+
+#[test]
+fn test_config_language() {
+    let config: Config = toml::from_str(r#"language = "de""#).unwrap();
+    assert_eq!(config.language, Some(Language::German));
+
+    let config: Config = toml::from_str("").unwrap();
+    assert_eq!(config.language, None);
+}
+
+#[test]
+fn test_locale_conventions() {
+    let date = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+
+    let german = Locale::from_locale("de_DE.UTF-8");
+    assert_eq!(german.short_date(date), "30.06.");
+    assert_eq!(german.date(date), "30.06.2024");
+    assert_eq!(german.decimal(12.345, 1), "12,3");
+
+    let us = Locale::from_locale("en_US");
+    assert_eq!(us.short_date(date), "06/30");
+    assert_eq!(us.date(date), "06/30/2024");
+    assert_eq!(us.decimal(12.345, 1), "12.3");
+
+    assert_eq!(Locale::from_locale("en_GB").date(date), "30/06/2024");
+    // Unknown regions keep ISO dates
+    assert_eq!(Locale::from_locale("C").date(date), "2024-06-30");
+    assert_eq!(Locale::default().short_date(date), "06-30");
+}
+
+#[test]
+fn test_locale_weeks() {
+    // A Sunday
+    let date = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+
+    let german = Locale::from_locale("de_DE");
+    assert_eq!(
+        german.week_start(date),
+        NaiveDate::from_ymd_opt(2024, 6, 24).unwrap()
+    );
+    assert_eq!(german.week(date).week(), 26);
+    assert_eq!(german.weekdays()[0], Weekday::Mon);
+
+    let us = Locale::from_locale("en_US");
+    assert_eq!(us.week_start(date), date);
+    assert_eq!(us.week(date).week(), 27);
+    assert_eq!(us.weekdays()[0], Weekday::Sun);
+    assert_eq!(us.weekdays()[6], Weekday::Sat);
+}
+
+#[test]
+fn test_time_format() {
+    let evening = NaiveTime::from_hms_opt(18, 5, 0).unwrap();
+    let midnight = NaiveTime::from_hms_opt(0, 30, 0).unwrap();
+
+    let mut locale = Locale::from_locale("de_DE");
+    assert_eq!(locale.time_format, TimeFormat::TwentyFourHour);
+    assert_eq!(locale.time(evening), "18:05");
+
+    locale.time_format = TimeFormat::TwelveHour;
+    assert_eq!(locale.time(evening), "6:05 PM");
+    assert_eq!(locale.time(midnight), "12:30 AM");
+
+    assert_eq!(
+        Locale::from_locale("en_US").time_format,
+        TimeFormat::TwelveHour
+    );
+    let config: Config = toml::from_str(r#"time_format = "12h""#).unwrap();
+    assert_eq!(config.time_format, Some(TimeFormat::TwelveHour));
+}
@@ -0,0 +1,186 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sportfrei::api::polyline;
+use sportfrei::import::{gpx_points, read_archive_from, sport_type};
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="StravaGPX">
+ <trk><name>Morning Ride</name><trkseg>
+  <trkpt lat="52.5200000" lon="13.4050000"><ele>34.0</ele></trkpt>
+  <trkpt lon="13.4100000" lat="52.5210000"><ele>35.0</ele></trkpt>
+  <trkpt lat='52.5220000' lon='13.4150000'/>
+ </trkseg></trk>
+</gpx>"#;
+
+// The export's header repeats Distance and Elapsed Time: first the values
+// shown on strava.com, then the raw ones in meters and seconds
+const CSV: &str = "Activity ID,Activity Date,Activity Name,Activity Type,Activity Description,Elapsed Time,Distance,Max Heart Rate,Relative Effort,Commute,Activity Private Note,Activity Gear,Filename,Athlete Weight,Bike Weight,Elapsed Time,Moving Time,Distance,Max Speed,Average Speed,Elevation Gain,Average Heart Rate,Average Watts,Calories
+101,\"Jan 15, 2024, 8:30:00 AM\",Morning Ride,Ride,Along the river,3700,\"30.50\",171,45,false,Felt strong,Canyon,activities/101.gpx.gz,70,8,3700,3600,30512.4,14.2,8.47,210,142,180,850
+102,\"Mar 2, 2024, 6:05:12\u{202f}PM\",Zwift,Virtual Ride,,1800,\"15.00\",,,true,,,,70,,1800,1800,15000,,8.33,120,,,
+103,not a date,Broken,Run,,600,2.0,,,,,,,,,600,600,2000,,,,,,
+,\"Jan 16, 2024, 8:30:00 AM\",No id,Run,,600,2.0,,,,,,,,,600,600,2000,,,,,,
+";
+
+fn archive(files: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, data) in files {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(data).unwrap();
+    }
+    let mut cursor = zip.finish().unwrap();
+    cursor.set_position(0);
+    cursor
+}
+
+// A FIT file with two `record` messages of position and heart rate
+fn fit() -> Vec<u8> {
+    let mut messages = vec![0x40, 0, 0, 20, 0, 3, 0, 4, 0x85, 1, 4, 0x85, 3, 1, 0x02];
+    for (lat, hr) in [(52.52f64, 120u8), (52.53, 125)] {
+        messages.push(0x00);
+        messages.extend(((lat * 2_147_483_648.0 / 180.0) as i32).to_le_bytes());
+        messages.extend(((13.4 * 2_147_483_648.0 / 180.0) as i32).to_le_bytes());
+        messages.push(hr);
+    }
+    let mut file = vec![12, 0x10, 0x08, 0x08];
+    file.extend((messages.len() as u32).to_le_bytes());
+    file.extend(b".FIT");
+    file.extend(messages);
+    file.extend([0, 0]);
+    file
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn test_read_bulk_export() {
+    let gpx = gzip(GPX.as_bytes());
+    let import = read_archive_from(archive(&[
+        ("export_12345/activities.csv", CSV.as_bytes()),
+        ("export_12345/activities/101.gpx.gz", &gpx),
+    ]))
+    .unwrap();
+
+    assert_eq!(import.skipped, 2);
+    assert_eq!(import.activities.len(), 2);
+
+    let ride = &import.activities[0];
+    assert_eq!(ride.note.as_deref(), Some("Felt strong"));
+    let activity = &ride.activity;
+    assert_eq!(activity.id, 101);
+    assert_eq!(activity.name, "Morning Ride");
+    assert_eq!(activity.sport_type, "Ride");
+    assert_eq!(
+        activity.start_date.to_rfc3339(),
+        "2024-01-15T08:30:00+00:00"
+    );
+    // The raw column wins over the rounded kilometers
+    assert_eq!(activity.distance, 30512.4);
+    assert_eq!(activity.moving_time, 3600);
+    assert_eq!(activity.elapsed_time, 3700);
+    assert_eq!(activity.total_elevation_gain, 210.0);
+    assert_eq!(activity.average_heartrate, Some(142.0));
+    assert_eq!(activity.max_heartrate, Some(171.0));
+    assert_eq!(activity.average_watts, Some(180.0));
+    assert_eq!(activity.calories, Some(850.0));
+    assert_eq!(activity.suffer_score, Some(45.0));
+    assert_eq!(activity.description.as_deref(), Some("Along the river"));
+    assert_eq!(activity.commute, Some(false));
+    assert_eq!(activity.manual, Some(false));
+    let map = activity.map.as_ref().unwrap();
+    let line = polyline::decode(map.summary_polyline.as_deref().unwrap());
+    assert_eq!(line.len(), 3);
+    assert!((line[0].0 - 52.52).abs() < 1e-5 && (line[2].1 - 13.415).abs() < 1e-5);
+
+    let virtual_ride = &import.activities[1].activity;
+    assert_eq!(virtual_ride.sport_type, "VirtualRide");
+    assert_eq!(
+        virtual_ride.start_date.to_rfc3339(),
+        "2024-03-02T18:05:12+00:00"
+    );
+    assert_eq!(virtual_ride.commute, Some(true));
+    assert_eq!(virtual_ride.manual, Some(true));
+    assert!(virtual_ride.map.is_none());
+    assert!(import.activities[1].note.is_none());
+}
+
+#[test]
+fn test_fit_files_give_streams() {
+    let csv =
+        "Activity ID,Activity Date,Activity Name,Activity Type,Elapsed Time,Distance,Filename\n\
+               8,2024-02-01 07:00:00,Tempo,Run,1500,5.0,activities/8.fit.gz\n\
+               9,2024-02-02 07:00:00,Broken,Run,1500,5.0,activities/9.fit\n";
+    let fit = gzip(&fit());
+    let import = read_archive_from(archive(&[
+        ("activities.csv", csv.as_bytes()),
+        ("activities/8.fit.gz", &fit),
+        ("activities/9.fit", b"garbage"),
+    ]))
+    .unwrap();
+
+    let tempo = &import.activities[0];
+    let streams = tempo.streams.as_ref().unwrap();
+    assert_eq!(streams.heartrate.as_ref().unwrap().data, vec![120.0, 125.0]);
+    let line = polyline::decode(
+        tempo
+            .activity
+            .map
+            .as_ref()
+            .unwrap()
+            .summary_polyline
+            .as_deref()
+            .unwrap(),
+    );
+    assert_eq!(line.len(), 2);
+    assert!((line[1].0 - 52.53).abs() < 1e-5);
+
+    // A damaged file leaves the activity without a track
+    let broken = &import.activities[1];
+    assert!(broken.streams.is_none() && broken.activity.map.is_none());
+}
+
+#[test]
+fn test_archive_without_activities() {
+    let error = read_archive_from(archive(&[("export_12345/profile.csv", b"Athlete ID\n1\n")]))
+        .unwrap_err();
+    assert!(error.to_string().contains("activities.csv"));
+    assert!(read_archive_from(Cursor::new(b"not a zip".to_vec())).is_err());
+}
+
+#[test]
+fn test_older_export_distance_in_km() {
+    let csv = "Activity ID,Activity Date,Activity Name,Activity Type,Elapsed Time,Distance\n\
+               7,2019-05-01 07:00:00,Run,Run,1500,5.25\n";
+    let import = read_archive_from(archive(&[("activities.csv", csv.as_bytes())])).unwrap();
+    let activity = &import.activities[0].activity;
+    assert_eq!(activity.distance, 5250.0);
+    // Without a moving time the elapsed time stands in
+    assert_eq!(activity.moving_time, 1500);
+}
+
+#[test]
+fn test_sport_type_labels() {
+    assert_eq!(sport_type("Ride"), "Ride");
+    assert_eq!(sport_type("Virtual Ride"), "VirtualRide");
+    assert_eq!(sport_type("E-Bike Ride"), "EBikeRide");
+    assert_eq!(sport_type("Trail Run"), "TrailRun");
+    assert_eq!(sport_type("Weight Training"), "WeightTraining");
+    assert_eq!(sport_type("Rock Climb"), "RockClimbing");
+    assert_eq!(sport_type(""), "Workout");
+}
+
+#[test]
+fn test_gpx_points() {
+    let points = gpx_points(GPX);
+    assert_eq!(
+        points,
+        vec![(52.52, 13.405), (52.521, 13.41), (52.522, 13.415)]
+    );
+    assert!(gpx_points("<gpx></gpx>").is_empty());
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use chrono::{DateTime, NaiveDate};
+use sportfrei::api::types::Activity;
+use sportfrei::metrics::{TrainingMetrics, CSV_HEADER};
+
+fn activity(
+    id: u64,
+    sport: &str,
+    start: &str,
+    distance: f64,
+    moving_time: u32,
+    effort: f64,
+) -> Activity {
+    common::activity_with(
+        id,
+        sport,
+        start,
+        distance,
+        moving_time,
+        serde_json::json!({"total_elevation_gain": 50.0, "suffer_score": effort}),
+    )
+}
+
+fn date(text: &str) -> NaiveDate {
+    text.parse().unwrap()
+}
+
+// Monday the 8th to Wednesday the 10th, after an older run
+fn history() -> Vec<Activity> {
+    vec![
+        activity(1, "Run", "2024-01-02T07:00:00Z", 8000.0, 2400, 40.0),
+        activity(2, "Run", "2024-01-08T07:00:00Z", 10000.0, 3000, 50.0),
+        activity(3, "Ride", "2024-01-09T17:00:00Z", 30000.0, 3600, 60.0),
+        activity(4, "Run", "2024-01-10T07:00:00Z", 5000.0, 1500, 30.0),
+    ]
+}
+
+#[test]
+fn test_collect_metrics() {
+    let metrics =
+        TrainingMetrics::collect(&history(), None, date("2024-01-08"), date("2024-01-10"));
+
+    let sports: Vec<(&str, u32, f64)> = metrics
+        .week
+        .iter()
+        .map(|t| (t.sport.as_str(), t.count, t.distance))
+        .collect();
+    assert_eq!(sports, vec![("Run", 2, 15000.0), ("Ride", 1, 30000.0)]);
+    assert_eq!(metrics.current_streak, 3);
+    assert_eq!(metrics.longest_streak, 3);
+    assert_eq!(metrics.activities, 4);
+    assert_eq!(
+        metrics.last_activity,
+        Some(
+            DateTime::parse_from_rfc3339("2024-01-10T07:00:00Z")
+                .unwrap()
+                .into()
+        )
+    );
+    let today = metrics.fitness.as_ref().unwrap();
+    assert_eq!(today.date, date("2024-01-10"));
+    assert_eq!(today.load, 30.0);
+
+    // A day without activities breaks the streak
+    let metrics =
+        TrainingMetrics::collect(&history(), None, date("2024-01-08"), date("2024-01-12"));
+    assert_eq!(metrics.current_streak, 0);
+    assert_eq!(metrics.longest_streak, 3);
+}
+
+#[test]
+fn test_prometheus_format() {
+    let metrics =
+        TrainingMetrics::collect(&history(), None, date("2024-01-08"), date("2024-01-10"));
+    let text = metrics.to_prometheus();
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert!(lines.contains(&"# TYPE sportfrei_week_distance_meters gauge"));
+    assert!(lines.contains(&"sportfrei_week_distance_meters{sport=\"Run\"} 15000"));
+    assert!(lines.contains(&"sportfrei_week_distance_meters{sport=\"Ride\"} 30000"));
+    assert!(lines.contains(&"sportfrei_week_moving_time_seconds{sport=\"Run\"} 4500"));
+    assert!(lines.contains(&"sportfrei_training_load 30"));
+    assert!(lines.contains(&"sportfrei_streak_days 3"));
+    assert!(lines.contains(&"sportfrei_activities 4"));
+    assert!(lines.contains(&"sportfrei_last_activity_timestamp_seconds 1704870000"));
+    // Every sample has its HELP and TYPE
+    for line in lines.iter().filter(|l| !l.starts_with('#')) {
+        let name = line.split(['{', ' ']).next().unwrap();
+        assert!(text.contains(&format!("# TYPE {} gauge", name)), "{}", line);
+    }
+
+    // Without a load there is no fitness to report
+    let empty = TrainingMetrics::collect(&[], None, date("2024-01-08"), date("2024-01-10"));
+    let text = empty.to_prometheus();
+    assert!(!text.contains("sportfrei_fitness"));
+    assert!(!text.contains("sportfrei_last_activity"));
+    assert!(text.contains("sportfrei_activities 0"));
+}
+
+#[test]
+fn test_csv_row() {
+    let metrics =
+        TrainingMetrics::collect(&history(), None, date("2024-01-08"), date("2024-01-10"));
+    let time = DateTime::parse_from_rfc3339("2024-01-10T20:00:00Z")
+        .unwrap()
+        .into();
+    let row = metrics.csv_row(time);
+    let columns: Vec<&str> = row.split(',').collect();
+    assert_eq!(columns.len(), CSV_HEADER.split(',').count());
+    assert_eq!(
+        &columns[..5],
+        ["2024-01-10T20:00:00Z", "3", "45000", "8100", "150"]
+    );
+    assert_eq!(columns[5], "30.0");
+    assert_eq!(&columns[9..], ["3", "3", "4"]);
+
+    let empty = TrainingMetrics::collect(&[], None, date("2024-01-08"), date("2024-01-10"));
+    assert_eq!(
+        empty.csv_row(time),
+        "2024-01-10T20:00:00Z,0,0,0,0,,,,,0,0,0"
+    );
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use sportfrei::api::types::Activity;
+use sportfrei::config::NotificationConfig;
+use sportfrei::notify::{detect, Event};
+use sportfrei::ui::app::App;
+
+fn activity(id: u64, sport: &str, distance: f64, kudos: u32, prs: u32) -> Activity {
+    common::activity_with(
+        id,
+        sport,
+        &format!("2024-01-{:02}T07:00:00Z", id),
+        distance,
+        3000,
+        serde_json::json!({"kudos_count": kudos, "pr_count": prs}),
+    )
+}
+
+fn all() -> NotificationConfig {
+    NotificationConfig {
+        new_activities: true,
+        kudos: true,
+        records: true,
+    }
+}
+
+fn cached() -> Vec<Activity> {
+    vec![
+        activity(1, "Run", 10000.0, 2, 0),
+        activity(2, "Ride", 40000.0, 5, 0),
+    ]
+}
+
+#[test]
+fn test_detect_new_activities_kudos_and_records() {
+    let fetched = vec![
+        activity(2, "Ride", 40000.0, 8, 0),
+        activity(3, "Run", 12000.0, 0, 2),
+    ];
+    let events = detect(&cached(), &fetched, &all());
+
+    assert!(matches!(&events[0], Event::NewActivity(a) if a.id == 3));
+    let records: Vec<&str> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::Record { record, activity } if activity.id == 3 => Some(*record),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(records, vec!["Longest run"]);
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, Event::SegmentPrs { activity, count: 2 } if activity.id == 3)));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, Event::Kudos { activity, new: 3 } if activity.id == 2)));
+    assert_eq!(events.len(), 4);
+}
+
+#[test]
+fn test_detect_only_enabled_events() {
+    let fetched = vec![
+        activity(2, "Ride", 40000.0, 8, 0),
+        activity(3, "Run", 12000.0, 0, 2),
+    ];
+    assert!(detect(&cached(), &fetched, &NotificationConfig::default()).is_empty());
+
+    let kudos = NotificationConfig {
+        kudos: true,
+        ..NotificationConfig::default()
+    };
+    let events = detect(&cached(), &fetched, &kudos);
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0], Event::Kudos { new: 3, .. }));
+
+    // Unchanged activities, and kudos taken back, stay quiet
+    let unchanged = vec![activity(1, "Run", 10000.0, 1, 0)];
+    assert!(detect(&cached(), &unchanged, &all()).is_empty());
+}
+
+#[test]
+fn test_first_activity_of_a_sport_is_no_record() {
+    let records = NotificationConfig {
+        records: true,
+        ..NotificationConfig::default()
+    };
+    let fetched = vec![
+        activity(3, "Swim", 2000.0, 0, 0),
+        activity(4, "Run", 5000.0, 0, 0),
+    ];
+    // Swims have no records and the run is shorter than the cached one
+    assert!(detect(&cached(), &fetched, &records).is_empty());
+    // Nothing cached yet, so nothing was beaten
+    assert!(detect(&[], &fetched, &records).is_empty());
+}
+
+#[test]
+fn test_notification_text() {
+    let app = App::new();
+    let (title, body) = app.notification(&Event::NewActivity(activity(3, "Run", 12000.0, 0, 0)));
+    assert_eq!(title, "New activity: Activity 3");
+    assert_eq!(body, "Run · 12.0 km · 0:50:00");
+
+    let (title, body) = app.notification(&Event::Kudos {
+        activity: activity(2, "Ride", 40000.0, 8, 0),
+        new: 3,
+    });
+    assert_eq!(title, "3 new kudos");
+    assert_eq!(body, "on Activity 2 (8 in total)");
+
+    let (title, body) = app.notification(&Event::Record {
+        record: "Longest run",
+        activity: activity(3, "Run", 12000.0, 0, 0),
+    });
+    assert_eq!(title, "New personal record");
+    assert_eq!(body, "Longest run: Activity 3");
+
+    let (title, _) = app.notification(&Event::SegmentPrs {
+        activity: activity(3, "Run", 12000.0, 0, 1),
+        count: 1,
+    });
+    assert_eq!(title, "1 segment PR");
+}
@@ -0,0 +1,65 @@
+use sportfrei::api::oauth::{authorize_url, parse_callback, redirect_uri, Pkce};
+
+#[test]
+fn test_pkce_challenge_is_s256_of_verifier() {
+    let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mJ0kT7ePWzYl73BcRkbz9lpOwVSA1s");
+    assert_eq!(
+        pkce.challenge,
+        "A4mUVDq5U_xSt1E_IKK_xt2DeBsltQBXahDGUMUT80Y"
+    );
+
+    let generated = Pkce::generate();
+    assert_eq!(generated.verifier.len(), 64);
+    assert_ne!(generated.verifier, Pkce::generate().verifier);
+}
+
+#[test]
+fn test_authorize_url_contains_pkce_and_redirect_port() {
+    let pkce = Pkce::from_verifier("verifier");
+    let url = authorize_url("12345", &redirect_uri(8765), &pkce, "state123");
+
+    assert!(url.starts_with("https://www.strava.com/oauth/authorize?client_id=12345"));
+    assert!(url.contains("redirect_uri=http://localhost:8765"));
+    assert!(url.contains(&format!("code_challenge={}", pkce.challenge)));
+    assert!(url.contains("code_challenge_method=S256"));
+    assert!(url.contains("state=state123"));
+}
+
+#[test]
+fn test_parse_callback() {
+    let params =
+        parse_callback("GET /?state=abc&code=xyz&scope=read HTTP/1.1\r\nHost: localhost\r\n")
+            .unwrap();
+    assert_eq!(params.code.as_deref(), Some("xyz"));
+    assert_eq!(params.state.as_deref(), Some("abc"));
+    assert_eq!(params.error, None);
+
+    let denied = parse_callback("GET /?state=abc&error=access_denied HTTP/1.1\r\n").unwrap();
+    assert_eq!(denied.code, None);
+    assert_eq!(denied.error.as_deref(), Some("access_denied"));
+
+    assert!(parse_callback("GET /favicon.ico HTTP/1.1\r\n").is_none());
+}
+
+#[test]
+fn test_callback_outcome() {
+    let callback = |query: &str| parse_callback(&format!("GET /?{} HTTP/1.1\r\n", query)).unwrap();
+
+    let code = callback("state=abc&code=xyz").outcome("abc");
+    assert_eq!(code.unwrap().unwrap(), "xyz");
+
+    // Clicking Deny ends the wait right away
+    let denied = callback("state=abc&error=access_denied").outcome("abc");
+    assert!(denied
+        .unwrap()
+        .unwrap_err()
+        .to_string()
+        .contains("access_denied"));
+
+    // Callbacks of another attempt are ignored, denied or not
+    assert!(callback("state=old&error=access_denied")
+        .outcome("abc")
+        .is_none());
+    assert!(callback("state=old&code=xyz").outcome("abc").is_none());
+    assert!(callback("state=abc").outcome("abc").is_none());
+}
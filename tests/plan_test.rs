@@ -0,0 +1,75 @@
+mod common;
+
+use common::{activity, date};
+use sportfrei::config::Units;
+use sportfrei::plan::{Compliance, Plan};
+
+fn plan() -> Plan {
+    toml::from_str(
+        r#"
+        [[workout]]
+        date = 2024-03-12
+        sport = "Run"
+        name = "Tempo"
+        distance = 10
+
+        [[workout]]
+        date = "2024-03-13"
+        sport = "ride"
+        duration = 90
+
+        [[workout]]
+        date = 2024-03-14
+        sport = "Run"
+        distance = 8
+
+        [[workout]]
+        date = 2024-03-16
+        sport = "Swim"
+        "#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_plan_parses_bare_and_quoted_dates() {
+    let plan = plan();
+    assert_eq!(plan.workouts.len(), 4);
+    assert_eq!(plan.workouts[0].date, date(2024, 3, 12));
+    assert_eq!(plan.workouts[0].name.as_deref(), Some("Tempo"));
+    assert_eq!(plan.workouts[1].date, date(2024, 3, 13));
+    assert_eq!(plan.workouts[1].duration, Some(90));
+    assert_eq!(plan.workouts[0].target_label(Units::Metric), "Run 10 km");
+    assert_eq!(plan.workouts[1].target_label(Units::Metric), "ride 90 min");
+
+    assert!(toml::from_str::<Plan>("[[workout]]\ndate = \"soon\"\nsport = \"Run\"").is_err());
+}
+
+#[test]
+fn test_plan_compliance() {
+    let plan = plan();
+    let activities = [
+        // Two runs on the day add up to the tempo target
+        activity(1, "Run", "2024-03-12T07:00:00Z", 6000.0, 1800),
+        activity(2, "Run", "2024-03-12T18:00:00Z", 3500.0, 1200),
+        // Ride cut short: 45 of 90 minutes
+        activity(3, "Ride", "2024-03-13T07:00:00Z", 20000.0, 2700),
+        // Wrong sport doesn't count for the run on the 14th
+        activity(4, "Ride", "2024-03-14T07:00:00Z", 8000.0, 1200),
+    ];
+    let today = date(2024, 3, 15);
+    let compliance: Vec<Compliance> = plan
+        .workouts
+        .iter()
+        .map(|w| w.compliance(&activities, today, Units::Metric))
+        .collect();
+    assert_eq!(
+        compliance,
+        [
+            Compliance::Done,
+            Compliance::Partial(0.5),
+            Compliance::Missed,
+            Compliance::Planned
+        ]
+    );
+}
@@ -0,0 +1,26 @@
+use sportfrei::api::polyline;
+
+#[test]
+fn test_decode_polyline() {
+    let points = polyline::decode("_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+
+    assert_eq!(points.len(), 3);
+    let expected = [(38.5, -120.2), (40.7, -120.95), (43.252, -126.453)];
+    for ((lat, lng), (exp_lat, exp_lng)) in points.iter().zip(expected) {
+        assert!((lat - exp_lat).abs() < 1e-9);
+        assert!((lng - exp_lng).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_decode_empty_polyline() {
+    assert!(polyline::decode("").is_empty());
+}
+
+#[test]
+fn test_encode_polyline() {
+    let points = [(38.5, -120.2), (40.7, -120.95), (43.252, -126.453)];
+    assert_eq!(polyline::encode(&points), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+    assert_eq!(polyline::decode(&polyline::encode(&points)).len(), 3);
+    assert_eq!(polyline::encode(&[]), "");
+}
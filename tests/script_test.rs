@@ -0,0 +1,92 @@
+mod common;
+
+use sportfrei::api::types::Activity;
+use sportfrei::script::{card_title, Scripts};
+
+fn activity(id: u64, distance: f64, heartrate: Option<f64>) -> Activity {
+    common::activity_with(
+        id,
+        "Run",
+        "2024-01-15T07:00:00Z",
+        distance,
+        1800,
+        serde_json::json!({
+            "start_date_local": "2024-01-15T08:00:00Z",
+            "average_heartrate": heartrate
+        }),
+    )
+}
+
+const SCRIPT: &str = r#"
+    fn efficiency(a) {
+        if a.average_heartrate == () { return (); }
+        let speed = a.distance / a.moving_time;
+        speed * 60.0 / a.average_heartrate * 100.0
+    }
+
+    fn label(a) { a.sport_type + " #" + a.id }
+
+    fn broken(a) { a.distance / "x" }
+
+    fn card_long_runs(activities) {
+        let long = activities.filter(|a| a.distance >= 10000.0);
+        `${long.len()} of ${activities.len()}`
+    }
+
+    fn helper(a, b) { a + b }
+"#;
+
+#[test]
+fn test_script_columns() {
+    let scripts = Scripts::compile(SCRIPT).unwrap();
+    assert!(scripts.has_function("efficiency"));
+    assert!(!scripts.has_function("helper"));
+    assert!(!scripts.has_function("missing"));
+
+    let run = activity(7, 5400.0, Some(150.0));
+    // 3 m/s at 150 bpm
+    assert_eq!(
+        scripts.column("efficiency", &run),
+        Ok(Some("120.0".to_string()))
+    );
+    assert_eq!(
+        scripts.column("label", &run),
+        Ok(Some("Run #7".to_string()))
+    );
+    // () means no value
+    assert_eq!(
+        scripts.column("efficiency", &activity(8, 5400.0, None)),
+        Ok(None)
+    );
+    assert!(scripts.column("broken", &run).is_err());
+    assert!(scripts.column("missing", &run).is_err());
+}
+
+#[test]
+fn test_script_cards() {
+    let scripts = Scripts::compile(SCRIPT).unwrap();
+    assert_eq!(scripts.cards(), vec!["card_long_runs".to_string()]);
+    assert_eq!(card_title("card_long_runs"), "Long runs");
+
+    let short = activity(1, 5000.0, None);
+    let long = activity(2, 21100.0, None);
+    assert_eq!(
+        scripts.card("card_long_runs", &[&short, &long]).unwrap(),
+        "1 of 2"
+    );
+    // A different set of activities isn't served from the last result
+    assert_eq!(scripts.card("card_long_runs", &[&short]).unwrap(), "0 of 1");
+}
+
+#[test]
+fn test_script_errors() {
+    let error = Scripts::compile("fn efficiency(a) { a.distance +")
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("line 1"));
+
+    // Runaway loops hit the operations limit instead of freezing the UI
+    let scripts = Scripts::compile("fn spin(a) { loop {} }").unwrap();
+    let error = scripts.column("spin", &activity(1, 0.0, None)).unwrap_err();
+    assert!(error.to_lowercase().contains("too many operations"));
+}
@@ -0,0 +1,76 @@
+use ratatui::style::Color;
+use sportfrei::ui::theme::Theme;
+use std::collections::BTreeMap;
+
+fn custom(entries: &[(&str, &str)]) -> BTreeMap<String, BTreeMap<String, String>> {
+    let theme = entries
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    BTreeMap::from([("mine".to_string(), theme)])
+}
+
+#[test]
+fn test_built_in_themes() {
+    let none = BTreeMap::new();
+    assert_eq!(Theme::resolve(None, &none).unwrap(), Theme::dark());
+    for name in Theme::BUILT_IN {
+        assert!(Theme::resolve(Some(name), &none).is_ok());
+    }
+    assert_eq!(Theme::light().text, Color::Black);
+
+    let err = Theme::resolve(Some("neon"), &none).unwrap_err();
+    assert!(err.to_string().contains("dark, light, solarized, gruvbox"));
+}
+
+#[test]
+fn test_custom_theme_overrides_base() {
+    let themes = custom(&[("base", "light"), ("accent", "#268bd2"), ("run", "red")]);
+    let theme = Theme::resolve(Some("mine"), &themes).unwrap();
+
+    assert_eq!(theme.accent, Color::Rgb(0x26, 0x8b, 0xd2));
+    assert_eq!(theme.sport("Run"), Color::Red);
+    assert_eq!(theme.text, Theme::light().text);
+
+    let bad_color = custom(&[("accent", "not-a-color")]);
+    assert!(Theme::resolve(Some("mine"), &bad_color).is_err());
+    let bad_slot = custom(&[("sparkles", "red")]);
+    assert!(Theme::resolve(Some("mine"), &bad_slot).is_err());
+}
+
+#[test]
+fn test_virtual_sports_have_their_own_colors() {
+    let none = BTreeMap::new();
+    for name in Theme::BUILT_IN {
+        let theme = Theme::resolve(Some(name), &none).unwrap();
+        assert_ne!(theme.sport("VirtualRide"), theme.sport("Ride"), "{}", name);
+        assert_ne!(theme.sport("VirtualRun"), theme.sport("Run"), "{}", name);
+    }
+
+    let themes = custom(&[("virtual_ride", "#ff8800")]);
+    let theme = Theme::resolve(Some("mine"), &themes).unwrap();
+    assert_eq!(theme.sport("VirtualRide"), Color::Rgb(0xff, 0x88, 0x00));
+}
+
+#[test]
+fn test_sport_groups_share_colors() {
+    let theme = Theme::dark();
+    assert_eq!(theme.sport("GravelRide"), theme.ride);
+    assert_eq!(theme.sport("TrailRun"), theme.run);
+    assert_eq!(theme.sport("EMountainBikeRide"), theme.ebike);
+    assert_eq!(theme.sport("Kayaking"), theme.swim);
+    for sport in ["NordicSki", "AlpineSki", "Snowboard"] {
+        assert_eq!(theme.sport(sport), theme.winter);
+    }
+    assert_eq!(theme.sport("RockClimbing"), theme.other_sport);
+
+    // A single sport type overrides its group
+    let themes = custom(&[("ebike", "yellow"), ("GravelRide", "#aa7744")]);
+    let theme = Theme::resolve(Some("mine"), &themes).unwrap();
+    assert_eq!(theme.sport("GravelRide"), Color::Rgb(0xaa, 0x77, 0x44));
+    assert_eq!(theme.sport("Ride"), Theme::dark().ride);
+    assert_eq!(theme.sport("EBikeRide"), Color::Yellow);
+
+    let typo = custom(&[("GravelRides", "red")]);
+    assert!(Theme::resolve(Some("mine"), &typo).is_err());
+}
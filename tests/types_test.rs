@@ -1,4 +1,11 @@
-use sportfrei::api::types::{Activity, ActivityStats, Athlete, AthleteStats};
+use chrono::{TimeZone, Utc};
+use sportfrei::api::de;
+use sportfrei::api::types::{
+    Activity, ActivityStats, Athlete, AthleteStats, Comment, DetailedActivity, DetailedSegment,
+    Photo, Route, StreamSet, SummaryAthlete, SummarySegment, UpdatableActivity, Upload,
+    UploadDataType,
+};
+use std::path::Path;
 
 #[test]
 fn test_parse_athlete() {
@@ -21,6 +28,57 @@ fn test_parse_athlete() {
     assert_eq!(athlete.lastname, "Doe");
     assert_eq!(athlete.city, Some("Berlin".to_string()));
     assert_eq!(athlete.country, Some("Germany".to_string()));
+    assert!(athlete.bikes.is_empty());
+}
+
+#[test]
+fn test_parse_route() {
+    let json = r#"{
+        "id": 3344556677889900112,
+        "name": "Müggelsee & back!",
+        "distance": 42195.0,
+        "elevation_gain": 120.5,
+        "type": 2,
+        "starred": true,
+        "private": null,
+        "created_at": "2023-04-12T18:20:00Z",
+        "map": {"id": "r1", "summary_polyline": "_p~iF~ps|U"}
+    }"#;
+
+    let route: Route = serde_json::from_str(json).unwrap();
+    assert_eq!(route.id, 3344556677889900112);
+    assert_eq!(route.sport(), "Run");
+    assert!(route.starred);
+    assert!(!route.private);
+    assert_eq!(route.gpx_file_name(), "müggelsee-back.gpx");
+
+    let unnamed: Route = serde_json::from_str(r#"{"id": 7, "name": " - "}"#).unwrap();
+    assert_eq!(unnamed.sport(), "Workout");
+    assert_eq!(unnamed.gpx_file_name(), "route-7.gpx");
+}
+
+#[test]
+fn test_parse_athlete_gear() {
+    let json = r#"{
+        "id": 123456,
+        "firstname": "John",
+        "lastname": "Doe",
+        "bikes": [
+            {"id": "b12345", "primary": true, "name": "Canyon", "resource_state": 2, "distance": 812345.0}
+        ],
+        "shoes": [
+            {"id": "g67890", "primary": false, "name": "Pegasus", "resource_state": 2, "distance": 654321.0}
+        ]
+    }"#;
+
+    let athlete: Athlete = serde_json::from_str(json).unwrap();
+
+    assert_eq!(athlete.bikes.len(), 1);
+    assert!(athlete.bikes[0].is_bike());
+    assert!(athlete.bikes[0].primary);
+    assert_eq!(athlete.shoes[0].name, "Pegasus");
+    assert!(!athlete.shoes[0].is_bike());
+    assert_eq!(athlete.shoes[0].distance, 654321.0);
 }
 
 #[test]
@@ -142,6 +200,31 @@ fn test_parse_activity() {
     assert_eq!(activity.description, Some("Easy morning run".to_string()));
 }
 
+#[test]
+fn test_parse_detailed_activity() {
+    let json = r#"{
+        "id": 1, "name": "Tempo", "sport_type": "Run",
+        "start_date": "2024-01-15T08:30:00Z", "start_date_local": "2024-01-15T09:30:00Z",
+        "distance": 10000.0, "moving_time": 2700, "calories": 702.5,
+        "average_temp": 7, "device_name": "Garmin Forerunner 265",
+        "embed_token": "18e4615989b47dd4ff3dc711b0aa4502e4b311a9"
+    }"#;
+    let detail: DetailedActivity = serde_json::from_str(json).unwrap();
+    // Calories stay with the flattened activity
+    assert_eq!(detail.activity.calories, Some(702.5));
+    assert_eq!(detail.average_temp, Some(7.0));
+    assert_eq!(detail.device_name.as_deref(), Some("Garmin Forerunner 265"));
+    assert!(detail.embed_token.is_some());
+
+    let manual: DetailedActivity = serde_json::from_str(
+        r#"{"id": 2, "start_date": "2024-01-15T08:30:00Z",
+            "start_date_local": "2024-01-15T09:30:00Z"}"#,
+    )
+    .unwrap();
+    assert_eq!(manual.average_temp, None);
+    assert_eq!(manual.device_name, None);
+}
+
 #[test]
 fn test_parse_activity_list() {
     let json = r#"[
@@ -182,6 +265,58 @@ fn test_parse_activity_list() {
     assert_eq!(activities[1].activity_type, "Ride");
 }
 
+#[test]
+fn test_parse_activity_with_missing_and_odd_fields() {
+    let json = r#"{
+        "id": 3,
+        "name": null,
+        "type": "",
+        "sport_type": "VirtualRow",
+        "start_date": "2024-01-15 08:30:00",
+        "start_date_local": 1705311000,
+        "timezone": null,
+        "distance": null,
+        "moving_time": 1800
+    }"#;
+
+    let activity: Activity = serde_json::from_str(json).unwrap();
+
+    assert_eq!(activity.name, "");
+    assert_eq!(activity.activity_type, "Workout");
+    assert_eq!(activity.sport_type, "VirtualRow");
+    assert_eq!(
+        activity.start_date,
+        Utc.with_ymd_and_hms(2024, 1, 15, 8, 30, 0).unwrap()
+    );
+    assert_eq!(
+        activity.start_date_local,
+        Utc.with_ymd_and_hms(2024, 1, 15, 9, 30, 0).unwrap()
+    );
+    assert_eq!(activity.timezone, "");
+    assert_eq!(activity.distance, 0.0);
+    assert_eq!(activity.elapsed_time, 0);
+
+    // Only the id and dates can't be made up
+    assert!(serde_json::from_str::<Activity>(r#"{"id": 4, "start_date": "yesterday"}"#).is_err());
+}
+
+#[test]
+fn test_lenient_list_skips_malformed_entries() {
+    let json = r#"[
+        {"id": 1, "start_date": "2024-01-15T08:30:00Z", "start_date_local": "2024-01-15T09:30:00Z"},
+        {"name": "No id", "start_date": "2024-01-14T08:30:00Z", "start_date_local": "2024-01-14T09:30:00Z"},
+        {"id": 3, "start_date": "2024-01-13T08:30:00Z", "start_date_local": "2024-01-13T09:30:00Z"}
+    ]"#;
+
+    let activities: Vec<Activity> = de::list(json, true).unwrap();
+    assert_eq!(
+        activities.iter().map(|a| a.id).collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+    assert!(de::list::<Activity>(json, false).is_err());
+    assert!(de::list::<Activity>("{}", true).is_err());
+}
+
 #[test]
 fn test_activity_distance_conversion() {
     let json = r#"{
@@ -208,3 +343,129 @@ fn test_activity_distance_conversion() {
     let minutes = activity.moving_time / 60;
     assert_eq!(minutes, 5);
 }
+
+#[test]
+fn test_parse_stream_set() {
+    let json = r#"{
+        "distance": {"data": [0.0, 10.5, 21.0], "series_type": "distance", "original_size": 3, "resolution": "high"},
+        "heartrate": {"data": [120, 125, 131], "series_type": "distance", "original_size": 3, "resolution": "high"},
+        "latlng": {"data": [[52.52, 13.40], [52.53, 13.41]], "series_type": "distance", "original_size": 2, "resolution": "high"}
+    }"#;
+
+    let streams: StreamSet = serde_json::from_str(json).unwrap();
+
+    assert_eq!(streams.heartrate.unwrap().data, vec![120.0, 125.0, 131.0]);
+    assert_eq!(streams.distance.unwrap().data.len(), 3);
+    assert_eq!(streams.latlng.unwrap().data[1], [52.53, 13.41]);
+    assert!(streams.altitude.is_none());
+}
+
+#[test]
+fn test_parse_kudoers_and_comments() {
+    let kudoers: Vec<SummaryAthlete> =
+        serde_json::from_str(r#"[{"resource_state": 2, "firstname": "Jane", "lastname": "D."}]"#)
+            .unwrap();
+    assert_eq!(kudoers[0].firstname, "Jane");
+    assert_eq!(kudoers[0].id, None);
+
+    let comments: Vec<Comment> = serde_json::from_str(
+        r#"[{
+            "id": 7, "activity_id": 123, "text": "Great pace!",
+            "athlete": {"id": 42, "firstname": "Max", "lastname": "M."},
+            "created_at": "2024-01-15T10:00:00Z"
+        }]"#,
+    )
+    .unwrap();
+    assert_eq!(comments[0].text, "Great pace!");
+    assert_eq!(comments[0].athlete.id, Some(42));
+}
+
+#[test]
+fn test_serialize_updatable_activity_skips_unset_fields() {
+    let update = UpdatableActivity {
+        name: Some("Lunch Ride".to_string()),
+        trainer: Some(true),
+        ..Default::default()
+    };
+
+    let json = serde_json::to_value(&update).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"name": "Lunch Ride", "trainer": true})
+    );
+}
+
+#[test]
+fn test_parse_segments() {
+    let starred: Vec<SummarySegment> = serde_json::from_str(
+        r#"[{
+            "id": 229781, "name": "Hawk Hill", "activity_type": "Ride",
+            "distance": 2684.82, "average_grade": 5.7, "starred": true,
+            "athlete_pr_effort": {"pr_elapsed_time": 553, "pr_date": "1993-04-03", "effort_count": 2}
+        }]"#,
+    )
+    .unwrap();
+    let stats = starred[0].my_stats().unwrap();
+    assert_eq!(stats.pr_elapsed_time, Some(553));
+    assert_eq!(stats.effort_count, Some(2));
+
+    let detail: DetailedSegment = serde_json::from_str(
+        r#"{
+            "id": 229781, "name": "Hawk Hill", "distance": 2684.82,
+            "athlete_segment_stats": {"pr_elapsed_time": 540, "effort_count": 3},
+            "effort_count": 309974, "athlete_count": 30623, "star_count": 2428,
+            "total_elevation_gain": 155.7, "hazardous": true,
+            "local_legend": {"athlete_id": 42, "title": "Jane D.", "effort_description": "24 efforts",
+                             "effort_count": "1,024"}
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(detail.segment.my_stats().unwrap().effort_count, Some(3));
+    assert_eq!(detail.athlete_count, Some(30623));
+    assert_eq!(detail.total_elevation_gain, Some(155.7));
+    assert!(detail.hazardous);
+    let legend = detail.local_legend.unwrap();
+    assert_eq!(legend.athlete_id, Some(42));
+    assert_eq!(legend.effort_count, Some(1024));
+
+    let detail: DetailedSegment = serde_json::from_str(
+        r#"{"id": 1, "name": "Flat", "distance": 100.0, "hazardous": null,
+            "local_legend": {"athlete_id": 7, "effort_count": 12}}"#,
+    )
+    .unwrap();
+    assert!(!detail.hazardous);
+    assert_eq!(detail.local_legend.unwrap().effort_count, Some(12));
+}
+
+#[test]
+fn test_parse_photos() {
+    let photos: Vec<Photo> = serde_json::from_str(
+        r#"[
+            {"unique_id": "a1", "caption": "Summit", "source": 1,
+             "urls": {"100": "https://example.com/a1-100.jpg", "600": "https://example.com/a1-600.jpg"}},
+            {"unique_id": "b2", "caption": null}
+        ]"#,
+    )
+    .unwrap();
+    assert_eq!(photos[0].url(), Some("https://example.com/a1-600.jpg"));
+    assert_eq!(photos[0].caption.as_deref(), Some("Summit"));
+    assert_eq!(photos[1].url(), None);
+}
+
+#[test]
+fn test_upload_data_type_and_status() {
+    let guess = |name: &str| UploadDataType::from_path(Path::new(name));
+    assert_eq!(guess("ride.fit"), Some(UploadDataType::Fit));
+    assert_eq!(guess("/tmp/Run.GPX.gz"), Some(UploadDataType::GpxGz));
+    assert_eq!(guess("swim.tcx"), Some(UploadDataType::Tcx));
+    assert_eq!(guess("notes.txt"), None);
+    assert_eq!("fit.gz".parse(), Ok(UploadDataType::FitGz));
+
+    let upload: Upload = serde_json::from_str(
+        r#"{"id": 16486788, "id_str": "16486788", "external_id": "ride.fit",
+            "error": null, "status": "Your activity is ready.", "activity_id": 1234}"#,
+    )
+    .unwrap();
+    assert_eq!(upload.activity_id, Some(1234));
+    assert!(upload.error.is_none());
+}
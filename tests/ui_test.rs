@@ -2,17 +2,38 @@
 
 use ratatui::backend::TestBackend;
 use ratatui::buffer::Buffer;
+use ratatui::style::Color;
 use ratatui::Terminal;
-use sportfrei::api::types::{Activity, ActivityStats, Athlete, AthleteStats};
-use sportfrei::ui::app::{App, View};
+use sportfrei::analytics::EfficiencyMetric;
+use sportfrei::api::types::{
+    Activity, ActivityStats, ActivityZone, Athlete, AthleteStats, BestEffort, ClubActivity,
+    Comment, DetailedActivity, DetailedAthlete, DetailedSegment, FeedActivity, Gear, Page, Photo,
+    PolylineMap, Reference, Route, Split, Stream, StreamSet, SummaryAthlete, SummaryClub,
+    SummarySegment,
+};
+use sportfrei::cache::TagStore;
+use sportfrei::config::{TimeBasis, Units};
+use sportfrei::error::RateLimit;
+use sportfrei::goals::{Goal, GoalMetric, GoalPeriod};
+use sportfrei::i18n::{Language, Locale};
+use sportfrei::plan::Plan;
+use sportfrei::script::Scripts;
+use sportfrei::ui::app::{
+    App, ConfirmAction, DashboardRange, DetailTab, EditField, Effect, SortDirection, SortKey,
+    SplitsMode, TrainingPeriod, UploadState, View,
+};
+use sportfrei::ui::columns::ActivityColumn;
+use sportfrei::ui::graphics::GraphicsProtocol;
+use sportfrei::ui::keymap::{self, Action};
+use sportfrei::ui::theme::Theme;
 
 fn get_buffer_content(buffer: &Buffer) -> String {
     let mut content = String::new();
     let area = buffer.area();
     for y in 0..area.height {
         for x in 0..area.width {
-            let cell = buffer.get(x, y);
-            content.push_str(&cell.symbol().to_string());
+            let cell = &buffer[(x, y)];
+            content.push_str(cell.symbol());
         }
         content.push('\n');
     }
@@ -60,6 +81,168 @@ fn test_activities_table_columns() {
     assert!(content.contains(" 50"), "Elevation should be 50m");
 }
 
+#[test]
+fn test_activities_sport_icons() {
+    use sportfrei::ui::columns::SportIcons;
+
+    let backend = TestBackend::new(150, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    app.set_sport_icons(SportIcons::Ascii);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("★ RUN Morning Run"));
+    assert!(content.contains("★ BIK Evening Ride"));
+
+    app.set_sport_icons(SportIcons::Emoji);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("🏃"));
+    assert!(content.contains("🚴"));
+    // The wide glyph is measured as two cells, so the name still fits
+    assert!(content.contains("Morning Run"));
+
+    assert_eq!(SportIcons::Ascii.icon("NordicSki"), Some("SKI"));
+    assert_eq!(SportIcons::Emoji.icon("Snowboard"), Some("🏂"));
+    assert_eq!(SportIcons::None.icon("Run"), None);
+}
+
+#[test]
+fn test_activities_flag_personal_records() {
+    let backend = TestBackend::new(150, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("★ Morning Run"));
+    assert!(content.contains("★ Evening Ride"));
+    assert!(content.contains("★ holds a personal record"));
+
+    // A longer, faster and hillier run further back in the cache takes over
+    let mut older = create_test_activities(1).remove(0);
+    older.id = 99;
+    older.sport_type = "Run".to_string();
+    older.start_date = chrono::Utc::now() - chrono::Duration::days(100);
+    older.distance = 10000.0;
+    older.average_speed = Some(3.0);
+    older.total_elevation_gain = 100.0;
+    app.merge_history([older]);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(!content.contains("★ Morning Run"));
+    assert!(content.contains("★ Evening Ride"));
+
+    app.select_next_activity();
+    app.set_view(View::ActivityDetail);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("★ Longest ride, Biggest climb (ride), Fastest ride over 20k"));
+}
+
+#[test]
+fn test_activities_grouped_by_week_and_month() {
+    let backend = TestBackend::new(150, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    // Daily runs from Sunday, March 17th 2024 back to February 27th
+    let start = chrono::DateTime::parse_from_rfc3339("2024-03-17T08:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    let mut activities = create_test_activities(20);
+    for (i, activity) in activities.iter_mut().enumerate() {
+        activity.start_date = start - chrono::Duration::days(i as i64);
+        activity.start_date_local = activity.start_date;
+    }
+    let mut app = App::new();
+    app.set_data(create_test_athlete(), create_test_stats(), activities, 30);
+    app.set_view(View::Activities);
+    assert_eq!(
+        keymap::action(View::Activities, crossterm::event::KeyCode::Char('w')),
+        Some(Action::GroupActivities)
+    );
+
+    app.cycle_activity_grouping();
+    assert_eq!(app.activity_grouping(), Some(TrainingPeriod::Week));
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Week 11, 2024 — 35.0 km, 7 activities, 3h 30m"));
+    assert!(content.contains("Week 10, 2024 — 35.0 km, 7 activities"));
+
+    // Scrolled past its header, the group of the top row stays pinned
+    for _ in 0..15 {
+        app.select_next_activity();
+    }
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    let body: Vec<&str> = content.lines().skip(5).collect();
+    assert!(body[0].contains("Week 10, 2024") || body[0].contains("Week 9, 2024"));
+    assert!(content.contains("Activity 15"));
+    assert!(!content.contains("Week 11, 2024"));
+
+    app.cycle_activity_grouping();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("March 2024 — 85.0 km, 17 activities"));
+
+    app.cycle_activity_grouping();
+    assert_eq!(app.activity_grouping(), None);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(!content.contains("March 2024 —"));
+}
+
+#[test]
+fn test_tag_activities_and_filter_by_tag() {
+    let backend = TestBackend::new(150, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_data(
+        create_test_athlete(),
+        create_test_stats(),
+        create_test_activities(4),
+        30,
+    );
+    app.set_tags([(3, TagStore::parse("race"))]);
+    app.set_view(View::Activities);
+
+    app.select_next_activity();
+    app.start_tagging();
+    assert!(app.is_tagging());
+    for c in "#Race, easy".chars() {
+        app.tag_input(c);
+    }
+    let (id, tags) = app.submit_tags().unwrap();
+    assert_eq!(id, 1);
+    assert_eq!(tags, TagStore::parse("easy race"));
+
+    // Alphabetical: easy, race, then off
+    app.cycle_tag_filter();
+    assert_eq!(app.tag_filter(), Some("easy"));
+    assert_eq!(app.activities().len(), 1);
+    app.cycle_tag_filter();
+    assert_eq!(app.tag_filter(), Some("race"));
+    assert_eq!(app.activities().len(), 2);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("- #race"));
+    assert!(content.contains("#race — 10.0 km, 2 activities, 1h 00m"));
+
+    app.set_view(View::ActivityDetail);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("#easy #race"));
+
+    app.cycle_tag_filter();
+    assert_eq!(app.tag_filter(), None);
+    assert_eq!(app.activities().len(), 4);
+}
+
 #[test]
 fn test_activities_column_alignment() {
     let backend = TestBackend::new(150, 30);
@@ -118,6 +301,55 @@ fn test_activities_relative_performance_column() {
     assert!(content.contains(" 12"), "RelPerf should be ~12");
 }
 
+#[test]
+fn test_efficiency_metric_is_configurable_and_explained() {
+    let backend = TestBackend::new(150, 60);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    app.set_efficiency_metric(EfficiencyMetric::EfficiencyFactor);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    // Morning Run: 2.78 m/s * 60 / 150 bpm
+    assert!(content.contains("1.11"));
+
+    app.toggle_help();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("RelPerf: Efficiency factor (EF)"));
+    assert!(content.contains("Normalized power ÷ average HR"));
+
+    // Decoupling needs the streams of the activity
+    app.toggle_help();
+    app.set_efficiency_metric(EfficiencyMetric::AerobicDecoupling);
+    let stream = |data: Vec<f64>| Stream {
+        data,
+        series_type: Some("time".to_string()),
+        original_size: None,
+        resolution: None,
+    };
+    app.set_activity_streams(
+        1,
+        StreamSet {
+            time: Some(Stream {
+                data: vec![0, 600, 1200, 1800],
+                series_type: Some("time".to_string()),
+                original_size: None,
+                resolution: None,
+            }),
+            velocity_smooth: Some(stream(vec![3.0, 3.0, 2.775, 2.775])),
+            heartrate: Some(stream(vec![150.0, 150.0, 150.0, 150.0])),
+            ..Default::default()
+        },
+    );
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    // Only Morning Run has streams loaded
+    assert!(content.contains("7.5"));
+    assert!(content.contains("---"));
+}
+
 #[test]
 fn test_horizontal_scrolling() {
     let mut app = create_test_app();
@@ -143,6 +375,197 @@ fn test_horizontal_scrolling() {
     assert_eq!(app.scroll_offset(), 0);
 }
 
+#[test]
+fn test_horizontal_scrolling_hides_columns() {
+    let backend = TestBackend::new(60, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Name"));
+    assert!(!content.contains("RelPerf"));
+    assert!(!content.contains("◀"));
+    assert!(!content.contains("◀"));
+
+    for _ in 0..20 {
+        app.scroll_right();
+    }
+    // Stops once only the date and the last column are left
+    assert_eq!(app.scroll_offset(), 8);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Date"));
+    assert!(content.contains("Gear"));
+    assert!(!content.contains("Name"));
+    assert!(content.contains("◀ 8 hidden"));
+    assert!(!content.contains("hidden ▶"));
+}
+
+#[test]
+fn test_selection_stays_visible_when_scrolling() {
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_data(
+        create_test_athlete(),
+        create_test_stats(),
+        create_test_activities(50),
+        50,
+    );
+    app.set_view(View::Activities);
+    terminal.draw(|f| app.render(f)).unwrap();
+
+    app.select_last_activity();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Activity 49"));
+    assert!(!content.contains("Activity 0 "));
+
+    // 20 rows minus header, footer, borders and the table header
+    app.page_up_activities();
+    assert_eq!(app.get_selected_activity().unwrap().id, 38);
+    app.page_down_activities();
+    assert_eq!(app.get_selected_activity().unwrap().id, 49);
+
+    app.select_first_activity();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Activity 0 "));
+    assert!(!content.contains("Activity 49"));
+}
+
+#[test]
+fn test_sort_by_distance_keeps_selection() {
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+
+    // Select "Evening Ride" (25 km) before sorting
+    app.select_next_activity();
+    assert_eq!(app.get_selected_activity().unwrap().id, 2);
+
+    app.toggle_sort_mode();
+    assert!(app.is_sort_mode());
+    assert_eq!(app.sort_key(), Some(SortKey::Date));
+
+    app.next_sort_key();
+    assert_eq!(app.sort_key(), Some(SortKey::Distance));
+    assert_eq!(app.sort_direction(), SortDirection::Descending);
+    assert_eq!(app.get_selected_activity().unwrap().id, 2);
+
+    app.toggle_sort_direction();
+    assert_eq!(app.get_selected_activity().unwrap().id, 2);
+
+    // Ascending distance: the 5 km run comes first
+    app.select_prev_activity();
+    assert_eq!(app.get_selected_activity().unwrap().name, "Morning Run");
+}
+
+#[test]
+fn test_sort_applies_to_new_pages() {
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    app.toggle_sort_mode();
+    app.next_sort_key();
+    app.toggle_sort_mode();
+    assert!(!app.is_sort_mode());
+
+    let mut longest = create_test_activities(1);
+    longest[0].id = 99;
+    longest[0].distance = 42195.0;
+    app.add_activities(longest.into(), 30);
+
+    // Selection stays on the first activity, which is now the marathon
+    assert_eq!(app.get_selected_activity().unwrap().id, 1);
+    app.select_prev_activity();
+    app.select_prev_activity();
+    assert_eq!(app.get_selected_activity().unwrap().id, 99);
+}
+
+#[test]
+fn test_hide_commutes_and_private_activities() {
+    let backend = TestBackend::new(140, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    let mut extra = create_test_activities(2);
+    extra[0].id = 3;
+    extra[0].commute = Some(true);
+    extra[0].start_date = chrono::Utc::now() - chrono::Duration::days(1);
+    extra[1].id = 4;
+    extra[1].private = Some(true);
+    extra[1].start_date = chrono::Utc::now() - chrono::Duration::days(2);
+    app.add_activities(extra.into(), 30);
+    app.select_next_activity();
+    let selected = app.get_selected_activity().unwrap().id;
+
+    app.set_hidden(true, false);
+    assert_eq!(app.activities().len(), 3);
+    assert!(app.activities().iter().all(|a| a.id != 3));
+    assert_eq!(app.get_selected_activity().unwrap().id, selected);
+
+    app.toggle_hide_private();
+    assert_eq!(app.activities().len(), 2);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("no commutes or private"));
+    // Hidden activities stay cached
+    assert_eq!(app.history_len(), 4);
+
+    // Shown again in date order
+    app.toggle_hide_commutes();
+    app.toggle_hide_private();
+    let ids: Vec<u64> = app.activities().iter().map(|a| a.id).collect();
+    assert_eq!(ids.len(), 4);
+    assert_eq!(ids[2..], [3, 4]);
+    assert_eq!(app.get_selected_activity().unwrap().id, selected);
+}
+
+#[test]
+fn test_training_view_aggregates_weeks_and_months() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let date = |s: &str| {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    };
+    let mut activities = create_test_activities(3);
+    // Two runs in ISO week 3 of 2024, one ride in week 5 (February)
+    activities[0].start_date_local = date("2024-01-15T08:00:00Z");
+    activities[1].start_date_local = date("2024-01-17T08:00:00Z");
+    activities[2].start_date_local = date("2024-02-01T08:00:00Z");
+    activities[2].sport_type = "Ride".to_string();
+    activities[2].distance = 30000.0;
+
+    let mut app = App::new();
+    app.set_data(create_test_athlete(), create_test_stats(), activities, 30);
+    app.set_view(View::Training);
+    assert_eq!(app.training_period(), TrainingPeriod::Week);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Weekly Training"));
+    assert!(content.contains("2024-W03"));
+    assert!(content.contains("2024-W05"));
+    assert!(content.contains("Run 2"));
+    assert!(content.contains("Ride 1"));
+
+    app.toggle_training_period();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Monthly Training"));
+    assert!(content.contains("2024-01"));
+    assert!(content.contains("2024-02"));
+    assert!(content.contains("30.0"), "February total should be 30 km");
+}
+
 fn create_test_app() -> App {
     let athlete = Athlete {
         id: 12345,
@@ -153,6 +576,8 @@ fn create_test_app() -> App {
         country: Some("Germany".to_string()),
         profile: None,
         profile_medium: None,
+        bikes: Vec::new(),
+        shoes: Vec::new(),
     };
 
     let stats = AthleteStats {
@@ -219,7 +644,10 @@ fn create_test_app() -> App {
             max_speed: Some(3.5),
             average_heartrate: Some(150.0),
             max_heartrate: Some(175.0),
+            average_watts: None,
+            weighted_average_watts: None,
             calories: Some(350.0),
+            suffer_score: None,
             description: None,
             kudos_count: Some(5),
             comment_count: Some(1),
@@ -227,8 +655,10 @@ fn create_test_app() -> App {
             pr_count: Some(1),
             private: Some(false),
             commute: Some(false),
+            trainer: Some(false),
             manual: Some(false),
             gear_id: None,
+            map: None,
         },
         Activity {
             id: 2,
@@ -246,7 +676,10 @@ fn create_test_app() -> App {
             max_speed: Some(8.5),
             average_heartrate: Some(140.0),
             max_heartrate: Some(170.0),
+            average_watts: None,
+            weighted_average_watts: None,
             calories: Some(600.0),
+            suffer_score: None,
             description: None,
             kudos_count: Some(10),
             comment_count: Some(0),
@@ -254,8 +687,10 @@ fn create_test_app() -> App {
             pr_count: Some(0),
             private: Some(false),
             commute: Some(false),
+            trainer: Some(false),
             manual: Some(false),
             gear_id: None,
+            map: None,
         },
     ];
 
@@ -326,6 +761,315 @@ fn test_dashboard_shows_stats() {
     );
 }
 
+#[test]
+fn test_dashboard_uses_athlete_stats_and_history() {
+    let backend = TestBackend::new(150, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    let loaded = app.history_len();
+    let mut cached = create_test_activities(3);
+    for (i, activity) in cached.iter_mut().enumerate() {
+        activity.id = 1000 + i as u64;
+    }
+    app.merge_history(cached);
+    assert_eq!(app.history_len(), loaded + 3);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    // Biggest ride and four-week totals come from the stats endpoint
+    assert!(content.contains("50.0 km"));
+    assert!(content.contains("last 4 weeks: 150.0 km"));
+    assert!(content.contains("Year to date: 50 runs, 25 rides"));
+    assert!(content.contains(&format!("({} cached)", loaded + 3)));
+}
+
+#[test]
+fn test_dashboard_time_ranges() {
+    let backend = TestBackend::new(150, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_view(View::Dashboard);
+    let mut activities = create_test_activities(2);
+    activities[0].distance = 10000.0;
+    activities[0].moving_time = 3000;
+    activities[1].distance = 12000.0;
+    activities[1].moving_time = 4200;
+    activities[1].start_date_local = chrono::Utc::now() - chrono::Duration::days(10);
+    app.merge_history(activities);
+
+    assert_eq!(
+        keymap::action(View::Dashboard, crossterm::event::KeyCode::Char('h')),
+        Some(Action::Left)
+    );
+    assert_eq!(app.dashboard_range(), DashboardRange::AllTime);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("h/l  7 days  30 days"));
+    assert!(content.contains("Activities this month"));
+
+    // The last week holds the 10 km run, the week before the 12 km one
+    app.set_dashboard_range(DashboardRange::Week);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Biggest Distance (7 days)"));
+    assert!(content.contains("10.0 km ↓"));
+    assert!(content.contains("(period before: 12.0 km)"));
+    assert!(content.contains("5:00 /km ↑"));
+    assert!(content.contains("(period before: 5:50)"));
+
+    app.set_dashboard_range(DashboardRange::Month);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Biggest Distance (30 days)"));
+    assert!(content.contains("12.0 km ↑"));
+    assert!(content.contains("2 ↑"));
+}
+
+#[test]
+fn test_dashboard_climbing_card() {
+    let backend = TestBackend::new(150, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_view(View::Dashboard);
+    let mut activities = create_test_activities(2);
+    activities[0].distance = 20000.0;
+    activities[0].total_elevation_gain = 300.0;
+    activities[0].start_date_local = chrono::Utc::now() - chrono::Duration::days(2);
+    activities[1].distance = 10000.0;
+    activities[1].total_elevation_gain = 450.0;
+    activities[1].start_date_local = chrono::Utc::now() - chrono::Duration::days(10);
+    app.merge_history(activities);
+
+    // The last week holds 300 m over 20 km, the week before 450 m
+    app.set_dashboard_range(DashboardRange::Week);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Climbing (7 days)"));
+    assert!(content.contains("300 m ↓ (period before: 450 m)"));
+    assert!(content.contains("15.0 m/km over 20.0 km"));
+
+    app.set_dashboard_range(DashboardRange::Month);
+    app.set_units(Units::Imperial);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("2461 ft ↑ (period before: 0 ft)"));
+    // 750 m over 30 km is 25 m/km, or 132 ft/mi
+    assert!(content.contains("132.0 ft/mi over 18.6 mi"));
+}
+
+#[test]
+fn test_activities_climb_density_column() {
+    let backend = TestBackend::new(120, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_view(View::Activities);
+    app.set_activity_columns(vec![ActivityColumn::Name, ActivityColumn::ClimbDensity]);
+    let mut activities = create_test_activities(2);
+    activities[0].distance = 12000.0;
+    activities[0].total_elevation_gain = 540.0;
+    // Too short to say anything about the terrain
+    activities[1].distance = 400.0;
+    activities[1].total_elevation_gain = 10.0;
+    app.add_activities(activities.into(), 30);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Climb"));
+    assert!(content.contains("45"));
+    assert!(content.contains("---"));
+}
+
+#[test]
+fn test_handle_key_dispatches_to_views() {
+    use crossterm::event::KeyCode;
+
+    let mut app = create_test_app();
+    app.set_view(View::Dashboard);
+    assert!(app.handle_key(KeyCode::Char('h')).is_none());
+    assert_eq!(app.dashboard_range(), DashboardRange::YearToDate);
+    app.handle_key(KeyCode::Right);
+    app.handle_key(KeyCode::Right);
+    assert_eq!(app.dashboard_range(), DashboardRange::AllTime);
+
+    // The number keys pick a tab in every view, the dashboard included
+    app.handle_key(KeyCode::Char('3'));
+    assert_eq!(app.current_view(), View::Segments);
+    assert_eq!(app.dashboard_range(), DashboardRange::AllTime);
+    app.handle_key(KeyCode::Char('a'));
+    assert_eq!(app.current_view(), View::Activities);
+
+    // Opening an activity leaves loading its details to the event loop
+    app.handle_key(KeyCode::Char('j'));
+    let selected = app.get_selected_activity().unwrap().id;
+    match app.handle_key(KeyCode::Enter) {
+        Some(Effect::Open(activity)) => assert_eq!(activity.id, selected),
+        other => panic!("expected to open an activity, got {:?}", other),
+    }
+    assert_eq!(app.current_view(), View::ActivityDetail);
+    app.handle_key(KeyCode::Esc);
+    assert_eq!(app.current_view(), View::Activities);
+
+    // A second refresh waits for the first
+    assert!(matches!(
+        app.handle_key(KeyCode::Char('r')),
+        Some(Effect::Refresh)
+    ));
+    assert!(app.is_refreshing());
+    assert!(app.handle_key(KeyCode::Char('r')).is_none());
+    assert!(matches!(
+        app.handle_key(KeyCode::Char('q')),
+        Some(Effect::Quit)
+    ));
+}
+
+#[test]
+fn test_overlays_take_keys() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    let id = app.get_selected_activity().unwrap().id;
+
+    // Open help swallows view keys until it is closed
+    app.handle_key(KeyCode::Char('?'));
+    app.handle_key(KeyCode::Char('b'));
+    assert_eq!(app.current_view(), View::Activities);
+    app.handle_key(KeyCode::Char('?'));
+    assert!(!app.is_help_open());
+
+    // Typed tags go to the event loop to be stored
+    app.handle_key(KeyCode::Char('T'));
+    for c in "race q".chars() {
+        app.handle_key(KeyCode::Char(c));
+    }
+    match app.handle_key(KeyCode::Enter) {
+        Some(Effect::SaveTags(tagged, tags)) => {
+            assert_eq!(tagged, id);
+            assert!(tags.contains("race") && tags.contains("q"));
+        }
+        other => panic!("expected tags to save, got {:?}", other),
+    }
+
+    // Confirming a deletion leaves the request to the event loop
+    app.handle_key(KeyCode::Char('X'));
+    assert!(app.is_confirming());
+    app.handle_key(KeyCode::Char('j'));
+    assert_eq!(app.get_selected_activity().unwrap().id, id);
+    assert!(matches!(
+        app.handle_key(KeyCode::Char('y')),
+        Some(Effect::Delete(deleted)) if deleted == id
+    ));
+
+    assert!(matches!(
+        app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+        Some(Effect::Quit)
+    ));
+}
+
+#[test]
+fn test_tab_bar() {
+    use crossterm::event::KeyCode;
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = create_test_app();
+    app.set_view(View::Dashboard);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains(" 1 Dashboard | 2 Activities | 3 Segments"));
+    assert!(!content.contains("‹"));
+
+    // Shift+Tab wraps around to the last tab, and the bar scrolls to show it
+    app.handle_key(KeyCode::BackTab);
+    assert_eq!(app.current_view(), View::SavedRoutes);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("‹"));
+    assert!(content.contains(" Social | Saved routes "));
+    app.handle_key(KeyCode::Tab);
+    assert_eq!(app.current_view(), View::Dashboard);
+
+    // Details sit on the activities tab
+    app.set_view(View::ActivityDetail);
+    app.next_view();
+    assert_eq!(app.current_view(), View::Segments);
+    app.handle_key(KeyCode::Char('9'));
+    assert_eq!(app.current_view(), View::Fitness);
+}
+
+#[test]
+fn test_esc_walks_back_through_views() {
+    use crossterm::event::KeyCode;
+
+    let mut app = create_test_app();
+    app.set_view(View::Dashboard);
+    app.handle_key(KeyCode::Char('a'));
+    app.handle_key(KeyCode::Enter);
+    assert_eq!(app.current_view(), View::ActivityDetail);
+    app.handle_key(KeyCode::Char('S'));
+    // Showing the current view again adds no step
+    app.handle_key(KeyCode::Char('S'));
+
+    for view in [View::ActivityDetail, View::Activities, View::Dashboard] {
+        app.handle_key(KeyCode::Esc);
+        assert_eq!(app.current_view(), view);
+    }
+    // Nothing left to go back to
+    assert!(!app.back());
+    app.handle_key(KeyCode::Esc);
+    assert_eq!(app.current_view(), View::Dashboard);
+
+    // Deleting the open activity returns to where the details were opened from
+    app.set_view(View::Activities);
+    app.set_view(View::ActivityDetail);
+    let id = app.get_selected_activity().unwrap().id;
+    app.remove_activity(id);
+    assert_eq!(app.current_view(), View::Activities);
+}
+
+#[test]
+fn test_copy_activity_link_and_summary() {
+    use crossterm::event::KeyCode;
+
+    let mut app = App::new();
+    let mut activities = create_test_activities(1);
+    activities[0].id = 4242;
+    activities[0].name = "Morning Run".to_string();
+    activities[0].start_date_local =
+        chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2024, 3, 15, 7, 30, 0).unwrap();
+    activities[0].total_elevation_gain = 84.0;
+    app.add_activities(activities.into(), 30);
+    app.set_view(View::Activities);
+
+    match app.handle_key(KeyCode::Char('y')) {
+        Some(Effect::Copy(text, notice)) => {
+            assert_eq!(text, "https://www.strava.com/activities/4242");
+            assert_eq!(notice, "Link copied");
+        }
+        other => panic!("expected a copy, got {:?}", other),
+    }
+    // The year heatmap stays on y everywhere else
+    assert_eq!(app.current_view(), View::Activities);
+
+    app.set_view(View::ActivityDetail);
+    match app.handle_key(KeyCode::Char('Y')) {
+        Some(Effect::Copy(text, _)) => assert_eq!(
+            text,
+            "Morning Run (Run, 2024-03-15)\n\
+             5.00 km in 0:30:00 · 6:00 /km · 84 m elevation\n\
+             https://www.strava.com/activities/4242"
+        ),
+        other => panic!("expected a copy, got {:?}", other),
+    }
+
+    app.show_notice("Link copied".to_string());
+    assert_eq!(app.notice(), Some("Link copied"));
+}
+
 #[test]
 fn test_dashboard_widgets_show_trend_arrows() {
     let backend = TestBackend::new(80, 30);
@@ -399,48 +1143,1832 @@ fn test_activity_detail_renders() {
 }
 
 #[test]
-fn test_navigation_keys() {
-    let mut app = create_test_app();
-    app.set_view(View::Dashboard);
+fn test_activity_detail_renders_stream_graphs() {
+    let backend = TestBackend::new(100, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
 
-    // Initially on dashboard
-    assert_eq!(app.current_view(), View::Dashboard);
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
 
-    // Press 'a' to go to activities
-    app.set_view(View::Activities);
-    assert_eq!(app.current_view(), View::Activities);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Loading streams..."));
 
-    // Press 'd' to go back to dashboard
-    app.set_view(View::Dashboard);
-    assert_eq!(app.current_view(), View::Dashboard);
+    let stream = |data: Vec<f64>| Stream {
+        data,
+        series_type: Some("distance".to_string()),
+        original_size: None,
+        resolution: None,
+    };
+    app.set_activity_streams(
+        1,
+        StreamSet {
+            heartrate: Some(stream(vec![120.0, 140.0, 160.0])),
+            altitude: Some(stream(vec![30.0, 45.0, 38.0])),
+            ..Default::default()
+        },
+    );
+    assert!(app.has_streams_for(1));
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Heart Rate (120-160 bpm)"));
+    assert!(content.contains("Elevation (30-45 m)"));
+    assert!(!content.contains("Decoupling"));
+
+    // A steady run whose heart rate creeps up at the same speed
+    app.set_activity_streams(
+        1,
+        StreamSet {
+            time: Some(Stream {
+                data: vec![0, 600, 1200, 1800],
+                series_type: Some("distance".to_string()),
+                original_size: None,
+                resolution: None,
+            }),
+            velocity_smooth: Some(stream(vec![3.0; 4])),
+            heartrate: Some(stream(vec![150.0, 150.0, 156.0, 156.0])),
+            ..Default::default()
+        },
+    );
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Decoupling: 3.8% (well coupled, good aerobic base)"));
 }
 
 #[test]
-fn test_activity_selection() {
-    let mut app = create_test_app();
-    app.set_view(View::Activities);
+fn test_activity_detail_elevation_profile_marks_climb_and_top() {
+    let backend = TestBackend::new(120, 50);
+    let mut terminal = Terminal::new(backend).unwrap();
 
-    // Initially first activity is selected (index 0)
-    assert_eq!(app.get_selected_activity().unwrap().name, "Morning Run");
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
 
-    // Select next
-    app.select_next_activity();
-    assert_eq!(app.get_selected_activity().unwrap().name, "Evening Ride");
+    let stream = |data: Vec<f64>| Stream {
+        data,
+        series_type: Some("distance".to_string()),
+        original_size: None,
+        resolution: None,
+    };
+    // Gentle 2% until 600 m, then 10% over 200 m and a descent
+    let distance: Vec<f64> = (0..=10).map(|i| i as f64 * 100.0).collect();
+    let altitude = vec![
+        100.0, 102.0, 104.0, 106.0, 108.0, 110.0, 112.0, 122.0, 132.0, 120.0, 110.0,
+    ];
+    app.set_activity_streams(
+        1,
+        StreamSet {
+            distance: Some(stream(distance)),
+            altitude: Some(stream(altitude)),
+            ..Default::default()
+        },
+    );
 
-    // Select previous
-    app.select_prev_activity();
-    assert_eq!(app.get_selected_activity().unwrap().name, "Morning Run");
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Elevation (100-132 m)"));
+    assert!(content.contains("steepest 10.0%"));
+    assert!(content.contains("max 132 m"));
+    assert!(content.contains("1.0 km"));
 }
 
 #[test]
-fn test_infinite_scroll_triggers() {
-    let mut app = create_test_app();
-    app.set_view(View::Activities);
+fn test_grade_adjusted_pace_in_detail_and_table() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
 
-    // Set has_more_activities to true to test the logic
-    // (In real usage, this would be set based on API response)
-    // For now, just test the selection logic works
-    assert!(app.get_selected_activity().is_some());
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+
+    let stream = |data: Vec<f64>| Stream {
+        data,
+        series_type: Some("distance".to_string()),
+        original_size: None,
+        resolution: None,
+    };
+    // 5:00 /km on flat ground, while the summary says 6:00 /km
+    app.set_activity_streams(
+        1,
+        StreamSet {
+            time: Some(Stream {
+                data: (0..=10).map(|i| i * 30).collect(),
+                series_type: Some("distance".to_string()),
+                original_size: None,
+                resolution: None,
+            }),
+            distance: Some(stream((0..=10).map(|i| i as f64 * 100.0).collect())),
+            altitude: Some(stream(vec![50.0; 11])),
+            ..Default::default()
+        },
+    );
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Pace: 6:00 /km (grade adjusted 5:00 /km)"));
+
+    app.set_view(View::Activities);
+    app.set_activity_columns(vec![
+        ActivityColumn::Name,
+        ActivityColumn::Pace,
+        ActivityColumn::Gap,
+    ]);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("GAP"));
+    let row = |name: &str| {
+        content
+            .lines()
+            .find(|l| l.contains(name))
+            .unwrap()
+            .to_string()
+    };
+    assert!(row("Morning Run").contains("6:00"));
+    assert!(row("Morning Run").contains("5:00"));
+    // Not loaded, and not a sport on foot either
+    assert!(row("Evening Ride").contains("---"));
+}
+
+#[test]
+fn test_activity_detail_uses_imperial_units() {
+    let backend = TestBackend::new(100, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_units(Units::Imperial);
+    app.set_view(View::ActivityDetail);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+
+    // Morning Run: 5000 m = 3.11 mi, 50 m = 164 ft
+    assert!(content.contains("Distance: 3.11 mi"));
+    assert!(content.contains("Elevation Gain: 164 ft"));
+}
+
+#[test]
+fn test_activity_detail_renders_splits() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+
+    let split = |index: u32, moving_time: u32, elevation: f64| Split {
+        distance: 1000.0,
+        elapsed_time: moving_time,
+        elevation_difference: elevation,
+        moving_time,
+        split: index,
+        average_speed: Some(1000.0 / moving_time as f64),
+        average_heartrate: Some(148.0),
+        average_grade_adjusted_speed: (elevation > 0.0).then_some(1000.0 / 300.0),
+        pace_zone: None,
+    };
+    let mut activity = app.get_selected_activity().unwrap().clone();
+    activity.calories = Some(512.0);
+    app.set_activity_detail(DetailedActivity {
+        activity,
+        segment_efforts: None,
+        splits_metric: Some(vec![split(1, 330, 12.0), split(2, 345, -4.0)]),
+        splits_standard: None,
+        laps: None,
+        best_efforts: None,
+        average_temp: Some(18.0),
+        device_name: Some("Garmin Forerunner 265".to_string()),
+        embed_token: Some("abc123".to_string()),
+    });
+    assert!(app.has_detail_for(1));
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Recorded with Garmin Forerunner 265 · 18 °C · 512 kcal"));
+    assert!(content.contains("Splits (km)"));
+    assert!(content.contains("5:30"), "First split pace should be 5:30");
+    assert!(content.contains("5:45"), "Second split pace should be 5:45");
+    assert!(content.contains("+12"));
+    assert!(content.contains("148"));
+    // Strava's grade adjusted pace for the climbing split only
+    assert!(content.contains("GAP"));
+    assert!(content.contains("5:00"));
+    assert!(content.contains("--:--"));
+
+    // Standard splits are missing for this activity
+    app.cycle_splits_mode();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Splits (mi)"));
+    assert!(content.contains("No splits for this activity"));
+
+    app.set_units(Units::Imperial);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("64 °F"));
+}
+
+#[test]
+fn test_activity_detail_split_pace_bars() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+
+    let split = |index: u32, moving_time: u32| Split {
+        distance: 1000.0,
+        elapsed_time: moving_time,
+        elevation_difference: 0.0,
+        moving_time,
+        split: index,
+        average_speed: Some(1000.0 / moving_time as f64),
+        average_heartrate: None,
+        average_grade_adjusted_speed: None,
+        pace_zone: None,
+    };
+    let activity = app.get_selected_activity().unwrap().clone();
+    app.set_activity_detail(DetailedActivity {
+        activity,
+        segment_efforts: None,
+        splits_metric: Some(vec![split(1, 330), split(2, 345), split(3, 310)]),
+        splits_standard: None,
+        laps: None,
+        best_efforts: None,
+        average_temp: None,
+        device_name: None,
+        embed_token: None,
+    });
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Pace"));
+
+    // Slower splits get longer bars, lined up with their table rows
+    let bar_length = |pace: &str| {
+        let line = content.lines().find(|l| l.contains(pace)).unwrap();
+        line.matches('█').count()
+    };
+    assert!(bar_length("5:10") < bar_length("5:30"));
+    assert!(bar_length("5:30") < bar_length("5:45"));
+
+    // Fastest and slowest splits stand out from the rest
+    let buffer = terminal.backend().buffer();
+    let bar_colors: Vec<Color> = buffer
+        .content()
+        .iter()
+        .filter(|cell| cell.symbol() == "█")
+        .map(|cell| cell.fg)
+        .collect();
+    let theme = Theme::dark();
+    assert!(bar_colors.contains(&theme.positive));
+    assert!(bar_colors.contains(&theme.error));
+    assert!(bar_colors.contains(&theme.accent));
+}
+
+#[test]
+fn test_activity_detail_lap_power() {
+    use crossterm::event::KeyCode;
+
+    let backend = TestBackend::new(200, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+    let lap = |index: u32, moving_time: u32, watts: f64| {
+        serde_json::json!({
+            "id": index, "name": format!("Lap {}", index),
+            "activity": {"id": 1, "resource_state": 1},
+            "athlete": {"id": 12345, "resource_state": 1},
+            "moving_time": moving_time, "elapsed_time": moving_time,
+            "start_date": "2024-01-15T08:00:00Z", "start_date_local": "2024-01-15T09:00:00Z",
+            "distance": 2000.0, "average_heartrate": 150.0,
+            "average_watts": watts, "lap_index": index
+        })
+    };
+    let activity = app.get_selected_activity().unwrap().clone();
+    app.set_activity_detail(DetailedActivity {
+        activity,
+        segment_efforts: None,
+        splits_metric: None,
+        splits_standard: None,
+        laps: Some(
+            serde_json::from_value(serde_json::json!([
+                lap(1, 300, 180.0),
+                lap(2, 240, 310.0),
+                lap(3, 300, 150.0)
+            ]))
+            .unwrap(),
+        ),
+        best_efforts: None,
+        average_temp: None,
+        device_name: None,
+        embed_token: None,
+    });
+
+    assert_eq!(
+        keymap::action(View::ActivityDetail, KeyCode::Char('z')),
+        Some(Action::ToggleLaps)
+    );
+    app.handle_key(KeyCode::Char('z'));
+    assert_eq!(app.splits_mode(), SplitsMode::Laps);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Laps - z: splits"));
+    // Interval laps are charted by their power rather than their pace
+    assert!(content.contains("Power"));
+    assert!(content.contains("310 W"));
+    let bar_length = |watts: &str| {
+        let line = content.lines().find(|l| l.contains(watts)).unwrap();
+        line.matches('█').count()
+    };
+    assert!(bar_length("150 W") < bar_length("180 W"));
+    assert!(bar_length("180 W") < bar_length("310 W"));
+
+    // Back to the splits in the configured units
+    app.handle_key(KeyCode::Char('z'));
+    assert_eq!(app.splits_mode(), SplitsMode::Metric);
+}
+
+#[test]
+fn test_date_range_picker() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    assert!(app.date_range().is_empty());
+
+    app.start_range_picker();
+    assert!(app.is_picking_range());
+    "2024-13".chars().for_each(|c| app.range_input(c));
+    assert!(!app.submit_range());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Jump to Date Range"));
+    assert!(content.contains("'2024-13' is not a date"));
+
+    app.range_backspace();
+    app.range_backspace();
+    "03".chars().for_each(|c| app.range_input(c));
+    app.range_next_field();
+    "2024-03".chars().for_each(|c| app.range_input(c));
+    assert!(app.submit_range());
+    assert!(!app.is_picking_range());
+
+    let range = app.date_range();
+    let (after, before) = (range.after.unwrap(), range.before.unwrap());
+    // All of March, whatever the local timezone offset
+    assert!((31 * 86400 - 3600..=31 * 86400 + 3600).contains(&(before - after)));
+
+    // The list restarts at page 1 of the new range
+    assert_eq!(app.activity_page(), 0);
+    assert!(app.should_load_more());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("2024-03-01 to 2024-03-31 (oldest first)"));
+    assert!(content.contains("No activities in this date range"));
+
+    // Empty fields clear the range again
+    app.start_range_picker();
+    assert!(app.submit_range());
+    assert!(app.date_range().is_empty());
+}
+
+#[test]
+fn test_go_to_date_prompt() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    assert_eq!(
+        keymap::action(View::Activities, crossterm::event::KeyCode::Char('g')),
+        Some(Action::GoToDate)
+    );
+
+    app.start_date_jump();
+    assert!(app.is_jumping_to_date());
+    "next tuesday".chars().for_each(|c| app.date_jump_push(c));
+    assert!(!app.submit_date_jump());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Go to Date"));
+    assert!(content.contains("Can't read 'next tuesday'"));
+
+    while !app.date_jump_input().is_empty() {
+        app.date_jump_backspace();
+    }
+    "2023-06".chars().for_each(|c| app.date_jump_push(c));
+    assert!(app.submit_date_jump());
+    assert!(!app.is_jumping_to_date());
+
+    // Newest first from the end of June back
+    let range = app.date_range();
+    assert_eq!(range.after, None);
+    assert!(range.before.is_some());
+    assert_eq!(app.activity_page(), 0);
+    assert!(app.should_load_more());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("up to 2023-06-30"));
+    assert!(!content.contains("oldest first"));
+}
+
+#[test]
+fn test_achievements_view() {
+    let backend = TestBackend::new(140, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut activities = create_test_activities(3);
+    for (activity, (prs, achievements)) in activities.iter_mut().zip([(2, 3), (0, 1), (0, 0)]) {
+        activity.pr_count = Some(prs);
+        activity.achievement_count = Some(achievements);
+    }
+    let mut app = App::new();
+    app.set_data(
+        create_test_athlete(),
+        create_test_stats(),
+        activities.clone(),
+        10,
+    );
+    app.set_view(View::Achievements);
+
+    // Only activities with PRs need their best efforts fetched
+    assert_eq!(app.achievement_details_needed(), vec![activities[0].id]);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("SportFrei - Achievements"));
+    assert!(content.contains("Activity 0"));
+    assert!(content.contains("Activity 1"));
+    assert!(!content.contains("Activity 2"));
+    assert!(content.contains("loading..."));
+
+    let effort = |name: &str, distance: f64, elapsed_time: u32, pr_rank: Option<u32>| BestEffort {
+        id: 1,
+        name: name.to_string(),
+        activity: Reference {
+            id: activities[0].id,
+            resource_state: 1,
+        },
+        athlete: Reference {
+            id: 12345,
+            resource_state: 1,
+        },
+        elapsed_time,
+        moving_time: elapsed_time,
+        start_date: activities[0].start_date,
+        start_date_local: activities[0].start_date_local,
+        distance,
+        pr_rank,
+    };
+    // Selecting another activity shows the detail didn't replace the detail view's
+    app.select_next_activity();
+    app.set_activity_detail(DetailedActivity {
+        activity: activities[0].clone(),
+        segment_efforts: None,
+        splits_metric: None,
+        splits_standard: None,
+        laps: None,
+        best_efforts: Some(vec![
+            effort("5k", 5000.0, 1290, Some(1)),
+            effort("1 mile", 1609.0, 390, Some(2)),
+            effort("10k", 10000.0, 2700, None),
+        ]),
+        average_temp: None,
+        device_name: None,
+        embed_token: None,
+    });
+    assert!(!app.has_detail_for(activities[0].id));
+    assert!(app.achievement_details_needed().is_empty());
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("5k PR 21:30, 1 mile 2nd 6:30"));
+    assert!(!content.contains("6:30, 10k"));
+
+    // Every fetched best effort counts towards personal bests, ranked or not
+    let mut needed = app.best_effort_details_needed();
+    needed.sort();
+    assert_eq!(needed, vec![activities[1].id, activities[2].id]);
+    assert!(content.contains("Personal Bests (1 of 3 cached runs scanned)"));
+    assert!(content.contains("0:21:30"));
+    assert!(content.contains("0:45:00"));
+}
+
+#[test]
+fn test_error_banner_and_log() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.report_error("Failed to load activities page 3: timeout".to_string());
+    app.report_error("Failed to load gear g1: 404".to_string());
+    assert_eq!(app.error_count(), 2);
+    assert_eq!(app.status_message(), Some("Failed to load gear g1: 404"));
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Failed to load gear g1: 404"));
+    assert!(content.contains("2 errors - [L]og"));
+
+    app.dismiss_status();
+    assert_eq!(app.status_message(), None);
+
+    app.toggle_error_log();
+    assert!(app.is_error_log_open());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Recent Errors (2)"));
+    assert!(content.contains("Failed to load activities page 3: timeout"));
+
+    app.toggle_error_log();
+    assert!(!app.is_error_log_open());
+}
+
+#[test]
+fn test_help_overlay_lists_view_bindings() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Clubs);
+    app.toggle_help();
+    assert!(app.is_help_open());
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Keys - ?/Esc close"));
+    assert!(content.contains("Next club activity"));
+    assert!(content.contains("Previous club"));
+    assert!(content.contains("Starred segments"));
+    // Shadowed by the club bindings
+    assert!(!content.contains("Next activity"));
+    assert!(!content.contains("Scroll columns"));
+
+    app.toggle_help();
+    assert!(!app.is_help_open());
+}
+
+#[test]
+fn test_help_overlay_leaves_out_shadowed_keys() {
+    let backend = TestBackend::new(120, 70);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    app.toggle_help();
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Copy Strava link"));
+    // y copies the link here, so only 8 is left for the heatmap
+    let heatmap = content
+        .lines()
+        .find(|line| line.contains("Year heatmap"))
+        .unwrap();
+    assert!(heatmap.contains("│8 "));
+    assert!(!heatmap.contains("y/8"));
+}
+
+#[test]
+fn test_keymap_prefers_view_bindings() {
+    use crossterm::event::KeyCode;
+
+    assert_eq!(
+        keymap::action(View::Activities, KeyCode::Char('j')),
+        Some(Action::Down)
+    );
+    assert_eq!(
+        keymap::action(View::Activities, KeyCode::Char('l')),
+        Some(Action::Right)
+    );
+    assert_eq!(keymap::action(View::Gear, KeyCode::Char('l')), None);
+    assert_eq!(
+        keymap::action(View::Gear, KeyCode::Char('S')),
+        Some(Action::ShowView(View::Segments))
+    );
+    assert_eq!(keymap::action(View::Dashboard, KeyCode::Char('w')), None);
+}
+
+#[test]
+fn test_segments_view() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_data(create_test_athlete(), create_test_stats(), Vec::new(), 10);
+    app.set_view(View::Segments);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Loading starred segments..."));
+
+    let segments: Vec<SummarySegment> = serde_json::from_str(
+        r#"[
+            {"id": 1, "name": "Hawk Hill", "activity_type": "Ride", "distance": 2684.8,
+             "athlete_pr_effort": {"pr_elapsed_time": 553, "effort_count": 7}},
+            {"id": 2, "name": "Tempelhof Loop", "activity_type": "Run", "distance": 6000.0}
+        ]"#,
+    )
+    .unwrap();
+    app.set_starred_segments(segments);
+    assert_eq!(app.segment_details_needed(), vec![1, 2]);
+
+    let detail: DetailedSegment = serde_json::from_str(
+        r#"{"id": 1, "name": "Hawk Hill", "distance": 2684.8,
+            "effort_count": 4210, "athlete_count": 980, "star_count": 57,
+            "total_elevation_gain": 155.0, "hazardous": true,
+            "local_legend": {"athlete_id": 12345, "title": "John D.", "effort_count": "18"}}"#,
+    )
+    .unwrap();
+    app.set_segment_detail(detail);
+    assert_eq!(app.segment_details_needed(), vec![2]);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Starred Segments (2)"));
+    assert!(content.contains("Hawk Hill"));
+    assert!(content.contains("9:13"));
+    assert!(content.contains("You!"));
+    assert!(content.contains("loading..."));
+    assert!(content.contains("You are the Local Legend with 18 efforts in the last 90 days"));
+    assert!(content.contains("My efforts: 7, PR 9:13 on ?"));
+    assert!(content.contains("4210 efforts by 980 athletes, starred 57 times"));
+    assert!(content.contains("Elevation gain 155 m · flagged as hazardous"));
+
+    app.handle_key(crossterm::event::KeyCode::Char('j'));
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Loading segment details..."));
+}
+
+#[test]
+fn test_profile_view() {
+    use crossterm::event::KeyCode;
+
+    let backend = TestBackend::new(100, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.handle_key(KeyCode::Char('i'));
+    assert_eq!(app.current_view(), View::Profile);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Loading profile..."));
+
+    let profile: DetailedAthlete = serde_json::from_str(
+        r#"{"id": 1, "username": "jdoe", "firstname": "Jane", "lastname": "Doe",
+            "city": "Berlin", "state": "Berlin", "country": "Germany", "premium": true,
+            "follower_count": 48, "friend_count": 52, "measurement_preference": "meters",
+            "ftp": 245, "weight": 70.0, "created_at": "2016-05-02T08:00:00Z",
+            "clubs": [{"id": 1, "name": "Tiergarten Runners"}],
+            "shoes": [{"id": "g1", "name": "Pegasus", "distance": 0.0}]}"#,
+    )
+    .unwrap();
+    app.set_profile(profile);
+    assert!(app.has_profile());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Jane Doe (@jdoe)"));
+    assert!(content.contains("Berlin, Germany"));
+    assert!(content.contains("2016-05-02"));
+    assert!(content.contains("Summit"));
+    assert!(content.contains("70.0 kg"));
+    assert!(content.contains("245 W (3.50 W/kg)"));
+    assert!(content.contains("48"));
+    assert!(content.contains("0 bikes, 1 shoes"));
+    assert!(content.contains("Tiergarten Runners"));
+
+    app.set_units(Units::Imperial);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("154.3 lb"));
+}
+
+#[test]
+fn test_saved_routes_view() {
+    use crossterm::event::KeyCode;
+
+    let backend = TestBackend::new(100, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.handle_key(KeyCode::Char('v'));
+    assert_eq!(app.current_view(), View::SavedRoutes);
+    assert!(!app.has_saved_routes());
+    assert!(app.handle_key(KeyCode::Char('x')).is_none());
+
+    let routes: Vec<Route> = serde_json::from_str(
+        r#"[{"id": 41, "name": "Tiergarten Loop", "distance": 10250.0, "elevation_gain": 38.0,
+             "type": 2, "starred": true, "created_at": "2023-04-12T18:20:00Z"},
+            {"id": 42, "name": "Havel & Wannsee", "distance": 68400.0, "elevation_gain": 412.0,
+             "type": 1}]"#,
+    )
+    .unwrap();
+    app.set_saved_routes(routes);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Saved Routes (2) - x downloads the GPX"));
+    assert!(content.contains("★  Tiergarten Loop"));
+    assert!(content.contains("68.4"));
+    assert!(content.contains("412"));
+    assert!(content.contains("2023-04-12"));
+
+    assert!(content.contains("Loading elevation..."));
+    assert!(content.contains("Not enough Run activities in the"));
+
+    // Runs at 3 m/s with 600 m/h climbing
+    let mut activities = create_test_activities(4);
+    for (i, activity) in activities.iter_mut().enumerate() {
+        activity.distance = 5000.0 * (i + 1) as f64;
+        activity.total_elevation_gain = [20.0, 150.0, 40.0, 300.0][i];
+        activity.moving_time =
+            (activity.distance / 3.0 + activity.total_elevation_gain * 6.0) as u32;
+    }
+    app.add_activities(activities.into(), 30);
+    app.set_view(View::SavedRoutes);
+    assert_eq!(app.selected_saved_route_id(), Some(41));
+    let streams: StreamSet = serde_json::from_str(
+        r#"{"distance": {"data": [0.0, 5000.0, 10250.0]},
+            "altitude": {"data": [35.0, 58.0, 35.0]}}"#,
+    )
+    .unwrap();
+    app.set_route_streams(41, streams);
+    assert!(app.has_route_streams_for(41));
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Elevation (35-58 m)"));
+    assert!(content.contains("Estimated moving time"));
+    // 10.25 km at 3 m/s plus 38 m at 600 m/h, about 1:00:45
+    assert!(content.contains("1:00:4"));
+    assert!(content.contains("5:33 /km on the flat"));
+    assert!(content.contains("plus 600 m/h climbing"));
+
+    app.handle_key(KeyCode::Char('j'));
+    assert_eq!(app.selected_saved_route_id(), Some(42));
+    assert!(matches!(
+        app.handle_key(KeyCode::Char('x')),
+        Some(Effect::DownloadRoute(42))
+    ));
+    assert_eq!(app.notice(), Some("Downloading Havel & Wannsee..."));
+    assert_eq!(
+        app.saved_route(42).map(|r| r.gpx_file_name()),
+        Some("havel-wannsee.gpx".to_string())
+    );
+}
+
+#[test]
+fn test_social_view() {
+    use crossterm::event::KeyCode;
+
+    let backend = TestBackend::new(100, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.handle_key(KeyCode::Char('u'));
+    assert_eq!(app.current_view(), View::Social);
+    assert!(app.should_load_following());
+    app.set_following_loading(true);
+    assert!(!app.should_load_following());
+
+    let feed: Vec<FeedActivity> = serde_json::from_str(
+        r#"[{"id": 11, "athlete": {"firstname": "Sam", "lastname": "B."}, "name": "Long run",
+             "sport_type": "Run", "start_date": "2024-06-30T07:00:00Z", "distance": 21000.0,
+             "moving_time": 7000, "kudos_count": 4, "has_kudoed": true},
+            {"id": 12, "athlete": {"firstname": "Jo", "lastname": "K."}, "name": "Havel loop",
+             "sport_type": "Ride", "start_date": "2024-06-29T13:00:00Z", "distance": 72000.0,
+             "moving_time": 9400, "kudos_count": 9, "has_kudoed": true},
+            {"id": 13, "athlete": {"firstname": "Mika", "lastname": "L."}, "name": "Jog",
+             "sport_type": "Run", "start_date": "2024-06-29T06:00:00Z", "distance": 6000.0,
+             "moving_time": 2200, "kudos_count": 1, "has_kudoed": false}]"#,
+    )
+    .unwrap();
+    app.add_following_activities(1, feed.into(), 30);
+    // A short page means there is nothing more to load
+    assert!(!app.should_load_following());
+    assert_eq!(app.following_page(), 1);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Followers: -   Following: -"));
+    assert!(content.contains("Sam B."));
+    assert!(content.contains("Havel loop"));
+    assert!(content.contains("♥ 9"));
+
+    // n skips the activities that already have the user's kudos
+    app.handle_key(KeyCode::Char('n'));
+    match app.handle_key(KeyCode::Char('K')) {
+        Some(Effect::Browse(url, _)) => {
+            assert_eq!(url, "https://www.strava.com/activities/13")
+        }
+        other => panic!("expected a browser link, got {:?}", other),
+    }
+    app.handle_key(KeyCode::Char('n'));
+    assert_eq!(app.notice(), Some("No more activities without your kudos"));
+}
+
+#[test]
+fn test_social_view_without_feed_access() {
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_view(View::Social);
+    app.set_following_loading(true);
+    app.set_following_unavailable();
+    assert!(!app.should_load_following());
+    let profile: DetailedAthlete =
+        serde_json::from_str(r#"{"id": 1, "follower_count": 48, "friend_count": 52}"#).unwrap();
+    app.set_profile(profile);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Followers: 48   Following: 52"));
+    assert!(content.contains("Strava doesn't share the feed"));
+}
+
+#[test]
+fn test_clubs_view() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_view(View::Clubs);
+    assert!(!app.should_load_club_activities());
+
+    let clubs: Vec<SummaryClub> = serde_json::from_str(
+        r#"[{"id": 1, "name": "Berlin Runners", "member_count": 120},
+            {"id": 2, "name": "Gravel Crew"}]"#,
+    )
+    .unwrap();
+    app.set_clubs(clubs);
+    assert_eq!(app.selected_club_id(), Some(1));
+    assert!(app.should_load_club_activities());
+
+    // A failed page waits for r instead of being requested again
+    app.set_club_loading(true);
+    app.set_club_load_error(1);
+    assert!(!app.should_load_club_activities());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Club activities failed to load - r to retry"));
+    app.handle_key(crossterm::event::KeyCode::Char('r'));
+    assert!(app.should_load_club_activities());
+    assert!(!app.is_refreshing());
+
+    let club_activity = |name: &str| ClubActivity {
+        athlete: SummaryAthlete {
+            id: None,
+            firstname: "Jane".to_string(),
+            lastname: "D.".to_string(),
+        },
+        name: name.to_string(),
+        distance: 10000.0,
+        moving_time: 3000,
+        elapsed_time: 3100,
+        total_elevation_gain: 50.0,
+        sport_type: "Run".to_string(),
+    };
+    app.set_club_loading(true);
+    app.add_club_activities(1, 1, vec![club_activity("Sunday Long Run")].into(), 10);
+    // A short page means the feed is exhausted
+    assert!(!app.should_load_club_activities());
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Berlin Runners - 120 members"));
+    assert!(content.contains("Gravel Crew"));
+    assert!(content.contains("Jane D."));
+    assert!(content.contains("Sunday Long Run"));
+    assert!(content.contains("5:00"));
+
+    // Switching clubs starts a fresh feed and drops pages for the old club
+    app.next_club();
+    assert_eq!(app.selected_club_id(), Some(2));
+    assert_eq!(app.club_activity_page(), 0);
+    app.add_club_activities(1, 1, vec![club_activity("Stale")].into(), 10);
+    assert!(app.should_load_club_activities());
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Loading club activities..."));
+    assert!(!content.contains("Stale"));
+
+    // A full page with a malformed entry skipped doesn't end the feed
+    app.set_club_loading(true);
+    app.add_club_activities(
+        2,
+        1,
+        Page {
+            items: vec![club_activity("Gravel Loop")],
+            received: 2,
+        },
+        2,
+    );
+    assert!(app.should_load_club_activities());
+}
+
+#[test]
+fn test_progress_view() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut activities = create_test_activities(3);
+    let last_year = chrono::Utc::now()
+        .checked_sub_months(chrono::Months::new(12))
+        .unwrap();
+    activities[2].start_date = last_year;
+    activities[2].start_date_local = last_year;
+    let mut app = App::new();
+    app.merge_history(activities);
+    app.set_view(View::Progress);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("SportFrei - Progress"));
+    assert!(content.contains("3 cached activities"));
+    assert!(content.contains(&chrono::Local::now().format("%b %Y").to_string()));
+    assert!(content.contains(&format!("{} (last year)", last_year.format("%b %Y"))));
+    // Two runs this month against one in the same month last year
+    assert!(content.contains("+100%"));
+}
+
+#[test]
+fn test_heatmap_view() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    // start_date_local holds local wall-clock time
+    let now = chrono::Local::now().naive_local().and_utc();
+    let mut activities = create_test_activities(5);
+    for (activity, days_ago) in activities.iter_mut().zip([0, 1, 10, 11, 12]) {
+        activity.start_date_local = now - chrono::Duration::days(days_ago);
+    }
+    let mut app = App::new();
+    app.merge_history(activities);
+    app.set_view(View::Heatmap);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("SportFrei - Activity Calendar"));
+    assert!(content.contains("last 53 weeks"));
+    assert!(content.contains("Mon"));
+    assert!(content.contains("■"));
+    assert!(content.contains("5 active days"));
+    assert!(content.contains("Current streak: 2 days"));
+    assert!(content.contains("Longest streak: 3 days"));
+}
+
+#[test]
+fn test_routes_view() {
+    let backend = TestBackend::new(140, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_view(View::Routes);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("SportFrei - Repeated Routes"));
+    assert!(content.contains("No route done twice yet"));
+
+    let now = chrono::Utc::now();
+    let mut activities = create_test_activities(4);
+    for (i, activity) in activities.iter_mut().enumerate() {
+        activity.name = format!("Park loop {}", i);
+        activity.start_date = now - chrono::Duration::days(30 - i as i64 * 7);
+        activity.start_date_local = activity.start_date;
+        activity.moving_time = [1900, 1850, 1800, 1820][i];
+        activity.map = Some(PolylineMap {
+            id: None,
+            polyline: None,
+            summary_polyline: Some("_p~iF~ps|U_ulLnnqC_mqNvxq`@".to_string()),
+        });
+    }
+    app.merge_history(activities);
+    assert_eq!(
+        keymap::action(View::Activities, crossterm::event::KeyCode::Char('R')),
+        Some(Action::ShowView(View::Routes))
+    );
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Repeated Routes (1)"));
+    assert!(content.contains("Park loop 3"));
+    assert!(content.contains("4 repeats, newest first"));
+    assert!(content.contains("best 0:30:00"));
+    assert!(content.contains("+0:20"));
+    assert!(content.contains("+1:40"));
+
+    // The selection can't run past the last route
+    app.select_next_route();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("4 repeats, newest first"));
+}
+
+#[test]
+fn test_plan_view() {
+    let backend = TestBackend::new(140, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Plan);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("No planned workouts"));
+
+    // Last month, so everything planned is due
+    let today = chrono::Local::now().date_naive();
+    let first = chrono::Datelike::with_day(&today, 1).unwrap() - chrono::Months::new(1);
+    let plan: Plan = toml::from_str(&format!(
+        "[[workout]]\ndate = \"{}\"\nsport = \"Run\"\ndistance = 5\n\n\
+         [[workout]]\ndate = \"{}\"\nsport = \"Ride\"\nduration = 60\n",
+        first + chrono::Duration::days(2),
+        first + chrono::Duration::days(3)
+    ))
+    .unwrap();
+    app.set_plan(plan.workouts);
+    let mut run = create_test_activities(1);
+    run[0].id = 50;
+    run[0].start_date_local = (first + chrono::Duration::days(2))
+        .and_hms_opt(8, 0, 0)
+        .unwrap()
+        .and_utc();
+    app.merge_history(run);
+
+    app.prev_plan_month();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains(&format!("Training plan - {}", first.format("%B %Y"))));
+    assert!(content.contains("1 of 2 due done · 0 partial · 1 missed"));
+    assert!(content.contains("compliance 50%"));
+    assert!(content.contains("✓ Run 5 km"));
+    assert!(content.contains("✗ Ride 60 min"));
+}
+
+#[test]
+fn test_sports_view() {
+    let backend = TestBackend::new(120, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    let mut swim = create_test_activities(1);
+    swim[0].id = 60;
+    swim[0].sport_type = "Swim".to_string();
+    swim[0].distance = 1000.0;
+    swim[0].moving_time = 1200;
+    app.merge_history(swim);
+    app.set_view(View::Sports);
+    assert_eq!(
+        keymap::action(View::Dashboard, crossterm::event::KeyCode::Char('B')),
+        Some(Action::ShowView(View::Sports))
+    );
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Sports (3 types in 3 cached activities"));
+    // Ride has the most moving time and shows speed; run and swim show pace
+    let ride = content.find("Ride").unwrap();
+    let swim = content.find("Swim").unwrap();
+    assert!(ride < swim);
+    assert!(content.contains("25.0 km/h"));
+    assert!(content.contains("6:00 /km"));
+    assert!(content.contains("2:00 /100m"));
+    assert!(content.contains("Total"));
+}
+
+#[test]
+fn test_exclude_indoor_activities() {
+    use crossterm::event::KeyCode;
+
+    let backend = TestBackend::new(150, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    let mut zwift = create_test_activities(1);
+    zwift[0].id = 70;
+    zwift[0].sport_type = "VirtualRide".to_string();
+    zwift[0].distance = 30000.0;
+    zwift[0].moving_time = 3600;
+    app.merge_history(zwift);
+    app.set_view(View::Sports);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Sports (3 types in 3 cached activities"));
+    assert!(content.contains("VirtualRide"));
+    assert!(content.contains("of which indoor"));
+
+    assert_eq!(
+        keymap::action(View::Sports, KeyCode::Char('I')),
+        Some(Action::ExcludeIndoor)
+    );
+    app.handle_key(KeyCode::Char('I'));
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Sports (2 types in 2 cached activities"));
+    assert!(!content.contains("VirtualRide"));
+    assert!(!content.contains("of which indoor"));
+    assert!(content.contains("no indoor"));
+
+    // Only the totals leave them out
+    app.set_view(View::Dashboard);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("(3 cached, no indoor)"));
+    assert_eq!(app.history_len(), 3);
+}
+
+#[test]
+fn test_fitness_view() {
+    use crossterm::event::KeyCode;
+
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_view(View::Fitness);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("SportFrei - Fitness & Freshness"));
+    assert!(content.contains("No relative effort or heart rate data"));
+
+    let now = chrono::Local::now().naive_local().and_utc();
+    let mut activities = create_test_activities(10);
+    for (days_ago, activity) in activities.iter_mut().enumerate() {
+        activity.start_date_local = now - chrono::Duration::days(days_ago as i64 * 2);
+        activity.suffer_score = Some(80.0);
+    }
+    app.merge_history(activities);
+    assert_eq!(
+        keymap::action(View::Activities, KeyCode::Char('F')),
+        Some(Action::ShowView(View::Fitness))
+    );
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Fitness "));
+    assert!(content.contains("Fatigue "));
+    assert!(content.contains("Form "));
+    assert!(content.contains("heart rates 60-175 bpm"));
+    assert!(content.contains("Last 19 days"));
+}
+
+#[test]
+fn test_dashboard_race_predictions() {
+    let backend = TestBackend::new(140, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(!content.contains("Race predictions"));
+
+    let now = chrono::Utc::now();
+    let effort = BestEffort {
+        id: 1,
+        name: "5k".to_string(),
+        activity: Reference {
+            id: 1,
+            resource_state: 1,
+        },
+        athlete: Reference {
+            id: 12345,
+            resource_state: 1,
+        },
+        elapsed_time: 1200,
+        moving_time: 1200,
+        start_date: now,
+        start_date_local: now,
+        distance: 5000.0,
+        pr_rank: None,
+    };
+    let efforts = vec![effort];
+    app.merge_best_efforts([(1, &efforts)]);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Race predictions (from 5k in 0:20:00"));
+    assert!(content.contains("VO2max ~50"));
+    assert!(content.contains("10k 0:41:42"));
+    assert!(content.contains("Marathon "));
+}
+
+#[test]
+fn test_dashboard_goals() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(!content.contains("Goals"));
+
+    app.set_goals(vec![Goal {
+        period: GoalPeriod::Year,
+        metric: GoalMetric::Distance,
+        target: 1000.0,
+        sport: Some("Run".to_string()),
+    }]);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Goals (projection assumes the current pace)"));
+    assert!(content.contains("Annual Run 1000km"));
+}
+
+#[test]
+fn test_custom_activity_columns() {
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut activities = create_test_activities(2);
+    activities[0].kudos_count = Some(17);
+    activities[0].average_watts = Some(212.4);
+    let mut app = App::new();
+    app.set_data(create_test_athlete(), create_test_stats(), activities, 10);
+    app.set_view(View::Activities);
+    app.set_activity_columns(vec![
+        ActivityColumn::Date,
+        ActivityColumn::Name,
+        ActivityColumn::Kudos,
+        ActivityColumn::Power,
+    ]);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Kudos"));
+    assert!(content.contains("Watts"));
+    assert!(content.contains("212"));
+    assert!(!content.contains("Cal"));
+    assert!(!content.contains("RelPerf"));
+
+    // An empty list keeps the current columns
+    app.set_activity_columns(Vec::new());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Watts"));
+}
+
+#[test]
+fn test_script_columns_and_cards() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_data(
+        create_test_athlete(),
+        create_test_stats(),
+        create_test_activities(2),
+        10,
+    );
+    app.set_scripts(
+        Scripts::compile(
+            r#"
+            fn km(a) { a.distance / 1000.0 }
+            fn broken(a) { a.nothing.len() }
+            fn card_total_kudos(activities) {
+                let total = 0;
+                for a in activities { total += a.kudos_count; }
+                `${total} kudos`
+            }
+            "#,
+        )
+        .unwrap(),
+    );
+    app.set_activity_columns(vec![
+        ActivityColumn::Name,
+        ActivityColumn::Script("km".to_string()),
+        ActivityColumn::Script("broken".to_string()),
+    ]);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Total kudos"));
+    assert!(content.contains("10 kudos"));
+
+    app.set_view(View::Activities);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    let row = content.lines().find(|l| l.contains("Activity 0")).unwrap();
+    assert!(content.contains("km"));
+    assert!(row.contains("5.0"));
+    assert!(row.contains("ERR"));
+}
+
+#[test]
+fn test_upload_progress_in_status_bar() {
+    let backend = TestBackend::new(120, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = App::new();
+    app.set_upload("B1F83012.FIT".to_string(), UploadState::Uploading);
+    app.set_upload("C2A91544.FIT".to_string(), UploadState::Uploading);
+    app.set_upload("B1F83012.FIT".to_string(), UploadState::Processing);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Strava is processing B1F83012.FIT (+1)"));
+
+    app.upload_rejected("B1F83012.FIT", "duplicate of activity 123");
+    app.clear_upload("C2A91544.FIT");
+    assert!(app.uploads().is_empty());
+    assert_eq!(
+        app.status_message(),
+        Some("Failed to upload B1F83012.FIT: duplicate of activity 123")
+    );
+}
+
+#[test]
+fn test_time_basis_toggle() {
+    use crossterm::event::KeyCode;
+
+    let backend = TestBackend::new(120, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut activities = create_test_activities(2);
+    activities[1].name = "Long Ride".to_string();
+    activities[1].sport_type = "Ride".to_string();
+    activities[1].distance = 36000.0;
+    activities[1].moving_time = 3600;
+    activities[1].elapsed_time = 4800;
+    activities[1].average_speed = Some(10.0);
+    activities[1].max_speed = Some(15.0);
+    let mut app = App::new();
+    app.set_data(create_test_athlete(), create_test_stats(), activities, 10);
+    app.set_view(View::Activities);
+    app.set_activity_columns(vec![
+        ActivityColumn::Name,
+        ActivityColumn::Duration,
+        ActivityColumn::Pace,
+        ActivityColumn::Speed,
+        ActivityColumn::MaxSpeed,
+    ]);
+    let row = |app: &mut App, terminal: &mut Terminal<TestBackend>, name: &str| {
+        terminal.draw(|f| app.render(f)).unwrap();
+        get_buffer_content(terminal.backend().buffer())
+            .lines()
+            .find(|l| l.contains(name))
+            .unwrap()
+            .to_string()
+    };
+
+    let run = row(&mut app, &mut terminal, "Activity 0");
+    assert!(run.contains("0:30:00"));
+    assert!(run.contains("6:00"));
+    let ride = row(&mut app, &mut terminal, "Long Ride");
+    assert!(ride.contains("1:00:00"));
+    assert!(ride.contains("36.0"));
+    assert!(ride.contains("54.0"));
+
+    app.handle_key(KeyCode::Char('E'));
+    assert_eq!(app.time_basis(), TimeBasis::Elapsed);
+    let run = row(&mut app, &mut terminal, "Activity 0");
+    assert!(run.contains("0:33:20"));
+    assert!(run.contains("6:40"));
+    let ride = row(&mut app, &mut terminal, "Long Ride");
+    assert!(ride.contains("1:20:00"));
+    assert!(ride.contains("27.0"));
+    // The top speed doesn't depend on the basis
+    assert!(ride.contains("54.0"));
+
+    // Rides by elapsed time, runs by moving time
+    app.handle_key(KeyCode::Char('E'));
+    assert_eq!(app.time_basis(), TimeBasis::BySport);
+    assert!(row(&mut app, &mut terminal, "Activity 0").contains("0:30:00"));
+    assert!(row(&mut app, &mut terminal, "Long Ride").contains("1:20:00"));
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("elapsed time for rides"));
+
+    app.handle_key(KeyCode::Char('E'));
+    assert_eq!(app.time_basis(), TimeBasis::Moving);
+}
+
+#[test]
+fn test_gear_view() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut athlete = create_test_athlete();
+    athlete.shoes.push(Gear {
+        id: "g1".to_string(),
+        name: "Pegasus".to_string(),
+        primary: true,
+        distance: 650000.0,
+        brand_name: None,
+        model_name: None,
+        description: None,
+        retired: None,
+    });
+    let mut activities = create_test_activities(2);
+    activities[0].gear_id = Some("g1".to_string());
+    activities[1].gear_id = Some("g2".to_string());
+
+    let mut app = App::new();
+    app.set_data(athlete, create_test_stats(), activities, 10);
+    assert_eq!(app.gear_ids(), vec!["g1".to_string(), "g2".to_string()]);
+
+    app.set_view(View::Gear);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("SportFrei - Gear"));
+    assert!(content.contains("Pegasus"));
+    assert!(content.contains("650.0"));
+    assert!(content.contains("primary"));
+
+    // Gear only referenced by an activity shows up once its details load
+    app.set_gear(Gear {
+        id: "g2".to_string(),
+        name: "Old Trail Shoes".to_string(),
+        primary: false,
+        distance: 900000.0,
+        brand_name: Some("Salomon".to_string()),
+        model_name: Some("Speedcross".to_string()),
+        description: None,
+        retired: Some(true),
+    });
+    assert!(app.has_gear_for("g2"));
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Salomon Speedcross"));
+    assert!(content.contains("retired"));
+
+    app.set_view(View::Activities);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Gear"));
+    assert!(content.contains("Old Trail Shoes"));
+}
+
+#[test]
+fn test_activity_detail_map_tab() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+    app.next_detail_tab();
+    assert_eq!(app.detail_tab(), DetailTab::Map);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("No GPS data for this activity"));
+
+    app.set_activity_streams(
+        1,
+        StreamSet {
+            latlng: Some(Stream {
+                data: vec![[52.52, 13.40], [52.53, 13.41], [52.52, 13.42]],
+                series_type: Some("distance".to_string()),
+                original_size: None,
+                resolution: None,
+            }),
+            ..Default::default()
+        },
+    );
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Map (green: start, red: finish)"));
+    assert!(content
+        .chars()
+        .any(|c| ('\u{2801}'..='\u{28ff}').contains(&c)));
+}
+
+#[test]
+fn test_activity_detail_social_tab() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+    app.prev_detail_tab();
+    assert_eq!(app.detail_tab(), DetailTab::Social);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Loading kudos and comments..."));
+
+    let athlete = |first: &str| SummaryAthlete {
+        id: None,
+        firstname: first.to_string(),
+        lastname: "X.".to_string(),
+    };
+    app.set_activity_social(
+        1,
+        vec![athlete("Jane"), athlete("Max")],
+        vec![Comment {
+            id: 1,
+            activity_id: 1,
+            text: "Strong finish!".to_string(),
+            athlete: athlete("Jane"),
+            created_at: chrono::Utc::now(),
+        }],
+    );
+    assert!(app.has_social_for(1));
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Kudos (2)"));
+    assert!(content.contains("Max X."));
+    assert!(content.contains("Comments (1)"));
+    assert!(content.contains("Strong finish!"));
+
+    // Wraps around back to the overview
+    app.next_detail_tab();
+    assert_eq!(app.detail_tab(), DetailTab::Overview);
+}
+
+#[test]
+fn test_activity_detail_zones_tab() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+    app.prev_detail_tab();
+    app.prev_detail_tab();
+    assert_eq!(app.detail_tab(), DetailTab::Zones);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Loading zones..."));
+    // Waits for the streams, which may make Strava's zones unnecessary
+    assert_eq!(app.zones_needed(), None);
+
+    let stream = |data: Vec<f64>| Stream {
+        data,
+        series_type: Some("time".to_string()),
+        original_size: None,
+        resolution: None,
+    };
+    let time = Stream {
+        data: (0..10).map(|i| i * 60).collect(),
+        series_type: Some("time".to_string()),
+        original_size: None,
+        resolution: None,
+    };
+    app.set_activity_streams(
+        1,
+        StreamSet {
+            time: Some(time.clone()),
+            altitude: Some(stream(vec![40.0; 10])),
+            ..Default::default()
+        },
+    );
+    assert_eq!(app.zones_needed(), Some(1));
+
+    let zones: Vec<ActivityZone> = serde_json::from_str(
+        r#"[{"type": "power", "score": 31, "sensor_based": true,
+             "distribution_buckets": [{"min": 0, "max": 135, "time": 1800},
+                                      {"min": 135, "max": -1, "time": 600}]}]"#,
+    )
+    .unwrap();
+    app.set_activity_zones(1, zones);
+    assert_eq!(app.zones_needed(), None);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Power Zones"));
+    assert!(content.contains("Z1   0-135 W"));
+    assert!(content.contains("0:30:00 75%"));
+    assert!(content.contains("Z2    135+ W"));
+    assert!(content.contains("Score 31"));
+    assert!(content.contains("from Strava"));
+
+    // With a heart rate stream the zones are computed locally
+    app.set_heart_rates(None, Some(200));
+    app.set_activity_streams(
+        1,
+        StreamSet {
+            time: Some(time),
+            heartrate: Some(stream(vec![
+                130.0, 130.0, 130.0, 130.0, 130.0, 185.0, 185.0, 185.0, 185.0, 185.0,
+            ])),
+            ..Default::default()
+        },
+    );
+    assert_eq!(app.zones_needed(), None);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Heart Rate Zones"));
+    assert!(content.contains("Z2 120-140 bpm"));
+    assert!(content.contains("0:04:00 44%"));
+    assert!(content.contains("0:05:00 56%"));
+    assert!(content.contains("computed from the heart rate stream"));
+
+    // Nothing to bucket
+    app.set_activity_streams(1, StreamSet::default());
+    app.set_activity_zones(1, Vec::new());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("No heart rate or power zones for this activity"));
+}
+
+#[test]
+fn test_activity_detail_notes() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_notes([(2, "Windy on the way back".to_string())]);
+    app.set_view(View::ActivityDetail);
+    assert_eq!(
+        keymap::action(View::ActivityDetail, crossterm::event::KeyCode::Char('n')),
+        Some(Action::Note)
+    );
+
+    app.start_note();
+    assert!(app.is_editing_note());
+    assert_eq!(app.detail_tab(), DetailTab::Notes);
+    for c in "Felt strong\nNegative split".chars() {
+        app.note_input(c);
+    }
+    app.note_input(' ');
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Ctrl+S save"));
+    assert!(content.contains("Negative split _"));
+
+    let (id, note) = app.submit_note().unwrap();
+    assert_eq!(id, 1);
+    assert_eq!(note, "Felt strong\nNegative split");
+    assert!(!app.is_editing_note());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Felt strong"));
+    assert!(!content.contains("Windy"));
+
+    // Cancelling keeps the saved note
+    app.select_next_activity();
+    app.start_note();
+    app.note_backspace();
+    app.cancel_note();
+    assert_eq!(app.note_of(2), Some("Windy on the way back"));
+}
+
+#[test]
+fn test_activity_detail_photos_tab() {
+    let backend = TestBackend::new(120, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+    app.next_detail_tab();
+    app.next_detail_tab();
+    assert_eq!(app.detail_tab(), DetailTab::Photos);
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Loading photos..."));
+
+    let photo = Photo {
+        unique_id: Some("a1".to_string()),
+        caption: Some("Summit".to_string()),
+        urls: [("600".to_string(), "https://example.com/a1.jpg".to_string())].into(),
+    };
+    app.set_activity_photos(1, vec![photo], Some(vec![0xff, 0xd8]));
+    assert!(app.has_photos_for(1));
+
+    // Without a graphics protocol the URLs are listed instead
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Photos (1)"));
+    assert!(content.contains("Summit"));
+    assert!(content.contains("https://example.com/a1.jpg"));
+    assert!(app.photo_placement().is_none());
+
+    app.set_graphics_protocol(Some(GraphicsProtocol::Kitty));
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Preview"));
+    let (id, area, image) = app.photo_placement().unwrap();
+    assert_eq!((id, image), (1, &[0xff, 0xd8][..]));
+    assert!(area.width > 0 && area.height > 0);
+
+    // Popups hide the image
+    app.toggle_help();
+    terminal.draw(|f| app.render(f)).unwrap();
+    assert!(app.photo_placement().is_none());
+}
+
+#[test]
+fn test_confirm_delete_and_mute() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    app.request_delete();
+    assert!(app.is_confirming());
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Delete Activity"));
+    assert!(content.contains("Delete 'Morning Run' from Strava?"));
+
+    app.cancel_confirm();
+    assert!(!app.is_confirming());
+
+    app.request_mute();
+    assert_eq!(app.confirm(), Some(ConfirmAction::Mute(1)));
+    assert!(!app.is_confirming());
+
+    app.set_view(View::ActivityDetail);
+    app.remove_activity(1);
+    assert_eq!(app.current_view(), View::Activities);
+    assert_eq!(app.get_selected_activity().map(|a| a.id), Some(2));
+}
+
+#[test]
+fn test_edit_form_builds_update_with_changed_fields() {
+    let backend = TestBackend::new(100, 40);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+    app.start_edit();
+    assert!(app.is_editing());
+    assert_eq!(app.edit_field(), Some(EditField::Name));
+
+    for c in " 10k".chars() {
+        app.edit_input(c);
+    }
+    app.edit_next_field();
+    app.edit_next_field();
+    assert_eq!(app.edit_field(), Some(EditField::Commute));
+    app.edit_input(' ');
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Edit Activity"));
+    assert!(content.contains("Morning Run 10k"));
+    assert!(content.contains("Commute:     [x]"));
+
+    let (id, update) = app.submit_edit().unwrap();
+    assert_eq!(id, 1);
+    assert_eq!(update.name.as_deref(), Some("Morning Run 10k"));
+    assert_eq!(update.commute, Some(true));
+    assert_eq!(update.description, None);
+    assert_eq!(update.trainer, None);
+    assert_eq!(update.gear_id, None);
+
+    // A second submit while saving is ignored
+    assert!(app.submit_edit().is_none());
+
+    app.set_edit_error("Forbidden".to_string());
+    assert!(app.is_editing());
+    app.cancel_edit();
+    assert!(!app.is_editing());
+}
+
+#[test]
+fn test_edit_form_without_changes_closes() {
+    let mut app = create_test_app();
+    app.set_view(View::ActivityDetail);
+    app.start_edit();
+    assert!(app.submit_edit().is_none());
+    assert!(!app.is_editing());
+}
+
+#[test]
+fn test_navigation_keys() {
+    let mut app = create_test_app();
+    app.set_view(View::Dashboard);
+
+    // Initially on dashboard
+    assert_eq!(app.current_view(), View::Dashboard);
+
+    // Press 'a' to go to activities
+    app.set_view(View::Activities);
+    assert_eq!(app.current_view(), View::Activities);
+
+    // Press 'd' to go back to dashboard
+    app.set_view(View::Dashboard);
+    assert_eq!(app.current_view(), View::Dashboard);
+}
+
+#[test]
+fn test_activity_selection() {
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+
+    // Initially first activity is selected (index 0)
+    assert_eq!(app.get_selected_activity().unwrap().name, "Morning Run");
+
+    // Select next
+    app.select_next_activity();
+    assert_eq!(app.get_selected_activity().unwrap().name, "Evening Ride");
+
+    // Select previous
+    app.select_prev_activity();
+    assert_eq!(app.get_selected_activity().unwrap().name, "Morning Run");
+}
+
+#[test]
+fn test_infinite_scroll_triggers() {
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+
+    // Set has_more_activities to true to test the logic
+    // (In real usage, this would be set based on API response)
+    // For now, just test the selection logic works
+    assert!(app.get_selected_activity().is_some());
 
     // Test that selection moves correctly
     app.select_next_activity();
@@ -473,13 +3001,195 @@ fn test_footer_shows_navigation() {
     assert!(content.contains("[A]ctivities"));
 }
 
+#[test]
+fn test_german_ui() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_language(Language::German);
+    app.set_view(View::Activities);
+
+    terminal
+        .draw(|f| {
+            app.render(f);
+        })
+        .unwrap();
+
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("SportFrei - Aktivitäten"));
+    assert!(content.contains("Datum"));
+    assert!(content.contains("Distanz"));
+    assert!(content.contains("[?]Hilfe"));
+    assert!(!content.contains("Distance"));
+}
+
+#[test]
+fn test_locale_formats_activity_dates() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_locale(Locale::from_locale("de_DE"));
+    app.set_view(View::Activities);
+
+    terminal
+        .draw(|f| {
+            app.render(f);
+        })
+        .unwrap();
+
+    let content = get_buffer_content(terminal.backend().buffer());
+    let today = chrono::Utc::now().format("%d.%m.").to_string();
+    assert!(content.contains(&today));
+    assert!(content.contains("25,0"));
+}
+
+#[test]
+fn test_toggle_time_format() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    app.toggle_time_format();
+
+    terminal
+        .draw(|f| {
+            app.render(f);
+        })
+        .unwrap();
+
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains(" AM") || content.contains(" PM"));
+    assert_eq!(
+        keymap::action(View::Activities, crossterm::event::KeyCode::Char('H')),
+        Some(Action::TimeFormat)
+    );
+}
+
+#[test]
+fn test_status_bar_shows_context() {
+    let backend = TestBackend::new(140, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    app.set_rate_limit(RateLimit {
+        short_remaining: 88,
+        daily_remaining: 660,
+    });
+    app.toggle_hide_commutes();
+    app.set_loading(true);
+
+    terminal
+        .draw(|f| {
+            app.render(f);
+        })
+        .unwrap();
+
+    let content = get_buffer_content(terminal.backend().buffer());
+    let status = content.lines().nth(22).unwrap();
+    assert!(status.contains("Activities"), "{}", status);
+    assert!(status.contains("2 of 150 activities"), "{}", status);
+    assert!(status.contains("no commutes"), "{}", status);
+    assert!(status.contains("API 88/15 min, 660 today"), "{}", status);
+    assert!(status.contains('⠋') || status.contains('⠙'), "{}", status);
+    assert!(!status.contains("synced"));
+}
+
+#[test]
+fn test_skeleton_rows_while_loading_next_page() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = create_test_app();
+    app.set_view(View::Activities);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(!content.contains('░'));
+
+    app.set_loading(true);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let lines: Vec<String> = get_buffer_content(terminal.backend().buffer())
+        .lines()
+        .map(str::to_string)
+        .collect();
+    // Header, two activities, then the placeholders
+    let loading = lines
+        .iter()
+        .position(|l| l.contains("Loading more activities..."))
+        .unwrap();
+    assert!(
+        lines[loading - 1].contains("Evening Ride") || lines[loading - 1].contains("Morning Run")
+    );
+    assert!(lines[loading + 1].contains('░'));
+    assert!(lines[loading + 2].contains('░'));
+}
+
+#[test]
+fn test_end_of_list_row() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_view(View::Activities);
+    app.add_activities(create_test_activities(30).into(), 30);
+    app.select_last_activity();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(!content.contains("end of activities"));
+
+    // A short page means Strava has nothing older, and the marker shows
+    // below the selected last activity
+    app.add_activities(vec![].into(), 30);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("— end of activities —"));
+
+    // Loading and the end marker don't show at once
+    app.set_loading(true);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(!content.contains("end of activities"));
+}
+
+#[test]
+fn test_failed_page_error_row_and_retry() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    app.set_view(View::Activities);
+    app.add_activities(create_test_activities(30).into(), 30);
+    app.select_last_activity();
+    app.set_loading(true);
+    app.set_load_error(2, "Network error: timed out".to_string());
+
+    // The failed page stays put instead of being requested again
+    assert_eq!(app.failed_page(), Some(2));
+    assert!(!app.should_load_more());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("✗ Page 2 failed: Network error: timed out - r to retry"));
+
+    // Retrying hands the same page back to the paging check
+    assert!(app.retry_failed_page());
+    assert!(!app.retry_failed_page());
+    assert!(app.should_load_more());
+    assert_eq!(app.activity_page() + 1, 2);
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(!content.contains("r to retry"));
+}
+
 #[test]
 fn test_infinite_scroll_no_crash_on_empty_response() {
     let mut app = create_test_app();
     app.set_view(View::Activities);
 
     // Add empty activities (simulating end of list)
-    app.add_activities(vec![], 10);
+    app.add_activities(vec![].into(), 10);
 
     // should_load_more should now be false since we got empty results
     assert!(!app.should_load_more());
@@ -498,8 +3208,9 @@ fn test_load_error_state() {
     assert!(app.is_loading());
 
     // Simulate error - clear loading flag
-    app.set_load_error();
+    app.set_load_error(2, "Network error: timed out".to_string());
     assert!(!app.is_loading());
+    assert_eq!(app.failed_page(), Some(2));
 }
 
 #[test]
@@ -542,6 +3253,153 @@ fn test_screen_size_based_loading_large_terminal() {
     assert!(app.should_load_more());
 }
 
+#[test]
+fn test_configured_prefetch_distance_and_page_limit() {
+    let mut app = App::new();
+    app.set_data(
+        create_test_athlete(),
+        create_test_stats(),
+        create_test_activities(30),
+        30,
+    );
+    app.set_view(View::Activities);
+    app.set_paging(10, Some(2));
+    for _ in 0..19 {
+        app.select_next_activity();
+    }
+    assert!(!app.should_load_more());
+    app.select_next_activity();
+    assert!(app.should_load_more());
+
+    // The second page is the last one allowed
+    app.add_activities(create_test_activities(30).into(), 30);
+    for _ in 0..40 {
+        app.select_next_activity();
+    }
+    assert!(!app.should_load_more());
+}
+
+#[test]
+fn test_taller_terminal_loads_until_table_is_filled() {
+    let mut app = App::new();
+    app.set_data(
+        create_test_athlete(),
+        create_test_stats(),
+        create_test_activities(20),
+        20,
+    );
+    app.set_view(View::Activities);
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+    terminal.draw(|f| app.render(f)).unwrap();
+    assert!(!app.should_load_more());
+
+    // Resizing reflows the table, which now has room for more than 20 rows
+    terminal.backend_mut().resize(120, 50);
+    terminal.draw(|f| app.render(f)).unwrap();
+    assert!(app.should_load_more());
+}
+
+#[test]
+fn test_refresh_inserts_new_activities_and_keeps_selection() {
+    let all = create_test_activities(21);
+    let mut app = App::new();
+    app.set_data(
+        create_test_athlete(),
+        create_test_stats(),
+        all[10..15].to_vec(),
+        5,
+    );
+    app.set_view(View::Activities);
+    app.select_next_activity();
+    app.select_next_activity();
+    assert_eq!(app.get_selected_activity().map(|a| a.id), Some(12));
+
+    // A new upload on top, followed by a renamed known activity
+    let mut renamed = all[10].clone();
+    renamed.name = "Renamed".to_string();
+    app.set_refreshing(true);
+    app.merge_refresh(
+        create_test_athlete(),
+        create_test_stats(),
+        vec![all[20].clone(), renamed, all[11].clone()],
+    );
+
+    assert!(!app.is_refreshing());
+    assert_eq!(app.get_selected_activity().map(|a| a.id), Some(12));
+    app.select_first_activity();
+    assert_eq!(app.get_selected_activity().map(|a| a.id), Some(20));
+    app.select_next_activity();
+    assert_eq!(app.get_selected_activity().unwrap().name, "Renamed");
+
+    // Pages shifted by the new upload repeat an activity, which is skipped
+    app.add_activities(all[14..16].to_vec().into(), 5);
+    app.select_last_activity();
+    assert_eq!(app.get_selected_activity().map(|a| a.id), Some(15));
+    app.select_prev_activity();
+    assert_eq!(app.get_selected_activity().map(|a| a.id), Some(14));
+    app.select_prev_activity();
+    assert_eq!(app.get_selected_activity().map(|a| a.id), Some(13));
+}
+
+#[test]
+fn test_auto_refresh_inserts_new_activities_on_top() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let all = create_test_activities(4);
+    let mut app = App::new();
+    app.set_data(
+        create_test_athlete(),
+        create_test_stats(),
+        all[..2].to_vec(),
+        10,
+    );
+    app.set_view(View::Activities);
+    app.select_next_activity();
+
+    let mut newer = all[2..].to_vec();
+    newer[1].start_date += chrono::Duration::hours(1);
+    // Known activities in the response are ignored
+    newer.push(all[0].clone());
+    assert_eq!(app.insert_new_activities(newer), 2);
+    assert_eq!(app.insert_new_activities(all[3..].to_vec()), 0);
+
+    assert_eq!(app.get_selected_activity().map(|a| a.id), Some(1));
+    app.select_first_activity();
+    assert_eq!(app.get_selected_activity().map(|a| a.id), Some(3));
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("2 new activities"));
+}
+
+#[test]
+fn test_dashboard_fills_in_as_startup_data_arrives() {
+    let backend = TestBackend::new(120, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let mut app = App::new();
+    assert_eq!(app.activity_page(), 0);
+    assert!(app.should_load_more());
+
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Welcome! (loading athlete...)"));
+    assert!(content.contains("Year to date: loading..."));
+
+    app.set_athlete(create_test_athlete());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Welcome, John!"));
+    assert!(content.contains("Year to date: loading..."));
+
+    app.set_stats(create_test_stats());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content = get_buffer_content(terminal.backend().buffer());
+    assert!(content.contains("Year to date: 50 runs, 25 rides"));
+}
+
 #[test]
 fn test_add_activities_updates_has_more_based_on_per_page() {
     let per_page: u32 = 20;
@@ -555,7 +3413,19 @@ fn test_add_activities_updates_has_more_based_on_per_page() {
     assert!(!app.should_load_more());
 
     // Add 10 more activities - total 25 > 20
-    app.add_activities(create_test_activities(10), per_page);
+    app.add_activities(create_test_activities(10).into(), per_page);
+
+    // A full page with malformed entries skipped isn't the last one
+    let mut app = App::new();
+    app.add_activities(
+        Page {
+            items: create_test_activities(19),
+            received: 20,
+        },
+        per_page,
+    );
+    app.select_last_activity();
+    assert!(app.should_load_more());
 }
 
 #[test]
@@ -567,7 +3437,7 @@ fn test_activity_page_increments() {
     assert_eq!(initial_page, 1);
 
     // Add more activities
-    app.add_activities(create_test_activities(10), 30);
+    app.add_activities(create_test_activities(10).into(), 30);
 
     // Page should increment
     assert_eq!(app.activity_page(), 2);
@@ -583,6 +3453,8 @@ fn create_test_athlete() -> Athlete {
         country: Some("Germany".to_string()),
         profile: None,
         profile_medium: None,
+        bikes: Vec::new(),
+        shoes: Vec::new(),
     }
 }
 
@@ -653,7 +3525,10 @@ fn create_test_activities(count: usize) -> Vec<Activity> {
             max_speed: Some(3.5),
             average_heartrate: Some(150.0),
             max_heartrate: Some(175.0),
+            average_watts: None,
+            weighted_average_watts: None,
             calories: Some(350.0),
+            suffer_score: None,
             description: None,
             kudos_count: Some(5),
             comment_count: Some(1),
@@ -661,8 +3536,10 @@ fn create_test_activities(count: usize) -> Vec<Activity> {
             pr_count: Some(1),
             private: Some(false),
             commute: Some(false),
+            trainer: Some(false),
             manual: Some(false),
             gear_id: None,
+            map: None,
         })
         .collect()
 }
@@ -0,0 +1,92 @@
+use chrono::{Duration, Utc};
+use sportfrei::cache::UploadLog;
+use sportfrei::watch::FolderWatch;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sportfrei-watch-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn names(paths: Vec<PathBuf>) -> Vec<String> {
+    paths
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn test_new_fit_files_are_uploaded_once_complete() {
+    let dir = temp_dir("new");
+    let mut watch = FolderWatch::new(dir.clone(), UploadLog::default(), Utc::now());
+    assert!(watch.scan().is_empty());
+
+    fs::write(dir.join("B1F83012.FIT"), b"first").unwrap();
+    fs::write(dir.join("ride.fit.gz"), b"second").unwrap();
+    fs::write(dir.join("notes.txt"), b"not an activity").unwrap();
+    // Only offered once they stayed the same between two scans
+    assert!(watch.scan().is_empty());
+    assert_eq!(
+        names(watch.scan()),
+        vec!["B1F83012.FIT".to_string(), "ride.fit.gz".to_string()]
+    );
+    // Handed out once per session
+    assert!(watch.scan().is_empty());
+
+    // A file still being copied waits until it stops growing
+    fs::write(dir.join("run.fit"), b"par").unwrap();
+    assert!(watch.scan().is_empty());
+    fs::write(dir.join("run.fit"), b"partial and then some").unwrap();
+    assert!(watch.scan().is_empty());
+    assert_eq!(names(watch.scan()), vec!["run.fit".to_string()]);
+
+    fs::remove_dir_all(&dir).unwrap();
+    // An unplugged watch has nothing to upload
+    assert!(watch.scan().is_empty());
+}
+
+#[test]
+fn test_upload_log_survives_restarts() {
+    let dir = temp_dir("log");
+    let log_path = dir.join("uploads.json");
+    let files = dir.join("Activity");
+    fs::create_dir_all(&files).unwrap();
+
+    // Files from before the folder was first watched are left alone
+    let old = files.join("old.fit");
+    fs::write(&old, b"old").unwrap();
+    File::options()
+        .write(true)
+        .open(&old)
+        .unwrap()
+        .set_modified(SystemTime::now() - std::time::Duration::from_secs(3600))
+        .unwrap();
+    let started = Utc::now() - Duration::minutes(1);
+    let mut watch = FolderWatch::new(files.clone(), UploadLog::default(), started);
+    fs::write(files.join("new.fit"), b"new").unwrap();
+    fs::write(files.join("duplicate.fit"), b"dup").unwrap();
+    watch.scan();
+    let ready = watch.scan();
+    assert_eq!(
+        names(ready.clone()),
+        vec!["duplicate.fit".to_string(), "new.fit".to_string()]
+    );
+    watch.finish(&ready[0], None);
+    watch.finish(&ready[1], Some(42));
+    watch.log().save_to(&log_path).unwrap();
+
+    // The next session keeps the start and skips what was uploaded or rejected
+    let log = UploadLog::load_from(&log_path).unwrap();
+    assert_eq!(log.since, Some(started));
+    assert!(log.contains("new.fit") && log.contains("duplicate.fit"));
+    fs::write(files.join("later.FIT"), b"later").unwrap();
+    let mut watch = FolderWatch::new(files, log, Utc::now());
+    watch.scan();
+    assert_eq!(names(watch.scan()), vec!["later.FIT".to_string()]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,454 @@
+// Headless tests of the UI's loading path: the worker runs against an
+// in-memory API and its responses are applied to the app like the UI loop does
+
+use chrono::Utc;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use sportfrei::api::types::{
+    Activity, ActivityZone, Athlete, AthleteStats, ClubActivity, Comment, DateRange,
+    DetailedActivity, DetailedAthlete, DetailedSegment, FeedActivity, Gear, Page, Photo, Route,
+    StreamKey, StreamSet, SummaryAthlete, SummaryClub, SummarySegment, UpdatableActivity, Upload,
+    UploadDataType,
+};
+use sportfrei::api::StravaApi;
+use sportfrei::error::{Error, Result};
+use sportfrei::ui::app::{App, UploadState};
+use sportfrei::worker::{Request, Response, Worker};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Default)]
+struct MockState {
+    // Newest first, like Strava's list
+    activities: Vec<Activity>,
+    failure: Option<u16>,
+    requested_pages: Vec<u32>,
+}
+
+#[derive(Clone, Default)]
+struct MockApi {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockApi {
+    fn with_activities(count: u64) -> Self {
+        let api = Self::default();
+        api.state.lock().unwrap().activities = (0..count).map(|i| activity(count - i)).collect();
+        api
+    }
+
+    fn fail_with(&self, status: Option<u16>) {
+        self.state.lock().unwrap().failure = status;
+    }
+
+    fn requested_pages(&self) -> Vec<u32> {
+        self.state.lock().unwrap().requested_pages.clone()
+    }
+
+    fn check(&self) -> Result<()> {
+        match self.state.lock().unwrap().failure {
+            Some(status) => Err(Error::from_response(
+                StatusCode::from_u16(status).unwrap(),
+                &HeaderMap::new(),
+                "",
+                "read",
+                Utc::now(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl StravaApi for MockApi {
+    async fn get_athlete(&self) -> Result<Athlete> {
+        self.check()?;
+        Ok(serde_json::from_value(serde_json::json!({
+            "id": 12345, "firstname": "Test", "lastname": "User"
+        }))?)
+    }
+
+    async fn get_detailed_athlete(&self) -> Result<DetailedAthlete> {
+        self.check()?;
+        Ok(serde_json::from_value(serde_json::json!({
+            "id": 12345, "firstname": "Test", "lastname": "User", "follower_count": 3
+        }))?)
+    }
+
+    async fn get_athlete_stats(&self, _athlete_id: u64) -> Result<AthleteStats> {
+        self.check()?;
+        let totals = serde_json::json!({
+            "count": 0, "distance": 0.0, "moving_time": 0, "elapsed_time": 0, "elevation_gain": 0.0
+        });
+        Ok(serde_json::from_value(serde_json::json!({
+            "recent_run_totals": totals, "recent_ride_totals": totals,
+            "ytd_run_totals": totals, "ytd_ride_totals": totals,
+            "all_run_totals": totals, "all_ride_totals": totals
+        }))?)
+    }
+
+    async fn get_activities(
+        &self,
+        page: u32,
+        per_page: u32,
+        _range: DateRange,
+    ) -> Result<Page<Activity>> {
+        self.check()?;
+        let mut state = self.state.lock().unwrap();
+        state.requested_pages.push(page);
+        let start = (page as usize - 1) * per_page as usize;
+        Ok(state
+            .activities
+            .iter()
+            .skip(start)
+            .take(per_page as usize)
+            .cloned()
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn get_activity(&self, activity_id: u64) -> Result<DetailedActivity> {
+        Err(not_mocked(activity_id))
+    }
+
+    async fn update_activity(
+        &self,
+        activity_id: u64,
+        _update: &UpdatableActivity,
+    ) -> Result<DetailedActivity> {
+        Err(not_mocked(activity_id))
+    }
+
+    async fn delete_activity(&self, activity_id: u64) -> Result<()> {
+        Err(not_mocked(activity_id))
+    }
+
+    // Processing takes one status check
+    async fn create_upload(
+        &self,
+        _path: &Path,
+        _data_type: UploadDataType,
+        _name: Option<&str>,
+    ) -> Result<Upload> {
+        self.check()?;
+        Ok(upload(None))
+    }
+
+    async fn get_upload(&self, _upload_id: u64) -> Result<Upload> {
+        self.check()?;
+        Ok(upload(Some(42)))
+    }
+
+    async fn get_gear(&self, _gear_id: &str) -> Result<Gear> {
+        Err(not_mocked(0))
+    }
+
+    async fn get_clubs(&self) -> Result<Vec<SummaryClub>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_club_activities(
+        &self,
+        _club_id: u64,
+        _page: u32,
+        _per_page: u32,
+    ) -> Result<Page<ClubActivity>> {
+        Ok(Vec::new().into())
+    }
+
+    async fn get_following_activities(
+        &self,
+        _page: u32,
+        _per_page: u32,
+    ) -> Result<Page<FeedActivity>> {
+        Ok(Vec::new().into())
+    }
+
+    async fn get_routes(&self, _athlete_id: u64, _page: u32, _per_page: u32) -> Result<Vec<Route>> {
+        Ok(Vec::new())
+    }
+
+    async fn export_route_gpx(&self, _route_id: u64) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_route_streams(&self, _route_id: u64) -> Result<StreamSet> {
+        Ok(StreamSet::default())
+    }
+
+    async fn get_starred_segments(
+        &self,
+        _page: u32,
+        _per_page: u32,
+    ) -> Result<Vec<SummarySegment>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_segment(&self, segment_id: u64) -> Result<DetailedSegment> {
+        Err(not_mocked(segment_id))
+    }
+
+    async fn get_activity_streams(
+        &self,
+        _activity_id: u64,
+        _keys: &[StreamKey],
+    ) -> Result<StreamSet> {
+        Ok(StreamSet::default())
+    }
+
+    async fn get_activity_kudoers(&self, _activity_id: u64) -> Result<Vec<SummaryAthlete>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_activity_comments(&self, _activity_id: u64) -> Result<Vec<Comment>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_activity_photos(&self, _activity_id: u64) -> Result<Vec<Photo>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_activity_zones(&self, _activity_id: u64) -> Result<Vec<ActivityZone>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_photo(&self, _url: &str) -> Result<Vec<u8>> {
+        Err(not_mocked(0))
+    }
+}
+
+fn not_mocked(id: u64) -> Error {
+    Error::NotFound(id.to_string())
+}
+
+fn activity(id: u64) -> Activity {
+    let start = format!("2024-01-{:02}T08:00:00Z", id.min(28));
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "name": format!("Activity {}", id),
+        "type": "Run",
+        "sport_type": "Run",
+        "start_date": start,
+        "start_date_local": start,
+        "timezone": "Europe/Berlin",
+        "distance": 5000.0,
+        "moving_time": 1800,
+        "elapsed_time": 1800,
+        "total_elevation_gain": 0.0
+    }))
+    .unwrap()
+}
+
+fn upload(activity_id: Option<u64>) -> Upload {
+    Upload {
+        id: 7,
+        external_id: None,
+        error: None,
+        status: "Your activity is still being processed.".to_string(),
+        activity_id,
+    }
+}
+
+fn recv(worker: &Worker) -> Response {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        if let Some(response) = worker.try_recv() {
+            return response;
+        }
+        assert!(Instant::now() < deadline, "no response from the worker");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+// The paging part of the UI loop, run until the app stops asking for pages
+fn load_pages(app: &mut App, worker: &Worker, per_page: u32) {
+    while app.should_load_more() {
+        app.set_loading(true);
+        worker.send(Request::Page {
+            page: app.activity_page() + 1,
+            per_page,
+            range: app.date_range(),
+        });
+        match recv(worker) {
+            Response::Page {
+                result: Ok(activities),
+                ..
+            } => app.add_activities(activities, per_page),
+            Response::Page {
+                result: Err(e),
+                page,
+                ..
+            } => {
+                app.set_load_error(page, e.to_string());
+                app.report_api_error(format!("Failed to load activities page {}", page), &e);
+                return;
+            }
+            _ => panic!("expected a page"),
+        }
+    }
+}
+
+#[test]
+fn test_pages_load_while_scrolling() {
+    let api = MockApi::with_activities(25);
+    let worker = Worker::start(api.clone()).unwrap();
+    let mut app = App::new();
+
+    load_pages(&mut app, &worker, 10);
+    assert_eq!(app.activities().len(), 10);
+    assert_eq!(app.activities()[0].id, 25);
+
+    // Getting close to the end fetches the next page, a short one ends the list
+    while app.activities().len() < 25 {
+        app.select_next_activity();
+        load_pages(&mut app, &worker, 10);
+    }
+    for _ in 0..30 {
+        app.select_next_activity();
+        load_pages(&mut app, &worker, 10);
+    }
+    assert_eq!(app.activities().last().unwrap().id, 1);
+    assert_eq!(api.requested_pages(), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_failed_page_is_reported_and_loads_later() {
+    let api = MockApi::with_activities(5);
+    api.fail_with(Some(503));
+    let worker = Worker::start(api.clone()).unwrap();
+    let mut app = App::new();
+
+    load_pages(&mut app, &worker, 10);
+    assert!(app.activities().is_empty());
+    assert!(!app.is_loading());
+    assert_eq!(app.error_count(), 1);
+    assert!(app.status_message().unwrap().contains("503"));
+
+    // The failed page waits for a retry
+    api.fail_with(None);
+    load_pages(&mut app, &worker, 10);
+    assert_eq!(app.failed_page(), Some(1));
+    assert!(app.activities().is_empty());
+
+    assert!(app.retry_failed_page());
+    load_pages(&mut app, &worker, 10);
+    assert_eq!(app.activities().len(), 5);
+    assert_eq!(app.activity_page(), 1);
+    assert_eq!(app.failed_page(), None);
+}
+
+#[test]
+fn test_rate_limit_pauses_paging() {
+    let api = MockApi::with_activities(5);
+    api.fail_with(Some(429));
+    let worker = Worker::start(api.clone()).unwrap();
+    let mut app = App::new();
+
+    load_pages(&mut app, &worker, 10);
+    assert!(app.is_rate_limited());
+    assert!(!app.is_confirming());
+
+    // No more requests until the limit resets, even once Strava would answer
+    api.fail_with(None);
+    load_pages(&mut app, &worker, 10);
+    assert!(app.activities().is_empty());
+    assert_eq!(api.requested_pages(), Vec::<u32>::new());
+}
+
+#[test]
+fn test_lost_authorization_prompts_once() {
+    let api = MockApi::with_activities(5);
+    api.fail_with(Some(401));
+    let worker = Worker::start(api.clone()).unwrap();
+    let mut app = App::new();
+
+    load_pages(&mut app, &worker, 10);
+    assert!(app.is_confirming());
+    app.cancel_confirm();
+
+    assert!(app.retry_failed_page());
+    load_pages(&mut app, &worker, 10);
+    assert!(!app.is_confirming());
+    assert_eq!(app.error_count(), 2);
+
+    // Other failures are only reported
+    let mut app = App::new();
+    api.fail_with(Some(404));
+    load_pages(&mut app, &worker, 10);
+    assert!(!app.is_confirming());
+}
+
+#[test]
+fn test_athlete_and_stats_load() {
+    let worker = Worker::start(MockApi::default()).unwrap();
+    let mut app = App::new();
+
+    worker.send(Request::Athlete);
+    let athlete = match recv(&worker) {
+        Response::Athlete { result } => result.unwrap(),
+        _ => panic!("expected the athlete"),
+    };
+    worker.send(Request::Stats {
+        athlete_id: athlete.id,
+    });
+    app.set_athlete(*athlete);
+    match recv(&worker) {
+        Response::Stats { result } => app.set_stats(*result.unwrap()),
+        _ => panic!("expected stats"),
+    }
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let content: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(content.contains("Welcome, Test!"));
+    assert!(!content.contains("loading"));
+}
+
+#[test]
+fn test_watched_file_upload_progress() {
+    let worker = Worker::start(MockApi::default()).unwrap();
+    let mut app = App::new();
+    let path = PathBuf::from("/media/watch/B1F83012.FIT");
+
+    app.set_upload("B1F83012.FIT".to_string(), UploadState::Uploading);
+    worker.send(Request::Upload {
+        path: path.clone(),
+        data_type: UploadDataType::Fit,
+    });
+    let upload = match recv(&worker) {
+        Response::Upload { path: sent, result } => {
+            assert_eq!(sent, path);
+            result.unwrap()
+        }
+        _ => panic!("expected the upload"),
+    };
+    assert_eq!(upload.activity_id, None);
+    app.set_upload("B1F83012.FIT".to_string(), UploadState::Processing);
+    assert_eq!(
+        app.uploads(),
+        &[("B1F83012.FIT".to_string(), UploadState::Processing)]
+    );
+
+    worker.send(Request::UploadStatus {
+        path,
+        upload_id: upload.id,
+    });
+    match recv(&worker) {
+        Response::Upload { result, .. } => assert_eq!(result.unwrap().activity_id, Some(42)),
+        _ => panic!("expected the upload status"),
+    }
+
+    app.upload_finished("B1F83012.FIT", activity(42));
+    assert!(app.uploads().is_empty());
+    assert!(app.notice().unwrap().starts_with("Uploaded B1F83012.FIT: "));
+    assert_eq!(app.find_activity(42).map(|a| a.id), Some(42));
+}